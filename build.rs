@@ -0,0 +1,60 @@
+// build.rs - Embed the schema files under schemas/ at compile time
+//
+// Writes `$OUT_DIR/embedded_schemas.rs` with one `include_str!` per
+// `.toml`/`.json`/`.yaml` file anywhere under schemas/, keyed by its path
+// relative to schemas/. load_embedded picks the `<table>/<table>.<ext>` files
+// out of these and resolves their includes against the rest, so shared
+// include files are embedded too without touching the code. Tenant overlays
+// (`schemas/tenants/`) are left to directory loads.
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+const EXTENSIONS: [&str; 4] = ["toml", "json", "yaml", "yml"];
+
+fn main() {
+    let schemas_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("schemas");
+    println!("cargo:rerun-if-changed={}", schemas_dir.display());
+
+    let mut files = Vec::new();
+    collect(&schemas_dir, &schemas_dir, &mut files);
+    files.sort();
+
+    let mut code = String::from(
+        "// Generated by build.rs: (path relative to schemas/, content) per schema file\n\
+         pub(crate) const EMBEDDED_SCHEMA_FILES: &[(&str, &str)] = &[\n",
+    );
+    for (relative, path) in &files {
+        let path = path.to_str().expect("schema path is UTF-8");
+        writeln!(code, "    ({:?}, include_str!({:?})),", relative, path).unwrap();
+    }
+    code.push_str("];\n");
+
+    let out = Path::new(&std::env::var("OUT_DIR").expect("OUT_DIR")).join("embedded_schemas.rs");
+    std::fs::write(out, code).expect("writing embedded_schemas.rs");
+}
+
+// Every schema file under `dir`, as ("users/users.toml", absolute path)
+fn collect(root: &Path, dir: &Path, files: &mut Vec<(String, PathBuf)>) {
+    for entry in std::fs::read_dir(dir)
+        .expect("readable schemas/ directory")
+        .flatten()
+    {
+        let path = entry.path();
+        if path.is_dir() {
+            if dir != root || entry.file_name() != "tenants" {
+                collect(root, &path, files);
+            }
+            continue;
+        }
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        if !extension.is_some_and(|ext| EXTENSIONS.contains(&ext)) {
+            continue;
+        }
+        let relative = path.strip_prefix(root).expect("file under schemas/");
+        let relative: Vec<_> = relative
+            .components()
+            .map(|part| part.as_os_str().to_str().expect("schema path is UTF-8"))
+            .collect();
+        files.push((relative.join("/"), path));
+    }
+}
@@ -0,0 +1,178 @@
+// src/analytics.rs - Opt-in counts of the schema surface renders go through
+//
+// With USAGE_ANALYTICS=true every served render (cached ones included) counts
+// its component, its theme, each context it renders in and the variant each
+// field resolves to there. Only names are counted, never values.
+// GET /api/analytics/usage serves the counts, so components, contexts and
+// variants production never renders can be pruned with confidence.
+// Themes and contexts are only counted if the registry has them, so names
+// taken from requests can't grow the counts without bound.
+use crate::config::config;
+use crate::schema::SchemaRegistry;
+use dashmap::DashMap;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// Render counts by name
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct UsageReport {
+    pub components: BTreeMap<String, u64>,
+    // "<table>.<context>"
+    pub contexts: BTreeMap<String, u64>,
+    // "<table>.<field>.<variant>"
+    pub variants: BTreeMap<String, u64>,
+    pub themes: BTreeMap<String, u64>,
+}
+
+// Counters bumped in place, so concurrent renders never wait on each other
+// once a name has been seen
+#[derive(Debug, Default)]
+struct Counts(DashMap<String, AtomicU64>);
+
+impl Counts {
+    fn bump(&self, key: &str) {
+        match self.0.get(key) {
+            Some(count) => count.fetch_add(1, Ordering::Relaxed),
+            None => self
+                .0
+                .entry(key.to_string())
+                .or_default()
+                .fetch_add(1, Ordering::Relaxed),
+        };
+    }
+
+    fn snapshot(&self) -> BTreeMap<String, u64> {
+        self.0
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().load(Ordering::Relaxed)))
+            .collect()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct UsageTracker {
+    enabled: bool,
+    components: Counts,
+    contexts: Counts,
+    variants: Counts,
+    themes: Counts,
+}
+
+impl UsageTracker {
+    // A disabled tracker ignores everything it is given
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    // 📊 Count one render of `component` in `theme`; the theme only if
+    // `registry` has it
+    pub fn record_component(&self, registry: &SchemaRegistry, component: &str, theme: &str) {
+        if !self.enabled {
+            return;
+        }
+        self.components.bump(component);
+        if registry.get_theme(theme).is_some() {
+            self.themes.bump(theme);
+        }
+    }
+
+    // Count one render of `table` in `context`, with the (field, variant)
+    // pairs its fields resolved to; nothing if `registry` lacks the context
+    pub fn record_context(
+        &self,
+        registry: &SchemaRegistry,
+        table: &str,
+        context: &str,
+        variants: &[(&str, String)],
+    ) {
+        let known = registry
+            .get_table(table)
+            .is_some_and(|schema| schema.context(context).is_some());
+        if !self.enabled || !known {
+            return;
+        }
+        self.contexts.bump(&format!("{}.{}", table, context));
+        for (field, variant) in variants {
+            self.variants
+                .bump(&format!("{}.{}.{}", table, field, variant));
+        }
+    }
+
+    pub fn snapshot(&self) -> UsageReport {
+        UsageReport {
+            components: self.components.snapshot(),
+            contexts: self.contexts.snapshot(),
+            variants: self.variants.snapshot(),
+            themes: self.themes.snapshot(),
+        }
+    }
+
+    pub fn clear(&self) {
+        for counts in [
+            &self.components,
+            &self.contexts,
+            &self.variants,
+            &self.themes,
+        ] {
+            counts.0.clear();
+        }
+    }
+}
+
+static USAGE_TRACKER: OnceLock<UsageTracker> = OnceLock::new();
+
+pub fn usage_tracker() -> &'static UsageTracker {
+    USAGE_TRACKER.get_or_init(|| UsageTracker::new(config().usage_analytics))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_counts_names_only_when_enabled() {
+        let mut registry = SchemaRegistry::load_embedded();
+        registry
+            .load_themes_file(std::path::Path::new("themes.toml"))
+            .unwrap();
+        let tracker = UsageTracker::new(true);
+        tracker.record_component(&registry, "user_card", "light");
+        tracker.record_component(&registry, "user_card", "dark");
+        tracker.record_context(&registry, "users", "card", &[("name", "h1".to_string())]);
+        tracker.record_context(&registry, "users", "list", &[("name", "h3".to_string())]);
+        tracker.record_context(&registry, "users", "card", &[("name", "h1".to_string())]);
+
+        let report = tracker.snapshot();
+        assert_eq!(report.components["user_card"], 2);
+        assert_eq!(report.themes.len(), 2);
+        assert_eq!(report.contexts["users.card"], 2);
+        assert_eq!(report.variants["users.name.h1"], 2);
+        assert_eq!(report.variants["users.name.h3"], 1);
+
+        // Made-up themes and contexts from requests are not counted
+        tracker.record_component(&registry, "user_card", "no-such-theme");
+        tracker.record_context(&registry, "users", "no-such-context", &[]);
+        tracker.record_context(&registry, "no_such_table", "card", &[]);
+        let report = tracker.snapshot();
+        assert_eq!(report.components["user_card"], 3);
+        assert_eq!(report.themes.len(), 2);
+        assert_eq!(report.contexts.len(), 2);
+
+        tracker.clear();
+        assert_eq!(tracker.snapshot(), UsageReport::default());
+
+        let disabled = UsageTracker::new(false);
+        disabled.record_component(&registry, "user_card", "light");
+        disabled.record_context(&registry, "users", "card", &[("name", "h1".to_string())]);
+        assert_eq!(disabled.snapshot(), UsageReport::default());
+    }
+}
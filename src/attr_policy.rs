@@ -0,0 +1,149 @@
+// src/attr_policy.rs - Which attributes schema variants may set
+//
+// ATTR_DENY lists attribute names no variant may use, with `prefix*` patterns
+// ("on*,style"); it defaults to `on*` so event handlers can't slip into a
+// schema. ATTR_ALLOW optionally limits tags to known attributes:
+// "a:href|target|rel,img:src|alt,*:title|data-*" (`*` applies to every tag).
+// The policy is checked when schemas are validated or edited, and again at
+// render time, where offending variant attributes are dropped. Names that
+// could break out of the attribute (spaces, `=`, quotes, `<`, `>`, `/`) are
+// never permitted, whatever the lists say.
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttrPolicy {
+    // Tag -> permitted patterns; tags without an entry (and no `*`) allow anything
+    allow: HashMap<String, Vec<String>>,
+    deny: Vec<String>,
+}
+
+impl Default for AttrPolicy {
+    fn default() -> Self {
+        Self {
+            allow: HashMap::new(),
+            deny: vec!["on*".to_string()],
+        }
+    }
+}
+
+impl AttrPolicy {
+    // Build from ATTR_ALLOW / ATTR_DENY style specs; a missing spec keeps the default
+    pub fn parse(allow: Option<&str>, deny: Option<&str>) -> Self {
+        let defaults = Self::default();
+        let allow = allow.map_or(defaults.allow, |spec| {
+            spec.split(',')
+                .filter_map(|entry| {
+                    let (tag, names) = entry.split_once(':')?;
+                    Some((tag.trim().to_ascii_lowercase(), patterns(names, '|')))
+                })
+                .collect()
+        });
+        let deny = deny.map_or(defaults.deny, |spec| patterns(spec, ','));
+        Self { allow, deny }
+    }
+
+    // 🛂 Why `name` may not appear on `tag`, or None when it may
+    pub fn violation(&self, tag: &str, name: &str) -> Option<String> {
+        if !is_valid_name(name) {
+            return Some(format!("'{}' is not a valid attribute name", name));
+        }
+        let name = name.to_ascii_lowercase();
+        if let Some(pattern) = self.deny.iter().find(|pattern| matches(pattern, &name)) {
+            return Some(format!("attribute '{}' is denied ({})", name, pattern));
+        }
+
+        let tag = tag.to_ascii_lowercase();
+        let mut allowed = [self.allow.get(&tag), self.allow.get("*")]
+            .into_iter()
+            .flatten()
+            .flatten()
+            .peekable();
+        if allowed.peek().is_some() && !allowed.any(|pattern| matches(pattern, &name)) {
+            return Some(format!("attribute '{}' is not allowed on <{}>", name, tag));
+        }
+        None
+    }
+
+    pub fn permits(&self, tag: &str, name: &str) -> bool {
+        self.violation(tag, name).is_none()
+    }
+}
+
+// An attribute name that can't end the attribute or the tag early
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.chars().any(|c| {
+            c.is_whitespace() || c.is_control() || matches!(c, '=' | '"' | '\'' | '<' | '>' | '/')
+        })
+}
+
+fn patterns(spec: &str, separator: char) -> Vec<String> {
+    spec.split(separator)
+        .map(|pattern| pattern.trim().to_ascii_lowercase())
+        .filter(|pattern| !pattern.is_empty())
+        .collect()
+}
+
+fn matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allow_and_deny_lists() {
+        let policy = AttrPolicy::default();
+        assert!(policy.permits("a", "href"));
+        assert!(!policy.permits("img", "onError"));
+        assert!(policy.permits("a", "style"));
+
+        let policy = AttrPolicy::parse(Some("a:href|rel, *:title|data-*"), Some("on*,style"));
+        assert!(policy.permits("a", "href"));
+        assert!(policy.permits("A", "data-id"));
+        assert!(policy.permits("span", "title"));
+        assert!(!policy.permits("a", "style"));
+        assert_eq!(
+            policy.violation("img", "src").as_deref(),
+            Some("attribute 'src' is not allowed on <img>")
+        );
+        assert_eq!(
+            policy.violation("a", "onclick").as_deref(),
+            Some("attribute 'onclick' is denied (on*)")
+        );
+    }
+
+    #[test]
+    fn test_names_that_break_out_of_the_attribute_are_rejected() {
+        let policy = AttrPolicy::parse(Some("*:data-*"), None);
+        for name in [
+            "data-x onclick",
+            "data-x=1",
+            "data-\"x",
+            "data-x>",
+            "data-x/",
+            "",
+        ] {
+            assert!(!policy.permits("span", name), "{:?}", name);
+            assert!(!AttrPolicy::default().permits("span", name), "{:?}", name);
+        }
+        assert!(policy.permits("span", "data-x"));
+
+        // Dropped at render time too, even when the schema skipped validation
+        let schema = crate::schema::TableSchema::from_toml_str(
+            "[variants.name.h1]\nbase = \"h1\"\nattrs = { \"data-x onclick\" = \"alert(1)\" }\n\
+             [contexts.card]\nname = \"h1\"\n",
+        )
+        .unwrap();
+        let mut registry = crate::schema::SchemaRegistry::load_embedded();
+        registry.insert_table("widgets", schema);
+        let html = registry
+            .render_field("widgets", "name", "card", "Ada")
+            .unwrap();
+        assert!(!html.contains("onclick"), "{}", html);
+    }
+}
@@ -0,0 +1,193 @@
+// src/bench.rs - Render-time and output-size comparison of schema bundles
+//
+// `bench compare --before a.bin --after b.bin` renders one corpus of requests
+// against each compiled bundle (see compile-registry) and reports, per
+// component, the mean render time and output size on either side, so schema
+// edits that slow renders down or bloat the markup are caught before they
+// ship. The corpus defaults to every record of every component in every
+// context of its table, taken from the "before" bundle.
+use crate::component_registry::{ComponentRegistry, RenderParams};
+use crate::config::WarmupEntry;
+use crate::schema::registry;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+// Renders of one component against one bundle
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct RenderStats {
+    pub renders: usize,
+    pub failures: usize,
+    pub total: Duration,
+    // Output bytes of the successful renders
+    pub bytes: usize,
+}
+
+impl RenderStats {
+    pub fn mean_ms(&self) -> f64 {
+        if self.renders == 0 {
+            0.0
+        } else {
+            self.total.as_secs_f64() * 1000.0 / self.renders as f64
+        }
+    }
+
+    pub fn mean_bytes(&self) -> f64 {
+        if self.renders == 0 {
+            0.0
+        } else {
+            self.bytes as f64 / self.renders as f64
+        }
+    }
+}
+
+// One component measured against both bundles
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComponentDelta {
+    pub component: String,
+    pub before: RenderStats,
+    pub after: RenderStats,
+}
+
+impl ComponentDelta {
+    // Change of the mean render time, in percent of the "before" mean
+    pub fn time_change(&self) -> Option<f64> {
+        percent_change(self.before.mean_ms(), self.after.mean_ms())
+    }
+
+    // Change of the mean output size, in percent of the "before" mean
+    pub fn size_change(&self) -> Option<f64> {
+        percent_change(self.before.mean_bytes(), self.after.mean_bytes())
+    }
+}
+
+fn percent_change(before: f64, after: f64) -> Option<f64> {
+    (before > 0.0).then(|| (after - before) / before * 100.0)
+}
+
+// Every (component, record, context) the active schemas can render
+pub async fn default_corpus(components: &ComponentRegistry) -> Vec<WarmupEntry> {
+    let schemas = registry();
+    let mut names = components.list_components();
+    names.sort();
+    let mut corpus = Vec::new();
+    for name in names {
+        let Some(component) = components.get_component(name) else {
+            continue;
+        };
+        let Some(schema) = schemas.get_table(&component.table) else {
+            continue;
+        };
+        // Components needing request params have no args to render with here
+        let Ok(ids) = components.collection_ids(name, None, None).await else {
+            continue;
+        };
+        let mut contexts: Vec<_> = schema.contexts.keys().collect();
+        contexts.sort();
+        for id in &ids {
+            for context in &contexts {
+                corpus.push(WarmupEntry {
+                    component: name.clone(),
+                    id: id.clone(),
+                    context: Some(context.to_string()),
+                    theme: None,
+                });
+            }
+        }
+    }
+    corpus
+}
+
+// 🏋️ Render the corpus `iterations` times with the active schemas, by
+// component. Turn the registry's cache off first to measure actual renders.
+pub async fn measure(
+    components: &ComponentRegistry,
+    corpus: &[WarmupEntry],
+    iterations: usize,
+) -> BTreeMap<String, RenderStats> {
+    let mut stats: BTreeMap<String, RenderStats> = BTreeMap::new();
+    for _ in 0..iterations {
+        for entry in corpus {
+            let params = RenderParams {
+                context: entry.context.as_deref(),
+                theme: entry.theme.as_deref(),
+                ..Default::default()
+            };
+            let started = Instant::now();
+            let rendered = components
+                .render_component(&entry.component, &entry.id, params)
+                .await;
+            let elapsed = started.elapsed();
+            let component = stats.entry(entry.component.clone()).or_default();
+            match rendered {
+                Ok(html) => {
+                    component.renders += 1;
+                    component.total += elapsed;
+                    component.bytes += html.len();
+                }
+                Err(_) => component.failures += 1,
+            }
+        }
+    }
+    stats
+}
+
+// Per component measured on either side, sorted by name; a component only
+// one bundle renders is compared against empty stats
+pub fn compare(
+    before: &BTreeMap<String, RenderStats>,
+    after: &BTreeMap<String, RenderStats>,
+) -> Vec<ComponentDelta> {
+    let mut names: Vec<_> = before.keys().chain(after.keys()).collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .map(|name| ComponentDelta {
+            component: name.clone(),
+            before: before.get(name).cloned().unwrap_or_default(),
+            after: after.get(name).cloned().unwrap_or_default(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_measure_and_compare_corpus_renders() {
+        let mut components = ComponentRegistry::new();
+        components.set_cache_enabled(false);
+        let corpus = default_corpus(&components).await;
+        assert!(corpus.iter().any(|entry| entry.component == "user_card"));
+
+        let before = measure(&components, &corpus, 2).await;
+        let user_card = &before["user_card"];
+        let per_pass = corpus
+            .iter()
+            .filter(|entry| entry.component == "user_card")
+            .count();
+        assert_eq!((user_card.renders, user_card.failures), (per_pass * 2, 0));
+        assert!(user_card.mean_bytes() > 0.0);
+
+        // Records a bundle lacks count as failures
+        let missing = WarmupEntry::parse("user_card:404").unwrap();
+        assert_eq!(
+            measure(&components, &[missing], 1).await["user_card"].failures,
+            1
+        );
+
+        let mut after = before.clone();
+        after.get_mut("user_card").unwrap().bytes *= 2;
+        after.insert("new_card".to_string(), RenderStats::default());
+        let deltas = compare(&before, &after);
+        let delta = |name| deltas.iter().find(|delta| delta.component == name).unwrap();
+        assert_eq!(
+            delta("user_card").size_change().map(f64::round),
+            Some(100.0)
+        );
+        assert_eq!(delta("new_card").before, RenderStats::default());
+        assert_eq!(delta("new_card").size_change(), None);
+    }
+}
@@ -0,0 +1,167 @@
+// src/budget.rs - Render-time budgets: record actual latencies and flag violations
+use crate::config::config;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+// Default shortest gap between two "budget exceeded" lines for one key
+const LOG_INTERVAL: Duration = Duration::from_secs(60);
+
+// Actual render times recorded against one budget
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct BudgetStats {
+    pub budget_ms: f64,
+    pub renders: u64,
+    pub violations: u64,
+    pub total_ms: f64,
+    pub max_ms: f64,
+    // When a violation of this budget was last logged, and how many have
+    // happened since without a log line
+    #[serde(skip)]
+    logged_at: Option<Instant>,
+    #[serde(skip)]
+    unlogged: u64,
+}
+
+impl BudgetStats {
+    pub fn mean_ms(&self) -> f64 {
+        if self.renders == 0 {
+            0.0
+        } else {
+            self.total_ms / self.renders as f64
+        }
+    }
+}
+
+// Budget keys: "component:<name>" and "field:<table>.<field>". Violations
+// are always counted; they are logged at most once per `log_interval` per key,
+// so a slow hot path doesn't flood the log.
+#[derive(Debug)]
+pub struct BudgetTracker {
+    stats: RwLock<HashMap<String, BudgetStats>>,
+    log_interval: Duration,
+}
+
+impl Default for BudgetTracker {
+    fn default() -> Self {
+        Self {
+            stats: RwLock::default(),
+            log_interval: LOG_INTERVAL,
+        }
+    }
+}
+
+impl BudgetTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn log_interval(mut self, interval: Duration) -> Self {
+        self.log_interval = interval;
+        self
+    }
+
+    // ⏱️ Record one render; returns false (and maybe logs) when it blew its budget
+    pub fn record(&self, key: &str, budget_ms: f64, elapsed: Duration) -> bool {
+        let elapsed_ms = elapsed.as_secs_f64() * 1000.0;
+        let within_budget = elapsed_ms <= budget_ms;
+
+        let mut stats = self
+            .stats
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let entry = stats.entry(key.to_string()).or_default();
+        entry.budget_ms = budget_ms;
+        entry.renders += 1;
+        entry.total_ms += elapsed_ms;
+        entry.max_ms = entry.max_ms.max(elapsed_ms);
+        if !within_budget {
+            entry.violations += 1;
+            let due = entry
+                .logged_at
+                .is_none_or(|logged_at| logged_at.elapsed() >= self.log_interval);
+            if due {
+                let unlogged = std::mem::take(&mut entry.unlogged);
+                let more = match unlogged {
+                    0 => String::new(),
+                    n => format!(", {} more since the last report", n),
+                };
+                eprintln!(
+                    "⏱️ Render budget exceeded: {} took {:.2}ms (budget {}ms{})",
+                    key, elapsed_ms, budget_ms, more
+                );
+                entry.logged_at = Some(Instant::now());
+            } else {
+                entry.unlogged += 1;
+            }
+        }
+
+        within_budget
+    }
+
+    // Sorted copy of all recorded stats
+    pub fn snapshot(&self) -> BTreeMap<String, BudgetStats> {
+        let stats = self
+            .stats
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        stats.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+
+    pub fn total_violations(&self) -> u64 {
+        let stats = self
+            .stats
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        stats.values().map(|entry| entry.violations).sum()
+    }
+
+    pub fn clear(&self) {
+        let mut stats = self
+            .stats
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        stats.clear();
+    }
+}
+
+static BUDGET_TRACKER: OnceLock<BudgetTracker> = OnceLock::new();
+
+pub fn budget_tracker() -> &'static BudgetTracker {
+    BUDGET_TRACKER.get_or_init(|| {
+        BudgetTracker::new().log_interval(Duration::from_secs(config().budget_log_interval_secs))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_actuals_and_violations() {
+        let tracker = BudgetTracker::new();
+        assert!(tracker.record("component:card", 5.0, Duration::from_millis(2)));
+        assert!(!tracker.record("component:card", 5.0, Duration::from_millis(8)));
+
+        let stats = &tracker.snapshot()["component:card"];
+        assert_eq!(stats.renders, 2);
+        assert_eq!(stats.violations, 1);
+        assert!((stats.mean_ms() - 5.0).abs() < 0.01);
+        assert!((stats.max_ms - 8.0).abs() < 0.01);
+        assert_eq!(tracker.total_violations(), 1);
+    }
+
+    #[test]
+    fn test_violation_logs_are_rate_limited_but_all_counted() {
+        let tracker = BudgetTracker::new().log_interval(Duration::from_secs(3600));
+        for _ in 0..3 {
+            assert!(!tracker.record("field:users.name", 1.0, Duration::from_millis(2)));
+        }
+        let stats = &tracker.snapshot()["field:users.name"];
+        assert_eq!(stats.violations, 3);
+        // The first was logged, the other two wait for the next report
+        assert!(stats.logged_at.is_some());
+        assert_eq!(stats.unlogged, 2);
+    }
+}
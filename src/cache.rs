@@ -0,0 +1,259 @@
+// src/cache.rs - In-memory cache for rendered component HTML
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::{Duration, Instant};
+
+// Entries kept unless RENDER_CACHE_CAPACITY says otherwise
+pub const DEFAULT_CAPACITY: usize = 10_000;
+// How long expired renders stay available to get_stale
+pub const DEFAULT_STALE_FOR: Duration = Duration::from_secs(3600);
+
+// Everything that can change the HTML of a component render
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    pub component: String,
+    pub record_id: String,
+    pub context: String,
+    pub theme: String,
+    pub minified: bool,
+    pub currency: Option<String>,
+    pub jsonld: bool,
+    pub platform: Option<String>,
+    // The table's `[locales]` entry the render used
+    pub locale: Option<String>,
+    // Values of the component's required request params ("org_id=7")
+    pub scope: Vec<String>,
+    pub tenant: Option<String>,
+}
+
+impl CacheKey {
+    pub fn new(component: &str, record_id: &str, context: &str, theme: &str) -> Self {
+        Self {
+            component: component.to_string(),
+            record_id: record_id.to_string(),
+            context: context.to_string(),
+            theme: theme.to_string(),
+            minified: false,
+            currency: None,
+            jsonld: false,
+            platform: None,
+            locale: None,
+            scope: Vec::new(),
+            tenant: None,
+        }
+    }
+
+    pub fn minified(mut self, minified: bool) -> Self {
+        self.minified = minified;
+        self
+    }
+
+    // Renders with a structured data block appended are cached separately
+    pub fn jsonld(mut self, jsonld: bool) -> Self {
+        self.jsonld = jsonld;
+        self
+    }
+
+    // Platforms pick their own variants; print also changes classes and attributes
+    pub fn platform(mut self, platform: Option<&str>) -> Self {
+        self.platform = platform.map(str::to_string);
+        self
+    }
+
+    // Languages with `[locales]` overrides render their own variants
+    pub fn locale(mut self, locale: Option<&str>) -> Self {
+        self.locale = locale.map(str::to_string);
+        self
+    }
+
+    // Renders of components with required params are cached per param value
+    pub fn scope(mut self, scope: &[(String, String)]) -> Self {
+        self.scope = scope
+            .iter()
+            .map(|(param, value)| format!("{}={}", param, value))
+            .collect();
+        self
+    }
+
+    // Renders converted to a viewer currency are cached per currency
+    pub fn currency(mut self, currency: Option<&str>) -> Self {
+        self.currency = currency.map(str::to_ascii_uppercase);
+        self
+    }
+
+    // Tenants restyle fields through their schema overlays
+    pub fn tenant(mut self, tenant: Option<&str>) -> Self {
+        self.tenant = tenant.map(str::to_string);
+        self
+    }
+}
+
+#[derive(Debug)]
+struct CachedRender {
+    html: String,
+    expires_at: Instant,
+    // Tick of the last read or write, for least-recently-used eviction
+    last_used: AtomicU64,
+}
+
+// Keys carry client-chosen query values, so the cache is bounded: past
+// `capacity` entries the least recently used go first, and expired entries
+// are only kept `stale_for` as outage fallbacks
+#[derive(Debug)]
+pub struct RenderCache {
+    entries: RwLock<HashMap<CacheKey, CachedRender>>,
+    ttl: Duration,
+    capacity: usize,
+    stale_for: Duration,
+    clock: AtomicU64,
+}
+
+impl RenderCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+            ttl,
+            capacity: DEFAULT_CAPACITY,
+            stale_for: DEFAULT_STALE_FOR,
+            clock: AtomicU64::new(0),
+        }
+    }
+
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    // How long an expired render may still be served by get_stale
+    pub fn keep_stale_for(mut self, stale_for: Duration) -> Self {
+        self.stale_for = stale_for;
+        self
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, HashMap<CacheKey, CachedRender>> {
+        self.entries
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<CacheKey, CachedRender>> {
+        self.entries
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // Fetch a cached render if it has not expired yet
+    pub fn get(&self, key: &CacheKey) -> Option<String> {
+        let entries = self.read();
+        let cached = entries
+            .get(key)
+            .filter(|cached| Instant::now() < cached.expires_at)?;
+        cached.last_used.store(self.tick(), Ordering::Relaxed);
+        Some(cached.html.clone())
+    }
+
+    // Fetch a cached render even if it has expired (within `stale_for`), as a
+    // fallback when fresh data cannot be loaded
+    pub fn get_stale(&self, key: &CacheKey) -> Option<String> {
+        let entries = self.read();
+        let now = Instant::now();
+        let cached = entries
+            .get(key)
+            .filter(|cached| now < cached.expires_at + self.stale_for)?;
+        cached.last_used.store(self.tick(), Ordering::Relaxed);
+        Some(cached.html.clone())
+    }
+
+    pub fn insert(&self, key: CacheKey, html: String) {
+        self.insert_with_ttl(key, html, self.ttl);
+    }
+
+    // Store a render with its own lifetime (per-component cache_ttl)
+    pub fn insert_with_ttl(&self, key: CacheKey, html: String, ttl: Duration) {
+        if ttl.is_zero() {
+            return;
+        }
+        let mut entries = self.write();
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            self.evict(&mut entries);
+        }
+        entries.insert(
+            key,
+            CachedRender {
+                html,
+                expires_at: Instant::now() + ttl,
+                last_used: AtomicU64::new(self.tick()),
+            },
+        );
+    }
+
+    // Drop entries past their stale window, then the least recently used
+    // down to 7/8 of capacity, so a full cache doesn't evict on every insert
+    fn evict(&self, entries: &mut HashMap<CacheKey, CachedRender>) {
+        let now = Instant::now();
+        entries.retain(|_, cached| now < cached.expires_at + self.stale_for);
+        let keep = self.capacity - self.capacity / 8 - 1;
+        if entries.len() <= keep {
+            return;
+        }
+        if keep == 0 {
+            entries.clear();
+            return;
+        }
+        let mut ticks: Vec<u64> = entries
+            .values()
+            .map(|cached| cached.last_used.load(Ordering::Relaxed))
+            .collect();
+        let cut = entries.len() - keep;
+        let (_, oldest_kept, _) = ticks.select_nth_unstable(cut);
+        let oldest_kept = *oldest_kept;
+        entries.retain(|_, cached| cached.last_used.load(Ordering::Relaxed) >= oldest_kept);
+    }
+
+    pub fn clear(&self) {
+        self.write().clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_evicts_least_recently_used_and_old_stale_entries() {
+        let cache = RenderCache::new(Duration::from_secs(60)).with_capacity(8);
+        let key = |id: usize| CacheKey::new("user_card", &id.to_string(), "card", "light");
+        for id in 0..8 {
+            cache.insert(key(id), format!("<p>{}</p>", id));
+        }
+        // Recently read entries survive the eviction a full cache makes room with
+        assert!(cache.get(&key(0)).is_some());
+        cache.insert(key(8), "<p>8</p>".to_string());
+        assert!(cache.len() <= 8);
+        assert!(cache.get(&key(0)).is_some() && cache.get(&key(8)).is_some());
+        assert!(cache.get(&key(1)).is_none());
+
+        let cache = RenderCache::new(Duration::ZERO).keep_stale_for(Duration::from_millis(5));
+        cache.insert_with_ttl(key(1), "<p>1</p>".to_string(), Duration::from_nanos(1));
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(
+            (cache.get(&key(1)), cache.get_stale(&key(1)).is_some()),
+            (None, true)
+        );
+        std::thread::sleep(Duration::from_millis(10));
+        assert_eq!(cache.get_stale(&key(1)), None);
+    }
+}
@@ -0,0 +1,205 @@
+// src/chat_format.rs - Slack Block Kit and Discord embed output (format=slack|discord)
+//
+// Chat messages cannot carry our HTML, so each field's role comes from the base
+// tag of the variant its context resolves to: headings become the title, img
+// the image, `a` a link (using the variant's href), everything else a labelled
+// field. The same schema drives the web card and the bot message.
+use crate::schema::{SchemaRegistry, TableSchema};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatFormat {
+    Slack,
+    Discord,
+}
+
+impl ChatFormat {
+    pub fn parse(format: &str) -> Option<Self> {
+        match format {
+            "slack" => Some(ChatFormat::Slack),
+            "discord" => Some(ChatFormat::Discord),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ChatMessage {
+    title: Option<String>,
+    image: Option<String>,
+    // (label, value, link)
+    fields: Vec<(String, String, Option<String>)>,
+}
+
+// 💬 Build the message for `fields` (in order) of a record in `context`
+pub fn chat_message(
+    format: ChatFormat,
+    schema: &TableSchema,
+    context: &str,
+    fields: &[String],
+    record: &HashMap<String, String>,
+) -> Value {
+    let message = collect(schema, context, fields, record);
+    match format {
+        ChatFormat::Slack => slack_blocks(&message),
+        ChatFormat::Discord => discord_embed(&message),
+    }
+}
+
+fn collect(
+    schema: &TableSchema,
+    context: &str,
+    fields: &[String],
+    record: &HashMap<String, String>,
+) -> ChatMessage {
+    let mut message = ChatMessage::default();
+    for field in fields {
+        let Some(value) = record.get(field).filter(|value| !value.is_empty()) else {
+            continue;
+        };
+        let variant = SchemaRegistry::resolve_variant_for_value(schema, field, context, value)
+            .and_then(|name| schema.variants.get(field)?.get(&name));
+        let base = variant
+            .map(|variant| variant.base.as_str())
+            .unwrap_or("span");
+        let href = variant
+            .and_then(|variant| variant.attrs.as_ref()?.get("href"))
+            .map(|href| href.replace("{value}", value));
+
+        match base {
+            "h1" | "h2" | "h3" if message.title.is_none() => message.title = Some(value.clone()),
+            "img" if message.image.is_none() => message.image = Some(value.clone()),
+            "img" => {}
+            "a" => message.fields.push((
+                label(field),
+                value.clone(),
+                href.or_else(|| Some(value.clone())),
+            )),
+            _ => message.fields.push((label(field), value.clone(), None)),
+        }
+    }
+    message
+}
+
+// "created_at" -> "Created at"
+fn label(field: &str) -> String {
+    let words = field.replace('_', " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// Slack mrkdwn treats &, < and > as control characters
+fn slack_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn slack_blocks(message: &ChatMessage) -> Value {
+    let mut section = json!({ "type": "section" });
+    if let Some(title) = &message.title {
+        section["text"] = json!({ "type": "mrkdwn", "text": format!("*{}*", slack_escape(title)) });
+    }
+    // Slack allows at most 10 fields per section
+    let fields: Vec<Value> = message
+        .fields
+        .iter()
+        .take(10)
+        .map(|(label, value, link)| {
+            let value = match link {
+                Some(link) => format!("<{}|{}>", slack_escape(link), slack_escape(value)),
+                None => slack_escape(value),
+            };
+            json!({ "type": "mrkdwn", "text": format!("*{}*\n{}", slack_escape(label), value) })
+        })
+        .collect();
+    if !fields.is_empty() {
+        section["fields"] = Value::Array(fields);
+    }
+    if let Some(image) = &message.image {
+        let alt = message.title.as_deref().unwrap_or("image");
+        section["accessory"] = json!({ "type": "image", "image_url": image, "alt_text": alt });
+    }
+
+    // A section needs text or fields; with neither the message is empty
+    let blocks = if section.get("text").is_some() || section.get("fields").is_some() {
+        vec![section]
+    } else {
+        Vec::new()
+    };
+    json!({ "blocks": blocks })
+}
+
+fn discord_embed(message: &ChatMessage) -> Value {
+    let mut embed = json!({});
+    if let Some(title) = &message.title {
+        embed["title"] = json!(title);
+    }
+    // Discord allows at most 25 fields per embed
+    let fields: Vec<Value> = message
+        .fields
+        .iter()
+        .take(25)
+        .map(|(label, value, link)| {
+            let value = match link {
+                Some(link) => format!("[{}]({})", value, link),
+                None => value.clone(),
+            };
+            json!({ "name": label, "value": value, "inline": true })
+        })
+        .collect();
+    if !fields.is_empty() {
+        embed["fields"] = Value::Array(fields);
+    }
+    if let Some(image) = &message.image {
+        embed["thumbnail"] = json!({ "url": image });
+    }
+    json!({ "embeds": [embed] })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::registry;
+
+    #[test]
+    fn test_user_card_as_slack_and_discord() {
+        let registry = registry();
+        let schema = registry.get_table("users").unwrap();
+        let record = registry.get_mock_record("users", "1").unwrap();
+        let fields = ["name", "email", "avatar_url", "created_at"].map(String::from);
+
+        let slack = chat_message(ChatFormat::Slack, schema, "card", &fields, &record);
+        let section = &slack["blocks"][0];
+        assert_eq!(section["text"]["text"], "*John Doe*");
+        assert_eq!(
+            section["fields"][0]["text"],
+            "*Email*\n<mailto:john@example.com|john@example.com>"
+        );
+        assert_eq!(
+            section["fields"][1]["text"],
+            "*Created at*\n2024-01-15T10:30:00Z"
+        );
+        assert_eq!(section["accessory"]["type"], "image");
+
+        let discord = chat_message(ChatFormat::Discord, schema, "card", &fields, &record);
+        let embed = &discord["embeds"][0];
+        assert_eq!(embed["title"], "John Doe");
+        assert_eq!(
+            embed["fields"][0]["value"],
+            "[john@example.com](mailto:john@example.com)"
+        );
+        assert!(
+            embed["thumbnail"]["url"]
+                .as_str()
+                .unwrap()
+                .starts_with("https://")
+        );
+
+        assert_eq!(ChatFormat::parse("teams"), None);
+    }
+}
@@ -0,0 +1,132 @@
+// src/class_report.rs - CSS class usage across components, contexts and themes
+use crate::codegen::{split_classes, template_class_attributes};
+use crate::component_registry::ComponentTemplate;
+use crate::schema::SchemaRegistry;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+// A theme class that no component/context combination ever emits
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnreachableClass {
+    pub theme: String,
+    pub tag: String,
+    pub class: String,
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ClassReport {
+    // class -> number of (component, context, theme) renders emitting it
+    pub usage: BTreeMap<String, usize>,
+    pub unreachable: Vec<UnreachableClass>,
+}
+
+impl ClassReport {
+    // Classes ordered by usage, most used first
+    pub fn by_usage(&self) -> Vec<(&str, usize)> {
+        let mut classes: Vec<_> = self
+            .usage
+            .iter()
+            .map(|(class, count)| (class.as_str(), *count))
+            .collect();
+        classes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        classes
+    }
+}
+
+// 📊 Walk every component through every context of its table under every
+// theme, counting the classes each render would emit. Theme classes that
+// never show up are reported as unreachable: their tag is not the base of any
+// used variant, or every such variant overrides the theme classes.
+pub fn class_report(registry: &SchemaRegistry, components: &[&ComponentTemplate]) -> ClassReport {
+    let mut report = ClassReport::default();
+    // theme -> classes emitted under it
+    let mut emitted: BTreeMap<&str, BTreeSet<String>> = BTreeMap::new();
+
+    for component in components {
+        let Some(schema) = registry.get_table(&component.table) else {
+            continue;
+        };
+        // Without contexts the defaults decide the variants
+        let mut contexts: Vec<&str> = schema.contexts.keys().map(String::as_str).collect();
+        if contexts.is_empty() {
+            contexts.push("default");
+        }
+        let template_classes: Vec<String> = template_class_attributes(&component.template)
+            .into_iter()
+            .flat_map(split_classes)
+            .collect();
+
+        for theme in registry.list_themes() {
+            let theme_emitted = emitted.entry(theme.as_str()).or_default();
+            for context in &contexts {
+                let field_classes = component.required_fields.iter().filter_map(|field| {
+                    registry.field_classes(&component.table, field, context, theme)
+                });
+                let classes = field_classes
+                    .flat_map(|class_list| split_classes(&class_list).collect::<Vec<_>>())
+                    .chain(template_classes.iter().cloned());
+
+                for class in classes {
+                    *report.usage.entry(class.clone()).or_default() += 1;
+                    theme_emitted.insert(class);
+                }
+            }
+        }
+    }
+
+    let mut themes: Vec<_> = registry.list_themes();
+    themes.sort();
+    for theme_name in themes {
+        let Some(theme) = registry.get_theme(theme_name) else {
+            continue;
+        };
+        let theme_emitted = emitted.get(theme_name.as_str());
+        let mut tags: Vec<_> = theme.tags.iter().collect();
+        tags.sort();
+        for (tag, class_list) in tags {
+            for class in split_classes(class_list) {
+                if !theme_emitted.is_some_and(|classes| classes.contains(&class)) {
+                    report.unreachable.push(UnreachableClass {
+                        theme: theme_name.clone(),
+                        tag: tag.clone(),
+                        class,
+                    });
+                }
+            }
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_counts_usage_and_flags_unreachable_theme_classes() {
+        let registry = SchemaRegistry::load_embedded();
+        let component = ComponentTemplate::builder()
+            .name("row")
+            .table("users")
+            .template_str("<li class=\"py-2\">{email} {created_at}</li>")
+            .build()
+            .unwrap();
+
+        let report = class_report(&registry, &[&component]);
+
+        // One render per context (card, list, print, detail) per theme
+        let renders = registry.list_themes().len() * 4;
+        assert_eq!(report.usage["py-2"], renders);
+
+        // `created_at` uses the theme's <time> classes in card, but not in list
+        assert!(report.usage.contains_key("text-gray-500"));
+
+        // Email renders as <a>, so the theme's `link` classes are never used
+        let unreachable_link = report.unreachable.iter().any(|entry| {
+            entry.theme == "light" && entry.tag == "link" && entry.class == "underline"
+        });
+        assert!(unreachable_link);
+        assert!(!report.unreachable.iter().any(|entry| entry.tag == "time"));
+    }
+}
@@ -0,0 +1,258 @@
+// src/client.rs - Typed client for a remote render server
+//
+// For Rust services that render through another instance's API instead of
+// loading schemas themselves:
+//
+//     let client = RenderClient::new("https://ui.internal");
+//     let html = client
+//         .render(&RenderRequest::new("user_card").id("1").context("list"))
+//         .await?;
+//
+// Failures the server reports come back as the ComponentError it rendered
+// them from (a missing record is `RecordNotFound`, not a bare 404), so callers
+// handle remote and local renders the same way.
+use crate::component_registry::ComponentError;
+use crate::http_source::{encode, fetch_url};
+use serde::Deserialize;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub enum ClientError {
+    // The server answered with a render failure
+    Render(ComponentError),
+    // Any other non-success response: (status, body)
+    Http(u16, String),
+    // No usable response: connection refused, timeout, malformed JSON
+    Transport(String),
+}
+
+impl std::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::Render(err) => write!(f, "{}", err),
+            ClientError::Http(status, body) => write!(f, "HTTP {}: {}", status, body),
+            ClientError::Transport(msg) => write!(f, "Render server unreachable: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+// One GET /api/:component call; unset options use the server's defaults
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RenderRequest {
+    component: String,
+    query: Vec<(String, String)>,
+}
+
+impl RenderRequest {
+    pub fn new(component: &str) -> Self {
+        Self {
+            component: component.to_string(),
+            query: Vec::new(),
+        }
+    }
+
+    pub fn id(self, id: &str) -> Self {
+        self.param("id", id)
+    }
+
+    pub fn context(self, context: &str) -> Self {
+        self.param("context", context)
+    }
+
+    pub fn theme(self, theme: &str) -> Self {
+        self.param("theme", theme)
+    }
+
+    pub fn platform(self, platform: &str) -> Self {
+        self.param("platform", platform)
+    }
+
+    pub fn lang(self, lang: &str) -> Self {
+        self.param("lang", lang)
+    }
+
+    pub fn currency(self, currency: &str) -> Self {
+        self.param("currency", currency)
+    }
+
+    pub fn tenant(self, tenant: &str) -> Self {
+        self.param("tenant", tenant)
+    }
+
+    pub fn minify(self, minify: bool) -> Self {
+        self.param("minify", &minify.to_string())
+    }
+
+    // Any other query param, e.g. a component's required `org_id`
+    pub fn param(mut self, name: &str, value: &str) -> Self {
+        self.query.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    fn path(&self) -> String {
+        let query: Vec<_> = self
+            .query
+            .iter()
+            .map(|(name, value)| format!("{}={}", encode(name), encode(value)))
+            .collect();
+        format!("/api/{}?{}", encode(&self.component), query.join("&"))
+    }
+}
+
+// GET /api/:component/info
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ComponentInfo {
+    pub name: String,
+    pub table: String,
+    pub required_fields: Vec<String>,
+    pub slots: Vec<String>,
+    pub requires: Vec<String>,
+    pub static_record: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RenderClient {
+    // e.g. "https://ui.internal", without trailing slash
+    base_url: String,
+    timeout: Duration,
+}
+
+impl RenderClient {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    // Upper bound for one call, including connecting
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    // 🛰️ Rendered HTML of a component
+    pub async fn render(&self, request: &RenderRequest) -> Result<String, ClientError> {
+        self.get(&request.path()).await
+    }
+
+    pub async fn list_components(&self) -> Result<Vec<String>, ClientError> {
+        #[derive(Deserialize)]
+        struct Components {
+            components: Vec<String>,
+        }
+        let body = self.get("/api/components").await?;
+        let list: Components = parse_json(&body)?;
+        Ok(list.components)
+    }
+
+    pub async fn component_info(&self, component: &str) -> Result<ComponentInfo, ClientError> {
+        let body = self
+            .get(&format!("/api/{}/info", encode(component)))
+            .await?;
+        parse_json(&body)
+    }
+
+    async fn get(&self, path: &str) -> Result<String, ClientError> {
+        let url = format!("{}{}", self.base_url, path);
+        let (status, body) = match tokio::time::timeout(self.timeout, fetch_url(&url)).await {
+            Err(_) => return Err(ClientError::Transport(format!("{} timed out", url))),
+            Ok(Err(e)) => return Err(ClientError::Transport(e.to_string())),
+            Ok(Ok(response)) => response,
+        };
+        let body = String::from_utf8_lossy(&body).into_owned();
+        match status {
+            200..=299 => Ok(body),
+            _ => Err(response_error(status, body)),
+        }
+    }
+}
+
+fn parse_json<T: serde::de::DeserializeOwned>(body: &str) -> Result<T, ClientError> {
+    serde_json::from_str(body).map_err(|e| ClientError::Transport(format!("invalid JSON: {}", e)))
+}
+
+// The ComponentError a failed response was rendered from, when it says
+fn response_error(status: u16, body: String) -> ClientError {
+    // "Component 'x' not found" -> x
+    let quoted = |prefix: &str, suffix: &str| {
+        body.strip_prefix(prefix)?
+            .strip_suffix(suffix)
+            .map(str::to_string)
+    };
+    let err = match status {
+        404 => quoted("Component '", "' not found")
+            .map(ComponentError::ComponentNotFound)
+            .or_else(|| {
+                quoted("Record with id '", "' not found").map(ComponentError::RecordNotFound)
+            }),
+        400 => quoted("Component '", "' renders a record; an id is required")
+            .map(ComponentError::MissingRecordId)
+            .or_else(|| {
+                quoted("Invalid pagination cursor '", "'").map(ComponentError::InvalidCursor)
+            })
+            .or_else(|| {
+                let reason = body.strip_prefix("Unsupported format: ")?;
+                Some(ComponentError::UnsupportedFormat(reason.to_string()))
+            }),
+        422 => serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|json| serde_json::from_value(json.get("missing")?.clone()).ok())
+            .map(ComponentError::MissingParams),
+        _ => None,
+    };
+    match err {
+        Some(err) => ClientError::Render(err),
+        None => ClientError::Http(status, body),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::web::create_router;
+
+    #[tokio::test]
+    async fn test_client_renders_and_maps_errors() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, create_router()).await.unwrap() });
+        let client = RenderClient::new(&base);
+
+        let html = client
+            .render(&RenderRequest::new("user_card").id("1"))
+            .await
+            .unwrap();
+        assert!(html.contains("John Doe"), "{}", html);
+        assert!(
+            client
+                .list_components()
+                .await
+                .unwrap()
+                .contains(&"user_card".to_string())
+        );
+        assert_eq!(
+            client.component_info("user_card").await.unwrap().table,
+            "users"
+        );
+
+        let missing = client
+            .render(&RenderRequest::new("user_card").id("404"))
+            .await;
+        let Err(ClientError::Render(ComponentError::RecordNotFound(id))) = missing else {
+            panic!("expected a missing record, got {:?}", missing);
+        };
+        assert_eq!(id, "404");
+        let unknown = client.component_info("nope").await;
+        assert!(matches!(
+            unknown,
+            Err(ClientError::Render(ComponentError::ComponentNotFound(_)))
+        ));
+        let down = RenderClient::new("http://127.0.0.1:9")
+            .render(&RenderRequest::new("x"))
+            .await;
+        assert!(matches!(down, Err(ClientError::Transport(_))), "{:?}", down);
+    }
+}
@@ -0,0 +1,329 @@
+// src/codegen.rs - Generate build tooling config from themes, schemas and templates
+use crate::component_registry::ComponentTemplate;
+use crate::schema::SchemaRegistry;
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeMap, BTreeSet};
+
+// Theme token name prefixes and the Tailwind theme key they extend
+const TOKEN_CATEGORIES: [(&str, &str); 6] = [
+    ("color-", "colors"),
+    ("spacing-", "spacing"),
+    ("font-size-", "fontSize"),
+    ("border-radius-", "borderRadius"),
+    ("radius-", "borderRadius"),
+    ("shadow-", "boxShadow"),
+];
+
+// 🔍 Every utility class that can appear in rendered output: theme tag
+// classes, variant override/extend classes and literal template classes.
+pub fn referenced_classes(
+    registry: &SchemaRegistry,
+    components: &[&ComponentTemplate],
+) -> BTreeSet<String> {
+    let mut classes = BTreeSet::new();
+
+    for theme_name in registry.list_themes() {
+        if let Some(theme) = registry.get_theme(theme_name) {
+            for tag_classes in theme.tags.values() {
+                classes.extend(split_classes(tag_classes));
+            }
+        }
+    }
+
+    for table in registry.list_tables() {
+        let Some(schema) = registry.get_table(table) else {
+            continue;
+        };
+        for variant in schema
+            .variants
+            .values()
+            .flat_map(|variants| variants.values())
+        {
+            for class_list in [&variant.override_class, &variant.extend]
+                .into_iter()
+                .flatten()
+            {
+                classes.extend(split_classes(class_list));
+            }
+        }
+    }
+
+    for component in components {
+        for class_list in template_class_attributes(&component.template) {
+            classes.extend(split_classes(class_list));
+        }
+    }
+
+    classes
+}
+
+// 🚀 tailwind.config.js with a safelist of every referenced class and a theme
+// extension built from the design tokens. Tokens whose value differs between
+// themes are emitted as CSS variables (`var(--color-brand-500)`).
+pub fn tailwind_config(registry: &SchemaRegistry, components: &[&ComponentTemplate]) -> String {
+    let safelist: Vec<String> = referenced_classes(registry, components)
+        .into_iter()
+        .collect();
+
+    // token name -> distinct values across themes
+    let mut token_values: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for theme_name in registry.list_themes() {
+        if let Some(theme) = registry.get_theme(theme_name) {
+            for (token, value) in &theme.tokens {
+                token_values.entry(token).or_default().insert(value);
+            }
+        }
+    }
+
+    let mut extend: BTreeMap<&str, Map<String, Value>> = BTreeMap::new();
+    for (token, values) in &token_values {
+        let Some((prefix, theme_key)) = TOKEN_CATEGORIES
+            .iter()
+            .find(|(prefix, _)| token.starts_with(prefix))
+        else {
+            continue;
+        };
+        let value = match values.iter().next() {
+            Some(value) if values.len() == 1 => value.to_string(),
+            _ => format!("var(--{})", token),
+        };
+        extend
+            .entry(theme_key)
+            .or_default()
+            .insert(token[prefix.len()..].to_string(), Value::String(value));
+    }
+
+    let config = json!({
+        "content": ["./templates/**/*.html"],
+        "safelist": safelist,
+        "theme": { "extend": extend },
+    });
+    let body = serde_json::to_string_pretty(&config).unwrap_or_else(|_| "{}".to_string());
+
+    format!(
+        "// Generated by `codegen tailwind` from themes, schemas and component templates.\n// Do not edit by hand; re-run the command after changing them.\nmodule.exports = {};\n",
+        body
+    )
+}
+
+// 📦 A typed TypeScript client: one params interface per component (its id,
+// required params and the contexts of its table) and `renderComponent`,
+// which checks them at compile time and fetches GET /api/:component
+pub fn typescript_client(registry: &SchemaRegistry, components: &[&ComponentTemplate]) -> String {
+    // Empty tables in themes.toml (its `[themes]` header) aren't themes
+    let mut themes: Vec<_> = registry
+        .list_themes()
+        .into_iter()
+        .filter(|name| {
+            registry
+                .get_theme(name)
+                .is_some_and(|theme| !theme.tags.is_empty() || !theme.tokens.is_empty())
+        })
+        .collect();
+    themes.sort();
+    let mut components = components.to_vec();
+    components.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut ts = String::from(
+        "// Generated by `codegen client` from registered components and schemas.\n\
+         // Do not edit by hand; re-run the command after changing them.\n\n",
+    );
+    ts.push_str(&format!(
+        "export type Theme = {};\n\n",
+        string_union(&themes)
+    ));
+    ts.push_str(
+        "export interface RenderOptions {\n  theme?: Theme;\n  \
+         platform?: \"web\" | \"mobile\" | \"email\" | \"print\";\n  format?: string;\n  \
+         lang?: string;\n  timezone?: string;\n  currency?: string;\n  \
+         minify?: boolean;\n  jsonld?: boolean;\n}\n\n",
+    );
+
+    let mut entries = Vec::new();
+    for component in &components {
+        let schema = registry.get_table(&component.table);
+        let mut contexts: Vec<_> = schema
+            .map(|s| s.contexts.keys().collect())
+            .unwrap_or_default();
+        contexts.sort();
+        let single = component.static_record.is_some() || schema.is_some_and(|s| s.singleton);
+        let name = format!("{}Params", pascal_case(&component.name));
+
+        ts.push_str(&format!(
+            "export interface {} extends RenderOptions {{\n",
+            name
+        ));
+        ts.push_str(if single {
+            "  id?: string;\n"
+        } else {
+            "  id: string;\n"
+        });
+        ts.push_str(&format!("  context?: {};\n", string_union(&contexts)));
+        for param in &component.requires {
+            ts.push_str(&format!("  {}: string;\n", serde_json::json!(param)));
+        }
+        ts.push_str("}\n\n");
+        entries.push(format!(
+            "  {}: {};\n",
+            serde_json::json!(component.name),
+            name
+        ));
+    }
+
+    ts.push_str(&format!(
+        "export interface Components {{\n{}}}\n\n",
+        entries.concat()
+    ));
+    ts.push_str(CLIENT_RUNTIME);
+    ts
+}
+
+// Hand-written part of the generated client
+const CLIENT_RUNTIME: &str = r#"export type ComponentName = keyof Components;
+
+export class RenderError extends Error {
+  constructor(public status: number, message: string) {
+    super(message);
+    this.name = "RenderError";
+  }
+}
+
+let baseUrl = "";
+
+// Where the server is, e.g. "https://ui.example.com" (default: same origin)
+export function configure(options: { baseUrl: string }): void {
+  baseUrl = options.baseUrl.replace(/\/+$/, "");
+}
+
+export async function renderComponent<C extends ComponentName>(
+  component: C,
+  params: Components[C],
+  init?: RequestInit,
+): Promise<string> {
+  const query = new URLSearchParams();
+  for (const [key, value] of Object.entries(params)) {
+    if (value !== undefined && value !== null) query.set(key, String(value));
+  }
+  const url = `${baseUrl}/api/${encodeURIComponent(component)}?${query}`;
+  const response = await fetch(url, init);
+  if (!response.ok) throw new RenderError(response.status, await response.text());
+  return response.text();
+}
+"#;
+
+fn string_union<S: AsRef<str>>(values: &[S]) -> String {
+    match values {
+        [] => "string".to_string(),
+        values => values
+            .iter()
+            .map(|value| serde_json::json!(value.as_ref()).to_string())
+            .collect::<Vec<_>>()
+            .join(" | "),
+    }
+}
+
+fn pascal_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map_or(String::new(), |first| {
+                first.to_ascii_uppercase().to_string() + chars.as_str()
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn split_classes(class_list: &str) -> impl Iterator<Item = String> + '_ {
+    class_list.split_whitespace().map(str::to_string)
+}
+
+// Literal `class="..."` values of a template; classes containing
+// `{placeholders}` are dynamic and skipped.
+pub(crate) fn template_class_attributes(template: &str) -> Vec<&str> {
+    let mut values = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("class=\"") {
+        let value_start = start + "class=\"".len();
+        let Some(len) = rest[value_start..].find('"') else {
+            break;
+        };
+        let value = &rest[value_start..value_start + len];
+        if !value.contains('{') {
+            values.push(value);
+        }
+        rest = &rest[value_start + len..];
+    }
+
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tailwind_config_covers_themes_schemas_and_templates() {
+        let registry = SchemaRegistry::load_embedded();
+        let component = ComponentTemplate::builder()
+            .name("card")
+            .table("users")
+            .template_str("<div class=\"p-4 shadow-lg\"><span class=\"{tone}\">{name}</span></div>")
+            .build()
+            .unwrap();
+
+        let classes = referenced_classes(&registry, &[&component]);
+        assert!(classes.contains("text-4xl")); // theme
+        assert!(classes.contains("rounded-full")); // variant override
+        assert!(classes.contains("shadow-lg")); // template
+        assert!(!classes.iter().any(|class| class.contains('{')));
+
+        let config = tailwind_config(&registry, &[&component]);
+        assert!(config.starts_with("// Generated"));
+        assert!(config.contains("\"shadow-lg\""));
+        assert!(config.contains("module.exports = {"));
+    }
+
+    #[test]
+    fn test_typescript_client_types_components() {
+        let registry = SchemaRegistry::load_embedded();
+        let card = ComponentTemplate::builder()
+            .name("user_card")
+            .table("users")
+            .template_str("<div>{name}</div>")
+            .requires("org_id")
+            .build()
+            .unwrap();
+        let footer = ComponentTemplate::builder()
+            .name("site_footer")
+            .table("users")
+            .template_str("<footer>{name}</footer>")
+            .static_record("1")
+            .build()
+            .unwrap();
+
+        let client = typescript_client(&registry, &[&footer, &card]);
+        let card_params = client
+            .split("export interface UserCardParams")
+            .nth(1)
+            .unwrap();
+        let card_params = &card_params[..card_params.find('}').unwrap()];
+        assert!(card_params.contains("  id: string;\n"), "{}", card_params);
+        assert!(
+            card_params.contains(r#"context?: "card" | "#),
+            "{}",
+            card_params
+        );
+        assert!(
+            card_params.contains(r#"  "org_id": string;"#),
+            "{}",
+            card_params
+        );
+        let footer_params =
+            "export interface SiteFooterParams extends RenderOptions {\n  id?: string;";
+        assert!(client.contains(footer_params), "{}", client);
+        assert!(client.contains(r#"  "user_card": UserCardParams;"#));
+        assert!(client.contains("export async function renderComponent<C extends ComponentName>("));
+    }
+}
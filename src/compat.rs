@@ -0,0 +1,306 @@
+// src/compat.rs - Schema diffing and breaking-change detection against consumers
+use crate::component_registry::ComponentTemplate;
+use crate::schema::TableSchema;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+// Structural differences between two versions of a table schema
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct SchemaDiff {
+    pub added_fields: Vec<String>,
+    pub removed_fields: Vec<String>,
+    pub added_variants: Vec<String>,   // "field.variant"
+    pub removed_variants: Vec<String>, // "field.variant"
+    pub added_contexts: Vec<String>,
+    pub removed_contexts: Vec<String>,
+    pub changed_contexts: Vec<String>,
+}
+
+impl SchemaDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_fields.is_empty()
+            && self.removed_fields.is_empty()
+            && self.added_variants.is_empty()
+            && self.removed_variants.is_empty()
+            && self.added_contexts.is_empty()
+            && self.removed_contexts.is_empty()
+            && self.changed_contexts.is_empty()
+    }
+}
+
+// 🔀 Compare two versions of a table schema
+pub fn diff_schemas(old: &TableSchema, new: &TableSchema) -> SchemaDiff {
+    let old_fields: BTreeSet<_> = old.variants.keys().collect();
+    let new_fields: BTreeSet<_> = new.variants.keys().collect();
+    let old_variants = variant_keys(old);
+    let new_variants = variant_keys(new);
+    let old_contexts: BTreeSet<_> = old.contexts.keys().collect();
+    let new_contexts: BTreeSet<_> = new.contexts.keys().collect();
+
+    SchemaDiff {
+        added_fields: new_fields
+            .difference(&old_fields)
+            .map(|f| f.to_string())
+            .collect(),
+        removed_fields: old_fields
+            .difference(&new_fields)
+            .map(|f| f.to_string())
+            .collect(),
+        added_variants: new_variants.difference(&old_variants).cloned().collect(),
+        removed_variants: old_variants.difference(&new_variants).cloned().collect(),
+        added_contexts: new_contexts
+            .difference(&old_contexts)
+            .map(|c| c.to_string())
+            .collect(),
+        removed_contexts: old_contexts
+            .difference(&new_contexts)
+            .map(|c| c.to_string())
+            .collect(),
+        changed_contexts: old_contexts
+            .intersection(&new_contexts)
+            .filter(|name| {
+                let (before, after) = (&old.contexts[**name], &new.contexts[**name]);
+                before.inherits != after.inherits || before.fields != after.fields
+            })
+            .map(|c| c.to_string())
+            .collect(),
+    }
+}
+
+fn variant_keys(schema: &TableSchema) -> BTreeSet<String> {
+    schema
+        .variants
+        .iter()
+        .flat_map(|(field, variants)| variants.keys().map(move |v| format!("{}.{}", field, v)))
+        .collect()
+}
+
+// Dependency graph: which schema entries point at each "field.variant"
+pub fn variant_references(schema: &TableSchema) -> BTreeMap<String, Vec<String>> {
+    let mut references: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for (context_name, context) in &schema.contexts {
+        for (field, variant) in &context.fields {
+            references
+                .entry(format!("{}.{}", field, variant))
+                .or_default()
+                .push(format!("contexts.{}", context_name));
+        }
+    }
+    for (field, variant) in schema.defaults.iter().flatten() {
+        references
+            .entry(format!("{}.{}", field, variant))
+            .or_default()
+            .push("defaults".to_string());
+    }
+
+    for sources in references.values_mut() {
+        sources.sort();
+    }
+    references
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Severity {
+    Breaking,
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompatIssue {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl CompatIssue {
+    fn breaking(message: String) -> Self {
+        Self {
+            severity: Severity::Breaking,
+            message,
+        }
+    }
+
+    fn warning(message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message,
+        }
+    }
+}
+
+// 🚦 Flag changes that would break components rendering `table` or clients of
+// the published API (removed fields, dangling variant references, removed contexts)
+pub fn check_compat(
+    table: &str,
+    old: &TableSchema,
+    new: &TableSchema,
+    components: &[&ComponentTemplate],
+) -> Vec<CompatIssue> {
+    let diff = diff_schemas(old, new);
+    let mut issues = Vec::new();
+
+    for field in &diff.removed_fields {
+        let users: Vec<_> = components
+            .iter()
+            .filter(|c| c.table == table && c.required_fields.contains(field))
+            .map(|c| c.name.as_str())
+            .collect();
+
+        if users.is_empty() {
+            issues.push(CompatIssue::warning(format!(
+                "field '{}' was removed",
+                field
+            )));
+        } else {
+            issues.push(CompatIssue::breaking(format!(
+                "field '{}' was removed but is rendered by component(s): {}",
+                field,
+                users.join(", ")
+            )));
+        }
+    }
+
+    let new_references = variant_references(new);
+    for variant in &diff.removed_variants {
+        match new_references.get(variant) {
+            Some(sources) => issues.push(CompatIssue::breaking(format!(
+                "variant '{}' was removed or renamed but is still referenced by {}",
+                variant,
+                sources.join(", ")
+            ))),
+            None => issues.push(CompatIssue::warning(format!(
+                "variant '{}' was removed",
+                variant
+            ))),
+        }
+    }
+
+    for context in &diff.removed_contexts {
+        issues.push(CompatIssue::breaking(format!(
+            "context '{}' was removed; requests using ?context={} will change output",
+            context, context
+        )));
+    }
+
+    for (name, context) in &new.contexts {
+        if let Some(parent) = &context.inherits
+            && !parent.contains('.')
+            && !new.contexts.contains_key(parent)
+        {
+            issues.push(CompatIssue::breaking(format!(
+                "context '{}' inherits from missing context '{}'",
+                name, parent
+            )));
+        }
+    }
+
+    for context in &diff.changed_contexts {
+        issues.push(CompatIssue::warning(format!(
+            "context '{}' changed its field mapping",
+            context
+        )));
+    }
+
+    // Versioned schemas promise a bump with every breaking change
+    if let Some(version) = &old.version
+        && old.version == new.version
+        && has_breaking(&issues)
+    {
+        issues.push(CompatIssue::warning(format!(
+            "breaking changes without a version bump (still '{}')",
+            version
+        )));
+    }
+
+    issues
+}
+
+// Two versions of a table schema side by side, for API consumers
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaComparison {
+    pub table: String,
+    pub from_version: Option<String>,
+    pub to_version: Option<String>,
+    pub diff: SchemaDiff,
+    pub issues: Vec<CompatIssue>,
+    pub breaking: bool,
+}
+
+// ⚖️ Diff and compatibility issues between two versions of `table`
+pub fn compare_versions(
+    table: &str,
+    old: &TableSchema,
+    new: &TableSchema,
+    components: &[&ComponentTemplate],
+) -> SchemaComparison {
+    let issues = check_compat(table, old, new, components);
+    SchemaComparison {
+        table: table.to_string(),
+        from_version: old.version.clone(),
+        to_version: new.version.clone(),
+        diff: diff_schemas(old, new),
+        breaking: has_breaking(&issues),
+        issues,
+    }
+}
+
+pub fn has_breaking(issues: &[CompatIssue]) -> bool {
+    issues
+        .iter()
+        .any(|issue| issue.severity == Severity::Breaking)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component_registry::component_registry;
+
+    const USERS: &str = include_str!("../schemas/users/users.toml");
+
+    #[test]
+    fn test_removed_variant_still_referenced_is_breaking() {
+        let old = TableSchema::from_toml_str(USERS).unwrap();
+        let new = TableSchema::from_toml_str(&USERS.replace("\nh2 = {", "\nheading = {")).unwrap();
+
+        let diff = diff_schemas(&old, &new);
+        assert_eq!(diff.removed_variants, vec!["name.h2".to_string()]);
+
+        let components: Vec<_> = vec![component_registry().get_component("user_card").unwrap()];
+        let issues = check_compat("users", &old, &new, &components);
+        assert!(has_breaking(&issues));
+        assert!(issues[0].message.contains("contexts.card"));
+    }
+
+    #[test]
+    fn test_breaking_changes_expect_a_version_bump() {
+        let old = TableSchema::from_toml_str(&format!("version = \"1\"\n{}", USERS)).unwrap();
+        let mut new = old.clone();
+        new.contexts.remove("list");
+
+        let comparison = compare_versions("users", &old, &new, &[]);
+        assert!(comparison.breaking);
+        assert_eq!(comparison.diff.removed_contexts, vec!["list".to_string()]);
+        let last = comparison.issues.last().unwrap();
+        assert_eq!(
+            last.message,
+            "breaking changes without a version bump (still '1')"
+        );
+
+        new.version = Some("2".to_string());
+        let comparison = compare_versions("users", &old, &new, &[]);
+        assert_eq!(comparison.to_version.as_deref(), Some("2"));
+        assert!(
+            comparison
+                .issues
+                .iter()
+                .all(|issue| !issue.message.contains("bump"))
+        );
+    }
+
+    #[test]
+    fn test_identical_schemas_are_compatible() {
+        let schema = TableSchema::from_toml_str(USERS).unwrap();
+        assert!(diff_schemas(&schema, &schema).is_empty());
+        assert!(check_compat("users", &schema, &schema, &[]).is_empty());
+    }
+}
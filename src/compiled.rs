@@ -0,0 +1,371 @@
+// src/compiled.rs - Binary registry bundle for fast production startup
+//
+// The schema types use `#[serde(flatten)]`, which non-self-describing formats
+// like bincode cannot decode, so the bundle is made of plain mirror structs.
+// Contexts are stored pre-resolved and component templates already compiled
+// (includes inlined, trim markers applied, placeholders extracted).
+use crate::component_registry::{ComponentTemplate, ResponsiveContext};
+use crate::content_scan::ScanAction;
+use crate::field_types::{EnumField, FieldRules, FieldType};
+use crate::forms::Normalizer;
+use crate::schema::{
+    Context, EventMapping, FieldVariant, JsonLdMapping, MissingRequired, MockRecord, Overflow,
+    PushMapping, Reference, Relation, Section, TableSchema, Theme,
+};
+use crate::search::SearchMapping;
+use crate::tree::{ThreadMapping, TreeMapping};
+use crate::views::SavedView;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Bumped whenever the bundle layout changes; older bundles are rejected
+pub const COMPILED_FORMAT_VERSION: u32 = 33;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CompiledBundle {
+    pub format_version: u32,
+    pub current_theme: String,
+    pub themes: Vec<(String, CompiledTheme)>,
+    pub tables: Vec<(String, CompiledTable)>,
+    // tenant -> its overlaid tables
+    pub tenants: Vec<(String, Vec<(String, CompiledTable)>)>,
+    pub components: Vec<CompiledComponent>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CompiledTheme {
+    tokens: HashMap<String, String>,
+    tags: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CompiledVariant {
+    base: String,
+    override_class: Option<String>,
+    extend: Option<String>,
+    attrs: Option<HashMap<String, String>>,
+    kind: Option<String>,
+    options: Option<HashMap<String, String>>,
+    max_length: Option<usize>,
+    overflow: Option<Overflow>,
+    field_type: Option<FieldType>,
+    deprecated: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CompiledContext {
+    inherits: Option<String>,
+    deprecated: Option<String>,
+    sections: HashMap<String, Section>,
+    required: Vec<String>,
+    fields: HashMap<String, String>,
+}
+
+type CompiledEnum = (
+    Vec<String>,
+    HashMap<String, String>,
+    HashMap<String, String>,
+);
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CompiledTable {
+    version: Option<String>,
+    singleton: bool,
+    variants: HashMap<String, HashMap<String, CompiledVariant>>,
+    defaults: Option<HashMap<String, String>>,
+    contexts: HashMap<String, CompiledContext>,
+    locales: HashMap<String, HashMap<String, HashMap<String, String>>>,
+    fallbacks: HashMap<String, String>,
+    // context -> field -> variant, with inheritance and defaults applied
+    resolved_contexts: HashMap<String, HashMap<String, String>>,
+    mock_data: Option<Vec<HashMap<String, String>>>,
+    relations: HashMap<String, Relation>,
+    references: HashMap<String, Reference>,
+    tree: Option<TreeMapping>,
+    threads: HashMap<String, ThreadMapping>,
+    views: HashMap<String, SavedView>,
+    search: Option<SearchMapping>,
+    aliases: HashMap<String, String>,
+    computed: HashMap<String, String>,
+    value_variants: HashMap<String, HashMap<String, String>>,
+    // field -> (values, labels, classes); EnumField reads two forms untagged
+    enums: HashMap<String, CompiledEnum>,
+    types: HashMap<String, FieldType>,
+    rules: HashMap<String, FieldRules>,
+    writable_by: HashMap<String, Vec<String>>,
+    normalize: HashMap<String, Vec<Normalizer>>,
+    budgets: HashMap<String, f64>,
+    short: HashMap<String, String>,
+    push: HashMap<String, PushMapping>,
+    event: Option<EventMapping>,
+    jsonld: HashMap<String, JsonLdMapping>,
+    content_scan: Option<ScanAction>,
+    missing_required: Option<MissingRequired>,
+    source: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CompiledComponent {
+    name: String,
+    table: String,
+    template: String,
+    required_fields: Vec<String>,
+    slots: Vec<String>,
+    requires: Vec<String>,
+    static_record: Option<String>,
+    cache_ttl: Option<u64>,
+    budget_ms: Option<f64>,
+    // (context, class) pairs
+    responsive: Vec<(String, String)>,
+}
+
+impl From<&Theme> for CompiledTheme {
+    fn from(theme: &Theme) -> Self {
+        Self {
+            tokens: theme.tokens.clone(),
+            tags: theme.tags.clone(),
+        }
+    }
+}
+
+impl From<CompiledTheme> for Theme {
+    fn from(theme: CompiledTheme) -> Self {
+        Self {
+            tokens: theme.tokens,
+            tags: theme.tags,
+        }
+    }
+}
+
+impl From<&FieldVariant> for CompiledVariant {
+    fn from(variant: &FieldVariant) -> Self {
+        Self {
+            base: variant.base.clone(),
+            override_class: variant.override_class.clone(),
+            extend: variant.extend.clone(),
+            attrs: variant.attrs.clone(),
+            kind: variant.kind.clone(),
+            options: variant.options.clone(),
+            max_length: variant.max_length,
+            overflow: variant.overflow,
+            field_type: variant.field_type,
+            deprecated: variant.deprecated.clone(),
+        }
+    }
+}
+
+impl From<CompiledVariant> for FieldVariant {
+    fn from(variant: CompiledVariant) -> Self {
+        Self {
+            base: variant.base,
+            // Extends chains were resolved before compiling
+            extends: None,
+            override_class: variant.override_class,
+            extend: variant.extend,
+            attrs: variant.attrs,
+            kind: variant.kind,
+            options: variant.options,
+            max_length: variant.max_length,
+            overflow: variant.overflow,
+            field_type: variant.field_type,
+            deprecated: variant.deprecated,
+        }
+    }
+}
+
+impl From<&TableSchema> for CompiledTable {
+    fn from(schema: &TableSchema) -> Self {
+        Self {
+            singleton: schema.singleton,
+            variants: schema
+                .variants
+                .iter()
+                .map(|(field, variants)| {
+                    let variants = variants
+                        .iter()
+                        .map(|(name, variant)| (name.clone(), variant.into()))
+                        .collect();
+                    (field.clone(), variants)
+                })
+                .collect(),
+            defaults: schema.defaults.clone(),
+            contexts: schema
+                .contexts
+                .iter()
+                .map(|(name, context)| {
+                    let context = CompiledContext {
+                        inherits: context.inherits.clone(),
+                        deprecated: context.deprecated.clone(),
+                        sections: context.sections.clone(),
+                        required: context.required.clone(),
+                        fields: context.fields.clone(),
+                    };
+                    (name.clone(), context)
+                })
+                .collect(),
+            locales: schema.locales.clone(),
+            fallbacks: schema.fallbacks.clone(),
+            resolved_contexts: schema.resolve_contexts(),
+            mock_data: schema
+                .mock_data
+                .as_ref()
+                .map(|records| records.iter().map(|record| record.fields.clone()).collect()),
+            relations: schema.relations.clone(),
+            references: schema.references.clone(),
+            tree: schema.tree.clone(),
+            threads: schema.threads.clone(),
+            views: schema.views.clone(),
+            version: schema.version.clone(),
+            search: schema.search.clone(),
+            aliases: schema.aliases.clone(),
+            computed: schema.computed.clone(),
+            value_variants: schema.value_variants.clone(),
+            enums: schema
+                .enums
+                .iter()
+                .map(|(field, spec)| {
+                    let spec = (
+                        spec.values.clone(),
+                        spec.labels.clone(),
+                        spec.classes.clone(),
+                    );
+                    (field.clone(), spec)
+                })
+                .collect(),
+            types: schema.types.clone(),
+            rules: schema.rules.clone(),
+            writable_by: schema.writable_by.clone(),
+            normalize: schema.normalize.clone(),
+            budgets: schema.budgets.clone(),
+            short: schema.short.clone(),
+            push: schema.push.clone(),
+            event: schema.event.clone(),
+            jsonld: schema.jsonld.clone(),
+            content_scan: schema.content_scan,
+            missing_required: schema.missing_required,
+            source: schema.source.clone(),
+        }
+    }
+}
+
+impl From<CompiledTable> for TableSchema {
+    fn from(table: CompiledTable) -> Self {
+        Self {
+            // Includes were merged before compiling
+            include: Vec::new(),
+            singleton: table.singleton,
+            variants: table
+                .variants
+                .into_iter()
+                .map(|(field, variants)| {
+                    let variants = variants
+                        .into_iter()
+                        .map(|(name, variant)| (name, variant.into()))
+                        .collect();
+                    (field, variants)
+                })
+                .collect(),
+            defaults: table.defaults,
+            contexts: table
+                .contexts
+                .into_iter()
+                .map(|(name, context)| {
+                    let context = Context {
+                        inherits: context.inherits,
+                        deprecated: context.deprecated,
+                        sections: context.sections,
+                        required: context.required,
+                        fields: context.fields,
+                    };
+                    (name, context)
+                })
+                .collect(),
+            locales: table.locales,
+            fallbacks: table.fallbacks,
+            mock_data: table.mock_data.map(|records| {
+                records
+                    .into_iter()
+                    .map(|fields| MockRecord { fields })
+                    .collect()
+            }),
+            relations: table.relations,
+            references: table.references,
+            tree: table.tree,
+            threads: table.threads,
+            views: table.views,
+            version: table.version,
+            search: table.search,
+            aliases: table.aliases,
+            computed: table.computed,
+            value_variants: table.value_variants,
+            enums: table
+                .enums
+                .into_iter()
+                .map(|(field, (values, labels, classes))| {
+                    let spec = EnumField {
+                        values,
+                        labels,
+                        classes,
+                    };
+                    (field, spec)
+                })
+                .collect(),
+            types: table.types,
+            rules: table.rules,
+            writable_by: table.writable_by,
+            normalize: table.normalize,
+            budgets: table.budgets,
+            short: table.short,
+            push: table.push,
+            event: table.event,
+            jsonld: table.jsonld,
+            content_scan: table.content_scan,
+            missing_required: table.missing_required,
+            source: table.source,
+            resolved_contexts: table.resolved_contexts,
+            shared_contexts: HashMap::new(),
+        }
+    }
+}
+
+impl From<&ComponentTemplate> for CompiledComponent {
+    fn from(component: &ComponentTemplate) -> Self {
+        Self {
+            name: component.name.clone(),
+            table: component.table.clone(),
+            template: component.template.clone(),
+            required_fields: component.required_fields.clone(),
+            slots: component.slots.clone(),
+            requires: component.requires.clone(),
+            static_record: component.static_record.clone(),
+            cache_ttl: component.cache_ttl,
+            budget_ms: component.budget_ms,
+            responsive: component
+                .responsive
+                .iter()
+                .map(|responsive| (responsive.context.clone(), responsive.class.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl From<CompiledComponent> for ComponentTemplate {
+    fn from(component: CompiledComponent) -> Self {
+        Self {
+            name: component.name,
+            table: component.table,
+            template: component.template,
+            required_fields: component.required_fields,
+            slots: component.slots,
+            requires: component.requires,
+            static_record: component.static_record,
+            cache_ttl: component.cache_ttl,
+            budget_ms: component.budget_ms,
+            responsive: component
+                .responsive
+                .into_iter()
+                .map(|(context, class)| ResponsiveContext { context, class })
+                .collect(),
+        }
+    }
+}
@@ -1,28 +1,242 @@
 // src/component_registry.rs - New file for component discovery
-use crate::schema::{SchemaRegistry, registry};
+use crate::analytics::usage_tracker;
+use crate::budget::budget_tracker;
+use crate::cache::{CacheKey, RenderCache};
+use crate::chat_format::{ChatFormat, chat_message};
+use crate::config::{WarmupEntry, config};
+use crate::content_scan::{Finding, ScanAction, scan_record};
+use crate::currency::currency_converter;
+use crate::cursor::Cursor;
+use crate::data_source::{DataError, DataSource, default_data_source, in_scope};
+use crate::formatter::minify_html;
+use crate::http_source::encode;
+use crate::ics::calendar;
+use crate::jsonld::jsonld_script;
+use crate::schema::{
+    Deprecation, FieldRenderOptions, MissingRequired, PRINT_CONTEXT, SchemaRegistry, TableSchema,
+    registry,
+};
+use crate::settings::singleton_record;
+use crate::short_text::{PushPayload, push_payload, short_line};
+use crate::tree::{TreeOptions, render_tree};
+use crate::variant_kinds::escape_attr;
+use dashmap::DashSet;
+use futures::stream::{self, Stream, StreamExt};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::path::{Component, Path};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone)]
 pub struct ComponentTemplate {
     pub name: String,
-    pub table: String,                // which table this component belongs to
-    pub template: String,             // HTML template with {field} placeholders
-    pub required_fields: Vec<String>, // fields needed for this component
+    pub table: String,                      // which table this component belongs to
+    pub template: String,                   // HTML template with {field} placeholders
+    pub required_fields: Vec<String>,       // fields needed for this component
+    pub slots: Vec<String>,                 // placeholders filled by the caller, not the record
+    pub requires: Vec<String>,              // request params every render needs besides `id`
+    pub static_record: Option<String>,      // record rendered on every request; no `id` needed
+    pub cache_ttl: Option<u64>,             // per-component cache lifetime in seconds
+    pub budget_ms: Option<f64>,             // render-time budget, excluding data fetching
+    pub responsive: Vec<ResponsiveContext>, // renders emitted when no context is requested
+}
+
+// One render of a responsive component, shown only where `class` lets it
+// (e.g. context "list" with "md:hidden", "card" with "hidden md:block")
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponsiveContext {
+    pub context: String,
+    pub class: String,
+}
+
+impl ComponentTemplate {
+    // 🏗️ Start defining a component in Rust code
+    pub fn builder() -> ComponentTemplateBuilder {
+        ComponentTemplateBuilder::default()
+    }
+}
+
+// Fluent builder for ComponentTemplate, used by discovery and embedding apps
+#[derive(Debug, Default, Clone)]
+pub struct ComponentTemplateBuilder {
+    name: Option<String>,
+    table: Option<String>,
+    template: Option<String>,
+    slots: Vec<String>,
+    requires: Vec<String>,
+    static_record: Option<String>,
+    cache_ttl: Option<u64>,
+    budget_ms: Option<f64>,
+    responsive: Vec<ResponsiveContext>,
+}
+
+impl ComponentTemplateBuilder {
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn table(mut self, table: &str) -> Self {
+        self.table = Some(table.to_string());
+        self
+    }
+
+    pub fn template_str(mut self, template: &str) -> Self {
+        self.template = Some(template.to_string());
+        self
+    }
+
+    // Declare a {slot} placeholder that callers fill via RenderParams::slots
+    pub fn slot(mut self, slot: &str) -> Self {
+        self.slots.push(slot.to_string());
+        self
+    }
+
+    // Require a request param (e.g. "org_id") on every render. The value scopes
+    // the record lookup and fills a `{org_id}` placeholder, HTML-escaped.
+    pub fn requires(mut self, param: &str) -> Self {
+        self.requires.push(param.to_string());
+        self
+    }
+
+    // Render record `id` of the table (e.g. a settings row) whatever the request
+    // asks for, for footers and banners; SITE_SETTINGS fills in missing fields
+    pub fn static_record(mut self, id: &str) -> Self {
+        self.static_record = Some(id.to_string());
+        self
+    }
+
+    // Cache renders of this component for `seconds` instead of the global TTL
+    pub fn cache_ttl(mut self, seconds: u64) -> Self {
+        self.cache_ttl = Some(seconds);
+        self
+    }
+
+    // Flag renders of this component slower than `ms` milliseconds
+    pub fn budget_ms(mut self, ms: f64) -> Self {
+        self.budget_ms = Some(ms);
+        self
+    }
+
+    // Also render in `context`, wrapped in a container with the CSS classes
+    // `class`; every declared context is emitted unless the caller picks one
+    pub fn responsive(mut self, context: &str, class: &str) -> Self {
+        self.responsive.push(ResponsiveContext {
+            context: context.to_string(),
+            class: class.to_string(),
+        });
+        self
+    }
+
+    pub fn build(self) -> Result<ComponentTemplate, ComponentError> {
+        let name = self.name.filter(|name| !name.is_empty()).ok_or_else(|| {
+            ComponentError::InvalidDefinition("component name is required".to_string())
+        })?;
+        let table = self
+            .table
+            .filter(|table| !table.is_empty())
+            .ok_or_else(|| {
+                ComponentError::InvalidDefinition(format!("component '{}' has no table", name))
+            })?;
+        let template = self.template.ok_or_else(|| {
+            ComponentError::InvalidDefinition(format!("component '{}' has no template", name))
+        })?;
+        let template = resolve_includes(&template, &config().template_dir, &mut Vec::new())?;
+        let template = apply_trim_markers(&template);
+
+        let placeholders = extract_field_placeholders(&template);
+        if let Some(slot) = self.slots.iter().find(|slot| !placeholders.contains(slot)) {
+            return Err(ComponentError::InvalidDefinition(format!(
+                "slot '{}' is not used in the template of component '{}'",
+                slot, name
+            )));
+        }
+
+        if let Some(param) = self
+            .requires
+            .iter()
+            .find(|param| param.is_empty() || *param == "id" || self.slots.contains(param))
+        {
+            return Err(ComponentError::InvalidDefinition(format!(
+                "component '{}' cannot require parameter '{}'",
+                name, param
+            )));
+        }
+
+        if self.static_record.as_deref() == Some("") {
+            return Err(ComponentError::InvalidDefinition(format!(
+                "component '{}' has an empty static record id",
+                name
+            )));
+        }
+
+        let required_fields = placeholders
+            .into_iter()
+            .filter(|field| !self.slots.contains(field) && !self.requires.contains(field))
+            .collect();
+
+        Ok(ComponentTemplate {
+            name,
+            table,
+            template,
+            required_fields,
+            slots: self.slots,
+            requires: self.requires,
+            static_record: self.static_record,
+            cache_ttl: self.cache_ttl,
+            budget_ms: self.budget_ms,
+            responsive: self.responsive,
+        })
+    }
 }
 // Add this struct before ComponentRegistry:
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct RenderParams<'a> {
     pub context: Option<&'a str>,
     pub theme: Option<&'a str>,
     pub platform: Option<&'a str>,
     pub format: Option<&'a str>,
     pub lang: Option<&'a str>,
+    // IANA timezone name, e.g. "Europe/Berlin"
+    pub timezone: Option<&'a str>,
+    // Viewer's currency (ISO code) for amounts shown with display_currency = "viewer"
+    pub currency: Option<&'a str>,
+    pub slots: Option<&'a HashMap<String, String>>,
+    // Request params; the component's `requires` are looked up here
+    pub args: Option<&'a HashMap<String, String>>,
+    pub minify: bool,
+    // Append a schema.org JSON-LD block when the table maps this component
+    pub jsonld: bool,
+    // Annotate output with data-sui-src attributes (development only)
+    pub debug: bool,
+    // Render even when a cached copy is fresh, replacing it (scheduled refreshes)
+    pub refresh: bool,
+    // Customer whose schema overlays apply (see SchemaRegistry::for_tenant)
+    pub tenant: Option<&'a str>,
+}
+
+// A finished render; `degraded` is set when the data source was unavailable
+// and the HTML comes from a stale cache entry or mock data instead
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOutput {
+    pub html: String,
+    pub degraded: bool,
+    // Fields the content scan flagged (tables with content_scan = "flag")
+    pub flagged: Vec<String>,
+    // Deprecated contexts and variants the render went through
+    pub deprecations: Vec<Deprecation>,
+    // Required fields the record lacked (tables with missing_required = "warn")
+    pub missing: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct ComponentRegistry {
     components: HashMap<String, ComponentTemplate>,
-    schema_registry: &'static SchemaRegistry,
+    max_concurrent_renders: usize,
+    cache: Arc<RenderCache>,
+    cache_enabled: bool,
+    data_source: Arc<dyn DataSource>,
 }
 impl Default for ComponentRegistry {
     fn default() -> Self {
@@ -33,7 +247,14 @@ impl ComponentRegistry {
     pub fn new() -> Self {
         let mut registry = Self {
             components: HashMap::new(),
-            schema_registry: registry(),
+            max_concurrent_renders: config().max_concurrent_renders,
+            cache: Arc::new(
+                RenderCache::new(Duration::from_secs(config().render_cache_ttl_secs))
+                    .with_capacity(config().render_cache_capacity)
+                    .keep_stale_for(Duration::from_secs(config().render_cache_stale_secs)),
+            ),
+            cache_enabled: true,
+            data_source: default_data_source(),
         };
 
         // Auto-discover all components from schema files
@@ -43,6 +264,18 @@ impl ComponentRegistry {
 
     // 🔍 Auto-discover components from SQL files
     fn discover_components(&mut self) {
+        // Templates from a compiled bundle are already resolved and validated
+        let compiled = registry().compiled_components().to_vec();
+        if !compiled.is_empty() {
+            for component in compiled {
+                let name = component.name.clone();
+                if let Err(e) = self.register(component) {
+                    eprintln!("Failed to load component {}: {}", name, e);
+                }
+            }
+            return;
+        }
+
         // For now, hardcoded discovery - later we'll scan directories
         let component_definitions = [
             (
@@ -64,114 +297,1124 @@ impl ComponentRegistry {
             // ("product_card", "products", template),
         ];
 
+        let config = config();
         for (name, table, template) in component_definitions {
-            let required_fields = self.extract_field_placeholders(template);
-
-            self.components.insert(
-                name.to_string(),
-                ComponentTemplate {
-                    name: name.to_string(),
-                    table: table.to_string(),
-                    template: template.to_string(),
-                    required_fields,
-                },
-            );
+            let mut builder = ComponentTemplate::builder()
+                .name(name)
+                .table(table)
+                .template_str(template);
+            let budget_ms = config
+                .component_budgets
+                .get(name)
+                .copied()
+                .unwrap_or(config.component_budget_ms);
+            if budget_ms > 0.0 {
+                builder = builder.budget_ms(budget_ms);
+            }
+            let component = builder
+                .build()
+                .and_then(|component| self.register(component));
+
+            if let Err(e) = component {
+                eprintln!("Failed to load component {}: {}", name, e);
+            }
         }
     }
 
-    // Extract {field} placeholders from template
-    fn extract_field_placeholders(&self, template: &str) -> Vec<String> {
-        let mut fields = Vec::new();
-        let mut chars = template.chars().peekable();
+    // ➕ Register (or replace) a component defined in Rust code
+    pub fn register(&mut self, component: ComponentTemplate) -> Result<(), ComponentError> {
+        let schema_registry = registry();
+        let Some(schema) = schema_registry.get_table(&component.table) else {
+            return Err(ComponentError::InvalidDefinition(format!(
+                "component '{}' references unknown table '{}'",
+                component.name, component.table
+            )));
+        };
+        if let Some(unknown) = component
+            .responsive
+            .iter()
+            .find(|responsive| !schema.contexts.contains_key(&responsive.context))
+        {
+            return Err(ComponentError::InvalidDefinition(format!(
+                "component '{}' uses unknown context '{}'",
+                component.name, unknown.context
+            )));
+        }
 
-        while let Some(ch) = chars.next() {
-            if ch == '{' {
-                let mut field = String::new();
-                while let Some(&next_ch) = chars.peek() {
-                    if next_ch == '}' {
-                        chars.next(); // consume '}'
-                        break;
+        self.components.insert(component.name.clone(), component);
+        Ok(())
+    }
+
+    // 🎯 Main API: Render component with parameters
+    pub async fn render_component(
+        &self,
+        component_name: &str,
+        record_id: &str,
+        params: RenderParams<'_>,
+    ) -> Result<String, ComponentError> {
+        self.render_component_output(component_name, record_id, params)
+            .await
+            .map(|output| output.html)
+    }
+
+    // Same as render_component, also reporting whether fallback data was used
+    pub async fn render_component_output(
+        &self,
+        component_name: &str,
+        record_id: &str,
+        params: RenderParams<'_>,
+    ) -> Result<RenderOutput, ComponentError> {
+        // 1. Find component template
+        let component =
+            self.components
+                .get(component_name)
+                .ok_or(ComponentError::ComponentNotFound(
+                    component_name.to_string(),
+                ))?;
+
+        let scope = Self::required_args(component, params.args)?;
+        let record_id = Self::record_id(component, record_id)?;
+
+        let minify = params.minify || config().minify_html;
+        let schema_registry = tenant_registry(params.tenant);
+        // Print renders use the table's "print" context unless one was asked for
+        let print = params.platform == Some("print");
+        let mut params = params;
+        if print
+            && params.context.is_none()
+            && schema_registry
+                .get_table(&component.table)
+                .is_some_and(|schema| schema.contexts.contains_key(PRINT_CONTEXT))
+        {
+            params.context = Some(PRINT_CONTEXT);
+        }
+        // Paper has no breakpoints, so print renders a single layout
+        let responsive = !print && params.context.is_none() && !component.responsive.is_empty();
+        // Only languages the table restyles get cache entries of their own
+        let locale = params.lang.and_then(|lang| {
+            schema_registry
+                .get_table(&component.table)?
+                .locale_key(lang)
+        });
+        let cache_key = CacheKey::new(
+            component_name,
+            record_id,
+            params
+                .context
+                .unwrap_or(if responsive { "responsive" } else { "card" }),
+            params.theme.unwrap_or("light"),
+        )
+        .minified(minify)
+        .currency(params.currency)
+        .jsonld(params.jsonld)
+        .platform(params.platform)
+        .locale(locale)
+        .scope(&scope)
+        .tenant(params.tenant);
+        // Slot content comes from the caller, so those renders are never cached
+        let cacheable = self.cache_enabled
+            && params.slots.is_none_or(|slots| slots.is_empty())
+            && !params.debug;
+        if cacheable
+            && !params.refresh
+            && let Some(html) = self.cache.get(&cache_key)
+        {
+            let record = HashMap::new();
+            Self::record_usage(&schema_registry, component, &params, responsive, &record);
+            return Ok(RenderOutput {
+                html,
+                degraded: false,
+                flagged: Vec::new(),
+                deprecations: Self::deprecations(
+                    &schema_registry,
+                    component,
+                    &params,
+                    responsive,
+                    &record,
+                ),
+                missing: Vec::new(),
+            });
+        }
+
+        // 2. Get data for this record
+        let mut degraded = false;
+        let record = self.component_record(&schema_registry, component, record_id, &scope);
+        let mut record_data = match record.await {
+            Ok(record) => record,
+            Err(DataError::NotFound(id)) => return Err(ComponentError::RecordNotFound(id)),
+            Err(err) => {
+                // Outage: prefer a stale render, then mock data, over an error
+                eprintln!(
+                    "Data source '{}' failed for {}:{}: {}; serving fallback",
+                    self.data_source.name(),
+                    component_name,
+                    record_id,
+                    err
+                );
+                if cacheable && let Some(html) = self.cache.get_stale(&cache_key) {
+                    let record = HashMap::new();
+                    Self::record_usage(&schema_registry, component, &params, responsive, &record);
+                    return Ok(RenderOutput {
+                        html,
+                        degraded: true,
+                        flagged: Vec::new(),
+                        deprecations: Self::deprecations(
+                            &schema_registry,
+                            component,
+                            &params,
+                            responsive,
+                            &record,
+                        ),
+                        missing: Vec::new(),
+                    });
+                }
+                degraded = true;
+                schema_registry
+                    .get_mock_record(&component.table, record_id)
+                    .filter(|record| in_scope(record, &scope))
+                    .ok_or(ComponentError::from(err))?
+            }
+        };
+
+        // 🔗 Values of referenced records for dot path placeholders ({company.name})
+        match self
+            .referenced_values(&schema_registry, component, &record_data)
+            .await
+        {
+            Ok(values) => record_data.extend(values),
+            Err(err) => {
+                eprintln!(
+                    "References of {}:{} unavailable: {}; rendering without them",
+                    component_name, record_id, err
+                );
+                degraded = true;
+            }
+        }
+
+        // 📋 Fields the rendered contexts require, before any partial markup
+        let mut missing = Vec::new();
+        if let Some(schema) = schema_registry.get_table(&component.table) {
+            for context in Self::render_contexts(component, &params, responsive) {
+                let lacking = schema.missing_fields(context, &record_data);
+                if lacking.is_empty() {
+                    continue;
+                }
+                if schema.missing_required.unwrap_or_default() == MissingRequired::Reject {
+                    return Err(ComponentError::MissingFields(context.to_string(), lacking));
+                }
+                eprintln!(
+                    "Record {} of {} lacks fields the '{}' context requires: {}",
+                    record_id,
+                    component.table,
+                    context,
+                    lacking.join(", ")
+                );
+                for field in lacking {
+                    if !missing.contains(&field) {
+                        missing.push(field);
                     }
-                    field.push(chars.next().unwrap());
                 }
-                if !field.is_empty() {
-                    fields.push(field);
+            }
+        }
+
+        // 🛡️ Check user-generated values before they reach the markup
+        let mut flagged = Vec::new();
+        let scan_action = schema_registry
+            .get_table(&component.table)
+            .and_then(|schema| schema.content_scan)
+            .or(config().content_scan);
+        if let Some(action) = scan_action {
+            let report = scan_record(
+                action,
+                &component.required_fields,
+                &mut record_data,
+                config().content_scan_max_token,
+            )
+            .map_err(|(field, finding)| ComponentError::UnsafeContent(field, finding))?;
+            for (field, finding) in report {
+                eprintln!(
+                    "Content scan ({:?}): {} in {}.{} of record {}",
+                    action, finding, component.table, field, record_id
+                );
+                if action == ScanAction::Flag && !flagged.contains(&field) {
+                    flagged.push(field);
+                }
+            }
+        }
+
+        // 🏷️ Related records for relation placeholders ({tags})
+        let related = match self
+            .related_values(&schema_registry, component, record_id)
+            .await
+        {
+            Ok(related) => related,
+            Err(err) => {
+                eprintln!(
+                    "Relations of {}:{} unavailable: {}; rendering without them",
+                    component_name, record_id, err
+                );
+                degraded = true;
+                HashMap::new()
+            }
+        };
+
+        // Renders only read cached rates, so make sure they are current
+        if params.currency.is_some()
+            && let Err(e) = currency_converter().refresh_if_stale().await
+        {
+            eprintln!("{}; showing stored currencies", e);
+        }
+
+        let started = Instant::now();
+        let mut html = if responsive {
+            self.render_responsive(component, &record_data, &related, &params)?
+        } else {
+            self.render_record(component, &record_data, &related, &params)?
+        };
+        if params.jsonld
+            && let Some(mapping) = schema_registry
+                .get_table(&component.table)
+                .and_then(|schema| schema.jsonld.get(&component.name))
+        {
+            html.push_str(&jsonld_script(mapping, &record_data));
+        }
+        if minify {
+            html = minify_html(&html);
+        }
+        if let Some(budget_ms) = component.budget_ms {
+            let key = format!("component:{}", component.name);
+            budget_tracker().record(&key, budget_ms, started.elapsed());
+        }
+        // Flagged and incomplete renders are re-checked (and re-reported) every time
+        if cacheable && !degraded && flagged.is_empty() && missing.is_empty() {
+            match component.cache_ttl {
+                Some(ttl) => {
+                    self.cache
+                        .insert_with_ttl(cache_key, html.clone(), Duration::from_secs(ttl))
                 }
+                None => self.cache.insert(cache_key, html.clone()),
             }
         }
 
-        fields.sort();
-        fields.dedup();
-        fields
+        Self::record_usage(
+            &schema_registry,
+            component,
+            &params,
+            responsive,
+            &record_data,
+        );
+        Ok(RenderOutput {
+            html,
+            degraded,
+            flagged,
+            deprecations: Self::deprecations(
+                &schema_registry,
+                component,
+                &params,
+                responsive,
+                &record_data,
+            ),
+            missing,
+        })
     }
 
-    // 🎯 Main API: Render component with parameters
-    pub async fn render_component(
+    // Deprecated schema parts a render goes through, for every context it
+    // renders in; each is logged the first time any render hits it
+    fn deprecations(
+        schema_registry: &SchemaRegistry,
+        component: &ComponentTemplate,
+        params: &RenderParams<'_>,
+        responsive: bool,
+        record: &HashMap<String, String>,
+    ) -> Vec<Deprecation> {
+        static LOGGED: OnceLock<DashSet<String>> = OnceLock::new();
+        let mut found = Vec::new();
+        for context in Self::render_contexts(component, params, responsive) {
+            let fields = &component.required_fields;
+            let table = &component.table;
+            for deprecation in schema_registry.deprecations(table, context, fields, record) {
+                if !found.contains(&deprecation) {
+                    found.push(deprecation);
+                }
+            }
+        }
+        let logged = LOGGED.get_or_init(DashSet::new);
+        for deprecation in &found {
+            if logged.insert(deprecation.to_string()) {
+                eprintln!("⚠️  {} (component '{}')", deprecation, component.name);
+            }
+        }
+        found
+    }
+
+    // 📊 Count the render's component, theme, contexts and variants when
+    // usage analytics are on (see analytics.rs)
+    fn record_usage(
+        schema_registry: &SchemaRegistry,
+        component: &ComponentTemplate,
+        params: &RenderParams<'_>,
+        responsive: bool,
+        record: &HashMap<String, String>,
+    ) {
+        let tracker = usage_tracker();
+        if !tracker.is_enabled() {
+            return;
+        }
+        let theme = params.theme.unwrap_or("light");
+        tracker.record_component(schema_registry, &component.name, theme);
+        let (table, fields) = (&component.table, &component.required_fields);
+        for context in Self::render_contexts(component, params, responsive) {
+            let variants = schema_registry.rendered_variants(table, context, fields, record);
+            tracker.record_context(schema_registry, table, context, &variants);
+        }
+    }
+
+    // Contexts a render goes through: each responsive one, or the requested one
+    fn render_contexts<'p>(
+        component: &'p ComponentTemplate,
+        params: &RenderParams<'p>,
+        responsive: bool,
+    ) -> Vec<&'p str> {
+        if responsive {
+            component
+                .responsive
+                .iter()
+                .map(|r| r.context.as_str())
+                .collect()
+        } else {
+            vec![params.context.unwrap_or("card")]
+        }
+    }
+
+    // ✉️ Render a component as one line of plain text (format=sms) from the
+    // table's `[short]` field templates, in template order
+    pub async fn render_component_sms(
         &self,
         component_name: &str,
         record_id: &str,
         params: RenderParams<'_>,
     ) -> Result<String, ComponentError> {
-        // 1. Find component template
+        let schema_registry = tenant_registry(params.tenant);
+        let text = self.text_record(&schema_registry, component_name, record_id, params.args);
+        let (component, fields, record) = text.await?;
+        let schema = Self::component_schema(&schema_registry, component)?;
+        Ok(short_line(schema, &fields, &record))
+    }
+
+    // 💬 Render a component as a Slack Block Kit message or Discord embed
+    pub async fn render_component_chat(
+        &self,
+        component_name: &str,
+        record_id: &str,
+        format: ChatFormat,
+        params: RenderParams<'_>,
+    ) -> Result<serde_json::Value, ComponentError> {
+        let schema_registry = tenant_registry(params.tenant);
+        let text = self.text_record(&schema_registry, component_name, record_id, params.args);
+        let (component, fields, record) = text.await?;
+        let schema = Self::component_schema(&schema_registry, component)?;
+        let context = params.context.unwrap_or("card");
+        Ok(chat_message(format, schema, context, &fields, &record))
+    }
+
+    // 🔔 Render a component as a push notification payload from the table's
+    // `[push.<component>]` mapping
+    pub async fn render_component_push(
+        &self,
+        component_name: &str,
+        record_id: &str,
+        params: RenderParams<'_>,
+    ) -> Result<PushPayload, ComponentError> {
+        let schema_registry = tenant_registry(params.tenant);
+        let text = self.text_record(&schema_registry, component_name, record_id, params.args);
+        let (component, _, record) = text.await?;
+        let schema = Self::component_schema(&schema_registry, component)?;
+        let mapping = schema.push.get(component_name).ok_or_else(|| {
+            ComponentError::UnsupportedFormat(format!(
+                "component '{}' has no push mapping",
+                component_name
+            ))
+        })?;
+        Ok(push_payload(mapping, &record))
+    }
+
+    // 📅 Render records of an event-like table as iCalendar: one record, or
+    // the collection (up to `limit`) when `record_id` is None. Both are
+    // limited to the scope of the component's required params.
+    pub async fn render_component_ics(
+        &self,
+        component_name: &str,
+        record_id: Option<&str>,
+        limit: Option<usize>,
+        params: RenderParams<'_>,
+    ) -> Result<String, ComponentError> {
+        let component =
+            self.components
+                .get(component_name)
+                .ok_or(ComponentError::ComponentNotFound(
+                    component_name.to_string(),
+                ))?;
+        let scope = Self::required_args(component, params.args)?;
+        let schema_registry = tenant_registry(params.tenant);
+        let schema = Self::component_schema(&schema_registry, component)?;
+        let mapping = schema.event.as_ref().ok_or_else(|| {
+            ComponentError::UnsupportedFormat(format!(
+                "table '{}' is not declared as an event table",
+                component.table
+            ))
+        })?;
+
+        let records = match record_id {
+            Some(id) => {
+                let id = Self::record_id(component, id)?;
+                vec![
+                    self.component_record(&schema_registry, component, id, &scope)
+                        .await?,
+                ]
+            }
+            // Scoped collections are filtered before the limit applies
+            None if scope.is_empty() => {
+                self.data_source
+                    .get_records(&component.table, limit)
+                    .await?
+            }
+            None => self
+                .data_source
+                .get_records(&component.table, None)
+                .await?
+                .into_iter()
+                .filter(|record| in_scope(record, &scope))
+                .take(limit.unwrap_or(usize::MAX))
+                .collect(),
+        };
+        Ok(calendar(&component.table, mapping, &records))
+    }
+
+    // 🌳 Render a record of a `[tree]` table with all its descendants as nested
+    // lists (format=tree), each node through the component. Components the
+    // table lists under `[threads]` page their replies; `offset` > 0 renders
+    // the next page of the record's replies ("load more").
+    pub async fn render_component_tree(
+        &self,
+        component_name: &str,
+        record_id: &str,
+        offset: usize,
+        params: RenderParams<'_>,
+    ) -> Result<String, ComponentError> {
+        let component =
+            self.components
+                .get(component_name)
+                .ok_or(ComponentError::ComponentNotFound(
+                    component_name.to_string(),
+                ))?;
+        let schema_registry = tenant_registry(params.tenant);
+        let schema = Self::component_schema(&schema_registry, component)?;
+        let mapping = schema.tree.as_ref().ok_or_else(|| {
+            ComponentError::UnsupportedFormat(format!(
+                "table '{}' is not declared as a tree",
+                component.table
+            ))
+        })?;
+        let scope = Self::required_args(component, params.args)?;
+        let record_id = Self::record_id(component, record_id)?;
+
+        let root = self.component_record(&schema_registry, component, record_id, &scope);
+        let root = root.await?;
+        let descendants: Vec<_> = self
+            .data_source
+            .get_descendants(&component.table, &mapping.parent, record_id)
+            .await?
+            .into_iter()
+            .filter(|record| in_scope(record, &scope))
+            .collect();
+
+        // "Load more" fetches the same component, keeping the request's scope
+        let load_more = |parent: &str, offset: usize| {
+            let mut url = format!(
+                "/api/{}?id={}&format=tree&offset={}",
+                encode(&component.name),
+                encode(parent),
+                offset
+            );
+            if let Some(context) = params.context {
+                url.push_str(&format!("&context={}", encode(context)));
+            }
+            for (param, value) in &scope {
+                url.push_str(&format!("&{}={}", encode(param), encode(value)));
+            }
+            url
+        };
+        let options = TreeOptions {
+            thread: schema.threads.get(&component.name),
+            offset,
+            load_more: Some(&load_more),
+        };
+
+        // Nodes are scanned like single renders; flagged ones render as they are
+        let scan_action = schema.content_scan.or(config().content_scan);
+        let no_relations = HashMap::new();
+        render_tree(
+            &schema_registry,
+            mapping,
+            &root,
+            &descendants,
+            &options,
+            |record| {
+                let mut record = record.clone();
+                if let Some(action) = scan_action {
+                    scan_record(
+                        action,
+                        &component.required_fields,
+                        &mut record,
+                        config().content_scan_max_token,
+                    )
+                    .map_err(|(field, finding)| ComponentError::UnsafeContent(field, finding))?;
+                }
+                self.render_record(component, &record, &no_relations, &params)
+            },
+        )
+    }
+
+    // (param, value) for each of the component's `requires`, in declaration
+    // order; every missing (or empty) param is reported at once
+    pub fn required_args(
+        component: &ComponentTemplate,
+        args: Option<&HashMap<String, String>>,
+    ) -> Result<Vec<(String, String)>, ComponentError> {
+        let mut scope = Vec::new();
+        let mut missing = Vec::new();
+        for param in &component.requires {
+            match args
+                .and_then(|args| args.get(param))
+                .filter(|value| !value.is_empty())
+            {
+                Some(value) => scope.push((param.clone(), value.clone())),
+                None => missing.push(param.clone()),
+            }
+        }
+        if missing.is_empty() {
+            Ok(scope)
+        } else {
+            Err(ComponentError::MissingParams(missing))
+        }
+    }
+
+    // Values of the related records for each relation the template uses
+    async fn related_values(
+        &self,
+        schema_registry: &SchemaRegistry,
+        component: &ComponentTemplate,
+        record_id: &str,
+    ) -> Result<HashMap<String, Vec<String>>, DataError> {
+        let Some(schema) = schema_registry.get_table(&component.table) else {
+            return Ok(HashMap::new());
+        };
+        let mut related = HashMap::new();
+        for field in &component.required_fields {
+            let Some(relation) = schema.relations.get(field) else {
+                continue;
+            };
+            let records = self.data_source.get_related(relation, record_id).await?;
+            let values = records
+                .into_iter()
+                .filter_map(|mut record| record.remove(&relation.field))
+                .collect();
+            related.insert(field.clone(), values);
+        }
+        Ok(related)
+    }
+
+    // Values for the template's dot paths the record doesn't have, followed
+    // through the tables' `[references]`. A missing reference (no id, no such
+    // record) or referenced field renders empty instead of failing the render.
+    async fn referenced_values(
+        &self,
+        schema_registry: &SchemaRegistry,
+        component: &ComponentTemplate,
+        record: &HashMap<String, String>,
+    ) -> Result<HashMap<String, String>, DataError> {
+        // Each referenced record is fetched once, however many fields use it
+        let mut fetched: HashMap<(String, String), HashMap<String, String>> = HashMap::new();
+        let mut values = HashMap::new();
+        let paths = component
+            .required_fields
+            .iter()
+            .filter(|field| field.contains('.') && !record.contains_key(*field));
+        for path in paths {
+            let mut table = component.table.as_str();
+            let mut current = Cow::Borrowed(record);
+            let mut rest = path.as_str();
+            while let Some((name, field)) = rest.split_once('.') {
+                let Some(reference) = schema_registry
+                    .get_table(table)
+                    .and_then(|schema| schema.references.get(name))
+                else {
+                    break;
+                };
+                let Some(id) = current.get(&reference.key) else {
+                    values.insert(path.clone(), String::new());
+                    break;
+                };
+                let key = (reference.table.clone(), id.clone());
+                let next = match fetched.get(&key) {
+                    Some(next) => next.clone(),
+                    None => {
+                        let next = match self.data_source.get_record(&key.0, &key.1).await {
+                            Ok(next) => match schema_registry.get_table(&reference.table) {
+                                Some(schema) => schema.materialize(&next).into_owned(),
+                                None => next,
+                            },
+                            Err(DataError::NotFound(_)) => HashMap::new(),
+                            Err(err) => return Err(err),
+                        };
+                        fetched.insert(key, next.clone());
+                        next
+                    }
+                };
+                table = &reference.table;
+                current = Cow::Owned(next);
+                rest = field;
+                if let Some(value) = current.get(rest) {
+                    values.insert(path.clone(), value.clone());
+                    break;
+                }
+                if !rest.contains('.') {
+                    values.insert(path.clone(), String::new());
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    // The record a render covers: a static component's own record, otherwise
+    // the requested one, which must then be given (singleton tables need none)
+    fn record_id<'a>(
+        component: &'a ComponentTemplate,
+        requested: &'a str,
+    ) -> Result<&'a str, ComponentError> {
+        match &component.static_record {
+            Some(id) => Ok(id),
+            None if requested.is_empty() && !is_singleton(&component.table) => {
+                Err(ComponentError::MissingRecordId(component.name.clone()))
+            }
+            None => Ok(requested),
+        }
+    }
+
+    // 🪧 Fetch a component's record. Static components layer their row over
+    // SITE_SETTINGS and may do without the row when settings exist.
+    async fn component_record(
+        &self,
+        schema_registry: &SchemaRegistry,
+        component: &ComponentTemplate,
+        record_id: &str,
+        scope: &[(String, String)],
+    ) -> Result<HashMap<String, String>, DataError> {
+        let mut record = if is_singleton(&component.table) {
+            singleton_record(self.data_source.as_ref(), &component.table, scope).await?
+        } else {
+            self.lookup_record(component, record_id, scope).await?
+        };
+        // Aliases and computed fields apply up front, so scans and text formats see them
+        if let Some(schema) = schema_registry.get_table(&component.table)
+            && let Cow::Owned(materialized) = schema.materialize(&record)
+        {
+            record = materialized;
+        }
+        Ok(record)
+    }
+
+    async fn lookup_record(
+        &self,
+        component: &ComponentTemplate,
+        record_id: &str,
+        scope: &[(String, String)],
+    ) -> Result<HashMap<String, String>, DataError> {
+        let lookup = self
+            .data_source
+            .get_scoped_record(&component.table, record_id, scope);
+        if component.static_record.is_none() {
+            return lookup.await;
+        }
+        let settings = &config().site_settings;
+        let mut record = match lookup.await {
+            Ok(record) => record,
+            Err(DataError::NotFound(_)) if !settings.is_empty() => HashMap::new(),
+            Err(err) => return Err(err),
+        };
+        for (field, value) in settings {
+            record.entry(field.clone()).or_insert_with(|| value.clone());
+        }
+        Ok(record)
+    }
+
+    // Component, its fields in template order and the record, for the
+    // non-HTML formats (required_fields itself is sorted). The record is
+    // looked up within the scope of the component's required params.
+    async fn text_record(
+        &self,
+        schema_registry: &SchemaRegistry,
+        component_name: &str,
+        record_id: &str,
+        args: Option<&HashMap<String, String>>,
+    ) -> Result<(&ComponentTemplate, Vec<String>, HashMap<String, String>), ComponentError> {
         let component =
             self.components
                 .get(component_name)
                 .ok_or(ComponentError::ComponentNotFound(
                     component_name.to_string(),
                 ))?;
+        let scope = Self::required_args(component, args)?;
+        let record_id = Self::record_id(component, record_id)?;
+        let record = self
+            .component_record(schema_registry, component, record_id, &scope)
+            .await?;
+        let mut fields = component.required_fields.clone();
+        fields.sort_by_key(|field| component.template.find(&format!("{{{}}}", field)));
+        Ok((component, fields, record))
+    }
+
+    fn component_schema<'r>(
+        schema_registry: &'r SchemaRegistry,
+        component: &ComponentTemplate,
+    ) -> Result<&'r TableSchema, ComponentError> {
+        schema_registry.get_table(&component.table).ok_or_else(|| {
+            ComponentError::InvalidDefinition(format!(
+                "component '{}' references unknown table '{}'",
+                component.name, component.table
+            ))
+        })
+    }
+
+    // 🔥 Pre-render a list of (component, id, context, theme) combos into the
+    // cache so the first requests after a deploy are served warm.
+    pub async fn warm_up(&self, entries: &[WarmupEntry]) -> WarmupReport {
+        self.prerender(entries, false).await
+    }
+
+    // Re-render entries over their cached copies, which keep being served
+    // until the new render replaces them
+    pub async fn refresh(&self, entries: &[WarmupEntry]) -> WarmupReport {
+        self.prerender(entries, true).await
+    }
+
+    async fn prerender(&self, entries: &[WarmupEntry], refresh: bool) -> WarmupReport {
+        let mut report = WarmupReport::default();
+
+        for entry in entries {
+            let params = RenderParams {
+                context: entry.context.as_deref(),
+                theme: entry.theme.as_deref(),
+                refresh,
+                ..Default::default()
+            };
+
+            match self
+                .render_component(&entry.component, &entry.id, params)
+                .await
+            {
+                Ok(_) => report.warmed += 1,
+                Err(err) => report.failed.push((entry.clone(), err)),
+            }
+        }
+
+        report
+    }
 
-        // 2. Get data for this record (mock data for now)
-        let record_data = self
-            .schema_registry
-            .get_mock_record(&component.table, record_id)
-            .ok_or(ComponentError::RecordNotFound(record_id.to_string()))?;
+    // Fetch records from somewhere other than the embedded mock data
+    pub fn set_data_source(&mut self, data_source: Arc<dyn DataSource>) {
+        self.data_source = data_source;
+    }
+
+    pub fn data_source(&self) -> &Arc<dyn DataSource> {
+        &self.data_source
+    }
 
+    // Turn render caching off entirely, e.g. to benchmark raw render cost
+    pub fn set_cache_enabled(&mut self, enabled: bool) {
+        self.cache_enabled = enabled;
+    }
+
+    // Access the render cache (e.g. to clear it after a schema change)
+    pub fn cache(&self) -> &RenderCache {
+        &self.cache
+    }
+
+    // 📱 One render per responsive context, each in a container gated by its
+    // CSS classes, so a single response serves every breakpoint
+    fn render_responsive(
+        &self,
+        component: &ComponentTemplate,
+        record_data: &HashMap<String, String>,
+        related: &HashMap<String, Vec<String>>,
+        params: &RenderParams<'_>,
+    ) -> Result<String, ComponentError> {
+        let mut html = String::new();
+        for responsive in &component.responsive {
+            let params = RenderParams {
+                context: Some(&responsive.context),
+                ..params.clone()
+            };
+            let rendered = self.render_record(component, record_data, related, &params)?;
+            html.push_str(&format!(
+                r#"<div class="{}" data-sui-context="{}">{}</div>"#,
+                escape_attr(&responsive.class),
+                escape_attr(&responsive.context),
+                rendered
+            ));
+        }
+        Ok(html)
+    }
+
+    // Render a component template against an already fetched record
+    fn render_record(
+        &self,
+        component: &ComponentTemplate,
+        record_data: &HashMap<String, String>,
+        related: &HashMap<String, Vec<String>>,
+        params: &RenderParams<'_>,
+    ) -> Result<String, ComponentError> {
         // 3. Apply theme (future: per-request theme switching)
+        let schema_registry = tenant_registry(params.tenant);
+        // Mock fallbacks and tree descendants don't come through component_record
+        let record_data = match schema_registry.get_table(&component.table) {
+            Some(schema) => schema.materialize(record_data),
+            None => Cow::Borrowed(record_data),
+        };
         let context = params.context.unwrap_or("card");
+        let options = FieldRenderOptions {
+            debug: params.debug,
+            currency: params.currency,
+            print: params.platform == Some("print"),
+            platform: params.platform,
+            lang: params.lang,
+            value_type: None,
+        };
+        let field_budgets = schema_registry
+            .get_table(&component.table)
+            .map(|schema| &schema.budgets);
 
         // 4. Render each field with schema styling
-        let rendered_fields: HashMap<_, _> = component
+        let mut rendered_fields: HashMap<_, _> = component
             .required_fields
             .iter()
             .filter_map(|field| {
-                record_data
-                    .get(field)
-                    .and_then(|field_value| {
-                        self.schema_registry.render_field(
-                            &component.table,
-                            field,
-                            context,
-                            field_value,
-                        )
-                    })
-                    .map(|rendered_html| (field.clone(), rendered_html))
+                let started = Instant::now();
+                let rendered = record_data.get(field).and_then(|field_value| {
+                    schema_registry.render_field_with(
+                        &component.table,
+                        field,
+                        context,
+                        field_value,
+                        options,
+                    )
+                });
+                if let Some(budget_ms) = field_budgets.and_then(|budgets| budgets.get(field)) {
+                    let key = format!("field:{}.{}", component.table, field);
+                    budget_tracker().record(&key, *budget_ms, started.elapsed());
+                }
+                rendered.map(|rendered_html| (field.clone(), rendered_html))
             })
             .collect();
 
+        // One chip per related value, in the relation's variant if it names one
+        let relations = schema_registry
+            .get_table(&component.table)
+            .map(|schema| &schema.relations);
+        for (field, values) in related {
+            let Some(relation) = relations.and_then(|relations| relations.get(field)) else {
+                continue;
+            };
+            let chips: Vec<String> = values
+                .iter()
+                .filter_map(|value| match &relation.variant {
+                    Some(variant) => schema_registry.render_variant_with(
+                        &component.table,
+                        field,
+                        variant,
+                        value,
+                        options,
+                    ),
+                    None => schema_registry.render_field_with(
+                        &component.table,
+                        field,
+                        context,
+                        value,
+                        options,
+                    ),
+                })
+                .collect();
+            rendered_fields.insert(field.clone(), chips.join(&relation.separator));
+        }
+
+        // Slots are filled verbatim by the caller and default to empty
+        for slot in &component.slots {
+            let content = params
+                .slots
+                .and_then(|slots| slots.get(slot))
+                .cloned()
+                .unwrap_or_default();
+            rendered_fields.insert(slot.clone(), content);
+        }
+
+        // Required params fill their placeholders like text
+        for param in &component.requires {
+            let value = params.args.and_then(|args| args.get(param));
+            rendered_fields.insert(param.clone(), escape_attr(value.map_or("", String::as_str)));
+        }
+
         // 5. Substitute fields in template
         let final_html = self.substitute_template(&component.template, &rendered_fields)?;
 
         Ok(final_html)
     }
 
-    // Replace {field} placeholders with rendered HTML
+    // 📚 Render a component for many records, streaming rows in order.
+    // At most `max_concurrent_renders` rows are in flight at once, and rows are
+    // only produced as fast as the consumer polls the stream.
+    pub fn render_collection_stream<'a>(
+        &'a self,
+        component_name: &'a str,
+        record_ids: Vec<String>,
+        params: RenderParams<'a>,
+    ) -> impl Stream<Item = Result<String, ComponentError>> + 'a {
+        stream::iter(record_ids)
+            .map(move |record_id| {
+                let params = params.clone();
+                async move {
+                    self.render_component(component_name, &record_id, params)
+                        .await
+                }
+            })
+            .buffered(self.max_concurrent_renders)
+    }
+
+    // Render a component for many records and collect the rows
+    pub async fn render_collection(
+        &self,
+        component_name: &str,
+        record_ids: Vec<String>,
+        params: RenderParams<'_>,
+    ) -> Result<Vec<String>, ComponentError> {
+        let rows: Vec<_> = self
+            .render_collection_stream(component_name, record_ids, params)
+            .collect()
+            .await;
+
+        rows.into_iter().collect()
+    }
+
+    // Ids of the records a collection render of this component would cover.
+    // `args` supplies the component's required params; records outside that
+    // scope are left out (after the limit is applied).
+    pub async fn collection_ids(
+        &self,
+        component_name: &str,
+        limit: Option<usize>,
+        args: Option<&HashMap<String, String>>,
+    ) -> Result<Vec<String>, ComponentError> {
+        let component =
+            self.components
+                .get(component_name)
+                .ok_or(ComponentError::ComponentNotFound(
+                    component_name.to_string(),
+                ))?;
+        let scope = Self::required_args(component, args)?;
+
+        Ok(self
+            .data_source
+            .get_records(&component.table, limit)
+            .await?
+            .into_iter()
+            .filter(|record| in_scope(record, &scope))
+            .filter_map(|record| record.get("id").cloned())
+            .collect())
+    }
+
+    // 📄 One page of a collection: ids of the records after the `after` cursor
+    // (from a previous page) in `sort_by` order, at most `limit` of them, plus
+    // the cursor of the next page. A cursor carries its sort field, so later
+    // pages need no `sort_by`; naming a different one is an error.
+    pub async fn collection_page(
+        &self,
+        component_name: &str,
+        sort_by: Option<&str>,
+        after: Option<&str>,
+        limit: Option<usize>,
+        args: Option<&HashMap<String, String>>,
+    ) -> Result<(Vec<String>, Option<String>), ComponentError> {
+        let component =
+            self.components
+                .get(component_name)
+                .ok_or(ComponentError::ComponentNotFound(
+                    component_name.to_string(),
+                ))?;
+        let scope = Self::required_args(component, args)?;
+        let after = match after {
+            Some(encoded) => {
+                let cursor = Cursor::decode(encoded)
+                    .filter(|cursor| sort_by.is_none_or(|sort_by| sort_by == cursor.sort_by))
+                    .ok_or_else(|| ComponentError::InvalidCursor(encoded.to_string()))?;
+                Some(cursor)
+            }
+            None => None,
+        };
+        let sort_by = after
+            .as_ref()
+            .map_or(sort_by.unwrap_or("id"), |cursor| cursor.sort_by.as_str());
+
+        let (records, next) = self
+            .data_source
+            .get_page(&component.table, sort_by, after.as_ref(), limit, &scope)
+            .await?;
+        let ids = records
+            .into_iter()
+            .filter_map(|mut record| record.remove("id"))
+            .collect();
+        Ok((ids, next.map(|cursor| cursor.encode())))
+    }
+
+    // Limit how many rows a collection render processes concurrently
+    pub fn set_max_concurrent_renders(&mut self, max: usize) {
+        self.max_concurrent_renders = max.max(1);
+    }
+
+    pub fn max_concurrent_renders(&self) -> usize {
+        self.max_concurrent_renders
+    }
+
+    // Replace {field} placeholders with rendered HTML.
+    // Single pass over the template, so braces inside rendered fields
+    // (e.g. literal attribute values) are never mistaken for placeholders.
     fn substitute_template(
         &self,
         template: &str,
         rendered_fields: &HashMap<String, String>,
     ) -> Result<String, ComponentError> {
-        let mut result = template.to_string();
+        let mut result = String::with_capacity(template.len());
+        let mut rest = template;
 
-        for (field, rendered_html) in rendered_fields {
-            let placeholder = format!("{{{}}}", field);
-            result = result.replace(&placeholder, rendered_html);
-        }
+        while let Some(start) = rest.find('{') {
+            result.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+
+            let Some(end) = after.find('}') else {
+                // No closing brace: keep the remaining text verbatim
+                result.push_str(&rest[start..]);
+                return Ok(result);
+            };
 
-        // Check for unresolved placeholders
-        if result.contains('{') && result.contains('}') {
-            return Err(ComponentError::UnresolvedPlaceholders);
+            // Check for unresolved placeholders
+            let field = &after[..end];
+            let rendered_html = rendered_fields
+                .get(field)
+                .ok_or(ComponentError::UnresolvedPlaceholders)?;
+            result.push_str(rendered_html);
+
+            rest = &after[end + 1..];
         }
 
+        result.push_str(rest);
         Ok(result)
     }
 
@@ -186,12 +1429,173 @@ impl ComponentRegistry {
     }
 }
 
+// The active schemas, as `tenant` sees them when the render names one
+fn tenant_registry(tenant: Option<&str>) -> Arc<SchemaRegistry> {
+    let schema_registry = registry();
+    match tenant {
+        Some(tenant) => schema_registry.for_tenant(tenant),
+        None => schema_registry,
+    }
+}
+
+fn is_singleton(table: &str) -> bool {
+    registry()
+        .get_table(table)
+        .is_some_and(|schema| schema.singleton)
+}
+
+// Extract {field} placeholders from template
+fn extract_field_placeholders(template: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch == '{' {
+            let mut field = String::new();
+            while let Some(&next_ch) = chars.peek() {
+                if next_ch == '}' {
+                    chars.next(); // consume '}'
+                    break;
+                }
+                field.push(chars.next().unwrap());
+            }
+            if !field.is_empty() {
+                fields.push(field);
+            }
+        }
+    }
+
+    fields.sort();
+    fields.dedup();
+    fields
+}
+
+// Strip whitespace around `{~field}` / `{field~}` markers: `~` before the name
+// trims whitespace preceding the placeholder, `~` after it trims what follows.
+fn apply_trim_markers(template: &str) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let inner = &rest[start + 1..start + len];
+
+        result.push_str(&rest[..start]);
+        if inner.starts_with('~') {
+            result.truncate(result.trim_end().len());
+        }
+        result.push('{');
+        result.push_str(inner.trim_start_matches('~').trim_end_matches('~'));
+        result.push('}');
+
+        rest = &rest[start + len + 1..];
+        if inner.len() > 1 && inner.ends_with('~') {
+            rest = rest.trim_start();
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+// Nested includes deeper than this are rejected
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+// Inline `{include "partials/footer.html"}` directives, relative to `root`.
+// `stack` holds the chain of partials being expanded to detect cycles.
+fn resolve_includes(
+    template: &str,
+    root: &Path,
+    stack: &mut Vec<String>,
+) -> Result<String, ComponentError> {
+    const OPEN: &str = "{include \"";
+    const CLOSE: &str = "\"}";
+
+    if !template.contains(OPEN) {
+        return Ok(template.to_string());
+    }
+    if stack.len() >= MAX_INCLUDE_DEPTH {
+        return Err(ComponentError::IncludeError(format!(
+            "includes nested deeper than {} levels: {}",
+            MAX_INCLUDE_DEPTH,
+            stack.join(" -> ")
+        )));
+    }
+
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find(OPEN) {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + OPEN.len()..];
+        let end = after.find(CLOSE).ok_or_else(|| {
+            ComponentError::IncludeError("unterminated {include} directive".to_string())
+        })?;
+        let include_path = &after[..end];
+
+        // Only plain relative paths inside the template root
+        let relative = Path::new(include_path);
+        if !relative
+            .components()
+            .all(|part| matches!(part, Component::Normal(_)))
+        {
+            return Err(ComponentError::IncludeError(format!(
+                "include path '{}' must stay inside the template directory",
+                include_path
+            )));
+        }
+        if stack.iter().any(|included| included == include_path) {
+            return Err(ComponentError::IncludeError(format!(
+                "include cycle: {} -> {}",
+                stack.join(" -> "),
+                include_path
+            )));
+        }
+
+        let partial = std::fs::read_to_string(root.join(relative)).map_err(|e| {
+            ComponentError::IncludeError(format!("cannot read '{}': {}", include_path, e))
+        })?;
+
+        stack.push(include_path.to_string());
+        result.push_str(&resolve_includes(&partial, root, stack)?);
+        stack.pop();
+
+        rest = &after[end + CLOSE.len()..];
+    }
+
+    result.push_str(rest);
+    Ok(result)
+}
+
+// Outcome of a warm-up run
+#[derive(Debug, Default)]
+pub struct WarmupReport {
+    pub warmed: usize,
+    pub failed: Vec<(WarmupEntry, ComponentError)>,
+}
+
 #[derive(Debug, Clone)]
 pub enum ComponentError {
     ComponentNotFound(String),
     RecordNotFound(String),
     UnresolvedPlaceholders,
     DatabaseError(String),
+    InvalidDefinition(String),
+    IncludeError(String),
+    // The component cannot be rendered in the requested output format
+    UnsupportedFormat(String),
+    // A field value failed the content scan of a content_scan = "reject" table
+    UnsafeContent(String, Finding),
+    // Request params the component requires but the caller did not send
+    MissingParams(Vec<String>),
+    // Fields a context requires that the record lacks: (context, fields)
+    MissingFields(String, Vec<String>),
+    // A record-bound component was asked for without an `id`
+    MissingRecordId(String),
+    // A collection `after` cursor that doesn't decode (or names another sort)
+    InvalidCursor(String),
 }
 
 impl std::fmt::Display for ComponentError {
@@ -203,16 +1607,816 @@ impl std::fmt::Display for ComponentError {
                 write!(f, "Template has unresolved placeholders")
             }
             ComponentError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
+            ComponentError::InvalidDefinition(msg) => {
+                write!(f, "Invalid component definition: {}", msg)
+            }
+            ComponentError::IncludeError(msg) => write!(f, "Template include failed: {}", msg),
+            ComponentError::UnsupportedFormat(msg) => write!(f, "Unsupported format: {}", msg),
+            ComponentError::UnsafeContent(field, finding) => {
+                write!(f, "Field '{}' contains unsafe content ({})", field, finding)
+            }
+            ComponentError::MissingParams(params) => {
+                write!(f, "Missing required parameters: {}", params.join(", "))
+            }
+            ComponentError::MissingFields(context, fields) => write!(
+                f,
+                "Record lacks fields the '{}' context requires: {}",
+                context,
+                fields.join(", ")
+            ),
+            ComponentError::MissingRecordId(name) => {
+                write!(
+                    f,
+                    "Component '{}' renders a record; an id is required",
+                    name
+                )
+            }
+            ComponentError::InvalidCursor(cursor) => {
+                write!(f, "Invalid pagination cursor '{}'", cursor)
+            }
         }
     }
 }
 
 impl std::error::Error for ComponentError {}
 
+impl From<DataError> for ComponentError {
+    fn from(err: DataError) -> Self {
+        match err {
+            DataError::NotFound(id) => ComponentError::RecordNotFound(id),
+            other => ComponentError::DatabaseError(other.to_string()),
+        }
+    }
+}
+
 // Global component registry
-use std::sync::OnceLock;
 static COMPONENT_REGISTRY: OnceLock<ComponentRegistry> = OnceLock::new();
 
 pub fn component_registry() -> &'static ComponentRegistry {
     COMPONENT_REGISTRY.get_or_init(ComponentRegistry::new)
 }
+
+// Install a registry built by the embedding application (e.g. with extra
+// components registered at startup). Fails if the global registry is already in use.
+pub fn set_component_registry(registry: ComponentRegistry) -> Result<(), ComponentRegistry> {
+    COMPONENT_REGISTRY.set(registry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint::lint_table;
+    use crate::schema::{SchemaError, update_registry};
+
+    #[tokio::test]
+    async fn test_warm_up_fills_cache() {
+        let registry = ComponentRegistry::new();
+        let entries =
+            WarmupEntry::parse_list("user_card:1:card:light, user_card:2:list, missing:1");
+
+        let report = registry.warm_up(&entries).await;
+        assert_eq!(report.warmed, 2);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(registry.cache().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_outage_serves_degraded_fallback() {
+        use crate::data_source::{ChaosConfig, ChaosDataSource, MockDataSource};
+
+        let mut registry = ComponentRegistry::new();
+        let params = RenderParams::default();
+        let fresh = registry
+            .render_component_output("user_card", "1", params.clone())
+            .await;
+        assert!(!fresh.unwrap().degraded);
+
+        registry.set_data_source(Arc::new(ChaosDataSource::new(
+            Arc::new(MockDataSource),
+            ChaosConfig {
+                latency: Duration::ZERO,
+                jitter: Duration::ZERO,
+                error_rate: 1.0,
+            },
+        )));
+
+        // Fresh cache hits never touch the data source
+        let cached = registry
+            .render_component_output("user_card", "1", params.clone())
+            .await;
+        assert!(!cached.unwrap().degraded);
+
+        // Expired renders are served stale, then mock data when nothing is cached
+        let key = CacheKey::new("user_card", "3", "card", "light");
+        registry
+            .cache()
+            .insert_with_ttl(key, "<p>stale</p>".to_string(), Duration::from_nanos(1));
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        let stale = registry
+            .render_component_output("user_card", "3", params.clone())
+            .await;
+        assert_eq!(
+            stale.unwrap(),
+            RenderOutput {
+                html: "<p>stale</p>".to_string(),
+                degraded: true,
+                flagged: Vec::new(),
+                deprecations: Vec::new(),
+                missing: Vec::new(),
+            }
+        );
+        registry.cache().clear();
+        let mock = registry
+            .render_component_output("user_card", "2", params)
+            .await
+            .unwrap();
+        assert!(mock.degraded && mock.html.contains("Jane Smith"));
+        assert!(registry.cache().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_register_built_component_with_slot() {
+        let mut registry = ComponentRegistry::new();
+        let component = ComponentTemplate::builder()
+            .name("user_badge")
+            .table("users")
+            .template_str("<div>{name}<nav>{actions}</nav></div>")
+            .slot("actions")
+            .cache_ttl(60)
+            .build()
+            .unwrap();
+        assert_eq!(component.required_fields, vec!["name".to_string()]);
+        registry.register(component).unwrap();
+
+        let slots = HashMap::from([("actions".to_string(), "<button>Edit</button>".to_string())]);
+        let html = registry
+            .render_component(
+                "user_badge",
+                "1",
+                RenderParams {
+                    slots: Some(&slots),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert!(html.contains("<nav><button>Edit</button></nav>"));
+
+        let unknown_table = ComponentTemplate::builder()
+            .name("ghost")
+            .table("ghosts")
+            .template_str("{name}")
+            .build()
+            .unwrap();
+        assert!(registry.register(unknown_table).is_err());
+        assert!(ComponentTemplate::builder().table("users").build().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_required_params_scope_and_fill_the_render() {
+        let mut registry = ComponentRegistry::new();
+        let component = ComponentTemplate::builder()
+            .name("member_badge")
+            .table("users")
+            .template_str(r#"<p data-org="{org_id}">{name}</p>"#)
+            .requires("org_id")
+            .requires("email")
+            .build()
+            .unwrap();
+        assert_eq!(component.required_fields, vec!["name".to_string()]);
+        registry.register(component).unwrap();
+
+        let render = |args: HashMap<String, String>| {
+            let registry = &registry;
+            async move {
+                let params = RenderParams {
+                    args: Some(&args),
+                    ..Default::default()
+                };
+                registry.render_component("member_badge", "1", params).await
+            }
+        };
+        let args = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>()
+        };
+
+        assert!(matches!(
+            render(args(&[("email", "")])).await,
+            Err(ComponentError::MissingParams(missing)) if missing == ["org_id", "email"]
+        ));
+        // The record's email is john@example.com, so other scopes can't see it
+        assert!(matches!(
+            render(args(&[("org_id", "7"), ("email", "eve@example.com")])).await,
+            Err(ComponentError::RecordNotFound(_))
+        ));
+        let html = render(args(&[("org_id", "<7>"), ("email", "john@example.com")]))
+            .await
+            .unwrap();
+        assert!(
+            html.starts_with(r#"<p data-org="&lt;7&gt;"><h2"#),
+            "{}",
+            html
+        );
+
+        let jane = args(&[("org_id", "7"), ("email", "jane.smith@example.com")]);
+        let ids = registry
+            .collection_ids("member_badge", None, Some(&jane))
+            .await
+            .unwrap();
+        assert_eq!(ids, vec!["2".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_static_component_renders_without_an_id() {
+        let mut registry = ComponentRegistry::new();
+        let component = ComponentTemplate::builder()
+            .name("site_footer")
+            .table("users")
+            .template_str("<footer>{email}</footer>")
+            .static_record("1")
+            .build()
+            .unwrap();
+        registry.register(component).unwrap();
+
+        let footer = registry
+            .render_component("site_footer", "", RenderParams::default())
+            .await
+            .unwrap();
+        assert!(footer.contains("john@example.com"), "{}", footer);
+        // A requested id never changes what a static component shows
+        let other = registry
+            .render_component("site_footer", "2", RenderParams::default())
+            .await
+            .unwrap();
+        assert_eq!(other, footer);
+
+        assert!(matches!(
+            registry.render_component("user_card", "", RenderParams::default()).await,
+            Err(ComponentError::MissingRecordId(name)) if name == "user_card"
+        ));
+        assert!(matches!(
+            registry
+                .render_component_sms("user_card", "", RenderParams::default())
+                .await,
+            Err(ComponentError::MissingRecordId(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_relations_render_as_chips_through_the_join_table() {
+        let tables = [
+            (
+                "articles",
+                r#"
+                [variants.title]
+                strong = { base = "strong" }
+                [variants.tags]
+                chip = { base = "em", override = "chip" }
+                [contexts.card]
+                title = "strong"
+                [relations.tags]
+                through = "article_tags"
+                local_key = "article_id"
+                foreign_key = "tag_id"
+                table = "tags"
+                field = "label"
+                variant = "chip"
+                separator = ", "
+                [[mock_data]]
+                id = "1"
+                title = "Hello"
+                "#,
+            ),
+            (
+                "tags",
+                r#"
+                [variants.label]
+                plain = { base = "em" }
+                [contexts.card]
+                [[mock_data]]
+                id = "t1"
+                label = "rust"
+                [[mock_data]]
+                id = "t2"
+                label = "web"
+                "#,
+            ),
+            (
+                "article_tags",
+                r#"
+                [variants.tag_id]
+                plain = { base = "em" }
+                [contexts.card]
+                [[mock_data]]
+                article_id = "1"
+                tag_id = "t2"
+                [[mock_data]]
+                article_id = "2"
+                tag_id = "t1"
+                [[mock_data]]
+                article_id = "1"
+                tag_id = "gone"
+                [[mock_data]]
+                article_id = "1"
+                tag_id = "t1"
+                "#,
+            ),
+        ];
+        update_registry(|registry| {
+            for (name, toml) in tables {
+                registry.insert_table(name, TableSchema::from_toml_str(toml)?);
+            }
+            Ok::<_, SchemaError>(())
+        })
+        .unwrap();
+
+        let mut registry = ComponentRegistry::new();
+        let component = ComponentTemplate::builder()
+            .name("article_row")
+            .table("articles")
+            .template_str("<li>{title} {tags}</li>")
+            .build()
+            .unwrap();
+        registry.register(component).unwrap();
+
+        let html = registry
+            .render_component("article_row", "1", RenderParams::default())
+            .await
+            .unwrap();
+        let chips = r#"<em class="chip">web</em>, <em class="chip">rust</em>"#;
+        assert_eq!(html, format!("<li><strong>Hello</strong> {}</li>", chips));
+    }
+
+    #[tokio::test]
+    async fn test_deprecated_variants_are_reported() {
+        let schema = TableSchema::from_toml_str(
+            r#"
+            [variants.name]
+            h1 = { base = "h1", deprecated = "use h2 instead" }
+            h2 = { base = "h2" }
+            [contexts.card]
+            name = "h1"
+            [contexts.detail]
+            name = "h2"
+            [contexts.compact]
+            inherits = "card"
+            deprecated = "use card instead"
+            [[mock_data]]
+            id = "1"
+            name = "Ada"
+            "#,
+        )
+        .unwrap();
+        let lints: Vec<_> = lint_table("legacy", &schema)
+            .iter()
+            .map(|d| d.to_string())
+            .collect();
+        assert_eq!(
+            lints,
+            ["warning: legacy: contexts.card.name: \
+              picks deprecated variant 'h1': use h2 instead"]
+        );
+        update_registry(|registry| {
+            registry.insert_table("legacy", schema);
+            Ok::<_, SchemaError>(())
+        })
+        .unwrap();
+
+        let mut registry = ComponentRegistry::new();
+        let component = ComponentTemplate::builder()
+            .name("legacy_card")
+            .table("legacy")
+            .template_str("<div>{name}</div>")
+            .build()
+            .unwrap();
+        registry.register(component).unwrap();
+        let params = RenderParams {
+            context: Some("compact"),
+            ..Default::default()
+        };
+        // Cached renders report them too
+        for _ in 0..2 {
+            let output = registry.render_component_output("legacy_card", "1", params.clone());
+            let output = output.await.unwrap();
+            assert!(output.html.starts_with("<div><h1"), "{}", output.html);
+            let paths: Vec<_> = output
+                .deprecations
+                .iter()
+                .map(|d| d.path.as_str())
+                .collect();
+            assert_eq!(paths, ["contexts.compact", "variants.name.h1"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_required_fields_reject_or_warn() {
+        let schema = TableSchema::from_toml_str(
+            r#"
+            [variants.name]
+            strong = { base = "strong" }
+            [variants.email]
+            link = { base = "a", attrs = { href = "mailto:{value}" } }
+            [contexts.card]
+            required = ["name", "email"]
+            name = "strong"
+            email = "link"
+            [[mock_data]]
+            id = "1"
+            name = "Ada"
+            email = "ada@example.com"
+            [[mock_data]]
+            id = "2"
+            name = "Grace"
+            email = ""
+            "#,
+        )
+        .unwrap();
+        assert_eq!(schema.contexts["card"].required, ["name", "email"]);
+        assert!(!schema.contexts["card"].fields.contains_key("required"));
+        update_registry(|registry| {
+            registry.insert_table("contacts", schema);
+            Ok::<_, SchemaError>(())
+        })
+        .unwrap();
+
+        let mut registry = ComponentRegistry::new();
+        registry.set_cache_enabled(false);
+        let component = ComponentTemplate::builder()
+            .name("contact_card")
+            .table("contacts")
+            .template_str("<div>{name} {email}</div>")
+            .build()
+            .unwrap();
+        registry.register(component).unwrap();
+        let params = RenderParams::default();
+
+        let complete = registry.render_component_output("contact_card", "1", params.clone());
+        assert!(complete.await.unwrap().missing.is_empty());
+        let rejected = registry.render_component_output("contact_card", "2", params.clone());
+        assert!(matches!(
+            rejected.await,
+            Err(ComponentError::MissingFields(context, fields))
+                if context == "card" && fields == ["email"]
+        ));
+
+        update_registry(|registry| {
+            let schema = registry.get_table_mut("contacts").unwrap();
+            schema.missing_required = Some(MissingRequired::Warn);
+            Ok::<_, SchemaError>(())
+        })
+        .unwrap();
+        let output = registry
+            .render_component_output("contact_card", "2", params)
+            .await
+            .unwrap();
+        assert!(output.html.contains("Grace"), "{}", output.html);
+        assert_eq!(output.missing, ["email"]);
+    }
+
+    #[tokio::test]
+    async fn test_dot_paths_render_referenced_records() {
+        let tables = [
+            (
+                "employees",
+                r#"
+                [variants.name]
+                strong = { base = "strong" }
+                [contexts.card]
+                name = "strong"
+                [references.company]
+                table = "companies"
+                key = "company_id"
+                [[mock_data]]
+                id = "1"
+                name = "Ada"
+                company_id = "c1"
+                [[mock_data]]
+                id = "2"
+                name = "Grace"
+                company_id = "gone"
+                "#,
+            ),
+            (
+                "companies",
+                r#"
+                [variants.name]
+                title = { base = "em" }
+                [contexts.card]
+                name = "title"
+                [[mock_data]]
+                id = "c1"
+                name = "Acme"
+                "#,
+            ),
+        ];
+        update_registry(|registry| {
+            for (name, toml) in tables {
+                registry.insert_table(name, TableSchema::from_toml_str(toml)?);
+            }
+            Ok::<_, SchemaError>(())
+        })
+        .unwrap();
+        let companies = registry().render_field("companies", "name", "card", "Acme");
+        assert_eq!(
+            registry().render_field("employees", "company.name", "card", "Acme"),
+            companies
+        );
+
+        let mut components = ComponentRegistry::new();
+        let component = ComponentTemplate::builder()
+            .name("employee_row")
+            .table("employees")
+            .template_str("<li>{name} at {company.name}</li>")
+            .build()
+            .unwrap();
+        components.register(component).unwrap();
+
+        let html = components
+            .render_component("employee_row", "1", RenderParams::default())
+            .await
+            .unwrap();
+        assert_eq!(html, "<li><strong>Ada</strong> at <em>Acme</em></li>");
+        // A dangling reference renders the field empty
+        let html = components
+            .render_component("employee_row", "2", RenderParams::default())
+            .await
+            .unwrap();
+        assert_eq!(html, "<li><strong>Grace</strong> at <em></em></li>");
+    }
+
+    #[tokio::test]
+    async fn test_responsive_component_renders_every_context() {
+        let mut registry = ComponentRegistry::new();
+        let component = ComponentTemplate::builder()
+            .name("user_responsive")
+            .table("users")
+            .template_str("<div>{avatar_url}</div>")
+            .responsive("list", "md:hidden")
+            .responsive("card", "hidden md:block")
+            .build()
+            .unwrap();
+        registry.register(component).unwrap();
+
+        let html = registry
+            .render_component("user_responsive", "1", RenderParams::default())
+            .await
+            .unwrap();
+        let list = html
+            .find(r#"<div class="md:hidden" data-sui-context="list">"#)
+            .unwrap();
+        let card = html
+            .find(r#"<div class="hidden md:block" data-sui-context="card">"#)
+            .unwrap();
+        assert!(list < card);
+
+        // An explicit context renders just that one
+        let params = RenderParams {
+            context: Some("card"),
+            ..Default::default()
+        };
+        let single = registry
+            .render_component("user_responsive", "1", params)
+            .await
+            .unwrap();
+        assert!(!single.contains("data-sui-context"));
+
+        let unknown = ComponentTemplate::builder()
+            .name("broken")
+            .table("users")
+            .template_str("{name}")
+            .responsive("tablet", "hidden")
+            .build()
+            .unwrap();
+        assert!(registry.register(unknown).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_text_formats_keep_the_required_scope() {
+        let schema = TableSchema::from_toml_str(
+            r#"
+            [variants.title]
+            h3 = { base = "h3" }
+            [event]
+            start = "starts_at"
+            title = "title"
+            [short]
+            title = "{value}"
+            [[mock_data]]
+            id = "1"
+            org_id = "7"
+            title = "Standup"
+            starts_at = "2024-01-15T10:00:00Z"
+            [[mock_data]]
+            id = "2"
+            org_id = "8"
+            title = "Offsite"
+            starts_at = "2024-02-01T09:00:00Z"
+            "#,
+        )
+        .unwrap();
+        crate::schema::register_table("org_events", schema).unwrap();
+        let mut registry = ComponentRegistry::new();
+        let component = ComponentTemplate::builder()
+            .name("org_event")
+            .table("org_events")
+            .template_str("{title}")
+            .requires("org_id")
+            .build()
+            .unwrap();
+        registry.register(component).unwrap();
+
+        let org = |id: &str| HashMap::from([("org_id".to_string(), id.to_string())]);
+        let (none, seven, eight) = (HashMap::new(), org("7"), org("8"));
+        let params = |args| RenderParams {
+            args: Some(args),
+            ..Default::default()
+        };
+        let missing = |err: Option<ComponentError>| match err {
+            Some(ComponentError::MissingParams(params)) => params == ["org_id"],
+            _ => false,
+        };
+        let hidden = |err: Option<ComponentError>| match err {
+            Some(ComponentError::RecordNotFound(id)) => id == "1",
+            _ => false,
+        };
+
+        let sms = |args| registry.render_component_sms("org_event", "1", params(args));
+        assert!(missing(sms(&none).await.err()));
+        assert!(hidden(sms(&eight).await.err()));
+        assert!(sms(&seven).await.unwrap().contains("Standup"));
+
+        let push = |args| registry.render_component_push("org_event", "1", params(args));
+        assert!(missing(push(&none).await.err()));
+        assert!(hidden(push(&eight).await.err()));
+
+        for format in [ChatFormat::Slack, ChatFormat::Discord] {
+            let chat =
+                |args| registry.render_component_chat("org_event", "1", format, params(args));
+            assert!(missing(chat(&none).await.err()));
+            assert!(hidden(chat(&eight).await.err()));
+            assert!(chat(&seven).await.is_ok());
+        }
+
+        let ics = |id, args| registry.render_component_ics("org_event", id, None, params(args));
+        assert!(missing(ics(Some("1"), &none).await.err()));
+        assert!(hidden(ics(Some("1"), &eight).await.err()));
+        assert!(missing(ics(None, &none).await.err()));
+        let calendar = ics(None, &seven).await.unwrap();
+        assert!(calendar.contains("Standup"), "{}", calendar);
+        assert!(!calendar.contains("Offsite"), "{}", calendar);
+    }
+
+    #[tokio::test]
+    async fn test_render_component_sms() {
+        let registry = ComponentRegistry::new();
+        let line = registry
+            .render_component_sms("user_card", "1", RenderParams::default())
+            .await
+            .unwrap();
+        assert_eq!(line, "John D. joined Jan 15");
+        assert!(matches!(
+            registry
+                .render_component_sms("user_card", "999", RenderParams::default())
+                .await,
+            Err(ComponentError::RecordNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_render_component_push() {
+        let registry = ComponentRegistry::new();
+        let payload = registry
+            .render_component_push("user_card", "2", RenderParams::default())
+            .await
+            .unwrap();
+        assert_eq!(payload.title, "Jane Smith");
+        assert_eq!(payload.body.as_deref(), Some("Jane joined Jan 14"));
+        assert_eq!(payload.deep_link.as_deref(), Some("app://users/2"));
+
+        let mut registry = registry;
+        let component = ComponentTemplate::builder()
+            .name("unmapped")
+            .table("users")
+            .template_str("{name}")
+            .build()
+            .unwrap();
+        registry.register(component).unwrap();
+        assert!(matches!(
+            registry
+                .render_component_push("unmapped", "1", RenderParams::default())
+                .await,
+            Err(ComponentError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_render_component_ics_requires_event_table() {
+        let registry = ComponentRegistry::new();
+        assert!(matches!(
+            registry
+                .render_component_ics("user_card", Some("1"), None, RenderParams::default())
+                .await,
+            Err(ComponentError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_jsonld_block_is_opt_in() {
+        let registry = ComponentRegistry::new();
+        let plain = registry
+            .render_component("user_card", "1", RenderParams::default())
+            .await
+            .unwrap();
+        assert!(!plain.contains("application/ld+json"));
+
+        let params = RenderParams {
+            jsonld: true,
+            ..Default::default()
+        };
+        let html = registry
+            .render_component("user_card", "1", params)
+            .await
+            .unwrap();
+        assert!(html.ends_with("</script>"));
+        assert!(html.contains(r#""@type":"Person""#));
+        assert!(html.contains(r#""name":"John Doe""#));
+    }
+
+    #[tokio::test]
+    async fn test_print_platform() {
+        let registry = ComponentRegistry::new();
+        let screen = registry
+            .render_component("user_card", "1", RenderParams::default())
+            .await
+            .unwrap();
+        assert!(screen.contains("href=\"mailto:john@example.com\""));
+
+        let params = RenderParams {
+            platform: Some("print"),
+            ..Default::default()
+        };
+        let print = registry
+            .render_component("user_card", "1", params)
+            .await
+            .unwrap();
+        assert!(!print.contains("href="));
+        assert!(print.contains("john@example.com"));
+        // The table's print context swaps in the small avatar
+        assert!(print.contains("w-8 h-8"));
+        assert!(print.contains("text-sm text-black"));
+    }
+
+    #[test]
+    fn test_trim_markers() {
+        let template = "<p>\n    {~name}\n</p>\n<p>{email~}\n   </p>";
+        assert_eq!(
+            apply_trim_markers(template),
+            "<p>{name}\n</p>\n<p>{email}</p>"
+        );
+
+        let component = ComponentTemplate::builder()
+            .name("trimmed")
+            .table("users")
+            .template_str("<b>\n {~name~} </b>")
+            .build()
+            .unwrap();
+        assert_eq!(component.template, "<b>{name}</b>");
+        assert_eq!(component.required_fields, vec!["name".to_string()]);
+    }
+
+    #[test]
+    fn test_resolve_includes() {
+        let root = std::env::temp_dir().join(format!("uuie_partials_{}", std::process::id()));
+        std::fs::create_dir_all(root.join("partials")).unwrap();
+        std::fs::write(root.join("partials/legal.html"), "<small>{name}</small>").unwrap();
+        std::fs::write(
+            root.join("partials/footer.html"),
+            r#"<footer>{include "partials/legal.html"}</footer>"#,
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("partials/loop.html"),
+            r#"{include "partials/loop.html"}"#,
+        )
+        .unwrap();
+
+        let html = resolve_includes(
+            r#"<div>{include "partials/footer.html"}</div>"#,
+            &root,
+            &mut Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(html, "<div><footer><small>{name}</small></footer></div>");
+
+        let cycle = resolve_includes(r#"{include "partials/loop.html"}"#, &root, &mut Vec::new());
+        assert!(matches!(cycle, Err(ComponentError::IncludeError(_))));
+
+        let escape = resolve_includes(r#"{include "../secret.html"}"#, &root, &mut Vec::new());
+        assert!(matches!(escape, Err(ComponentError::IncludeError(_))));
+
+        std::fs::remove_dir_all(root).ok();
+    }
+}
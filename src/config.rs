@@ -0,0 +1,421 @@
+// src/config.rs - Runtime configuration loaded from environment variables
+use crate::attr_policy::AttrPolicy;
+use crate::content_scan::ScanAction;
+use crate::currency::parse_rates;
+use crate::oembed::OembedRoute;
+use crate::scheduler::ScheduledTask;
+use std::collections::HashMap;
+use std::env;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    // Deployment environment name (APP_ENV, default "production"); dev-only features
+    // such as chaos, hot reload and debug output need "development"
+    pub environment: String,
+    // Maximum number of rows rendered concurrently by a single collection render
+    pub max_concurrent_renders: usize,
+    // How long rendered components stay cached (0 disables the cache)
+    pub render_cache_ttl_secs: u64,
+    // Most renders kept in the cache; the least recently used are evicted
+    pub render_cache_capacity: usize,
+    // How long expired renders are kept as fallbacks for data source outages
+    pub render_cache_stale_secs: u64,
+    // Components pre-rendered into the cache at startup
+    pub warmup: Vec<WarmupEntry>,
+    // Root directory for `{include "..."}` paths in component templates
+    pub template_dir: PathBuf,
+    // Minify every component render (collapse whitespace between tags)
+    pub minify_html: bool,
+    // Load schemas from this directory instead of the embedded copies;
+    // schema edits are persisted here
+    pub schemas_dir: Option<PathBuf>,
+    // Binary registry bundle (see `compile-registry`) preferred over TOML at startup
+    pub compiled_registry: Option<PathBuf>,
+    // Bearer token required by the schema editor endpoints (unset = disabled)
+    pub schema_editor_token: Option<String>,
+    // Number of published schema bundles kept for rollback
+    pub schema_history_limit: usize,
+    // Fail budget checks (e.g. in CI) when any render exceeds its budget
+    pub strict_budgets: bool,
+    // Render-time budget in ms for the built-in components, and per-component
+    // overrides ("user_card:20,banner:5"); 0 turns a budget off
+    pub component_budget_ms: f64,
+    pub component_budgets: HashMap<String, f64>,
+    // Shortest gap between two "budget exceeded" log lines for one component or field
+    pub budget_log_interval_secs: u64,
+    // Fault injection for data source calls (development only)
+    pub chaos_latency_ms: u64,
+    pub chaos_jitter_ms: u64,
+    pub chaos_error_rate: f64,
+    // Consecutive data source failures that open the circuit breaker
+    pub breaker_failure_threshold: u32,
+    // How long an open breaker waits before letting a probe call through
+    pub breaker_reset_secs: u64,
+    // Record backend: "mock" (embedded mock data), "postgres" or "http"
+    pub data_source: String,
+    // Base URL of the HTTP data source, e.g. http://records.internal:8080
+    pub data_source_url: Option<String>,
+    // Retry policy for transient data source errors
+    pub data_retry_attempts: u32,
+    pub data_retry_base_ms: u64,
+    pub data_retry_max_ms: u64,
+    // Distinct field renders memoized per schema registry (0 disables)
+    pub field_memo_capacity: usize,
+    // Hosts /img may fetch from (comma separated, subdomains included; empty disables)
+    pub image_proxy_allowlist: Vec<String>,
+    // Largest width or height /img will resize to
+    pub image_proxy_max_dimension: u32,
+    // How long resized images stay cached (also sent as Cache-Control max-age)
+    pub image_proxy_cache_ttl_secs: u64,
+    // Seconds to wait for an upstream image before giving up
+    pub image_proxy_timeout_secs: u64,
+    // Upstream image fetches allowed to run at once
+    pub image_proxy_max_fetches: usize,
+    // Static map images for `kind = "map"` variants: "osm" or "mapbox"
+    pub map_provider: String,
+    pub mapbox_access_token: Option<String>,
+    // Custom static map URL ({lat} {lng} {zoom} {width} {height} {token}); overrides the provider
+    pub map_static_url: Option<String>,
+    // Currency amounts are stored in unless a variant or value says otherwise
+    pub currency_base: String,
+    // Fixed exchange rates per unit of currency_base ("EUR:0.92,GBP:0.79")
+    pub currency_rates: HashMap<String, f64>,
+    // JSON rate source ({"base": .., "rates": {..}}); takes precedence over currency_rates
+    pub currency_rates_url: Option<String>,
+    pub currency_rates_ttl_secs: u64,
+    // Public URL patterns served by /oembed ("user_card=https://example.com/users/{id}")
+    pub oembed_routes: Vec<OembedRoute>,
+    // Largest embed size advertised; clients may ask for less via maxwidth/maxheight
+    pub oembed_width: u32,
+    pub oembed_height: u32,
+    // Scan action for tables that do not set `content_scan` (off when unset)
+    pub content_scan: Option<ScanAction>,
+    // Longest run of non-whitespace characters a scanned value may contain
+    pub content_scan_max_token: usize,
+    // Attributes schema variants may set (ATTR_ALLOW / ATTR_DENY, see attr_policy)
+    pub attr_policy: AttrPolicy,
+    // Fields for static components (footer, banner) as a JSON object of strings;
+    // a settings row, when the table has one, overrides them
+    pub site_settings: HashMap<String, String>,
+    // Background jobs are saved here and survive restarts (unset = memory only)
+    pub jobs_dir: Option<PathBuf>,
+    // Jobs allowed to wait for their turn; more are refused until some start
+    pub jobs_max_queued: usize,
+    // Finished jobs kept (newest first) and how long each is kept
+    pub jobs_max_finished: usize,
+    pub jobs_finished_ttl_secs: u64,
+    // Periodic refresh and export tasks (SCHEDULE, see scheduler.rs)
+    pub schedule: Vec<ScheduledTask>,
+    // Where scheduled exports are written (unset = kept as job results only)
+    pub export_dir: Option<PathBuf>,
+    // Count rendered components, contexts, variants and themes (see analytics.rs)
+    pub usage_analytics: bool,
+    // Where `kind = "upload"` files go: "local" (upload_dir) or "s3" (s3_bucket)
+    pub upload_storage: String,
+    // Local upload directory and the URL it is served from
+    pub upload_dir: PathBuf,
+    pub upload_base_url: String,
+    // Largest file one upload may send
+    pub upload_max_bytes: u64,
+    // S3 (or compatible) bucket for uploads; credentials from AWS_ACCESS_KEY_ID/AWS_SECRET_ACCESS_KEY
+    pub s3_bucket: Option<String>,
+    pub s3_region: String,
+    // Custom endpoint for S3-compatible stores (default https://s3.<region>.amazonaws.com)
+    pub s3_endpoint: Option<String>,
+    pub s3_access_key: Option<String>,
+    pub s3_secret_key: Option<String>,
+}
+
+// One (component, id, context, theme) combination to pre-render at startup
+#[derive(Debug, Clone, PartialEq)]
+pub struct WarmupEntry {
+    pub component: String,
+    pub id: String,
+    pub context: Option<String>,
+    pub theme: Option<String>,
+}
+
+impl WarmupEntry {
+    // Parse "component:id[:context[:theme]]"
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut parts = spec.trim().split(':').map(str::trim);
+        let component = parts.next().filter(|s| !s.is_empty())?;
+        let id = parts.next().filter(|s| !s.is_empty())?;
+        let context = parts.next().filter(|s| !s.is_empty());
+        let theme = parts.next().filter(|s| !s.is_empty());
+
+        Some(Self {
+            component: component.to_string(),
+            id: id.to_string(),
+            context: context.map(str::to_string),
+            theme: theme.map(str::to_string),
+        })
+    }
+
+    // Parse a comma separated list, skipping malformed entries
+    pub fn parse_list(specs: &str) -> Vec<Self> {
+        specs
+            .split(',')
+            .filter(|spec| !spec.trim().is_empty())
+            .filter_map(|spec| {
+                let entry = Self::parse(spec);
+                if entry.is_none() {
+                    eprintln!("Ignoring malformed warm-up entry: {}", spec);
+                }
+                entry
+            })
+            .collect()
+    }
+}
+
+impl Config {
+    // Build configuration from environment variables, falling back to defaults
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        Self {
+            environment: env::var("APP_ENV").unwrap_or(defaults.environment),
+            max_concurrent_renders: env_parse("MAX_CONCURRENT_RENDERS")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.max_concurrent_renders),
+            render_cache_ttl_secs: env_parse("RENDER_CACHE_TTL")
+                .unwrap_or(defaults.render_cache_ttl_secs),
+            render_cache_capacity: env_parse("RENDER_CACHE_CAPACITY")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.render_cache_capacity),
+            render_cache_stale_secs: env_parse("RENDER_CACHE_STALE")
+                .unwrap_or(defaults.render_cache_stale_secs),
+            warmup: env::var("WARMUP_COMPONENTS")
+                .map(|specs| WarmupEntry::parse_list(&specs))
+                .unwrap_or(defaults.warmup),
+            template_dir: env::var("TEMPLATE_DIR")
+                .map(PathBuf::from)
+                .unwrap_or(defaults.template_dir),
+            minify_html: env_parse("MINIFY_HTML").unwrap_or(defaults.minify_html),
+            schemas_dir: env::var("SCHEMAS_DIR").ok().map(PathBuf::from),
+            compiled_registry: env::var("COMPILED_REGISTRY").ok().map(PathBuf::from),
+            schema_editor_token: env::var("SCHEMA_EDITOR_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty()),
+            schema_history_limit: env_parse("SCHEMA_HISTORY_LIMIT")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.schema_history_limit),
+            strict_budgets: env_parse("STRICT_BUDGETS").unwrap_or(defaults.strict_budgets),
+            component_budget_ms: env_parse("COMPONENT_BUDGET_MS")
+                .filter(|ms: &f64| *ms >= 0.0)
+                .unwrap_or(defaults.component_budget_ms),
+            component_budgets: env::var("COMPONENT_BUDGETS")
+                .map(|spec| parse_budgets(&spec))
+                .unwrap_or(defaults.component_budgets),
+            budget_log_interval_secs: env_parse("BUDGET_LOG_INTERVAL")
+                .unwrap_or(defaults.budget_log_interval_secs),
+            chaos_latency_ms: env_parse("CHAOS_LATENCY_MS").unwrap_or(defaults.chaos_latency_ms),
+            chaos_jitter_ms: env_parse("CHAOS_JITTER_MS").unwrap_or(defaults.chaos_jitter_ms),
+            chaos_error_rate: env_parse("CHAOS_ERROR_RATE").unwrap_or(defaults.chaos_error_rate),
+            breaker_failure_threshold: env_parse("BREAKER_FAILURE_THRESHOLD")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.breaker_failure_threshold),
+            breaker_reset_secs: env_parse("BREAKER_RESET_SECS")
+                .unwrap_or(defaults.breaker_reset_secs),
+            data_source: env::var("DATA_SOURCE").unwrap_or(defaults.data_source),
+            data_source_url: env::var("DATA_SOURCE_URL").ok(),
+            data_retry_attempts: env_parse("DATA_RETRY_ATTEMPTS")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.data_retry_attempts),
+            data_retry_base_ms: env_parse("DATA_RETRY_BASE_MS")
+                .unwrap_or(defaults.data_retry_base_ms),
+            data_retry_max_ms: env_parse("DATA_RETRY_MAX_MS").unwrap_or(defaults.data_retry_max_ms),
+            field_memo_capacity: env_parse("FIELD_MEMO_CAPACITY")
+                .unwrap_or(defaults.field_memo_capacity),
+            image_proxy_allowlist: env::var("IMAGE_PROXY_ALLOWLIST")
+                .map(|hosts| {
+                    hosts
+                        .split(',')
+                        .map(|host| host.trim().to_string())
+                        .filter(|host| !host.is_empty())
+                        .collect()
+                })
+                .unwrap_or(defaults.image_proxy_allowlist),
+            image_proxy_max_dimension: env_parse("IMAGE_PROXY_MAX_DIMENSION")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.image_proxy_max_dimension),
+            image_proxy_cache_ttl_secs: env_parse("IMAGE_PROXY_CACHE_TTL")
+                .unwrap_or(defaults.image_proxy_cache_ttl_secs),
+            image_proxy_timeout_secs: env_parse("IMAGE_PROXY_TIMEOUT")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.image_proxy_timeout_secs),
+            image_proxy_max_fetches: env_parse("IMAGE_PROXY_MAX_FETCHES")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.image_proxy_max_fetches),
+            map_provider: env::var("MAP_PROVIDER").unwrap_or(defaults.map_provider),
+            mapbox_access_token: env::var("MAPBOX_ACCESS_TOKEN").ok(),
+            map_static_url: env::var("MAP_STATIC_URL").ok(),
+            currency_base: env::var("CURRENCY_BASE")
+                .map(|code| code.to_ascii_uppercase())
+                .unwrap_or(defaults.currency_base),
+            currency_rates: env::var("CURRENCY_RATES")
+                .map(|spec| parse_rates(&spec))
+                .unwrap_or(defaults.currency_rates),
+            currency_rates_url: env::var("CURRENCY_RATES_URL").ok(),
+            currency_rates_ttl_secs: env_parse("CURRENCY_RATES_TTL")
+                .unwrap_or(defaults.currency_rates_ttl_secs),
+            oembed_routes: env::var("OEMBED_ROUTES")
+                .map(|specs| OembedRoute::parse_list(&specs))
+                .unwrap_or(defaults.oembed_routes),
+            oembed_width: env_parse("OEMBED_WIDTH")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.oembed_width),
+            oembed_height: env_parse("OEMBED_HEIGHT")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.oembed_height),
+            content_scan: env_parse("CONTENT_SCAN").or(defaults.content_scan),
+            content_scan_max_token: env_parse("CONTENT_SCAN_MAX_TOKEN")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.content_scan_max_token),
+            attr_policy: AttrPolicy::parse(
+                env::var("ATTR_ALLOW").ok().as_deref(),
+                env::var("ATTR_DENY").ok().as_deref(),
+            ),
+            site_settings: env::var("SITE_SETTINGS")
+                .ok()
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or(defaults.site_settings),
+            jobs_dir: env::var("JOBS_DIR").ok().map(PathBuf::from),
+            jobs_max_queued: env_parse("JOBS_MAX_QUEUED")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.jobs_max_queued),
+            jobs_max_finished: env_parse("JOBS_MAX_FINISHED")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.jobs_max_finished),
+            jobs_finished_ttl_secs: env_parse("JOBS_FINISHED_TTL")
+                .unwrap_or(defaults.jobs_finished_ttl_secs),
+            schedule: env::var("SCHEDULE")
+                .map(|specs| ScheduledTask::parse_list(&specs))
+                .unwrap_or(defaults.schedule),
+            export_dir: env::var("EXPORT_DIR").ok().map(PathBuf::from),
+            usage_analytics: env_parse("USAGE_ANALYTICS").unwrap_or(defaults.usage_analytics),
+            upload_storage: env::var("UPLOAD_STORAGE").unwrap_or(defaults.upload_storage),
+            upload_dir: env::var("UPLOAD_DIR")
+                .map(PathBuf::from)
+                .unwrap_or(defaults.upload_dir),
+            upload_base_url: env::var("UPLOAD_BASE_URL")
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or(defaults.upload_base_url),
+            upload_max_bytes: env_parse("UPLOAD_MAX_BYTES")
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.upload_max_bytes),
+            s3_bucket: env::var("S3_BUCKET")
+                .ok()
+                .filter(|bucket| !bucket.is_empty()),
+            s3_region: env::var("S3_REGION")
+                .or_else(|_| env::var("AWS_REGION"))
+                .unwrap_or(defaults.s3_region),
+            s3_endpoint: env::var("S3_ENDPOINT").ok(),
+            s3_access_key: env::var("AWS_ACCESS_KEY_ID").ok(),
+            s3_secret_key: env::var("AWS_SECRET_ACCESS_KEY").ok(),
+        }
+    }
+}
+
+impl Config {
+    // Dev-only conveniences (pretty output, debug annotations) are gated on this
+    pub fn is_development(&self) -> bool {
+        matches!(self.environment.as_str(), "development" | "dev" | "local")
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            environment: "production".to_string(),
+            max_concurrent_renders: 16,
+            render_cache_ttl_secs: 60,
+            render_cache_capacity: crate::cache::DEFAULT_CAPACITY,
+            render_cache_stale_secs: crate::cache::DEFAULT_STALE_FOR.as_secs(),
+            warmup: Vec::new(),
+            template_dir: PathBuf::from("templates"),
+            minify_html: false,
+            schemas_dir: None,
+            compiled_registry: None,
+            schema_editor_token: None,
+            schema_history_limit: 10,
+            strict_budgets: false,
+            component_budget_ms: 20.0,
+            component_budgets: HashMap::new(),
+            budget_log_interval_secs: 60,
+            chaos_latency_ms: 0,
+            chaos_jitter_ms: 0,
+            chaos_error_rate: 0.0,
+            breaker_failure_threshold: 5,
+            breaker_reset_secs: 30,
+            data_source: "mock".to_string(),
+            data_source_url: None,
+            data_retry_attempts: 3,
+            data_retry_base_ms: 50,
+            data_retry_max_ms: 1000,
+            field_memo_capacity: 10_000,
+            image_proxy_allowlist: Vec::new(),
+            image_proxy_max_dimension: 1024,
+            image_proxy_cache_ttl_secs: 3600,
+            image_proxy_timeout_secs: 10,
+            image_proxy_max_fetches: 8,
+            map_provider: "osm".to_string(),
+            mapbox_access_token: None,
+            map_static_url: None,
+            currency_base: "USD".to_string(),
+            currency_rates: HashMap::new(),
+            currency_rates_url: None,
+            currency_rates_ttl_secs: 3600,
+            oembed_routes: Vec::new(),
+            oembed_width: 480,
+            oembed_height: 240,
+            content_scan: None,
+            content_scan_max_token: 256,
+            attr_policy: AttrPolicy::default(),
+            site_settings: HashMap::new(),
+            jobs_dir: None,
+            jobs_max_queued: 100,
+            jobs_max_finished: 200,
+            jobs_finished_ttl_secs: 7 * 24 * 3600,
+            schedule: Vec::new(),
+            export_dir: None,
+            usage_analytics: false,
+            upload_storage: "local".to_string(),
+            upload_dir: PathBuf::from("uploads"),
+            upload_base_url: "/uploads".to_string(),
+            upload_max_bytes: 10 * 1024 * 1024,
+            s3_bucket: None,
+            s3_region: "us-east-1".to_string(),
+            s3_endpoint: None,
+            s3_access_key: None,
+            s3_secret_key: None,
+        }
+    }
+}
+
+// "user_card:20,banner:5" -> budget in ms per component
+fn parse_budgets(spec: &str) -> HashMap<String, f64> {
+    spec.split(',')
+        .filter_map(|entry| {
+            let (component, ms) = entry.split_once(':')?;
+            let ms: f64 = ms.trim().parse().ok().filter(|ms: &f64| *ms >= 0.0)?;
+            Some((component.trim().to_string(), ms))
+        })
+        .collect()
+}
+
+// Parse an environment variable into any FromStr type
+fn env_parse<T: std::str::FromStr>(key: &str) -> Option<T> {
+    env::var(key)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+}
+
+// Global configuration
+use std::sync::OnceLock;
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+pub fn config() -> &'static Config {
+    CONFIG.get_or_init(Config::from_env)
+}
@@ -0,0 +1,229 @@
+// src/content_scan.rs - Render-time scanning of field values
+//
+// Defense in depth for tables holding user-generated content: values are
+// checked for embedded scripts, data: URIs and implausibly long tokens before
+// rendering. Tables opt in with `content_scan = "strip" | "reject" | "flag"`
+// (CONTENT_SCAN sets a default for every table).
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanAction {
+    // Remove the suspicious parts and render the rest
+    Strip,
+    // Refuse to render the record
+    Reject,
+    // Render unchanged, reporting the fields (X-Content-Flagged header)
+    Flag,
+}
+
+impl std::str::FromStr for ScanAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "strip" => Ok(ScanAction::Strip),
+            "reject" => Ok(ScanAction::Reject),
+            "flag" => Ok(ScanAction::Flag),
+            other => Err(format!("unknown content scan action '{}'", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Finding {
+    Script,
+    DataUri,
+    LongToken,
+}
+
+impl std::fmt::Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Finding::Script => write!(f, "embedded script"),
+            Finding::DataUri => write!(f, "data: URI"),
+            Finding::LongToken => write!(f, "overlong token"),
+        }
+    }
+}
+
+// What was found in which field
+pub type ScanReport = Vec<(String, Finding)>;
+
+// 🛡️ Scan `fields` of a record. Strip rewrites the record in place; Reject
+// fails on the first finding; every action reports what it found.
+pub fn scan_record(
+    action: ScanAction,
+    fields: &[String],
+    record: &mut HashMap<String, String>,
+    max_token: usize,
+) -> Result<ScanReport, (String, Finding)> {
+    let mut report = Vec::new();
+    for field in fields {
+        let Some(value) = record.get_mut(field) else {
+            continue;
+        };
+        let findings = scan(value, max_token);
+        if findings.is_empty() {
+            continue;
+        }
+        match action {
+            ScanAction::Reject => return Err((field.clone(), findings[0])),
+            ScanAction::Strip => *value = strip(value, &findings, max_token),
+            ScanAction::Flag => {}
+        }
+        report.extend(findings.into_iter().map(|finding| (field.clone(), finding)));
+    }
+    Ok(report)
+}
+
+// Everything suspicious about a value, at most once per kind
+pub fn scan(value: &str, max_token: usize) -> Vec<Finding> {
+    let lower = value.to_ascii_lowercase();
+    let mut findings = Vec::new();
+    if has_script(&lower) {
+        findings.push(Finding::Script);
+    }
+    if tokens(&lower).any(|token| token.starts_with("data:")) {
+        findings.push(Finding::DataUri);
+    }
+    if value
+        .split_whitespace()
+        .any(|token| token.chars().count() > max_token)
+    {
+        findings.push(Finding::LongToken);
+    }
+    findings
+}
+
+fn has_script(lower: &str) -> bool {
+    if lower.contains("<script") || lower.contains("javascript:") || lower.contains("vbscript:") {
+        return true;
+    }
+    // Event handler attributes inside markup: <img src=x onerror=...>
+    lower.contains('<')
+        && tokens(lower).any(|token| {
+            token.len() > 2
+                && token.starts_with("on")
+                && token[2..].chars().all(|c| c.is_ascii_lowercase())
+                && lower.contains(&format!("{}=", token))
+        })
+}
+
+// Words of a value split at whitespace, quotes, '=', parentheses and angle brackets
+fn tokens(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '=' | '(' | ')' | '<' | '>'))
+        .filter(|token| !token.is_empty())
+}
+
+fn strip(value: &str, findings: &[Finding], max_token: usize) -> String {
+    let mut value = value.to_string();
+    if findings.contains(&Finding::Script) {
+        value = remove_script_blocks(&value);
+        for scheme in ["javascript:", "vbscript:"] {
+            value = remove_ignore_case(&value, scheme);
+        }
+        // Whatever markup is left is shown as text, never parsed
+        value = value.replace('<', "&lt;").replace('>', "&gt;");
+    }
+    if findings.contains(&Finding::DataUri) {
+        value = value
+            .split(' ')
+            .filter(|word| !word.to_ascii_lowercase().starts_with("data:"))
+            .collect::<Vec<_>>()
+            .join(" ");
+    }
+    if findings.contains(&Finding::LongToken) {
+        value = value
+            .split(' ')
+            .map(|word| match word.char_indices().nth(max_token) {
+                Some((cut, _)) => format!("{}…", &word[..cut]),
+                None => word.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+    }
+    value
+}
+
+// Drop <script>...</script> blocks (an unterminated one runs to the end)
+fn remove_script_blocks(value: &str) -> String {
+    let mut result = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.to_ascii_lowercase().find("<script") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start..];
+        rest = match after.to_ascii_lowercase().find("</script>") {
+            Some(end) => &after[end + "</script>".len()..],
+            None => "",
+        };
+    }
+    result.push_str(rest);
+    result
+}
+
+fn remove_ignore_case(value: &str, needle: &str) -> String {
+    let mut result = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.to_ascii_lowercase().find(needle) {
+        result.push_str(&rest[..start]);
+        rest = &rest[start + needle.len()..];
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_actions() {
+        assert!(scan("Plain bio, with <b>bold</b> text", 50).is_empty());
+        assert_eq!(
+            scan("<img src=x onerror=alert(1)>", 50),
+            vec![Finding::Script]
+        );
+        assert_eq!(scan("one=two onwards", 50), Vec::<Finding>::new());
+        assert_eq!(
+            scan("data:text/html;base64,PHN", 50),
+            vec![Finding::DataUri]
+        );
+        assert_eq!(scan(&"a".repeat(51), 50), vec![Finding::LongToken]);
+
+        let fields = ["bio".to_string(), "avatar".to_string()];
+        let bio = "Hi<SCRIPT>steal()</script> <a href=JavaScript:x>me</a>";
+        let record = HashMap::from([
+            ("bio".to_string(), bio.to_string()),
+            (
+                "avatar".to_string(),
+                "data:image/png;base64,AAAA".to_string(),
+            ),
+        ]);
+
+        let mut flagged = record.clone();
+        let report = scan_record(ScanAction::Flag, &fields, &mut flagged, 50).unwrap();
+        assert_eq!(
+            report,
+            vec![
+                ("bio".to_string(), Finding::Script),
+                ("avatar".to_string(), Finding::DataUri),
+            ]
+        );
+        assert_eq!(flagged, record);
+
+        let mut stripped = record.clone();
+        scan_record(ScanAction::Strip, &fields, &mut stripped, 50).unwrap();
+        assert_eq!(stripped["bio"], "Hi &lt;a href=x&gt;me&lt;/a&gt;");
+        assert_eq!(stripped["avatar"], "");
+
+        let mut rejected = record.clone();
+        assert_eq!(
+            scan_record(ScanAction::Reject, &fields, &mut rejected, 50),
+            Err(("bio".to_string(), Finding::Script))
+        );
+        assert_eq!("FLAG".parse(), Ok(ScanAction::Flag));
+    }
+}
@@ -0,0 +1,338 @@
+// src/currency.rs - Exchange rates for displaying amounts in the viewer's currency
+//
+// Rates come from a pluggable RateProvider and are cached for a TTL. Rendering
+// is synchronous, so renders only read the cached table; the component
+// registry refreshes it (asynchronously) before renders that need it.
+use crate::config::config;
+use crate::http_source::fetch_url;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+// Renders wait for a refresh, so a slow rate source must not hold them up long
+const FETCH_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RateError {
+    Unavailable(String),
+    Invalid(String),
+}
+
+impl std::fmt::Display for RateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RateError::Unavailable(msg) => write!(f, "Exchange rates unavailable: {}", msg),
+            RateError::Invalid(msg) => write!(f, "Invalid exchange rates: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for RateError {}
+
+// Units of each currency per one unit of `base`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateTable {
+    pub base: String,
+    pub rates: HashMap<String, f64>,
+}
+
+impl RateTable {
+    fn rate(&self, currency: &str) -> Option<f64> {
+        if currency.eq_ignore_ascii_case(&self.base) {
+            return Some(1.0);
+        }
+        self.rates
+            .get(&currency.to_ascii_uppercase())
+            .copied()
+            .filter(|rate| *rate > 0.0)
+    }
+
+    pub fn convert(&self, amount: f64, from: &str, to: &str) -> Option<f64> {
+        Some(amount / self.rate(from)? * self.rate(to)?)
+    }
+}
+
+#[async_trait]
+pub trait RateProvider: Send + Sync + std::fmt::Debug {
+    fn name(&self) -> &str;
+
+    async fn fetch_rates(&self) -> Result<RateTable, RateError>;
+}
+
+// Fixed rates, e.g. from CURRENCY_RATES="EUR:0.92,GBP:0.79"
+#[derive(Debug, Clone)]
+pub struct StaticRateProvider {
+    table: RateTable,
+}
+
+impl StaticRateProvider {
+    pub fn new(base: &str, rates: HashMap<String, f64>) -> Self {
+        Self {
+            table: RateTable {
+                base: base.to_ascii_uppercase(),
+                rates,
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl RateProvider for StaticRateProvider {
+    fn name(&self) -> &str {
+        "static"
+    }
+
+    async fn fetch_rates(&self) -> Result<RateTable, RateError> {
+        Ok(self.table.clone())
+    }
+}
+
+// JSON endpoint answering `{"base": "USD", "rates": {"EUR": 0.92, ...}}`
+// (the shape used by Frankfurter, exchangerate.host and similar services)
+#[derive(Debug, Clone)]
+pub struct HttpRateProvider {
+    url: String,
+    base: String,
+}
+
+impl HttpRateProvider {
+    pub fn new(url: &str, base: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            base: base.to_ascii_uppercase(),
+        }
+    }
+}
+
+#[async_trait]
+impl RateProvider for HttpRateProvider {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    async fn fetch_rates(&self) -> Result<RateTable, RateError> {
+        let (status, body) = tokio::time::timeout(FETCH_TIMEOUT, fetch_url(&self.url))
+            .await
+            .map_err(|_| RateError::Unavailable(format!("timed out fetching {}", self.url)))?
+            .map_err(|e| RateError::Unavailable(e.to_string()))?;
+        if !(200..300).contains(&status) {
+            return Err(RateError::Unavailable(format!(
+                "HTTP {} from {}",
+                status, self.url
+            )));
+        }
+
+        let json: Value =
+            serde_json::from_slice(&body).map_err(|e| RateError::Invalid(e.to_string()))?;
+        let rates = json
+            .get("rates")
+            .and_then(Value::as_object)
+            .ok_or_else(|| RateError::Invalid("missing \"rates\" object".to_string()))?
+            .iter()
+            .filter_map(|(code, rate)| Some((code.to_ascii_uppercase(), rate.as_f64()?)))
+            .collect();
+        let base = json
+            .get("base")
+            .and_then(Value::as_str)
+            .map(str::to_ascii_uppercase)
+            .unwrap_or_else(|| self.base.clone());
+
+        Ok(RateTable { base, rates })
+    }
+}
+
+#[derive(Debug)]
+pub struct CurrencyConverter {
+    provider: Arc<dyn RateProvider>,
+    ttl: Duration,
+    rates: RwLock<Option<(RateTable, Instant)>>,
+    // Only one refresh at a time; concurrent renders wait for its result
+    refreshing: tokio::sync::Mutex<()>,
+}
+
+impl CurrencyConverter {
+    pub fn new(provider: Arc<dyn RateProvider>, ttl: Duration) -> Self {
+        Self {
+            provider,
+            ttl,
+            rates: RwLock::new(None),
+            refreshing: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn from_config() -> Self {
+        let config = config();
+        let provider: Arc<dyn RateProvider> = match &config.currency_rates_url {
+            Some(url) => Arc::new(HttpRateProvider::new(url, &config.currency_base)),
+            None => Arc::new(StaticRateProvider::new(
+                &config.currency_base,
+                config.currency_rates.clone(),
+            )),
+        };
+        Self::new(
+            provider,
+            Duration::from_secs(config.currency_rates_ttl_secs),
+        )
+    }
+
+    fn is_fresh(&self) -> bool {
+        let rates = self
+            .rates
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        rates
+            .as_ref()
+            .is_some_and(|(_, fetched_at)| fetched_at.elapsed() < self.ttl)
+    }
+
+    // 💱 Fetch rates when missing or expired. On failure the previous table
+    // keeps being used (amounts render unconverted if there never was one).
+    pub async fn refresh_if_stale(&self) -> Result<(), RateError> {
+        if self.is_fresh() {
+            return Ok(());
+        }
+        let _guard = self.refreshing.lock().await;
+        if self.is_fresh() {
+            return Ok(());
+        }
+
+        let table = self.provider.fetch_rates().await?;
+        let mut rates = self
+            .rates
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *rates = Some((table, Instant::now()));
+        Ok(())
+    }
+
+    // Convert with the cached rates, if both currencies are known
+    pub fn convert(&self, amount: f64, from: &str, to: &str) -> Option<f64> {
+        if from.eq_ignore_ascii_case(to) {
+            return Some(amount);
+        }
+        let rates = self
+            .rates
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        rates.as_ref()?.0.convert(amount, from, to)
+    }
+
+    pub fn provider(&self) -> &Arc<dyn RateProvider> {
+        &self.provider
+    }
+}
+
+static CURRENCY_CONVERTER: OnceLock<CurrencyConverter> = OnceLock::new();
+
+pub fn currency_converter() -> &'static CurrencyConverter {
+    CURRENCY_CONVERTER.get_or_init(CurrencyConverter::from_config)
+}
+
+// Use a custom rate source (keeping the configured TTL). Fails if the global
+// converter is already in use.
+pub fn set_rate_provider(provider: Arc<dyn RateProvider>) -> Result<(), Arc<dyn RateProvider>> {
+    let ttl = Duration::from_secs(config().currency_rates_ttl_secs);
+    CURRENCY_CONVERTER
+        .set(CurrencyConverter::new(provider, ttl))
+        .map_err(|converter| converter.provider)
+}
+
+// Parse "EUR:0.92,GBP:0.79" (entries that do not parse are skipped)
+pub fn parse_rates(spec: &str) -> HashMap<String, f64> {
+    spec.split(',')
+        .filter_map(|entry| {
+            let (code, rate) = entry.split_once(':')?;
+            Some((code.trim().to_ascii_uppercase(), rate.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+// "$1,234.50", "€12.00", "¥1,200", "CHF 9.95"
+pub fn format_amount(amount: f64, currency: &str) -> String {
+    let currency = currency.to_ascii_uppercase();
+    let decimals = match currency.as_str() {
+        "JPY" | "KRW" | "VND" | "CLP" | "ISK" => 0,
+        _ => 2,
+    };
+    let fixed = format!("{:.*}", decimals, amount.abs());
+    let (whole, fraction) = fixed.split_once('.').unwrap_or((&fixed, ""));
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if !fraction.is_empty() {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+
+    let sign = if amount < 0.0 && fixed.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        "-"
+    } else {
+        ""
+    };
+    match currency.as_str() {
+        "USD" => format!("{}${}", sign, grouped),
+        "EUR" => format!("{}€{}", sign, grouped),
+        "GBP" => format!("{}£{}", sign, grouped),
+        "JPY" => format!("{}¥{}", sign, grouped),
+        "INR" => format!("{}₹{}", sign, grouped),
+        _ => format!("{}{} {}", sign, currency, grouped),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug, Default)]
+    struct CountingProvider {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl RateProvider for CountingProvider {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        async fn fetch_rates(&self) -> Result<RateTable, RateError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(RateTable {
+                base: "USD".to_string(),
+                rates: parse_rates("EUR:0.5, GBP:0.25, bogus"),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_converter_caches_rates_for_ttl() {
+        let provider = Arc::new(CountingProvider::default());
+        let converter = CurrencyConverter::new(provider.clone(), Duration::from_secs(60));
+
+        assert_eq!(converter.convert(10.0, "USD", "EUR"), None);
+        assert_eq!(converter.convert(10.0, "usd", "USD"), Some(10.0));
+
+        converter.refresh_if_stale().await.unwrap();
+        converter.refresh_if_stale().await.unwrap();
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+
+        assert_eq!(converter.convert(10.0, "USD", "EUR"), Some(5.0));
+        // Cross rate through the base currency
+        assert_eq!(converter.convert(10.0, "eur", "GBP"), Some(5.0));
+        assert_eq!(converter.convert(10.0, "USD", "XYZ"), None);
+    }
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format_amount(1234.5, "usd"), "$1,234.50");
+        assert_eq!(format_amount(-0.004, "EUR"), "€0.00");
+        assert_eq!(format_amount(-1200.0, "JPY"), "-¥1,200");
+        assert_eq!(format_amount(9.95, "CHF"), "CHF 9.95");
+    }
+}
@@ -0,0 +1,152 @@
+// src/cursor.rs - Cursor pagination for collections
+//
+// Collection pages are ordered by a sort field with the record id as tie
+// breaker, so a cursor (the sort value and id of a page's last record) keeps
+// pointing at the same place while records are added or removed. Cursors are
+// opaque to clients: hex-encoded "field\u{1f}value\u{1f}id".
+use crate::data_source::Record;
+use crate::variant_kinds::escape_attr;
+use std::cmp::Ordering;
+
+const SEPARATOR: char = '\u{1f}';
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor {
+    // Field the collection is sorted by
+    pub sort_by: String,
+    pub value: String,
+    pub id: String,
+}
+
+impl Cursor {
+    // The position right after `record` in a collection sorted by `sort_by`
+    pub fn after(sort_by: &str, record: &Record) -> Self {
+        Self {
+            sort_by: sort_by.to_string(),
+            value: record.get(sort_by).cloned().unwrap_or_default(),
+            id: record.get("id").cloned().unwrap_or_default(),
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let raw = format!(
+            "{}{SEPARATOR}{}{SEPARATOR}{}",
+            self.sort_by, self.value, self.id
+        );
+        raw.bytes().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let bytes = (0..cursor.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(cursor.get(i..i + 2)?, 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        let raw = String::from_utf8(bytes).ok()?;
+        let mut parts = raw.split(SEPARATOR);
+        let cursor = Self {
+            sort_by: parts.next()?.to_string(),
+            value: parts.next()?.to_string(),
+            id: parts.next()?.to_string(),
+        };
+        parts.next().is_none().then_some(cursor)
+    }
+}
+
+// 📄 Sort `records` by `sort_by` (then id), skip everything up to `after` and
+// keep at most `limit`. Returns the page and the cursor of the next one, if
+// records remain.
+pub fn page(
+    mut records: Vec<Record>,
+    sort_by: &str,
+    after: Option<&Cursor>,
+    limit: Option<usize>,
+) -> (Vec<Record>, Option<Cursor>) {
+    records.sort_by(|a, b| compare_keys(sort_key(a, sort_by), sort_key(b, sort_by)));
+    if let Some(after) = after {
+        let position = (after.value.as_str(), after.id.as_str());
+        records.retain(|record| compare_keys(sort_key(record, sort_by), position).is_gt());
+    }
+    first_page(records, sort_by, limit)
+}
+
+// The first `limit` of `records` (already in page order) and, when more
+// follow, the cursor after the last of them
+pub fn first_page(
+    mut records: Vec<Record>,
+    sort_by: &str,
+    limit: Option<usize>,
+) -> (Vec<Record>, Option<Cursor>) {
+    let next = match limit {
+        Some(limit) if records.len() > limit => {
+            records.truncate(limit);
+            records.last().map(|last| Cursor::after(sort_by, last))
+        }
+        _ => None,
+    };
+    (records, next)
+}
+
+// ♾️ Closes a page for infinite-scroll frontends: when it scrolls into view,
+// fetch `next_url` and put its rows (and new sentinel) in place of this one
+pub fn sentinel(cursor: &str, next_url: &str) -> String {
+    format!(
+        r#"<div data-sui-sentinel data-next-cursor="{}" data-next-url="{}"></div>"#,
+        escape_attr(cursor),
+        escape_attr(next_url)
+    )
+}
+
+fn sort_key<'a>(record: &'a Record, sort_by: &str) -> (&'a str, &'a str) {
+    let field = |name: &str| record.get(name).map_or("", String::as_str);
+    (field(sort_by), field("id"))
+}
+
+fn compare_keys(a: (&str, &str), b: (&str, &str)) -> Ordering {
+    compare_values(a.0, b.0).then_with(|| compare_values(a.1, b.1))
+}
+
+// Numbers compare numerically ("9" before "10"), everything else as text
+pub(crate) fn compare_values(a: &str, b: &str) -> Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn record(id: &str, created_at: &str) -> Record {
+        HashMap::from([
+            ("id".to_string(), id.to_string()),
+            ("created_at".to_string(), created_at.to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_pages_follow_the_cursor() {
+        let records = vec![
+            record("10", "2024-01-02"),
+            record("9", "2024-01-02"),
+            record("2", "2024-01-01"),
+            record("11", "2024-01-03"),
+        ];
+        let ids = |page: &[Record]| page.iter().map(|r| r["id"].clone()).collect::<Vec<_>>();
+
+        let (first, next) = page(records.clone(), "created_at", None, Some(2));
+        assert_eq!(ids(&first), ["2", "9"]);
+        let next = next.unwrap();
+        assert_eq!(Cursor::decode(&next.encode()), Some(next.clone()));
+
+        let (second, last) = page(records.clone(), "created_at", Some(&next), Some(2));
+        assert_eq!(ids(&second), ["10", "11"]);
+        assert!(last.is_none());
+
+        let (by_id, _) = page(records, "id", None, None);
+        assert_eq!(ids(&by_id), ["2", "9", "10", "11"]);
+        assert_eq!(Cursor::decode("zz"), None);
+        assert_eq!(Cursor::decode("abc"), None);
+    }
+}
@@ -0,0 +1,1144 @@
+// src/data_source.rs - Where component records come from
+//
+// Renders fetch records through the DataSource trait, so backends (mock data,
+// Postgres, ...) and wrappers (fault injection, ...) can be swapped freely.
+use crate::config::{Config, config};
+use crate::cursor::{Cursor, page};
+use crate::database::PostgresDataSource;
+use crate::facets::{FacetValue, count_values};
+use crate::http_source::HttpDataSource;
+use crate::schema::{Relation, registry};
+use crate::search::{SearchHit, SearchMapping, search_records};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub type Record = HashMap<String, String>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataError {
+    NotFound(String),
+    Unavailable(String),
+    Timeout,
+    CircuitOpen,
+    // Errors that retrying will not fix (bad query, rejected request, ...)
+    Backend(String),
+    // The source (named) only serves reads
+    ReadOnly(String),
+}
+
+impl DataError {
+    // Connection blips and timeouts are worth retrying; everything else is not
+    pub fn is_transient(&self) -> bool {
+        matches!(self, DataError::Unavailable(_) | DataError::Timeout)
+    }
+}
+
+impl std::fmt::Display for DataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataError::NotFound(id) => write!(f, "Record with id '{}' not found", id),
+            DataError::Unavailable(msg) => write!(f, "Data source unavailable: {}", msg),
+            DataError::Timeout => write!(f, "Data source timed out"),
+            DataError::CircuitOpen => write!(f, "Data source circuit breaker is open"),
+            DataError::Backend(msg) => write!(f, "Data source error: {}", msg),
+            DataError::ReadOnly(name) => write!(f, "Data source '{}' is read-only", name),
+        }
+    }
+}
+
+impl std::error::Error for DataError {}
+
+#[async_trait]
+pub trait DataSource: Send + Sync + std::fmt::Debug {
+    // Short name used in logs, e.g. "mock" or "postgres"
+    fn name(&self) -> &str;
+
+    async fn get_record(&self, table: &str, id: &str) -> Result<Record, DataError>;
+
+    async fn get_records(
+        &self,
+        table: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<Record>, DataError>;
+
+    // A record as seen by a caller scoped to `scope` (a component's required
+    // params, e.g. org_id=7): records whose field of that name holds another
+    // value are reported as not found. Sources able to filter at the backend
+    // may override this.
+    async fn get_scoped_record(
+        &self,
+        table: &str,
+        id: &str,
+        scope: &[(String, String)],
+    ) -> Result<Record, DataError> {
+        let record = self.get_record(table, id).await?;
+        if in_scope(&record, scope) {
+            Ok(record)
+        } else {
+            Err(DataError::NotFound(id.to_string()))
+        }
+    }
+
+    // 📄 Up to `limit` records within `scope` that follow `after` in `sort_by`
+    // order (the id breaking ties), plus the cursor of the next page. Sources
+    // able to page at the backend (a keyset query) may override this.
+    async fn get_page(
+        &self,
+        table: &str,
+        sort_by: &str,
+        after: Option<&Cursor>,
+        limit: Option<usize>,
+        scope: &[(String, String)],
+    ) -> Result<(Vec<Record>, Option<Cursor>), DataError> {
+        let records = self.get_records(table, None).await?;
+        let records = records
+            .into_iter()
+            .filter(|record| in_scope(record, scope))
+            .collect();
+        Ok(page(records, sort_by, after, limit))
+    }
+
+    // 🔗 Records linked to record `id` through the relation's join table, in
+    // join row order. Join rows pointing at missing records are skipped.
+    // Sources able to join at the backend may override this.
+    async fn get_related(&self, relation: &Relation, id: &str) -> Result<Vec<Record>, DataError> {
+        let links = self.get_records(&relation.through, None).await?;
+        let mut related = Vec::new();
+        for link in links {
+            if link.get(&relation.local_key).is_none_or(|key| key != id) {
+                continue;
+            }
+            let Some(foreign) = link.get(&relation.foreign_key) else {
+                continue;
+            };
+            match self.get_record(&relation.table, foreign).await {
+                Ok(record) => related.push(record),
+                Err(DataError::NotFound(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(related)
+    }
+
+    // 🌳 Every record below `id` in a table whose `parent_field` holds the
+    // parent's id, breadth first. Sources able to query recursively (a CTE)
+    // may override this.
+    async fn get_descendants(
+        &self,
+        table: &str,
+        parent_field: &str,
+        id: &str,
+    ) -> Result<Vec<Record>, DataError> {
+        let mut remaining = self.get_records(table, None).await?;
+        let mut parents = vec![id.to_string()];
+        let mut descendants = Vec::new();
+        // Each record is taken at most once, so cycles in the data terminate
+        while !parents.is_empty() {
+            let (children, rest): (Vec<_>, Vec<_>) = remaining.into_iter().partition(|record| {
+                record
+                    .get(parent_field)
+                    .is_some_and(|parent| parents.contains(parent))
+            });
+            remaining = rest;
+            parents = children
+                .iter()
+                .filter_map(|child| child.get("id").cloned())
+                .collect();
+            descendants.extend(children);
+        }
+        Ok(descendants)
+    }
+
+    // 🧮 Distinct values of `field` with their counts, most frequent first.
+    // Sources able to aggregate at the backend (GROUP BY) may override this.
+    async fn get_facet(&self, table: &str, field: &str) -> Result<Vec<FacetValue>, DataError> {
+        let records = self.get_records(table, None).await?;
+        Ok(count_values(&records, field))
+    }
+
+    // 🔎 Records matching the full-text `query` in the mapping's fields, best
+    // first. Sources with a search index (Postgres tsvector) may override this.
+    async fn search(
+        &self,
+        table: &str,
+        mapping: &SearchMapping,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<SearchHit>, DataError> {
+        let records = self.get_records(table, None).await?;
+        Ok(search_records(records, mapping, query, limit))
+    }
+
+    // 🪪 Whether a record other than `except_id` holds `value` in `field`, for
+    // uniqueness checks. Sources able to query at the backend may override
+    // this; the default scans the table.
+    async fn value_exists(
+        &self,
+        table: &str,
+        field: &str,
+        value: &str,
+        except_id: Option<&str>,
+    ) -> Result<bool, DataError> {
+        let records = self.get_records(table, None).await?;
+        Ok(records.iter().any(|record| {
+            record.get(field).is_some_and(|v| v == value)
+                && (except_id.is_none() || record.get("id").map(String::as_str) != except_id)
+        }))
+    }
+
+    // ✍️ Store a new record and return its id. Sources are read-only unless
+    // they override this and update_record.
+    async fn insert_record(&self, _table: &str, _record: &Record) -> Result<String, DataError> {
+        Err(DataError::ReadOnly(self.name().to_string()))
+    }
+
+    // Overwrite the fields `record` holds on record `id`
+    async fn update_record(
+        &self,
+        _table: &str,
+        _id: &str,
+        _record: &Record,
+    ) -> Result<(), DataError> {
+        Err(DataError::ReadOnly(self.name().to_string()))
+    }
+}
+
+// Fields a record shares with the scope all hold the scoped value
+pub fn in_scope(record: &Record, scope: &[(String, String)]) -> bool {
+    scope
+        .iter()
+        .all(|(param, value)| record.get(param).is_none_or(|field| field == value))
+}
+
+// 📦 Mock records embedded in the table schemas
+#[derive(Debug, Default, Clone)]
+pub struct MockDataSource;
+
+#[async_trait]
+impl DataSource for MockDataSource {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    async fn get_record(&self, table: &str, id: &str) -> Result<Record, DataError> {
+        registry()
+            .get_mock_record(table, id)
+            .ok_or_else(|| DataError::NotFound(id.to_string()))
+    }
+
+    async fn get_records(
+        &self,
+        table: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<Record>, DataError> {
+        Ok(registry().get_mock_records(table, limit))
+    }
+}
+
+// Faults injected by ChaosDataSource
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChaosConfig {
+    // Added to every call
+    pub latency: Duration,
+    // Extra random latency in [0, jitter)
+    pub jitter: Duration,
+    // Probability (0.0 - 1.0) that a call fails with DataError::Unavailable
+    pub error_rate: f64,
+}
+
+impl ChaosConfig {
+    // Fault injection is a development tool: ignored outside development,
+    // and absent when nothing is configured
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let chaos = Self {
+            latency: Duration::from_millis(config.chaos_latency_ms),
+            jitter: Duration::from_millis(config.chaos_jitter_ms),
+            error_rate: config.chaos_error_rate.clamp(0.0, 1.0),
+        };
+        let enabled = !chaos.latency.is_zero() || !chaos.jitter.is_zero() || chaos.error_rate > 0.0;
+        (enabled && config.is_development()).then_some(chaos)
+    }
+}
+
+// 🐒 Wraps a data source with artificial latency and failures
+#[derive(Debug)]
+pub struct ChaosDataSource {
+    inner: Arc<dyn DataSource>,
+    chaos: ChaosConfig,
+}
+
+impl ChaosDataSource {
+    pub fn new(inner: Arc<dyn DataSource>, chaos: ChaosConfig) -> Self {
+        Self { inner, chaos }
+    }
+
+    async fn inject(&self) -> Result<(), DataError> {
+        let jitter = self.chaos.jitter.mul_f64(fastrand::f64());
+        let delay = self.chaos.latency + jitter;
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+        if fastrand::f64() < self.chaos.error_rate {
+            return Err(DataError::Unavailable(format!(
+                "injected fault in {}",
+                self.inner.name()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DataSource for ChaosDataSource {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn get_record(&self, table: &str, id: &str) -> Result<Record, DataError> {
+        self.inject().await?;
+        self.inner.get_record(table, id).await
+    }
+
+    async fn get_records(
+        &self,
+        table: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<Record>, DataError> {
+        self.inject().await?;
+        self.inner.get_records(table, limit).await
+    }
+
+    async fn get_scoped_record(
+        &self,
+        table: &str,
+        id: &str,
+        scope: &[(String, String)],
+    ) -> Result<Record, DataError> {
+        self.inject().await?;
+        self.inner.get_scoped_record(table, id, scope).await
+    }
+
+    async fn get_page(
+        &self,
+        table: &str,
+        sort_by: &str,
+        after: Option<&Cursor>,
+        limit: Option<usize>,
+        scope: &[(String, String)],
+    ) -> Result<(Vec<Record>, Option<Cursor>), DataError> {
+        self.inject().await?;
+        self.inner
+            .get_page(table, sort_by, after, limit, scope)
+            .await
+    }
+
+    async fn get_related(&self, relation: &Relation, id: &str) -> Result<Vec<Record>, DataError> {
+        self.inject().await?;
+        self.inner.get_related(relation, id).await
+    }
+
+    async fn get_descendants(
+        &self,
+        table: &str,
+        parent_field: &str,
+        id: &str,
+    ) -> Result<Vec<Record>, DataError> {
+        self.inject().await?;
+        self.inner.get_descendants(table, parent_field, id).await
+    }
+
+    async fn get_facet(&self, table: &str, field: &str) -> Result<Vec<FacetValue>, DataError> {
+        self.inject().await?;
+        self.inner.get_facet(table, field).await
+    }
+
+    async fn search(
+        &self,
+        table: &str,
+        mapping: &SearchMapping,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<SearchHit>, DataError> {
+        self.inject().await?;
+        self.inner.search(table, mapping, query, limit).await
+    }
+
+    async fn value_exists(
+        &self,
+        table: &str,
+        field: &str,
+        value: &str,
+        except_id: Option<&str>,
+    ) -> Result<bool, DataError> {
+        self.inject().await?;
+        self.inner
+            .value_exists(table, field, value, except_id)
+            .await
+    }
+
+    async fn insert_record(&self, table: &str, record: &Record) -> Result<String, DataError> {
+        self.inject().await?;
+        self.inner.insert_record(table, record).await
+    }
+
+    async fn update_record(&self, table: &str, id: &str, record: &Record) -> Result<(), DataError> {
+        self.inject().await?;
+        self.inner.update_record(table, id, record).await
+    }
+}
+
+// 🔁 Retry policy for transient data source errors, with full-jitter
+// exponential backoff: attempt n waits a random time in [0, base * 2^n),
+// capped at `max_delay`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    // Total attempts including the first call (1 = no retries)
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(50),
+            max_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_attempts: config.data_retry_attempts.max(1),
+            base_delay: Duration::from_millis(config.data_retry_base_ms),
+            max_delay: Duration::from_millis(config.data_retry_max_ms),
+        }
+    }
+
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let ceiling = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        ceiling.mul_f64(fastrand::f64())
+    }
+
+    // Run `call` until it succeeds, fails permanently or runs out of attempts
+    pub async fn run<T, F, Fut>(&self, mut call: F) -> Result<T, DataError>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T, DataError>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match call().await {
+                Err(err) if err.is_transient() && attempt + 1 < self.max_attempts => {
+                    tokio::time::sleep(self.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BreakerState {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    // A single probe call is in flight; everything else is rejected
+    HalfOpen,
+}
+
+// 🔌 Stops calling a failing data source. After `failure_threshold`
+// consecutive failures the circuit opens and calls fail fast with
+// DataError::CircuitOpen; once `reset_after` has passed one probe call is let
+// through, and its outcome closes or re-opens the circuit.
+#[derive(Debug)]
+pub struct CircuitBreakerDataSource {
+    inner: Arc<dyn DataSource>,
+    failure_threshold: u32,
+    reset_after: Duration,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreakerDataSource {
+    pub fn new(inner: Arc<dyn DataSource>, failure_threshold: u32, reset_after: Duration) -> Self {
+        Self {
+            inner,
+            failure_threshold: failure_threshold.max(1),
+            reset_after,
+            state: Mutex::new(BreakerState::Closed { failures: 0 }),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        !matches!(*self.lock_state(), BreakerState::Closed { .. })
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, BreakerState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // Decide whether a call may go through, moving Open -> HalfOpen when due
+    fn acquire(&self) -> Result<(), DataError> {
+        let mut state = self.lock_state();
+        match *state {
+            BreakerState::Closed { .. } => Ok(()),
+            BreakerState::Open { until } if Instant::now() >= until => {
+                *state = BreakerState::HalfOpen;
+                Ok(())
+            }
+            BreakerState::Open { .. } | BreakerState::HalfOpen => Err(DataError::CircuitOpen),
+        }
+    }
+
+    // Run one call to the inner source through the breaker
+    async fn guard<T>(
+        &self,
+        call: impl Future<Output = Result<T, DataError>>,
+    ) -> Result<T, DataError> {
+        self.acquire()?;
+        let result = call.await;
+        self.record(&result);
+        result
+    }
+
+    fn record<T>(&self, result: &Result<T, DataError>) {
+        let mut state = self.lock_state();
+        // A missing record (or a refused write) is a healthy answer from the backend
+        let failed = matches!(
+            result,
+            Err(err) if !matches!(err, DataError::NotFound(_) | DataError::ReadOnly(_))
+        );
+
+        *state = match (*state, failed) {
+            (_, false) => BreakerState::Closed { failures: 0 },
+            (BreakerState::Closed { failures }, true) if failures + 1 < self.failure_threshold => {
+                BreakerState::Closed {
+                    failures: failures + 1,
+                }
+            }
+            (_, true) => {
+                eprintln!(
+                    "🔌 Circuit opened for data source '{}' for {:?}",
+                    self.inner.name(),
+                    self.reset_after
+                );
+                BreakerState::Open {
+                    until: Instant::now() + self.reset_after,
+                }
+            }
+        };
+    }
+}
+
+#[async_trait]
+impl DataSource for CircuitBreakerDataSource {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn get_record(&self, table: &str, id: &str) -> Result<Record, DataError> {
+        self.guard(self.inner.get_record(table, id)).await
+    }
+
+    async fn get_records(
+        &self,
+        table: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<Record>, DataError> {
+        self.guard(self.inner.get_records(table, limit)).await
+    }
+
+    async fn get_scoped_record(
+        &self,
+        table: &str,
+        id: &str,
+        scope: &[(String, String)],
+    ) -> Result<Record, DataError> {
+        self.guard(self.inner.get_scoped_record(table, id, scope))
+            .await
+    }
+
+    async fn get_page(
+        &self,
+        table: &str,
+        sort_by: &str,
+        after: Option<&Cursor>,
+        limit: Option<usize>,
+        scope: &[(String, String)],
+    ) -> Result<(Vec<Record>, Option<Cursor>), DataError> {
+        self.guard(self.inner.get_page(table, sort_by, after, limit, scope))
+            .await
+    }
+
+    async fn get_related(&self, relation: &Relation, id: &str) -> Result<Vec<Record>, DataError> {
+        self.guard(self.inner.get_related(relation, id)).await
+    }
+
+    async fn get_descendants(
+        &self,
+        table: &str,
+        parent_field: &str,
+        id: &str,
+    ) -> Result<Vec<Record>, DataError> {
+        self.guard(self.inner.get_descendants(table, parent_field, id))
+            .await
+    }
+
+    async fn get_facet(&self, table: &str, field: &str) -> Result<Vec<FacetValue>, DataError> {
+        self.guard(self.inner.get_facet(table, field)).await
+    }
+
+    async fn search(
+        &self,
+        table: &str,
+        mapping: &SearchMapping,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<SearchHit>, DataError> {
+        self.guard(self.inner.search(table, mapping, query, limit))
+            .await
+    }
+
+    async fn value_exists(
+        &self,
+        table: &str,
+        field: &str,
+        value: &str,
+        except_id: Option<&str>,
+    ) -> Result<bool, DataError> {
+        self.guard(self.inner.value_exists(table, field, value, except_id))
+            .await
+    }
+
+    async fn insert_record(&self, table: &str, record: &Record) -> Result<String, DataError> {
+        self.guard(self.inner.insert_record(table, record)).await
+    }
+
+    async fn update_record(&self, table: &str, id: &str, record: &Record) -> Result<(), DataError> {
+        self.guard(self.inner.update_record(table, id, record))
+            .await
+    }
+}
+
+// 🪪 Hands out records under their schema names: columns a table's
+// `[aliases]` map are renamed in every record the inner source returns, and
+// field names in requests (scopes, facets, search fields) are turned back
+// into the columns the backend knows
+#[derive(Debug)]
+pub struct AliasedDataSource {
+    inner: Arc<dyn DataSource>,
+}
+
+impl AliasedDataSource {
+    pub fn new(inner: Arc<dyn DataSource>) -> Self {
+        Self { inner }
+    }
+
+    fn alias(table: &str, mut record: Record) -> Record {
+        if let Some(schema) = registry().get_table(table) {
+            schema.apply_aliases(&mut record);
+        }
+        record
+    }
+
+    fn alias_all(table: &str, records: Vec<Record>) -> Vec<Record> {
+        records
+            .into_iter()
+            .map(|record| Self::alias(table, record))
+            .collect()
+    }
+
+    fn column(table: &str, field: &str) -> String {
+        match registry().get_table(table) {
+            Some(schema) => schema.column_for(field).to_string(),
+            None => field.to_string(),
+        }
+    }
+
+    // A scope (param, value) keyed by data column
+    fn scope_columns(table: &str, scope: &[(String, String)]) -> Vec<(String, String)> {
+        scope
+            .iter()
+            .map(|(param, value)| (Self::column(table, param), value.clone()))
+            .collect()
+    }
+
+    // A record being written, keyed by data column
+    fn columns(table: &str, record: &Record) -> Record {
+        record
+            .iter()
+            .map(|(field, value)| (Self::column(table, field), value.clone()))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DataSource for AliasedDataSource {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn get_record(&self, table: &str, id: &str) -> Result<Record, DataError> {
+        let record = self.inner.get_record(table, id).await?;
+        Ok(Self::alias(table, record))
+    }
+
+    async fn get_records(
+        &self,
+        table: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<Record>, DataError> {
+        let records = self.inner.get_records(table, limit).await?;
+        Ok(Self::alias_all(table, records))
+    }
+
+    async fn get_scoped_record(
+        &self,
+        table: &str,
+        id: &str,
+        scope: &[(String, String)],
+    ) -> Result<Record, DataError> {
+        let scope = Self::scope_columns(table, scope);
+        let record = self.inner.get_scoped_record(table, id, &scope).await?;
+        Ok(Self::alias(table, record))
+    }
+
+    // Cursors go out under the field name and in under the column name
+    async fn get_page(
+        &self,
+        table: &str,
+        sort_by: &str,
+        after: Option<&Cursor>,
+        limit: Option<usize>,
+        scope: &[(String, String)],
+    ) -> Result<(Vec<Record>, Option<Cursor>), DataError> {
+        let column = Self::column(table, sort_by);
+        let after = after.map(|cursor| Cursor {
+            sort_by: column.clone(),
+            ..cursor.clone()
+        });
+        let scope = Self::scope_columns(table, scope);
+        let (records, next) = self
+            .inner
+            .get_page(table, &column, after.as_ref(), limit, &scope)
+            .await?;
+        let next = next.map(|cursor| Cursor {
+            sort_by: sort_by.to_string(),
+            ..cursor
+        });
+        Ok((Self::alias_all(table, records), next))
+    }
+
+    async fn get_related(&self, relation: &Relation, id: &str) -> Result<Vec<Record>, DataError> {
+        let records = self.inner.get_related(relation, id).await?;
+        Ok(Self::alias_all(&relation.table, records))
+    }
+
+    async fn get_descendants(
+        &self,
+        table: &str,
+        parent_field: &str,
+        id: &str,
+    ) -> Result<Vec<Record>, DataError> {
+        let parent_column = Self::column(table, parent_field);
+        let records = self
+            .inner
+            .get_descendants(table, &parent_column, id)
+            .await?;
+        Ok(Self::alias_all(table, records))
+    }
+
+    async fn get_facet(&self, table: &str, field: &str) -> Result<Vec<FacetValue>, DataError> {
+        self.inner
+            .get_facet(table, &Self::column(table, field))
+            .await
+    }
+
+    async fn search(
+        &self,
+        table: &str,
+        mapping: &SearchMapping,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<SearchHit>, DataError> {
+        let columns = SearchMapping {
+            fields: mapping
+                .fields
+                .iter()
+                .map(|field| Self::column(table, field))
+                .collect(),
+            snippet: mapping
+                .snippet
+                .as_ref()
+                .map(|field| Self::column(table, field)),
+            ..mapping.clone()
+        };
+        let hits = self.inner.search(table, &columns, query, limit).await?;
+        Ok(hits
+            .into_iter()
+            .map(|hit| SearchHit {
+                record: Self::alias(table, hit.record),
+                ..hit
+            })
+            .collect())
+    }
+
+    async fn value_exists(
+        &self,
+        table: &str,
+        field: &str,
+        value: &str,
+        except_id: Option<&str>,
+    ) -> Result<bool, DataError> {
+        self.inner
+            .value_exists(table, &Self::column(table, field), value, except_id)
+            .await
+    }
+
+    async fn insert_record(&self, table: &str, record: &Record) -> Result<String, DataError> {
+        self.inner
+            .insert_record(table, &Self::columns(table, record))
+            .await
+    }
+
+    async fn update_record(&self, table: &str, id: &str, record: &Record) -> Result<(), DataError> {
+        self.inner
+            .update_record(table, id, &Self::columns(table, record))
+            .await
+    }
+}
+
+// Backend selected by DATA_SOURCE ("mock", "postgres" or "http")
+fn backend_data_source(config: &Config) -> Arc<dyn DataSource> {
+    let retry = RetryPolicy::from_config(config);
+    let backend: Result<Arc<dyn DataSource>, String> = match config.data_source.as_str() {
+        "postgres" => std::env::var("DATABASE_URL")
+            .map_err(|_| "DATABASE_URL is not set".to_string())
+            .and_then(|url| {
+                PostgresDataSource::connect_lazy(&url, retry).map_err(|e| e.to_string())
+            })
+            .map(|source| Arc::new(source) as Arc<dyn DataSource>),
+        "http" => config
+            .data_source_url
+            .as_deref()
+            .ok_or_else(|| "DATA_SOURCE_URL is not set".to_string())
+            .map(|url| Arc::new(HttpDataSource::new(url, retry)) as Arc<dyn DataSource>),
+        _ => Ok(Arc::new(MockDataSource)),
+    };
+
+    backend.unwrap_or_else(|e| {
+        eprintln!(
+            "Cannot use '{}' data source: {}; using mock data",
+            config.data_source, e
+        );
+        Arc::new(MockDataSource)
+    })
+}
+
+// Data source used by the global component registry
+pub fn default_data_source() -> Arc<dyn DataSource> {
+    let mut source = backend_data_source(config());
+    if let Some(chaos) = ChaosConfig::from_config(config()) {
+        println!("🐒 Chaos enabled for data source: {:?}", chaos);
+        source = Arc::new(ChaosDataSource::new(source, chaos));
+    }
+
+    let breaker = CircuitBreakerDataSource::new(
+        source,
+        config().breaker_failure_threshold,
+        Duration::from_secs(config().breaker_reset_secs),
+    );
+    Arc::new(AliasedDataSource::new(Arc::new(breaker)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_chaos_injects_latency_and_errors() {
+        let failing = ChaosDataSource::new(
+            Arc::new(MockDataSource),
+            ChaosConfig {
+                latency: Duration::ZERO,
+                jitter: Duration::ZERO,
+                error_rate: 1.0,
+            },
+        );
+        assert!(matches!(
+            failing.get_record("users", "1").await,
+            Err(DataError::Unavailable(_))
+        ));
+
+        let slow = ChaosDataSource::new(
+            Arc::new(MockDataSource),
+            ChaosConfig {
+                latency: Duration::from_millis(20),
+                jitter: Duration::ZERO,
+                error_rate: 0.0,
+            },
+        );
+        let started = std::time::Instant::now();
+        assert_eq!(
+            slow.get_record("users", "1").await.unwrap()["name"],
+            "John Doe"
+        );
+        assert!(started.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_opens_and_probes() {
+        let failing = ChaosDataSource::new(
+            Arc::new(MockDataSource),
+            ChaosConfig {
+                latency: Duration::ZERO,
+                jitter: Duration::ZERO,
+                error_rate: 1.0,
+            },
+        );
+        let breaker =
+            CircuitBreakerDataSource::new(Arc::new(failing), 2, Duration::from_millis(30));
+
+        for _ in 0..2 {
+            assert!(matches!(
+                breaker.get_record("users", "1").await,
+                Err(DataError::Unavailable(_))
+            ));
+        }
+        assert!(breaker.is_open());
+        assert_eq!(
+            breaker.get_record("users", "1").await,
+            Err(DataError::CircuitOpen)
+        );
+
+        // After the reset window the probe reaches the (still failing) source
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert!(matches!(
+            breaker.get_record("users", "1").await,
+            Err(DataError::Unavailable(_))
+        ));
+        assert_eq!(
+            breaker.get_record("users", "1").await,
+            Err(DataError::CircuitOpen)
+        );
+
+        // A healthy source closes the circuit again; missing records don't trip it
+        let healthy = CircuitBreakerDataSource::new(Arc::new(MockDataSource), 1, Duration::ZERO);
+        assert!(healthy.get_record("users", "missing").await.is_err());
+        assert!(!healthy.is_open());
+    }
+
+    // Answers facets and searches itself; table scans fail, so any fallback
+    // to the trait defaults shows up as an error
+    #[derive(Debug)]
+    struct IndexedSource;
+
+    #[async_trait]
+    impl DataSource for IndexedSource {
+        fn name(&self) -> &str {
+            "indexed"
+        }
+
+        async fn get_record(&self, _table: &str, id: &str) -> Result<Record, DataError> {
+            Err(DataError::NotFound(id.to_string()))
+        }
+
+        async fn get_records(&self, _: &str, _: Option<usize>) -> Result<Vec<Record>, DataError> {
+            Err(DataError::Backend("table scan".to_string()))
+        }
+
+        async fn get_page(
+            &self,
+            _table: &str,
+            sort_by: &str,
+            _after: Option<&Cursor>,
+            _limit: Option<usize>,
+            _scope: &[(String, String)],
+        ) -> Result<(Vec<Record>, Option<Cursor>), DataError> {
+            let record = Record::from([("id".to_string(), "1".to_string())]);
+            Ok((vec![record.clone()], Some(Cursor::after(sort_by, &record))))
+        }
+
+        async fn value_exists(
+            &self,
+            _table: &str,
+            _field: &str,
+            value: &str,
+            _except_id: Option<&str>,
+        ) -> Result<bool, DataError> {
+            Ok(value == "taken")
+        }
+
+        async fn get_facet(&self, _: &str, _: &str) -> Result<Vec<FacetValue>, DataError> {
+            Ok(vec![FacetValue {
+                value: "indexed".to_string(),
+                count: 1,
+            }])
+        }
+
+        async fn search(
+            &self,
+            _table: &str,
+            _mapping: &SearchMapping,
+            query: &str,
+            _limit: Option<usize>,
+        ) -> Result<Vec<SearchHit>, DataError> {
+            Ok(vec![SearchHit {
+                record: Record::new(),
+                rank: 1.0,
+                snippet: query.to_string(),
+            }])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wrappers_forward_backend_queries() {
+        let calm = ChaosConfig {
+            latency: Duration::ZERO,
+            jitter: Duration::ZERO,
+            error_rate: 0.0,
+        };
+        let chaos = ChaosDataSource::new(Arc::new(IndexedSource), calm);
+        let source = CircuitBreakerDataSource::new(Arc::new(chaos), 1, Duration::from_secs(60));
+        let mapping = SearchMapping {
+            fields: vec!["name".to_string()],
+            language: "english".to_string(),
+            snippet: None,
+        };
+
+        let hits = source.search("users", &mapping, "ada", None).await.unwrap();
+        assert_eq!(hits[0].snippet, "ada");
+        let facet = source.get_facet("users", "role").await.unwrap();
+        assert_eq!(facet[0].value, "indexed");
+        let (records, next) = source
+            .get_page("users", "id", None, Some(1), &[])
+            .await
+            .unwrap();
+        assert_eq!((records.len(), next.unwrap().id.as_str()), (1, "1"));
+        let taken = source.value_exists("users", "email", "taken", None);
+        assert_eq!(taken.await, Ok(true));
+        assert!(!source.is_open());
+    }
+
+    #[tokio::test]
+    async fn test_retry_only_transient_errors() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(2),
+        };
+        let calls = AtomicU32::new(0);
+        let result = policy
+            .run(|| async {
+                match calls.fetch_add(1, Ordering::SeqCst) {
+                    0 => Err(DataError::Timeout),
+                    _ => Ok("row"),
+                }
+            })
+            .await;
+        assert_eq!(result, Ok("row"));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        calls.store(0, Ordering::SeqCst);
+        let result: Result<(), _> = policy
+            .run(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(DataError::Backend("syntax error".to_string()))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        calls.store(0, Ordering::SeqCst);
+        let result: Result<(), _> = policy
+            .run(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(DataError::Unavailable("reset".to_string()))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(policy.backoff(10) <= Duration::from_millis(2));
+    }
+
+    #[tokio::test]
+    async fn test_aliased_source_uses_schema_names() {
+        let schema = crate::schema::TableSchema::from_toml_str(
+            r#"
+            [variants.name]
+            plain = { base = "span" }
+            [contexts.card]
+            [aliases]
+            full_name = "name"
+            team_id = "team"
+            [[mock_data]]
+            id = "1"
+            full_name = "Ada"
+            team_id = "7"
+            [[mock_data]]
+            id = "2"
+            full_name = "Ada"
+            team_id = "8"
+            "#,
+        )
+        .unwrap();
+        crate::schema::update_registry(|registry| {
+            registry.insert_table("members", schema);
+            Ok::<_, crate::schema::SchemaError>(())
+        })
+        .unwrap();
+        let source = AliasedDataSource::new(Arc::new(MockDataSource));
+
+        let record = source.get_record("members", "1").await.unwrap();
+        assert_eq!(
+            (record["name"].as_str(), record.get("full_name")),
+            ("Ada", None)
+        );
+        let scope = [("team".to_string(), "8".to_string())];
+        let scoped = source.get_scoped_record("members", "1", &scope).await;
+        assert_eq!(scoped, Err(DataError::NotFound("1".to_string())));
+        let facet = source.get_facet("members", "name").await.unwrap();
+        assert_eq!((facet[0].value.as_str(), facet[0].count), ("Ada", 2));
+        let scope = [("team".to_string(), "7".to_string())];
+        let (records, next) = source
+            .get_page("members", "name", None, None, &scope)
+            .await
+            .unwrap();
+        assert_eq!((records.len(), records[0]["id"].as_str()), (1, "1"));
+        assert!(next.is_none());
+        let (_, next) = source
+            .get_page("members", "name", None, Some(1), &[])
+            .await
+            .unwrap();
+        assert_eq!(next.unwrap().sort_by, "name");
+        let taken = source.value_exists("members", "team", "8", Some("1"));
+        assert_eq!(taken.await, Ok(true));
+        let own = source.value_exists("members", "team", "8", Some("2"));
+        assert_eq!(own.await, Ok(false));
+    }
+
+    #[test]
+    fn test_chaos_is_development_only() {
+        let mut config = Config {
+            chaos_error_rate: 0.5,
+            ..Config::default()
+        };
+        // Unset APP_ENV means production
+        assert!(ChaosConfig::from_config(&config).is_none());
+
+        config.environment = "development".to_string();
+        assert!(ChaosConfig::from_config(&config).is_some());
+        assert!(ChaosConfig::from_config(&Config::default()).is_none());
+    }
+}
@@ -1,4 +1,9 @@
 // Database module - handles Supabase connection and SQL operations
+use crate::cursor::{Cursor, first_page};
+use crate::data_source::{DataError, DataSource, Record, RetryPolicy};
+use crate::facets::FacetValue;
+use crate::search::{MATCH_END, MATCH_START, SEARCH_COLUMN, SearchHit, SearchMapping};
+use async_trait::async_trait;
 use sqlx::{Column, PgPool, Row};
 use std::collections::HashMap;
 use std::env;
@@ -19,6 +24,12 @@ impl Database {
         Ok(Self { pool })
     }
 
+    // Create a pool that connects on first use (no I/O until then)
+    pub fn connect_lazy(database_url: &str) -> Result<Self, sqlx::Error> {
+        let pool = PgPool::connect_lazy(database_url)?;
+        Ok(Self { pool })
+    }
+
     // Execute schema SQL files (CREATE TABLE, CREATE COMPONENT, etc.)
     pub async fn execute_schema(&self, sql: &str) -> Result<(), sqlx::Error> {
         // Split SQL by semicolons and execute each statement
@@ -87,20 +98,179 @@ impl Database {
         };
 
         let rows = sqlx::query(&query).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(row_record).collect())
+    }
+
+    // Keyset page: up to `limit` rows within `scope` after the cursor, in
+    // `sort_by` order (NULLs first, the id breaking ties). The cursor's text
+    // values are cast to the column types through json_populate_record, so
+    // numbers and timestamps compare as such. Scope params the table has no
+    // column (or a NULL) for don't restrict, as with in_scope.
+    pub async fn get_page(
+        &self,
+        table: &str,
+        sort_by: &str,
+        after: Option<&Cursor>,
+        limit: Option<i64>,
+        scope: &[(String, String)],
+    ) -> Result<Vec<HashMap<String, String>>, sqlx::Error> {
+        let sort = quote_ident(sort_by);
+        let mut binds = Vec::new();
+        let mut from = format!("{} t", table);
+        let mut conditions = Vec::new();
+        if let Some(after) = after {
+            let mut position = serde_json::Map::new();
+            let value = (!after.value.is_empty()).then(|| after.value.clone());
+            position.insert(sort_by.to_string(), value.into());
+            position.insert("id".to_string(), after.id.clone().into());
+            binds.push(serde_json::Value::Object(position).to_string());
+            from.push_str(&format!(
+                ", json_populate_record(NULL::{}, $1::json) c",
+                table
+            ));
+            conditions.push(if after.value.is_empty() {
+                format!("(t.{sort} IS NOT NULL OR t.id > c.id)", sort = sort)
+            } else {
+                format!("(t.{sort}, t.id) > (c.{sort}, c.id)", sort = sort)
+            });
+        }
+        for (param, value) in scope {
+            binds.push(param.clone());
+            binds.push(value.clone());
+            let (key, value) = (binds.len() - 1, binds.len());
+            conditions.push(format!(
+                "(to_jsonb(t) ->> ${key} IS NULL OR to_jsonb(t) ->> ${key} = ${value})",
+                key = key,
+                value = value
+            ));
+        }
+        let query = format!(
+            "SELECT t.* FROM {from} WHERE {conditions} \
+             ORDER BY t.{sort} NULLS FIRST, t.id LIMIT {limit}",
+            from = from,
+            conditions = if conditions.is_empty() {
+                "TRUE".to_string()
+            } else {
+                conditions.join(" AND ")
+            },
+            sort = sort,
+            limit = limit.map_or("ALL".to_string(), |n| n.to_string())
+        );
+
+        let mut query_builder = sqlx::query(&query);
+        for bind in &binds {
+            query_builder = query_builder.bind(bind);
+        }
+        let rows = query_builder.fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(row_record).collect())
+    }
+
+    // Fetch every record below `id` with a recursive CTE; UNION drops
+    // repeated rows, so cycles in the data terminate
+    pub async fn get_descendants(
+        &self,
+        table: &str,
+        parent_field: &str,
+        id: &str,
+    ) -> Result<Vec<HashMap<String, String>>, sqlx::Error> {
+        let query = format!(
+            "WITH RECURSIVE tree AS (\
+                SELECT * FROM {table} WHERE {parent}::text = $1 \
+                UNION \
+                SELECT child.* FROM {table} child JOIN tree ON child.{parent} = tree.id\
+            ) SELECT * FROM tree",
+            table = table,
+            parent = parent_field
+        );
+        let rows = sqlx::query(&query).bind(id).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(row_record).collect())
+    }
+
+    // Count distinct non-NULL values of `field`, most frequent first
+    pub async fn get_facet(
+        &self,
+        table: &str,
+        field: &str,
+    ) -> Result<Vec<FacetValue>, sqlx::Error> {
+        let query = format!(
+            "SELECT {field}::text AS value, COUNT(*) AS count FROM {table} \
+             WHERE {field} IS NOT NULL GROUP BY 1 ORDER BY 2 DESC, 1",
+            table = table,
+            field = field
+        );
+        let rows = sqlx::query(&query).fetch_all(&self.pool).await?;
+        rows.iter()
+            .map(|row| {
+                let count: i64 = row.try_get("count")?;
+                Ok(FacetValue {
+                    value: row.try_get("value")?,
+                    count: usize::try_from(count).unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
 
-        let mut records = Vec::new();
-        for row in rows {
-            let mut record = HashMap::new();
-            for (i, column) in row.columns().iter().enumerate() {
-                let value: Option<String> = row.try_get(i).ok();
-                if let Some(val) = value {
-                    record.insert(column.name().to_string(), val);
+    // Ranked matches against the generated search column (see search.rs),
+    // with a snippet of the mapping's snippet field
+    pub async fn search(
+        &self,
+        table: &str,
+        mapping: &SearchMapping,
+        query: &str,
+        limit: Option<i32>,
+    ) -> Result<Vec<SearchHit>, sqlx::Error> {
+        let sql = format!(
+            "SELECT t.*, ts_rank(t.{column}, q)::text AS search_rank, \
+             ts_headline('{language}', coalesce(t.{snippet}::text, ''), q, \
+             'StartSel={start}, StopSel={end}') AS search_snippet \
+             FROM {table} t, websearch_to_tsquery('{language}', $1) q \
+             WHERE t.{column} @@ q ORDER BY ts_rank(t.{column}, q) DESC, t.id LIMIT {limit}",
+            column = SEARCH_COLUMN,
+            language = mapping.language,
+            snippet = mapping.snippet_field(),
+            start = MATCH_START,
+            end = MATCH_END,
+            table = table,
+            limit = limit.map_or("ALL".to_string(), |n| n.to_string())
+        );
+        let rows = sqlx::query(&sql).bind(query).fetch_all(&self.pool).await?;
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let mut record = row_record(row);
+                let rank = record.remove("search_rank");
+                SearchHit {
+                    rank: rank.and_then(|rank| rank.parse().ok()).unwrap_or_default(),
+                    snippet: record.remove("search_snippet").unwrap_or_default(),
+                    record,
                 }
-            }
-            records.push(record);
+            })
+            .collect())
+    }
+
+    // Whether a row other than `except_id` holds `value` in `field`
+    pub async fn value_exists(
+        &self,
+        table: &str,
+        field: &str,
+        value: &str,
+        except_id: Option<&str>,
+    ) -> Result<bool, sqlx::Error> {
+        let mut query = format!(
+            "SELECT 1 FROM {} WHERE {}::text = $1",
+            table,
+            quote_ident(field)
+        );
+        if except_id.is_some() {
+            query.push_str(" AND id::text <> $2");
         }
+        query.push_str(" LIMIT 1");
 
-        Ok(records)
+        let mut query_builder = sqlx::query(&query).bind(value);
+        if let Some(id) = except_id {
+            query_builder = query_builder.bind(id);
+        }
+        Ok(query_builder.fetch_optional(&self.pool).await?.is_some())
     }
 
     // Insert new record
@@ -117,7 +287,7 @@ impl Database {
             table,
             fields
                 .iter()
-                .map(|s| s.as_str())
+                .map(|field| quote_ident(field))
                 .collect::<Vec<_>>()
                 .join(", "),
             placeholders.join(", ")
@@ -134,12 +304,242 @@ impl Database {
         Ok(id)
     }
 
+    // Update the given fields of record `id`
+    pub async fn update_record(
+        &self,
+        table: &str,
+        id: &str,
+        data: &HashMap<String, String>,
+    ) -> Result<(), sqlx::Error> {
+        let fields: Vec<&String> = data.keys().collect();
+        let assignments: Vec<String> = fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| format!("{} = ${}", quote_ident(field), i + 2))
+            .collect();
+        let query = format!(
+            "UPDATE {} SET {} WHERE id::text = $1",
+            table,
+            assignments.join(", ")
+        );
+
+        let mut query_builder = sqlx::query(&query).bind(id);
+        for field in &fields {
+            query_builder = query_builder.bind(data.get(*field).unwrap());
+        }
+        let result = query_builder.execute(&self.pool).await?;
+        if result.rows_affected() == 0 {
+            return Err(sqlx::Error::RowNotFound);
+        }
+        Ok(())
+    }
+
     // Close database connection
     pub async fn close(&self) {
         self.pool.close().await;
     }
 }
 
+// 🐘 Records from Postgres, retrying transient connection errors
+#[derive(Debug)]
+pub struct PostgresDataSource {
+    db: Database,
+    retry: RetryPolicy,
+}
+
+impl PostgresDataSource {
+    pub fn new(db: Database, retry: RetryPolicy) -> Self {
+        Self { db, retry }
+    }
+
+    pub fn connect_lazy(database_url: &str, retry: RetryPolicy) -> Result<Self, sqlx::Error> {
+        Ok(Self::new(Database::connect_lazy(database_url)?, retry))
+    }
+}
+
+impl std::fmt::Debug for Database {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Database").finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl DataSource for PostgresDataSource {
+    fn name(&self) -> &str {
+        "postgres"
+    }
+
+    async fn get_record(&self, table: &str, id: &str) -> Result<Record, DataError> {
+        self.retry
+            .run(|| async {
+                self.db
+                    .get_record(table, id)
+                    .await
+                    .map_err(|e| classify_error(e, id))
+            })
+            .await
+    }
+
+    async fn get_records(
+        &self,
+        table: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<Record>, DataError> {
+        let limit = limit.map(|n| i32::try_from(n).unwrap_or(i32::MAX));
+        self.retry
+            .run(|| async {
+                self.db
+                    .get_records(table, limit)
+                    .await
+                    .map_err(|e| classify_error(e, table))
+            })
+            .await
+    }
+
+    // One row past the page tells whether another page follows
+    async fn get_page(
+        &self,
+        table: &str,
+        sort_by: &str,
+        after: Option<&Cursor>,
+        limit: Option<usize>,
+        scope: &[(String, String)],
+    ) -> Result<(Vec<Record>, Option<Cursor>), DataError> {
+        let fetch = limit.map(|n| i64::try_from(n.saturating_add(1)).unwrap_or(i64::MAX));
+        let records = self
+            .retry
+            .run(|| async {
+                self.db
+                    .get_page(table, sort_by, after, fetch, scope)
+                    .await
+                    .map_err(|e| classify_error(e, table))
+            })
+            .await?;
+        Ok(first_page(records, sort_by, limit))
+    }
+
+    async fn get_descendants(
+        &self,
+        table: &str,
+        parent_field: &str,
+        id: &str,
+    ) -> Result<Vec<Record>, DataError> {
+        self.retry
+            .run(|| async {
+                self.db
+                    .get_descendants(table, parent_field, id)
+                    .await
+                    .map_err(|e| classify_error(e, id))
+            })
+            .await
+    }
+
+    async fn get_facet(&self, table: &str, field: &str) -> Result<Vec<FacetValue>, DataError> {
+        self.retry
+            .run(|| async {
+                self.db
+                    .get_facet(table, field)
+                    .await
+                    .map_err(|e| classify_error(e, table))
+            })
+            .await
+    }
+
+    async fn search(
+        &self,
+        table: &str,
+        mapping: &SearchMapping,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<SearchHit>, DataError> {
+        let limit = limit.map(|n| i32::try_from(n).unwrap_or(i32::MAX));
+        self.retry
+            .run(|| async {
+                self.db
+                    .search(table, mapping, query, limit)
+                    .await
+                    .map_err(|e| classify_error(e, table))
+            })
+            .await
+    }
+
+    async fn value_exists(
+        &self,
+        table: &str,
+        field: &str,
+        value: &str,
+        except_id: Option<&str>,
+    ) -> Result<bool, DataError> {
+        self.retry
+            .run(|| async {
+                self.db
+                    .value_exists(table, field, value, except_id)
+                    .await
+                    .map_err(|e| classify_error(e, table))
+            })
+            .await
+    }
+
+    // Writes are not retried: a lost response may hide an applied insert
+    async fn insert_record(&self, table: &str, record: &Record) -> Result<String, DataError> {
+        self.db
+            .insert_record(table, record)
+            .await
+            .map_err(|e| classify_error(e, table))
+    }
+
+    async fn update_record(&self, table: &str, id: &str, record: &Record) -> Result<(), DataError> {
+        self.db
+            .update_record(table, id, record)
+            .await
+            .map_err(|e| classify_error(e, id))
+    }
+}
+
+// A column name as a quoted identifier, so a key can't change the statement
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+// Columns of a row as text; NULLs (and non-text values) are left out
+fn row_record(row: &sqlx::postgres::PgRow) -> HashMap<String, String> {
+    let mut record = HashMap::new();
+    for (i, column) in row.columns().iter().enumerate() {
+        let value: Option<String> = row.try_get(i).ok();
+        if let Some(val) = value {
+            record.insert(column.name().to_string(), val);
+        }
+    }
+    record
+}
+
+// Map sqlx errors onto retry-able (Unavailable/Timeout) and permanent errors
+fn classify_error(err: sqlx::Error, id: &str) -> DataError {
+    match err {
+        sqlx::Error::RowNotFound => DataError::NotFound(id.to_string()),
+        sqlx::Error::PoolTimedOut => DataError::Timeout,
+        sqlx::Error::Io(e) => DataError::Unavailable(e.to_string()),
+        sqlx::Error::Tls(e) => DataError::Unavailable(e.to_string()),
+        sqlx::Error::Database(db_err) => {
+            // Connection exceptions (08xxx), serialization failures, deadlocks,
+            // shutdowns and "too many connections" clear up on their own
+            let transient = db_err.code().is_some_and(|code| {
+                code.starts_with("08")
+                    || matches!(
+                        code.as_ref(),
+                        "40001" | "40P01" | "53300" | "57P01" | "57P03"
+                    )
+            });
+            if transient {
+                DataError::Unavailable(db_err.to_string())
+            } else {
+                DataError::Backend(db_err.to_string())
+            }
+        }
+        other => DataError::Backend(other.to_string()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,142 @@
+// src/describe.rs - Fully resolved view of a table schema
+//
+// A schema as written leaves a lot implicit: contexts inherit from each other
+// and fall back to defaults, and classes come from the theme unless a variant
+// overrides them. `SchemaRegistry::describe` spells all of that out, under the
+// current theme, for tooling and debugging (GET /api/schemas/:table/resolved).
+use crate::field_types::FieldType;
+use crate::schema::{Overflow, SchemaRegistry};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TableDescription {
+    pub table: String,
+    pub source: Option<String>,
+    pub theme: String,
+    // field -> variant name -> variant as rendered
+    pub variants: BTreeMap<String, BTreeMap<String, VariantDescription>>,
+    pub contexts: BTreeMap<String, ContextDescription>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VariantDescription {
+    pub base: String,
+    // Theme classes for the tag merged with the variant's override/extend
+    pub classes: String,
+    pub attrs: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    // The variant's own type, else the table's `[types]` entry
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub field_type: Option<FieldType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overflow: Option<Overflow>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContextDescription {
+    // Contexts this one inherits from, nearest first
+    pub inherits: Vec<String>,
+    // Variant each field renders with, inheritance and defaults applied
+    pub fields: BTreeMap<String, String>,
+}
+
+// 🔎 Describe `table`, or None when the registry doesn't know it
+pub fn describe_table(registry: &SchemaRegistry, table: &str) -> Option<TableDescription> {
+    let schema = registry.get_table(table)?;
+    let theme = registry.get_current_theme();
+
+    let variants = schema
+        .variants
+        .iter()
+        .map(|(field, variants)| {
+            let variants = variants
+                .iter()
+                .map(|(name, variant)| {
+                    let description = VariantDescription {
+                        base: variant.base.clone(),
+                        classes: registry.variant_classes(theme, variant),
+                        attrs: variant.attrs.iter().flatten().map(clone_pair).collect(),
+                        kind: variant.kind.clone(),
+                        field_type: variant
+                            .field_type
+                            .or_else(|| schema.types.get(field).copied()),
+                        max_length: variant.max_length,
+                        overflow: variant.overflow,
+                    };
+                    (name.clone(), description)
+                })
+                .collect();
+            (field.clone(), variants)
+        })
+        .collect();
+
+    let contexts = schema
+        .resolve_contexts()
+        .into_iter()
+        .map(|(name, fields)| {
+            let mut inherits = Vec::new();
+            let mut parent = schema
+                .context(&name)
+                .and_then(|(_, ctx)| ctx.inherits.clone());
+            // Stop at cycles and dangling parents; validation reports those
+            while let Some(next) = parent.filter(|next| *next != name && !inherits.contains(next)) {
+                parent = schema
+                    .context(&next)
+                    .and_then(|(_, ctx)| ctx.inherits.clone());
+                inherits.push(next);
+            }
+            let context = ContextDescription {
+                inherits,
+                fields: fields.into_iter().collect(),
+            };
+            (name, context)
+        })
+        .collect();
+
+    Some(TableDescription {
+        table: table.to_string(),
+        source: schema.source.clone(),
+        theme: theme.to_string(),
+        variants,
+        contexts,
+    })
+}
+
+fn clone_pair((key, value): (&String, &String)) -> (String, String) {
+    (key.clone(), value.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_resolves_inheritance_defaults_and_classes() {
+        let registry = SchemaRegistry::load_embedded();
+        let users = registry.describe("users").unwrap();
+        assert_eq!(users.source.as_deref(), Some("users.toml"));
+
+        // `list` inherits from `card`; fields it leaves out come from there
+        let list = &users.contexts["list"];
+        assert_eq!(list.inherits, vec!["card".to_string()]);
+        assert_eq!(list.fields["name"], "h2");
+        assert_eq!(list.fields["avatar_url"], "small");
+
+        let time = &users.variants["created_at"]["time"];
+        assert_eq!(time.field_type, Some(FieldType::Date));
+        assert_eq!(time.classes, registry.get_theme_css("time"));
+        let h1 = &users.variants["name"]["h1"];
+        assert_eq!(h1.classes, "text-2xl font-bold");
+
+        let json = serde_json::to_value(&users).unwrap();
+        assert_eq!(
+            json["variants"]["email"]["link"]["attrs"]["href"],
+            "mailto:{value}"
+        );
+        assert!(registry.describe("missing").is_none());
+    }
+}
@@ -0,0 +1,123 @@
+// src/facets.rs - Distinct field values with counts, for filter UIs
+//
+// GET /api/:table/facets?fields=status,plan counts how often each value of
+// the listed fields occurs, most frequent first, as JSON or as filter chips:
+// one group per field, one chip per value carrying `data-sui-facet` (the
+// field) and `data-value`, so a frontend can turn clicks into filters.
+// Only fields the table's schema declares can be faceted.
+use crate::data_source::Record;
+use crate::schema::SchemaRegistry;
+use crate::variant_kinds::escape_attr;
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FacetValue {
+    pub value: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Facet {
+    pub field: String,
+    pub values: Vec<FacetValue>,
+}
+
+// 🧮 Values of `field` across `records`, most frequent first (ties by
+// value); records without the field aren't counted
+pub fn count_values(records: &[Record], field: &str) -> Vec<FacetValue> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for value in records.iter().filter_map(|record| record.get(field)) {
+        *counts.entry(value).or_default() += 1;
+    }
+    let mut values: Vec<_> = counts
+        .into_iter()
+        .map(|(value, count)| FacetValue {
+            value: value.to_string(),
+            count,
+        })
+        .collect();
+    sort_values(&mut values);
+    values
+}
+
+pub(crate) fn sort_values(values: &mut [FacetValue]) {
+    values.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+}
+
+// 🏷️ Facets as groups of filter chips
+pub fn render_facets(registry: &SchemaRegistry, table: &str, facets: &[Facet]) -> String {
+    let class = |tag: &str| match registry.get_theme_css(tag) {
+        css if css.is_empty() => String::new(),
+        css => format!(r#" class="{}""#, css),
+    };
+    let mut html = format!(
+        r#"<div{} data-sui-facets="{}">"#,
+        class("facets"),
+        escape_attr(table)
+    );
+    for facet in facets {
+        let field = escape_attr(&facet.field);
+        html.push_str(&format!(
+            r#"<div{} data-facet="{}">"#,
+            class("facet"),
+            field
+        ));
+        for value in &facet.values {
+            let text = escape_attr(&value.value);
+            html.push_str(&format!(
+                r#"<button type="button"{} data-sui-facet="{}" data-value="{}">{}"#,
+                class("facet-chip"),
+                field,
+                text,
+                text
+            ));
+            html.push_str(&format!(
+                "<span{}>{}</span></button>",
+                class("facet-count"),
+                value.count
+            ));
+        }
+        html.push_str("</div>");
+    }
+    html.push_str("</div>");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_values_orders_by_frequency() {
+        let records: Vec<Record> = ["pro", "free", "pro", "team", "free", "pro"]
+            .iter()
+            .map(|plan| HashMap::from([("plan".to_string(), plan.to_string())]))
+            .chain([Record::new()])
+            .collect();
+        let counts: Vec<_> = count_values(&records, "plan")
+            .into_iter()
+            .map(|v| (v.value, v.count))
+            .collect();
+        assert_eq!(
+            counts,
+            [
+                ("pro".to_string(), 3),
+                ("free".to_string(), 2),
+                ("team".to_string(), 1)
+            ]
+        );
+
+        let facets = [Facet {
+            field: "plan".to_string(),
+            values: count_values(&records[..1], "plan"),
+        }];
+        let html = render_facets(&SchemaRegistry::new(), "users", &facets);
+        assert_eq!(
+            html,
+            "<div data-sui-facets=\"users\"><div data-facet=\"plan\">\
+             <button type=\"button\" data-sui-facet=\"plan\" data-value=\"pro\">pro<span>1</span>\
+             </button></div></div>"
+        );
+    }
+}
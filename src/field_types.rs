@@ -0,0 +1,472 @@
+// src/field_types.rs - Default formatting for typed field values
+//
+// Fields may declare what their values are (`[types] created_at = "date"`, or
+// `type = "url"` on a single variant). Plain variants of typed fields then get
+// fitting markup: dates in <time datetime>, links for URLs and emails, grouped
+// digits for numbers. Values that don't parse as their type render verbatim.
+//
+// Enum fields list their stored values and show each as a label, with
+// optional classes per value (badge colors):
+//   [enums]
+//   role = ["admin", "editor", "viewer"]        # labels "Admin", "Editor", ...
+//   [enums.status]
+//   values = ["active", "in_review"]
+//   labels = { in_review = "Pending review" }
+//   classes = { active = "bg-green-100", in_review = "bg-amber-100" }
+//
+// Input rules become HTML validation attributes on the field's input and
+// textarea variants, so forms built from the schema enforce them:
+//   [rules]
+//   email = { required = true, max_length = 120, pattern = ".+@.+" }
+//
+// Callers holding typed data pass a FieldValue instead of a string; fields
+// without a declared type then format by the value's own type (an Int gets
+// grouped digits), and Null renders nothing.
+use crate::schema::insert_generated;
+use crate::short_text::MONTHS;
+use crate::variant_kinds::escape_attr;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    #[default]
+    String,
+    Number,
+    Date,
+    Bool,
+    Url,
+    Email,
+}
+
+// A field value as the caller has it
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue<'a> {
+    Str(Cow<'a, str>),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    // ISO 8601 date or timestamp ("2024-01-15T10:30:00Z")
+    DateTime(Cow<'a, str>),
+    // Structured values for kinds like "map"; rendered as compact JSON
+    Json(serde_json::Value),
+    Null,
+}
+
+impl FieldValue<'_> {
+    // The type a field without a declared one formats this value as
+    pub fn field_type(&self) -> Option<FieldType> {
+        match self {
+            FieldValue::Int(_) | FieldValue::Float(_) => Some(FieldType::Number),
+            FieldValue::Bool(_) => Some(FieldType::Bool),
+            FieldValue::DateTime(_) => Some(FieldType::Date),
+            FieldValue::Str(_) | FieldValue::Json(_) | FieldValue::Null => None,
+        }
+    }
+
+    // The value as stored text, which is what templates and variants see
+    pub fn to_text(&self) -> Cow<'_, str> {
+        match self {
+            FieldValue::Str(text) | FieldValue::DateTime(text) => Cow::Borrowed(text),
+            FieldValue::Int(number) => Cow::Owned(number.to_string()),
+            FieldValue::Float(number) => Cow::Owned(number.to_string()),
+            FieldValue::Bool(flag) => Cow::Borrowed(if *flag { "true" } else { "false" }),
+            FieldValue::Json(serde_json::Value::String(text)) => Cow::Borrowed(text),
+            FieldValue::Json(json) => Cow::Owned(json.to_string()),
+            FieldValue::Null => Cow::Borrowed(""),
+        }
+    }
+
+    // Detach the value from the data it borrows from
+    pub fn into_owned(self) -> FieldValue<'static> {
+        match self {
+            FieldValue::Str(text) => FieldValue::Str(Cow::Owned(text.into_owned())),
+            FieldValue::DateTime(text) => FieldValue::DateTime(Cow::Owned(text.into_owned())),
+            FieldValue::Int(number) => FieldValue::Int(number),
+            FieldValue::Float(number) => FieldValue::Float(number),
+            FieldValue::Bool(flag) => FieldValue::Bool(flag),
+            FieldValue::Json(json) => FieldValue::Json(json),
+            FieldValue::Null => FieldValue::Null,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for FieldValue<'a> {
+    fn from(text: &'a str) -> Self {
+        FieldValue::Str(Cow::Borrowed(text))
+    }
+}
+
+impl<'a> From<&'a String> for FieldValue<'a> {
+    fn from(text: &'a String) -> Self {
+        FieldValue::Str(Cow::Borrowed(text))
+    }
+}
+
+impl From<String> for FieldValue<'_> {
+    fn from(text: String) -> Self {
+        FieldValue::Str(Cow::Owned(text))
+    }
+}
+
+impl<'a> From<&'a FieldValue<'_>> for FieldValue<'a> {
+    fn from(value: &'a FieldValue<'_>) -> Self {
+        match value {
+            FieldValue::Str(text) => FieldValue::Str(Cow::Borrowed(text)),
+            FieldValue::DateTime(text) => FieldValue::DateTime(Cow::Borrowed(text)),
+            other => other.clone().into_owned(),
+        }
+    }
+}
+
+impl From<i64> for FieldValue<'_> {
+    fn from(number: i64) -> Self {
+        FieldValue::Int(number)
+    }
+}
+
+impl From<i32> for FieldValue<'_> {
+    fn from(number: i32) -> Self {
+        FieldValue::Int(number.into())
+    }
+}
+
+impl From<f64> for FieldValue<'_> {
+    fn from(number: f64) -> Self {
+        FieldValue::Float(number)
+    }
+}
+
+impl From<bool> for FieldValue<'_> {
+    fn from(flag: bool) -> Self {
+        FieldValue::Bool(flag)
+    }
+}
+
+// JSON scalars map to their FieldValue; arrays and objects stay Json
+impl From<serde_json::Value> for FieldValue<'_> {
+    fn from(json: serde_json::Value) -> Self {
+        match json {
+            serde_json::Value::Null => FieldValue::Null,
+            serde_json::Value::Bool(flag) => FieldValue::Bool(flag),
+            serde_json::Value::Number(number) => match number.as_i64() {
+                Some(int) => FieldValue::Int(int),
+                None => number.as_f64().map_or(FieldValue::Null, FieldValue::Float),
+            },
+            serde_json::Value::String(text) => FieldValue::Str(Cow::Owned(text)),
+            other => FieldValue::Json(other),
+        }
+    }
+}
+
+impl<'a, T: Into<FieldValue<'a>>> From<Option<T>> for FieldValue<'a> {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(FieldValue::Null, Into::into)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(from = "EnumSpec")]
+pub struct EnumField {
+    pub values: Vec<String>,
+    // Display text per value; unlisted values are humanized ("in_review" -> "In review")
+    pub labels: HashMap<String, String>,
+    // Classes added to the element per value
+    pub classes: HashMap<String, String>,
+}
+
+// Either form an `[enums]` entry may take
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EnumSpec {
+    Values(Vec<String>),
+    Full {
+        values: Vec<String>,
+        #[serde(default)]
+        labels: HashMap<String, String>,
+        #[serde(default)]
+        classes: HashMap<String, String>,
+    },
+}
+
+impl From<EnumSpec> for EnumField {
+    fn from(spec: EnumSpec) -> Self {
+        match spec {
+            EnumSpec::Values(values) => Self {
+                values,
+                ..Default::default()
+            },
+            EnumSpec::Full {
+                values,
+                labels,
+                classes,
+            } => Self {
+                values,
+                labels,
+                classes,
+            },
+        }
+    }
+}
+
+impl EnumField {
+    pub fn contains(&self, value: &str) -> bool {
+        self.values.iter().any(|known| known == value)
+    }
+
+    pub fn label(&self, value: &str) -> String {
+        self.labels
+            .get(value)
+            .cloned()
+            .unwrap_or_else(|| humanize(value))
+    }
+}
+
+// Constraints on a field's input
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub struct FieldRules {
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub required: bool,
+    // In characters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    // Regular expression the whole value must match (inputs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    // No two records may share a value; checked on submission (see forms.rs)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub unique: bool,
+}
+
+impl FieldRules {
+    // Why a submitted value breaks these rules (None: the field was left out).
+    // `pattern` is left to the browser, having no regex engine here.
+    pub fn check(&self, value: Option<&str>) -> Vec<String> {
+        let value = value.unwrap_or_default();
+        if value.is_empty() {
+            return if self.required {
+                vec!["is required".to_string()]
+            } else {
+                Vec::new()
+            };
+        }
+        let length = value.chars().count();
+        let mut errors = Vec::new();
+        if let Some(min_length) = self.min_length.filter(|min| length < *min) {
+            errors.push(format!("must be at least {} characters", min_length));
+        }
+        if let Some(max_length) = self.max_length.filter(|max| length > *max) {
+            errors.push(format!("must be at most {} characters", max_length));
+        }
+        errors
+    }
+}
+
+// ✅ Add the validation attributes `rules` imply to a form control; other
+// tags are left alone, and textareas have no `pattern`
+pub fn rule_attributes(rules: &FieldRules, tag: &str, attrs: &mut HashMap<String, String>) {
+    if !matches!(tag, "input" | "textarea") {
+        return;
+    }
+    if rules.required {
+        insert_generated(attrs, "required", "required".to_string());
+    }
+    if let Some(min_length) = rules.min_length {
+        insert_generated(attrs, "minlength", min_length.to_string());
+    }
+    if let Some(max_length) = rules.max_length {
+        insert_generated(attrs, "maxlength", max_length.to_string());
+    }
+    if tag == "input"
+        && let Some(pattern) = &rules.pattern
+    {
+        insert_generated(attrs, "pattern", escape_attr(pattern));
+    }
+}
+
+// "in_review" -> "In review"
+fn humanize(value: &str) -> String {
+    let spaced = value.replace(['_', '-'], " ");
+    let mut chars = spaced.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => spaced,
+    }
+}
+
+// 🏷️ Content for an element showing a `field_type` value, adding the
+// attributes the type implies (an <a> gets its href, a <time> its datetime).
+// Print renders skip links, since paper can't follow them.
+pub fn typed_content(
+    field_type: FieldType,
+    tag: &str,
+    attrs: &mut HashMap<String, String>,
+    value: &str,
+    print: bool,
+) -> String {
+    if matches!(tag, "img" | "input" | "br" | "hr") {
+        return value.to_string();
+    }
+    let link = match field_type {
+        FieldType::String => None,
+        FieldType::Number => return group_digits(value).unwrap_or_else(|| value.to_string()),
+        FieldType::Bool => return yes_no(value).unwrap_or(value).to_string(),
+        FieldType::Date => {
+            let Some(display) = long_date(value) else {
+                return value.to_string();
+            };
+            let datetime = escape_attr(value.trim());
+            if tag == "time" {
+                insert_generated(attrs, "datetime", datetime);
+                return display;
+            }
+            return format!(r#"<time datetime="{}">{}</time>"#, datetime, display);
+        }
+        FieldType::Url => is_web_url(value).then(|| value.trim().to_string()),
+        FieldType::Email => is_email(value).then(|| format!("mailto:{}", value.trim())),
+    };
+    match link {
+        Some(href) if !print && tag == "a" => {
+            insert_generated(attrs, "href", escape_attr(&href));
+            value.to_string()
+        }
+        Some(href) if !print => format!(r#"<a href="{}">{}</a>"#, escape_attr(&href), value),
+        _ => value.to_string(),
+    }
+}
+
+// "Jan 15, 2024" from an ISO date or timestamp
+fn long_date(value: &str) -> Option<String> {
+    let date = value.trim().get(..10)?;
+    let mut parts = date.split('-');
+    let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    if year.len() != 4 || !year.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let month: usize = month.parse().ok()?;
+    let day: u32 = day.parse().ok()?;
+    let name = MONTHS.get(month.checked_sub(1)?)?;
+    (1..=31)
+        .contains(&day)
+        .then(|| format!("{} {}, {}", name, day, year))
+}
+
+// "1234567.50" -> "1,234,567.50"; the digits are kept exactly as written
+fn group_digits(value: &str) -> Option<String> {
+    let value = value.trim();
+    let (sign, unsigned) = match value.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", value),
+    };
+    let (whole, fraction) = match unsigned.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (unsigned, None),
+    };
+    let digits = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+    if !digits(whole) || fraction.is_some_and(|fraction| !digits(fraction)) {
+        return None;
+    }
+
+    let mut grouped = String::from(sign);
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+    Some(grouped)
+}
+
+fn yes_no(value: &str) -> Option<&'static str> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some("Yes"),
+        "false" | "0" | "no" | "off" => Some("No"),
+        _ => None,
+    }
+}
+
+// Only http(s) URLs become links, so `javascript:` values stay inert text
+fn is_web_url(value: &str) -> bool {
+    let lower = value.trim().to_ascii_lowercase();
+    (lower.starts_with("https://") || lower.starts_with("http://"))
+        && !lower.contains(char::is_whitespace)
+}
+
+fn is_email(value: &str) -> bool {
+    let value = value.trim();
+    match value.split_once('@') {
+        Some((user, domain)) => {
+            !user.is_empty()
+                && domain.contains('.')
+                && !domain.contains('@')
+                && !value.contains(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"'))
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typed_content_per_type() {
+        let mut attrs = HashMap::new();
+        let mut content = |field_type, tag, value| {
+            attrs.clear();
+            let html = typed_content(field_type, tag, &mut attrs, value, false);
+            let mut attrs: Vec<_> = attrs.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            attrs.sort();
+            (html, attrs.join(" "))
+        };
+
+        assert_eq!(
+            content(FieldType::Number, "span", "-1234567.50").0,
+            "-1,234,567.50"
+        );
+        assert_eq!(
+            content(FieldType::Number, "span", "12 apples").0,
+            "12 apples"
+        );
+        assert_eq!(content(FieldType::Bool, "span", "TRUE").0, "Yes");
+        assert_eq!(
+            content(FieldType::Date, "time", "2024-01-15T10:30:00Z"),
+            (
+                "Jan 15, 2024".to_string(),
+                "datetime=2024-01-15T10:30:00Z".to_string()
+            )
+        );
+        assert_eq!(
+            content(FieldType::Date, "p", "2024-01-15").0,
+            r#"<time datetime="2024-01-15">Jan 15, 2024</time>"#
+        );
+        assert_eq!(content(FieldType::Date, "p", "someday").0, "someday");
+        assert_eq!(
+            content(FieldType::Email, "a", "ada@example.com"),
+            (
+                "ada@example.com".to_string(),
+                "href=mailto:ada@example.com".to_string()
+            )
+        );
+        assert_eq!(
+            content(FieldType::Url, "p", "https://example.com/a?b=1&c=2").0,
+            r#"<a href="https://example.com/a?b=1&amp;c=2">https://example.com/a?b=1&c=2</a>"#
+        );
+        assert_eq!(
+            content(FieldType::Url, "p", "javascript:alert(1)").0,
+            "javascript:alert(1)"
+        );
+
+        let mut attrs = HashMap::new();
+        let printed = typed_content(FieldType::Url, "p", &mut attrs, "https://example.com", true);
+        assert_eq!(printed, "https://example.com");
+    }
+}
@@ -0,0 +1,184 @@
+// src/formatter.rs - Post-processing passes over rendered HTML
+
+// Elements whose content is whitespace-sensitive and left untouched
+const PRESERVE_TAGS: [&str; 4] = ["pre", "textarea", "script", "style"];
+
+// Elements that never have children
+const VOID_TAGS: [&str; 8] = ["img", "input", "br", "hr", "meta", "link", "source", "col"];
+
+// 🗜️ Collapse whitespace: runs of whitespace become a single space, and
+// whitespace-only gaps between two tags are removed entirely.
+pub fn minify_html(html: &str) -> String {
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        // Copy preserved elements verbatim
+        if let Some((tag, start)) = next_preserved(rest) {
+            collapse_whitespace_into(&mut output, &rest[..start]);
+            let close = format!("</{}", tag);
+            let end = find_ignore_case(&rest[start..], &close)
+                .map(|offset| start + offset)
+                .unwrap_or(rest.len());
+            output.push_str(&rest[start..end]);
+            rest = &rest[end..];
+            // The closing tag itself is minified like any other markup
+            if !rest.is_empty() {
+                let tag_end = rest.find('>').map(|i| i + 1).unwrap_or(rest.len());
+                output.push_str(&rest[..tag_end]);
+                rest = &rest[tag_end..];
+            }
+        } else {
+            collapse_whitespace_into(&mut output, rest);
+            break;
+        }
+    }
+
+    output.trim().to_string()
+}
+
+// 🪄 Re-indent HTML for human inspection: one element per line, two spaces
+// per nesting level. Elements that only contain text stay on a single line.
+pub fn pretty_print_html(html: &str) -> String {
+    let minified = minify_html(html);
+    let mut output = String::with_capacity(minified.len() * 2);
+    let mut depth = 0usize;
+    let mut rest = minified.as_str();
+
+    while !rest.is_empty() {
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            push_line(&mut output, depth, rest[..end].trim());
+            rest = &rest[end..];
+            continue;
+        }
+
+        let end = rest.find('>').map(|i| i + 1).unwrap_or(rest.len());
+        let tag = &rest[..end];
+        let name = tag_name(tag);
+        rest = &rest[end..];
+
+        if tag.starts_with("</") {
+            depth = depth.saturating_sub(1);
+            push_line(&mut output, depth, tag);
+            continue;
+        }
+
+        let self_contained =
+            tag.starts_with("<!") || tag.ends_with("/>") || VOID_TAGS.contains(&name.as_str());
+        if self_contained {
+            push_line(&mut output, depth, tag);
+            continue;
+        }
+
+        // Keep `<tag>text</tag>` and preserved elements on one line
+        let close = format!("</{}>", name);
+        let inline_end = if PRESERVE_TAGS.contains(&name.as_str()) {
+            find_ignore_case(rest, &close)
+        } else {
+            rest.find('<').filter(|&i| rest[i..].starts_with(&close))
+        };
+        if let Some(content_end) = inline_end {
+            let line = format!("{}{}{}", tag, &rest[..content_end], close);
+            push_line(&mut output, depth, &line);
+            rest = &rest[content_end + close.len()..];
+            continue;
+        }
+
+        push_line(&mut output, depth, tag);
+        depth += 1;
+    }
+
+    output
+}
+
+fn push_line(output: &mut String, depth: usize, line: &str) {
+    if line.is_empty() {
+        return;
+    }
+    output.push_str(&"  ".repeat(depth));
+    output.push_str(line);
+    output.push('\n');
+}
+
+// Lowercased element name of an opening or closing tag
+fn tag_name(tag: &str) -> String {
+    tag.trim_start_matches('<')
+        .trim_start_matches('/')
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+// Append `html` to `output`, collapsing whitespace relative to what is already written
+fn collapse_whitespace_into(output: &mut String, html: &str) {
+    let mut pending_space = false;
+
+    for ch in html.chars() {
+        if ch.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+        if pending_space {
+            // Drop whitespace sitting between two tags
+            let between_tags = ch == '<' && output.ends_with('>');
+            if !between_tags && !output.is_empty() {
+                output.push(' ');
+            }
+            pending_space = false;
+        }
+        output.push(ch);
+    }
+    if pending_space && !output.ends_with('>') {
+        output.push(' ');
+    }
+}
+
+// Find the earliest opening tag of a whitespace-sensitive element
+fn next_preserved(html: &str) -> Option<(&'static str, usize)> {
+    PRESERVE_TAGS
+        .iter()
+        .filter_map(|tag| {
+            let open = format!("<{}", tag);
+            find_ignore_case(html, &open)
+                .filter(|&i| {
+                    html[i + open.len()..]
+                        .chars()
+                        .next()
+                        .is_some_and(|c| c == '>' || c.is_whitespace())
+                })
+                .map(|i| (*tag, i))
+        })
+        .min_by_key(|(_, i)| *i)
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .to_ascii_lowercase()
+        .find(&needle.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minify_collapses_whitespace_between_tags() {
+        let html =
+            "<div class=\"card\">\n    <h1>John   Doe</h1>\n    <pre>  keep\n  me </pre>\n</div>\n";
+        assert_eq!(
+            minify_html(html),
+            "<div class=\"card\"><h1>John Doe</h1><pre>  keep\n  me </pre></div>"
+        );
+    }
+
+    #[test]
+    fn test_pretty_print_indents_nested_elements() {
+        let html = "<div class=\"card\"><img src=\"a.jpg\" /><div><h2>Jane</h2><a href=\"#\">mail</a></div></div>";
+        assert_eq!(
+            pretty_print_html(html),
+            "<div class=\"card\">\n  <img src=\"a.jpg\" />\n  <div>\n    <h2>Jane</h2>\n    <a href=\"#\">mail</a>\n  </div>\n</div>\n"
+        );
+    }
+}
@@ -0,0 +1,721 @@
+// src/forms.rs - Record submissions from forms and API clients
+//
+// `POST /api/<table>/records` creates a record and `PUT /api/<table>/records/<id>`
+// updates one. Before anything is written, fields the caller's role may not
+// set are refused (`[writable_by] salary = ["admin"]`) and the table's
+// `[rules]` are checked, so the constraints forms render hold for every client.
+// `unique = true` rules and async validators registered per field (a lookup
+// in another service, say) run last. Rejections come back per field, and
+// `record_form` renders them into the form's error slots.
+// Keys that aren't fields of the table, and its `id`, are refused up front:
+// they end up as column names in the backend's statements.
+// Values are cleaned up first by the field's normalizers, in order:
+//   [normalize]
+//   email = ["trim", "lowercase"]
+//   phone = ["e164"]          # "+44 (20) 7946-0018" -> "+442079460018"
+//   bio = ["strip_html"]
+// The role comes from the X-User-Role header, which the authenticating proxy
+// in front of the service is expected to set.
+use crate::data_source::{DataError, DataSource, Record};
+use crate::schema::{SchemaRegistry, TableSchema};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, OnceLock, RwLock};
+
+pub const ROLE_HEADER: &str = "x-user-role";
+
+// Field -> why the submission was refused on it
+pub type FieldErrors = BTreeMap<String, Vec<String>>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SubmitError {
+    TableNotFound(String),
+    Empty,
+    // Fields the caller's role may not write
+    Forbidden(FieldErrors),
+    Invalid(FieldErrors),
+    Data(DataError),
+}
+
+impl std::fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubmitError::TableNotFound(table) => write!(f, "Table '{}' not found", table),
+            SubmitError::Empty => write!(f, "Submission has no fields"),
+            SubmitError::Forbidden(fields) => {
+                let fields: Vec<_> = fields.keys().map(String::as_str).collect();
+                write!(f, "Not allowed to write: {}", fields.join(", "))
+            }
+            SubmitError::Invalid(fields) => {
+                let fields: Vec<_> = fields.keys().map(String::as_str).collect();
+                write!(f, "Invalid fields: {}", fields.join(", "))
+            }
+            SubmitError::Data(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Normalizer {
+    Trim,
+    Lowercase,
+    // International phone number: "+" and digits only; "00" becomes "+"
+    E164,
+    // Drops tags (and script/style contents), keeping the text
+    StripHtml,
+}
+
+impl Normalizer {
+    pub fn apply(self, value: &str) -> Result<String, String> {
+        match self {
+            Normalizer::Trim => Ok(value.trim().to_string()),
+            Normalizer::Lowercase => Ok(value.to_lowercase()),
+            Normalizer::E164 => e164(value),
+            Normalizer::StripHtml => Ok(strip_html(value)),
+        }
+    }
+}
+
+fn e164(value: &str) -> Result<String, String> {
+    let compact: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '-' | '.' | '(' | ')'))
+        .collect();
+    let digits = compact
+        .strip_prefix('+')
+        .or_else(|| compact.strip_prefix("00"))
+        .filter(|digits| (8..=15).contains(&digits.len()))
+        .filter(|digits| digits.chars().all(|c| c.is_ascii_digit()));
+    match digits {
+        Some(digits) => Ok(format!("+{}", digits)),
+        None => Err("must be an international phone number (+ and country code)".to_string()),
+    }
+}
+
+fn strip_html(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let tag = &rest[start..];
+        let Some(end) = tag.find('>') else {
+            // An unclosed "<" is text, not markup
+            text.push_str(tag);
+            return text;
+        };
+        let name = tag[1..end]
+            .split(|c: char| c.is_whitespace())
+            .next()
+            .unwrap_or_default();
+        rest = &tag[end + 1..];
+        for raw in ["script", "style"] {
+            if name.eq_ignore_ascii_case(raw) {
+                let close = format!("</{}", raw);
+                let lower = rest.to_ascii_lowercase();
+                rest = match lower.find(&close) {
+                    Some(i) => rest[i..].find('>').map_or("", |j| &rest[i + j + 1..]),
+                    None => "",
+                };
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+// 🧼 Run each field's normalizers over `record`, collecting the fields that
+// couldn't be normalized
+pub fn normalize(schema: &TableSchema, record: &mut Record) -> FieldErrors {
+    let mut errors = FieldErrors::new();
+    for (field, normalizers) in &schema.normalize {
+        let Some(value) = record.get_mut(field) else {
+            continue;
+        };
+        for normalizer in normalizers {
+            match normalizer.apply(value) {
+                Ok(normalized) => *value = normalized,
+                Err(message) => {
+                    errors.entry(field.clone()).or_default().push(message);
+                    break;
+                }
+            }
+        }
+    }
+    errors
+}
+
+// Checks a submitted value against the whole (normalized) submission
+pub type Validator =
+    Arc<dyn Fn(String, Record) -> BoxFuture<'static, Result<(), String>> + Send + Sync>;
+
+// 🧩 Async validators per table and field
+#[derive(Default, Clone)]
+pub struct Validators {
+    fields: HashMap<(String, String), Vec<Validator>>,
+}
+
+impl std::fmt::Debug for Validators {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut fields: Vec<_> = self.fields.keys().collect();
+        fields.sort();
+        f.debug_struct("Validators")
+            .field("fields", &fields)
+            .finish()
+    }
+}
+
+impl Validators {
+    pub fn register<F, Fut>(&mut self, table: &str, field: &str, validator: F)
+    where
+        F: Fn(String, Record) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        let validator: Validator =
+            Arc::new(move |value, record| Box::pin(validator(value, record)));
+        self.fields
+            .entry((table.to_string(), field.to_string()))
+            .or_default()
+            .push(validator);
+    }
+
+    // Run the validators of every field `record` carries, concurrently
+    pub async fn check(&self, table: &str, record: &Record) -> FieldErrors {
+        let checks = record.iter().flat_map(|(field, value)| {
+            let validators = self.fields.get(&(table.to_string(), field.clone()));
+            validators
+                .into_iter()
+                .flatten()
+                .map(move |validator| async move {
+                    let result = validator(value.clone(), record.clone()).await;
+                    (field.clone(), result)
+                })
+        });
+        let mut errors = FieldErrors::new();
+        for (field, result) in futures::future::join_all(checks).await {
+            if let Err(message) = result {
+                errors.entry(field).or_default().push(message);
+            }
+        }
+        errors
+    }
+}
+
+static VALIDATORS: OnceLock<RwLock<Validators>> = OnceLock::new();
+
+fn validators_cell() -> &'static RwLock<Validators> {
+    VALIDATORS.get_or_init(|| RwLock::new(Validators::default()))
+}
+
+// Validators the record endpoints run
+pub fn validators() -> Validators {
+    validators_cell()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+// ➕ Add an async validator for the record endpoints
+pub fn register_validator<F, Fut>(table: &str, field: &str, validator: F)
+where
+    F: Fn(String, Record) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), String>> + Send + 'static,
+{
+    validators_cell()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .register(table, field, validator);
+}
+
+// Fields declared `unique` whose submitted value another record already has,
+// one DataSource::value_exists lookup per field. Nothing stops a concurrent
+// submission from taking the value in between, so a unique constraint in the
+// database should back the rule.
+pub async fn unique_errors(
+    data_source: &dyn DataSource,
+    schema: &TableSchema,
+    table: &str,
+    id: Option<&str>,
+    record: &Record,
+) -> Result<FieldErrors, DataError> {
+    let unique: Vec<_> = schema
+        .rules
+        .iter()
+        .filter(|(_, rules)| rules.unique)
+        .filter_map(|(field, _)| Some((field, record.get(field).filter(|v| !v.is_empty())?)))
+        .collect();
+    if unique.is_empty() {
+        return Ok(FieldErrors::new());
+    }
+
+    let lookups = unique.iter().map(|(field, value)| async move {
+        let taken = data_source.value_exists(table, field, value, id).await?;
+        let message = vec!["is already taken".to_string()];
+        Ok::<_, DataError>(taken.then(|| (field.to_string(), message)))
+    });
+    let mut errors = FieldErrors::new();
+    for taken in futures::future::try_join_all(lookups).await? {
+        errors.extend(taken);
+    }
+    Ok(errors)
+}
+
+// 🚧 Keys of `record` that aren't writable fields of the table: anything
+// without a variant (or a column under `[aliases]`), and the record's id
+pub fn unknown_fields(schema: &TableSchema, record: &Record) -> FieldErrors {
+    record
+        .keys()
+        .filter_map(|field| {
+            let message = if field == "id" {
+                "can't be written"
+            } else if schema.variants.contains_key(field)
+                || schema.aliases.values().any(|aliased| aliased == field)
+            {
+                return None;
+            } else {
+                "is not a field of this table"
+            };
+            Some((field.clone(), vec![message.to_string()]))
+        })
+        .collect()
+}
+
+// 🔐 Fields of `record` that `role` (None: anonymous) may not write
+pub fn forbidden_fields(schema: &TableSchema, role: Option<&str>, record: &Record) -> FieldErrors {
+    record
+        .keys()
+        .filter_map(|field| {
+            let roles = schema.writable_by.get(field)?;
+            if role.is_some_and(|role| roles.iter().any(|allowed| allowed == role)) {
+                return None;
+            }
+            let message = format!("requires role {}", roles.join(" or "));
+            Some((field.clone(), vec![message]))
+        })
+        .collect()
+}
+
+// ✅ Values of `record` breaking their field's `[rules]`. Updates only touch
+// the fields they carry, so `partial` skips the ones left out.
+pub fn rule_errors(schema: &TableSchema, record: &Record, partial: bool) -> FieldErrors {
+    schema
+        .rules
+        .iter()
+        .filter(|(field, _)| !partial || record.contains_key(*field))
+        .map(|(field, rules)| {
+            (
+                field.clone(),
+                rules.check(record.get(field).map(String::as_str)),
+            )
+        })
+        .filter(|(_, errors)| !errors.is_empty())
+        .collect()
+}
+
+// 📨 Normalize and check a submission, then write it (as a new record when
+// `id` is None). Returns the id of the written record.
+pub async fn submit_record(
+    data_source: &dyn DataSource,
+    registry: &SchemaRegistry,
+    validators: &Validators,
+    table: &str,
+    id: Option<&str>,
+    role: Option<&str>,
+    mut record: Record,
+) -> Result<String, SubmitError> {
+    let schema = registry
+        .get_table(table)
+        .ok_or_else(|| SubmitError::TableNotFound(table.to_string()))?;
+    if record.is_empty() {
+        return Err(SubmitError::Empty);
+    }
+    let unknown = unknown_fields(schema, &record);
+    if !unknown.is_empty() {
+        return Err(SubmitError::Invalid(unknown));
+    }
+    let forbidden = forbidden_fields(schema, role, &record);
+    if !forbidden.is_empty() {
+        return Err(SubmitError::Forbidden(forbidden));
+    }
+    let mut errors = normalize(schema, &mut record);
+    for (field, messages) in rule_errors(schema, &record, id.is_some()) {
+        errors.entry(field).or_insert(messages);
+    }
+    if !errors.is_empty() {
+        return Err(SubmitError::Invalid(errors));
+    }
+    // Only values that passed the cheap checks are worth a lookup
+    let unique = unique_errors(data_source, schema, table, id, &record);
+    let (unique, mut errors) = futures::join!(unique, validators.check(table, &record));
+    for (field, messages) in unique.map_err(SubmitError::Data)? {
+        errors.entry(field).or_default().extend(messages);
+    }
+    if !errors.is_empty() {
+        return Err(SubmitError::Invalid(errors));
+    }
+
+    match id {
+        Some(id) => {
+            data_source
+                .update_record(table, id, &record)
+                .await
+                .map_err(SubmitError::Data)?;
+            Ok(id.to_string())
+        }
+        None => data_source
+            .insert_record(table, &record)
+            .await
+            .map_err(SubmitError::Data),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_source::MockDataSource;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    // Keeps written records in memory
+    #[derive(Debug, Default)]
+    struct MemorySource {
+        records: Mutex<Vec<Record>>,
+    }
+
+    #[async_trait]
+    impl DataSource for MemorySource {
+        fn name(&self) -> &str {
+            "memory"
+        }
+
+        async fn get_record(&self, _table: &str, id: &str) -> Result<Record, DataError> {
+            let records = self.records.lock().unwrap();
+            let record = records
+                .iter()
+                .find(|record| record.get("id").is_some_and(|v| v == id));
+            record
+                .cloned()
+                .ok_or_else(|| DataError::NotFound(id.to_string()))
+        }
+
+        async fn get_records(
+            &self,
+            _table: &str,
+            limit: Option<usize>,
+        ) -> Result<Vec<Record>, DataError> {
+            let records = self.records.lock().unwrap();
+            Ok(records
+                .iter()
+                .take(limit.unwrap_or(usize::MAX))
+                .cloned()
+                .collect())
+        }
+
+        async fn insert_record(&self, _table: &str, record: &Record) -> Result<String, DataError> {
+            let mut records = self.records.lock().unwrap();
+            let id = (records.len() + 1).to_string();
+            let mut record = record.clone();
+            record.insert("id".to_string(), id.clone());
+            records.push(record);
+            Ok(id)
+        }
+
+        async fn update_record(
+            &self,
+            _table: &str,
+            id: &str,
+            record: &Record,
+        ) -> Result<(), DataError> {
+            let mut records = self.records.lock().unwrap();
+            let existing = records
+                .iter_mut()
+                .find(|existing| existing.get("id").is_some_and(|v| v == id))
+                .ok_or_else(|| DataError::NotFound(id.to_string()))?;
+            existing.extend(record.clone());
+            Ok(())
+        }
+    }
+
+    fn record(fields: &[(&str, &str)]) -> Record {
+        fields
+            .iter()
+            .map(|(field, value)| (field.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_submissions_check_roles_and_rules_before_writing() {
+        let schema = TableSchema::from_toml_str(
+            "[variants.name]\ninput = { base = \"input\" }\n\
+             [variants.salary]\nspan = { base = \"span\" }\n\
+             [rules]\nname = { required = true, max_length = 5 }\n\
+             [writable_by]\nsalary = [\"admin\", \"hr\"]\n",
+        )
+        .unwrap();
+        let mut registry = SchemaRegistry::load_embedded();
+        registry.insert_table("staff", schema);
+        let source = MemorySource::default();
+        let validators = Validators::default();
+
+        let err = submit_record(
+            &source,
+            &registry,
+            &validators,
+            "staff",
+            None,
+            Some("viewer"),
+            record(&[("name", "Ada"), ("salary", "100")]),
+        )
+        .await
+        .unwrap_err();
+        let expected = BTreeMap::from([(
+            "salary".to_string(),
+            vec!["requires role admin or hr".to_string()],
+        )]);
+        assert_eq!(err, SubmitError::Forbidden(expected));
+
+        let err = submit_record(
+            &source,
+            &registry,
+            &validators,
+            "staff",
+            None,
+            None,
+            record(&[("name", "")]),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(&err, SubmitError::Invalid(fields) if fields["name"] == ["is required"]));
+        assert!(source.records.lock().unwrap().is_empty());
+
+        let new = record(&[("name", "Ada"), ("salary", "100")]);
+        let id = submit_record(
+            &source,
+            &registry,
+            &validators,
+            "staff",
+            None,
+            Some("hr"),
+            new,
+        )
+        .await
+        .unwrap();
+        // Updates only check the fields they carry
+        let update = record(&[("salary", "120")]);
+        submit_record(
+            &source,
+            &registry,
+            &validators,
+            "staff",
+            Some(&id),
+            Some("admin"),
+            update,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            source.get_record("staff", &id).await.unwrap()["salary"],
+            "120"
+        );
+
+        let ghost = record(&[("name", "Ada")]);
+        assert!(matches!(
+            submit_record(
+                &source,
+                &registry,
+                &validators,
+                "ghosts",
+                None,
+                None,
+                ghost.clone()
+            )
+            .await,
+            Err(SubmitError::TableNotFound(_))
+        ));
+        assert!(matches!(
+            MockDataSource.insert_record("users", &ghost).await,
+            Err(DataError::ReadOnly(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_undeclared_keys_never_reach_the_data_source() {
+        let schema = TableSchema::from_toml_str(
+            "[variants.name]\ninput = { base = \"input\" }\n\
+             [variants.avatar]\nimg = { base = \"img\" }\n\
+             [aliases]\navatar_url = \"avatar\"\n",
+        )
+        .unwrap();
+        let mut registry = SchemaRegistry::load_embedded();
+        registry.insert_table("people", schema);
+        let source = MemorySource::default();
+        let validators = Validators::default();
+
+        let crafted = record(&[("name = 'x', role", "admin"), ("name", "Ada")]);
+        let err = submit_record(
+            &source,
+            &registry,
+            &validators,
+            "people",
+            None,
+            None,
+            crafted,
+        )
+        .await
+        .unwrap_err();
+        let expected = BTreeMap::from([(
+            "name = 'x', role".to_string(),
+            vec!["is not a field of this table".to_string()],
+        )]);
+        assert_eq!(err, SubmitError::Invalid(expected));
+        assert!(source.records.lock().unwrap().is_empty());
+
+        let ada = record(&[("name", "Ada"), ("avatar", "/ada.png")]);
+        let id = submit_record(&source, &registry, &validators, "people", None, None, ada)
+            .await
+            .unwrap();
+        let rekey = record(&[("id", "99"), ("name", "Eve")]);
+        let err = submit_record(
+            &source,
+            &registry,
+            &validators,
+            "people",
+            Some(&id),
+            None,
+            rekey,
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            matches!(&err, SubmitError::Invalid(fields) if fields["id"] == ["can't be written"])
+        );
+        let saved = source.get_record("people", &id).await.unwrap();
+        assert_eq!(saved["name"], "Ada");
+    }
+
+    #[tokio::test]
+    async fn test_normalizers_run_before_rules_and_insert() {
+        let schema = TableSchema::from_toml_str(
+            "[variants.email]\ninput = { base = \"input\" }\n\
+             [variants.phone]\ninput = { base = \"input\" }\n\
+             [variants.bio]\ninput = { base = \"textarea\" }\n\
+             [rules]\nemail = { max_length = 14 }\n\
+             [normalize]\nemail = [\"trim\", \"lowercase\"]\nphone = [\"e164\"]\n\
+             bio = [\"strip_html\", \"trim\"]\n",
+        )
+        .unwrap();
+        let mut registry = SchemaRegistry::load_embedded();
+        registry.insert_table("signups", schema);
+        let source = MemorySource::default();
+        let validators = Validators::default();
+
+        let submission = record(&[
+            ("email", "  Ada@Example.io "),
+            ("phone", "0044 (20) 7946-0018"),
+            ("bio", "<p>Hi <b>there</b></p><script>alert(1)</script> "),
+        ]);
+        let id = submit_record(
+            &source,
+            &registry,
+            &validators,
+            "signups",
+            None,
+            None,
+            submission,
+        )
+        .await
+        .unwrap();
+        let saved = source.get_record("signups", &id).await.unwrap();
+        // Within max_length only once trimmed
+        assert_eq!(saved["email"], "ada@example.io");
+        assert_eq!(saved["phone"], "+442079460018");
+        assert_eq!(saved["bio"], "Hi there");
+
+        let national = record(&[("phone", "020 7946 0018")]);
+        let err = submit_record(
+            &source,
+            &registry,
+            &validators,
+            "signups",
+            None,
+            None,
+            national,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(&err, SubmitError::Invalid(fields) if fields.contains_key("phone")));
+    }
+
+    #[tokio::test]
+    async fn test_unique_rules_and_async_validators_reject_per_field() {
+        let schema = TableSchema::from_toml_str(
+            "[variants.email]\ninput = { base = \"input\" }\n\
+             [variants.handle]\ninput = { base = \"input\" }\n\
+             [rules]\nemail = { unique = true }\n",
+        )
+        .unwrap();
+        let mut registry = SchemaRegistry::load_embedded();
+        registry.insert_table("accounts", schema);
+        let source = MemorySource::default();
+        let mut validators = Validators::default();
+        validators.register("accounts", "handle", |handle, _| async move {
+            tokio::task::yield_now().await;
+            if handle == "admin" {
+                Err("is reserved".to_string())
+            } else {
+                Ok(())
+            }
+        });
+
+        let ada = record(&[("email", "ada@example.io"), ("handle", "ada")]);
+        let id = submit_record(&source, &registry, &validators, "accounts", None, None, ada)
+            .await
+            .unwrap();
+        // Re-saving a record's own value is not a clash
+        let same = record(&[("email", "ada@example.io")]);
+        submit_record(
+            &source,
+            &registry,
+            &validators,
+            "accounts",
+            Some(&id),
+            None,
+            same,
+        )
+        .await
+        .unwrap();
+
+        let clash = record(&[("email", "ada@example.io"), ("handle", "admin")]);
+        let err = submit_record(
+            &source,
+            &registry,
+            &validators,
+            "accounts",
+            None,
+            None,
+            clash,
+        )
+        .await
+        .unwrap_err();
+        let expected = BTreeMap::from([
+            ("email".to_string(), vec!["is already taken".to_string()]),
+            ("handle".to_string(), vec!["is reserved".to_string()]),
+        ]);
+        assert_eq!(err, SubmitError::Invalid(expected.clone()));
+
+        // The errors land in the form's slots
+        let submitted = record(&[("email", "ada@example.io"), ("handle", "admin")]);
+        let form = crate::settings::record_form(&registry, "accounts", &submitted, &expected);
+        let form = form.unwrap();
+        let slot = r#"<p id="accounts-handle-error" data-sui-error="handle">is reserved</p>"#;
+        assert!(form.contains(slot), "{}", form);
+        assert!(
+            form.contains(r#"aria-describedby="accounts-email-error""#),
+            "{}",
+            form
+        );
+    }
+}
@@ -0,0 +1,94 @@
+// src/hot_reload.rs - Reload schemas and themes when their files change
+//
+// Development only (feature "hot-reload"). Edits to `schemas/` or themes.toml
+// are picked up after a short quiet period and swapped in atomically; a file
+// that fails to parse keeps the previous registry in place. Tables registered,
+// replaced, patched or removed at runtime are re-applied on top of the files.
+use crate::component_registry::component_registry;
+use crate::schema::{SchemaError, SchemaRegistry, registry, swap_reloaded_registry};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+// Editors write files in several steps; wait for this much quiet before reloading
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+// Keeps the watcher alive; dropping it stops reloading
+pub struct HotReload {
+    _watcher: RecommendedWatcher,
+}
+
+// 🔥 Watch `schemas_dir` and `themes_path`, reloading the active registry on change
+pub fn watch(schemas_dir: &Path, themes_path: &Path) -> notify::Result<HotReload> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if event.is_ok_and(|event| !event.kind.is_access()) {
+            let _ = tx.send(());
+        }
+    })?;
+    watcher.watch(schemas_dir, RecursiveMode::Recursive)?;
+    if themes_path.exists() {
+        watcher.watch(themes_path, RecursiveMode::NonRecursive)?;
+    }
+
+    let schemas_dir = schemas_dir.to_path_buf();
+    let themes_path = themes_path.to_path_buf();
+    std::thread::spawn(move || reload_loop(rx, schemas_dir, themes_path));
+
+    Ok(HotReload { _watcher: watcher })
+}
+
+fn reload_loop(rx: mpsc::Receiver<()>, schemas_dir: PathBuf, themes_path: PathBuf) {
+    // Ends when the watcher (and with it the sender) is dropped
+    while rx.recv().is_ok() {
+        while rx.recv_timeout(DEBOUNCE).is_ok() {}
+
+        match reload(&schemas_dir, &themes_path) {
+            Ok(next) => {
+                for dropped in swap_reloaded_registry(next) {
+                    eprintln!("⚠️  Runtime schema change lost on reload: {}", dropped);
+                }
+                component_registry().cache().clear();
+                println!("🔄 Reloaded schemas from {}", schemas_dir.display());
+            }
+            Err(e) => eprintln!("Schema reload failed, keeping previous schemas: {}", e),
+        }
+    }
+}
+
+// Fresh registry from disk, keeping the active theme selection
+pub fn reload(schemas_dir: &Path, themes_path: &Path) -> Result<SchemaRegistry, SchemaError> {
+    let mut next = SchemaRegistry::load_from_dir(schemas_dir)?;
+    if themes_path.exists() {
+        next.load_themes_file(themes_path)?;
+    }
+    next.set_theme(registry().get_current_theme());
+    Ok(next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reload_reads_schemas_and_themes_from_disk() {
+        let root = std::env::temp_dir().join(format!("uuie_hot_reload_{}", std::process::id()));
+        std::fs::create_dir_all(root.join("schemas/users")).unwrap();
+        std::fs::copy(
+            "schemas/users/users.toml",
+            root.join("schemas/users/users.toml"),
+        )
+        .unwrap();
+        std::fs::write(root.join("themes.toml"), "[neon]\nh2 = \"text-pink-500\"\n").unwrap();
+
+        let next = reload(&root.join("schemas"), &root.join("themes.toml")).unwrap();
+        assert!(next.get_table("users").is_some());
+        assert_eq!(next.get_theme("neon").unwrap().tags["h2"], "text-pink-500");
+
+        std::fs::write(root.join("schemas/users/users.toml"), "variants = 3").unwrap();
+        assert!(reload(&root.join("schemas"), &root.join("themes.toml")).is_err());
+
+        std::fs::remove_dir_all(root).ok();
+    }
+}
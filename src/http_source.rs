@@ -0,0 +1,308 @@
+// src/http_source.rs - Records served by an HTTP/JSON API
+//
+// Expects `GET {base}/{table}/{id}` to return a JSON object and
+// `GET {base}/{table}?limit=N` a JSON array of objects. Only plain http:// is
+// supported; requests use HTTP/1.0 so responses are never chunked.
+use crate::data_source::{DataError, DataSource, Record, RetryPolicy};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+use tokio_rustls::rustls::{self, OwnedTrustAnchor, RootCertStore, ServerName};
+
+// Upper bound for one request, including connecting
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Responses larger than this are cut off (and fail to parse)
+const MAX_RESPONSE_BYTES: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct HttpDataSource {
+    // host:port
+    target: String,
+    // Path prefix without trailing slash, e.g. "/v1"
+    prefix: String,
+    retry: RetryPolicy,
+}
+
+impl HttpDataSource {
+    pub fn new(base_url: &str, retry: RetryPolicy) -> Self {
+        let rest = base_url.trim_start_matches("http://").trim_end_matches('/');
+        let (host, prefix) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, ""),
+        };
+        let target = if host.contains(':') {
+            host.to_string()
+        } else {
+            format!("{}:80", host)
+        };
+
+        Self {
+            target,
+            prefix: prefix.to_string(),
+            retry,
+        }
+    }
+
+    async fn get_json(&self, path: &str, missing: &str) -> Result<Value, DataError> {
+        let request = tokio::time::timeout(REQUEST_TIMEOUT, http_get(&self.target, path));
+        let (status, body) = match request.await {
+            Err(_) => return Err(DataError::Timeout),
+            Ok(Err(e)) => return Err(DataError::Unavailable(e.to_string())),
+            Ok(Ok(response)) => response,
+        };
+
+        match status {
+            200..=299 => serde_json::from_str(&body)
+                .map_err(|e| DataError::Backend(format!("invalid JSON from {}: {}", path, e))),
+            404 => Err(DataError::NotFound(missing.to_string())),
+            408 | 429 | 500..=599 => Err(DataError::Unavailable(format!("HTTP {}", status))),
+            _ => Err(DataError::Backend(format!("HTTP {} from {}", status, path))),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSource for HttpDataSource {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    async fn get_record(&self, table: &str, id: &str) -> Result<Record, DataError> {
+        let path = format!("{}/{}/{}", self.prefix, encode(table), encode(id));
+        let value = self.retry.run(|| self.get_json(&path, id)).await?;
+        to_record(value).ok_or_else(|| DataError::Backend(format!("{} is not an object", path)))
+    }
+
+    async fn get_records(
+        &self,
+        table: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<Record>, DataError> {
+        let mut path = format!("{}/{}", self.prefix, encode(table));
+        if let Some(limit) = limit {
+            path.push_str(&format!("?limit={}", limit));
+        }
+
+        match self.retry.run(|| self.get_json(&path, table)).await? {
+            Value::Array(items) => Ok(items.into_iter().filter_map(to_record).collect()),
+            _ => Err(DataError::Backend(format!("{} is not an array", path))),
+        }
+    }
+}
+
+// JSON object -> record; nulls are dropped, non-strings are stringified
+fn to_record(value: Value) -> Option<Record> {
+    let Value::Object(fields) = value else {
+        return None;
+    };
+    Some(
+        fields
+            .into_iter()
+            .filter_map(|(key, value)| match value {
+                Value::Null => None,
+                Value::String(s) => Some((key, s)),
+                other => Some((key, other.to_string())),
+            })
+            .collect(),
+    )
+}
+
+// Percent-encode a path segment or query value
+pub(crate) fn encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+// Minimal GET returning (status, body)
+pub(crate) async fn http_get(target: &str, path: &str) -> std::io::Result<(u16, String)> {
+    let stream = TcpStream::connect(target).await?;
+    let (status, body) = send(
+        stream,
+        "GET",
+        target,
+        path,
+        &[("Accept", "application/json")],
+        &[],
+    )
+    .await?;
+    Ok((status, String::from_utf8_lossy(&body).into_owned()))
+}
+
+// GET an absolute http:// or https:// URL, returning (status, raw body)
+pub(crate) async fn fetch_url(url: &str) -> std::io::Result<(u16, Vec<u8>)> {
+    request_url("GET", url, &[("Accept", "*/*")], &[]).await
+}
+
+// Send `body` to an absolute http:// or https:// URL with extra headers,
+// returning (status, raw body)
+pub(crate) async fn request_url(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> std::io::Result<(u16, Vec<u8>)> {
+    let invalid =
+        |msg: &str| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg.to_string());
+    let (tls, rest) = if let Some(rest) = url.strip_prefix("https://") {
+        (true, rest)
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        (false, rest)
+    } else {
+        return Err(invalid("only http:// and https:// URLs are supported"));
+    };
+    let (authority, path) = match rest.find(['/', '?']) {
+        Some(i) if rest[i..].starts_with('?') => (&rest[..i], format!("/{}", &rest[i..])),
+        Some(i) => (&rest[..i], rest[i..].to_string()),
+        None => (rest, "/".to_string()),
+    };
+    let host = authority.split(':').next().unwrap_or_default();
+    let target = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{}:{}", authority, if tls { 443 } else { 80 })
+    };
+
+    let stream = TcpStream::connect(&target).await?;
+    if !tls {
+        return send(stream, method, authority, &path, headers, body).await;
+    }
+    let server_name = ServerName::try_from(host).map_err(|_| invalid("invalid host name"))?;
+    let stream = tls_connector().connect(server_name, stream).await?;
+    send(stream, method, authority, &path, headers, body).await
+}
+
+// Shared client config with the bundled Mozilla root certificates
+fn tls_connector() -> TlsConnector {
+    static CONFIG: OnceLock<Arc<rustls::ClientConfig>> = OnceLock::new();
+    let config = CONFIG.get_or_init(|| {
+        let mut roots = RootCertStore::empty();
+        roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|anchor| {
+            OwnedTrustAnchor::from_subject_spki_name_constraints(
+                anchor.subject,
+                anchor.spki,
+                anchor.name_constraints,
+            )
+        }));
+        Arc::new(
+            rustls::ClientConfig::builder()
+                .with_safe_defaults()
+                .with_root_certificates(roots)
+                .with_no_client_auth(),
+        )
+    });
+    TlsConnector::from(config.clone())
+}
+
+async fn send<S>(
+    mut stream: S,
+    method: &str,
+    host: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> std::io::Result<(u16, Vec<u8>)>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut request = format!("{} {} HTTP/1.0\r\nHost: {}\r\n", method, path, host);
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    if !body.is_empty() || method != "GET" {
+        request.push_str(&format!("Content-Length: {}\r\n", body.len()));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+    stream.write_all(body).await?;
+
+    let mut response = Vec::new();
+    // Servers that skip close_notify end TLS streams with an error once the
+    // body is complete; HTTP/1.0 framing already tells us where it ends
+    if let Err(e) = (&mut stream)
+        .take(MAX_RESPONSE_BYTES)
+        .read_to_end(&mut response)
+        .await
+        && (response.is_empty() || e.kind() != std::io::ErrorKind::UnexpectedEof)
+    {
+        return Err(e);
+    }
+
+    let malformed = || std::io::Error::new(std::io::ErrorKind::InvalidData, "malformed response");
+    let header_end = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(malformed)?;
+    // "HTTP/1.1 200 OK"
+    let status = String::from_utf8_lossy(&response[..header_end])
+        .split(' ')
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(malformed)?;
+    response.drain(..header_end + 4);
+
+    Ok((status, response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, http::StatusCode, routing::get};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_http_source_retries_transient_errors() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let app = Router::new()
+            .route(
+                "/v1/users/:id",
+                get(move || {
+                    let counter = counter.clone();
+                    async move {
+                        // First call fails like an overloaded upstream
+                        if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                            return Err(StatusCode::SERVICE_UNAVAILABLE);
+                        }
+                        Ok(axum::Json(
+                            serde_json::json!({ "id": 1, "name": "Ada", "bio": null }),
+                        ))
+                    }
+                }),
+            )
+            .route("/v1/missing/:id", get(|| async { StatusCode::NOT_FOUND }));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}/v1", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let retry = RetryPolicy {
+            base_delay: Duration::from_millis(1),
+            ..RetryPolicy::default()
+        };
+        let source = HttpDataSource::new(&base, retry);
+
+        let record = source.get_record("users", "1").await.unwrap();
+        assert_eq!(record["name"], "Ada");
+        assert_eq!(record["id"], "1");
+        assert!(!record.contains_key("bio"));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        assert_eq!(
+            source.get_record("missing", "7").await,
+            Err(DataError::NotFound("7".to_string()))
+        );
+    }
+}
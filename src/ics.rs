@@ -0,0 +1,224 @@
+// src/ics.rs - iCalendar output for event-like tables (format=ics)
+//
+// Tables opt in with an `[event]` mapping naming the fields that hold the
+// start, end and title (plus optional description/location), e.g.
+//   [event]
+//   start = "starts_at"
+//   end = "ends_at"
+//   title = "name"
+use crate::schema::EventMapping;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// 📅 VCALENDAR with one VEVENT per record; records without a usable start are skipped
+pub fn calendar(
+    table: &str,
+    mapping: &EventMapping,
+    records: &[HashMap<String, String>],
+) -> String {
+    let stamp = utc_stamp(SystemTime::now());
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//UUIE//Schema Calendar//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+
+    for (index, record) in records.iter().enumerate() {
+        let Some(start) = record.get(&mapping.start).and_then(|value| ics_date(value)) else {
+            continue;
+        };
+        let id = record
+            .get("id")
+            .cloned()
+            .unwrap_or_else(|| index.to_string());
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!(
+            "UID:{}-{}@uuie",
+            escape_text(table),
+            escape_text(&id)
+        ));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART{}", start));
+        if let Some(end) = mapping
+            .end
+            .as_ref()
+            .and_then(|field| record.get(field))
+            .and_then(|value| ics_date(value))
+        {
+            lines.push(format!("DTEND{}", end));
+        }
+        let title = record
+            .get(&mapping.title)
+            .map(String::as_str)
+            .unwrap_or_default();
+        lines.push(format!("SUMMARY:{}", escape_text(title)));
+        for (property, field) in [
+            ("DESCRIPTION", &mapping.description),
+            ("LOCATION", &mapping.location),
+        ] {
+            if let Some(value) = field.as_ref().and_then(|field| record.get(field)) {
+                lines.push(format!("{}:{}", property, escape_text(value)));
+            }
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+// Property suffix for a date or timestamp: ";VALUE=DATE:20240115" for dates,
+// ":20240115T103000Z" for UTC times, ":20240115T103000" for floating times
+fn ics_date(value: &str) -> Option<String> {
+    let value = value.trim();
+    let digits = |part: &str, len: usize| {
+        (part.len() == len && part.chars().all(|c| c.is_ascii_digit())).then(|| part.to_string())
+    };
+    let date = value.get(..10)?;
+    let mut parts = date.split('-');
+    let date = format!(
+        "{}{}{}",
+        digits(parts.next()?, 4)?,
+        digits(parts.next()?, 2)?,
+        digits(parts.next()?, 2)?
+    );
+    let rest = &value[10..];
+    if rest.is_empty() {
+        return Some(format!(";VALUE=DATE:{}", date));
+    }
+
+    let time = rest.strip_prefix(['T', ' '])?;
+    let (time, utc) = match time.strip_suffix('Z') {
+        Some(time) => (time, true),
+        None => (time, false),
+    };
+    // Fractional seconds are dropped; offsets other than Z are not supported
+    let time = time.split('.').next()?;
+    let mut parts = time.split(':');
+    let time = format!(
+        "{}{}{}",
+        digits(parts.next()?, 2)?,
+        digits(parts.next()?, 2)?,
+        digits(parts.next().unwrap_or("00"), 2)?
+    );
+    Some(format!(":{}T{}{}", date, time, if utc { "Z" } else { "" }))
+}
+
+// TEXT values escape backslashes, separators and newlines (RFC 5545 3.3.11)
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+// Lines longer than 75 octets continue on the next line after a space
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut width = 0;
+    for ch in line.chars() {
+        let len = ch.len_utf8();
+        if width + len > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(ch);
+        width += len;
+    }
+    folded
+}
+
+// "20240115T103000Z"
+fn utc_stamp(time: SystemTime) -> String {
+    iso_timestamp(time).replace(['-', ':'], "")
+}
+
+// "2024-01-15T10:30:00Z", the form timestamps take in records
+pub(crate) fn iso_timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_calendar_from_records() {
+        let mapping = EventMapping {
+            start: "starts_at".to_string(),
+            end: Some("ends_at".to_string()),
+            title: "name".to_string(),
+            description: Some("notes".to_string()),
+            location: None,
+        };
+        let records = vec![
+            HashMap::from([
+                ("id".to_string(), "1".to_string()),
+                ("name".to_string(), "Launch; party, v2".to_string()),
+                ("starts_at".to_string(), "2024-01-15T18:00:00Z".to_string()),
+                (
+                    "ends_at".to_string(),
+                    "2024-01-15T21:30:00.000Z".to_string(),
+                ),
+                ("notes".to_string(), "x".repeat(100)),
+            ]),
+            HashMap::from([
+                ("id".to_string(), "2".to_string()),
+                ("name".to_string(), "Offsite".to_string()),
+                ("starts_at".to_string(), "2024-02-01".to_string()),
+            ]),
+            HashMap::from([
+                ("id".to_string(), "3".to_string()),
+                ("name".to_string(), "TBD".to_string()),
+            ]),
+        ];
+
+        let ics = calendar("events", &mapping, &records);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.contains("UID:events-1@uuie\r\n"));
+        assert!(ics.contains("DTSTART:20240115T180000Z\r\nDTEND:20240115T213000Z\r\n"));
+        assert!(ics.contains("SUMMARY:Launch\\; party\\, v2\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20240201\r\n"));
+        assert!(ics.lines().all(|line| line.len() <= 75));
+        assert!(ics.contains("\r\n xxxx"));
+    }
+
+    #[test]
+    fn test_utc_stamp() {
+        let time = UNIX_EPOCH + Duration::from_secs(1_709_251_199);
+        assert_eq!(utc_stamp(time), "20240229T235959Z");
+        assert_eq!(utc_stamp(UNIX_EPOCH), "19700101T000000Z");
+    }
+}
@@ -0,0 +1,432 @@
+// src/image_proxy.rs - Resizing proxy for remote images (avatars, logos, ...)
+//
+// `/img?src=<url>&w=128&h=128` fetches an allowlisted image, resizes it and
+// keeps the result in memory, so url variants can point at right-sized images
+// without a separate image service.
+use crate::config::config;
+use crate::http_source::fetch_url;
+use image::imageops::FilterType;
+use image::{ImageFormat, ImageReader};
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+// Cached images before the oldest ones are evicted
+const MAX_CACHED_IMAGES: usize = 512;
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_MAX_FETCHES: usize = 8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImageProxyError {
+    NotAllowed(String),
+    InvalidRequest(String),
+    Upstream(String),
+    Unsupported(String),
+}
+
+impl std::fmt::Display for ImageProxyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageProxyError::NotAllowed(src) => write!(f, "Image source not allowed: {}", src),
+            ImageProxyError::InvalidRequest(msg) => write!(f, "Invalid image request: {}", msg),
+            ImageProxyError::Upstream(msg) => write!(f, "Failed to fetch image: {}", msg),
+            ImageProxyError::Unsupported(msg) => write!(f, "Unsupported image: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ImageProxyError {}
+
+// A resized image ready to serve
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProxiedImage {
+    pub bytes: Arc<Vec<u8>>,
+    pub content_type: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ImageKey {
+    src: String,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+#[derive(Debug)]
+pub struct ImageProxy {
+    // Hosts images may be fetched from; subdomains of an entry are allowed too
+    allowlist: Vec<String>,
+    max_dimension: u32,
+    ttl: Duration,
+    timeout: Duration,
+    // Bounds upstream fetches running at once
+    fetches: Semaphore,
+    entries: RwLock<HashMap<ImageKey, (ProxiedImage, Instant)>>,
+    // One lock per key being fetched, so concurrent misses share a fetch
+    in_flight: Mutex<HashMap<ImageKey, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl ImageProxy {
+    pub fn new(allowlist: Vec<String>, max_dimension: u32, ttl: Duration) -> Self {
+        Self {
+            allowlist,
+            max_dimension,
+            ttl,
+            timeout: DEFAULT_TIMEOUT,
+            fetches: Semaphore::new(DEFAULT_MAX_FETCHES),
+            entries: RwLock::new(HashMap::new()),
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn max_fetches(mut self, max_fetches: usize) -> Self {
+        self.fetches = Semaphore::new(max_fetches.max(1));
+        self
+    }
+
+    pub fn from_config() -> Self {
+        let config = config();
+        Self::new(
+            config.image_proxy_allowlist.clone(),
+            config.image_proxy_max_dimension,
+            Duration::from_secs(config.image_proxy_cache_ttl_secs),
+        )
+        .timeout(Duration::from_secs(config.image_proxy_timeout_secs))
+        .max_fetches(config.image_proxy_max_fetches)
+    }
+
+    pub fn is_allowed(&self, src: &str) -> bool {
+        let Some(host) = source_host(src) else {
+            return false;
+        };
+        self.allowlist.iter().any(|allowed| {
+            let allowed = allowed.to_ascii_lowercase();
+            host == allowed || host.ends_with(&format!(".{}", allowed))
+        })
+    }
+
+    // 🖼️ Fetch `src` and fit it into width x height (at least one is required)
+    pub async fn get(
+        &self,
+        src: &str,
+        width: Option<u32>,
+        height: Option<u32>,
+    ) -> Result<ProxiedImage, ImageProxyError> {
+        if !self.is_allowed(src) {
+            return Err(ImageProxyError::NotAllowed(src.to_string()));
+        }
+        if width.is_none() && height.is_none() {
+            return Err(ImageProxyError::InvalidRequest(
+                "width or height is required".to_string(),
+            ));
+        }
+        for dimension in [width, height].into_iter().flatten() {
+            if dimension == 0 || dimension > self.max_dimension {
+                return Err(ImageProxyError::InvalidRequest(format!(
+                    "dimensions must be between 1 and {}",
+                    self.max_dimension
+                )));
+            }
+        }
+
+        let key = ImageKey {
+            src: src.to_string(),
+            width,
+            height,
+        };
+        if let Some(image) = self.cached(&key) {
+            return Ok(image);
+        }
+
+        // Whoever takes the key lock first fetches; the rest find it cached
+        let key_lock = self.key_lock(&key);
+        let _fetching = key_lock.lock().await;
+        let _in_flight = InFlight {
+            proxy: self,
+            key: &key,
+            lock: &key_lock,
+        };
+        if let Some(image) = self.cached(&key) {
+            return Ok(image);
+        }
+
+        let _permit = self
+            .fetches
+            .acquire()
+            .await
+            .map_err(|e| ImageProxyError::Upstream(e.to_string()))?;
+        let (status, body) = tokio::time::timeout(self.timeout, fetch_url(src))
+            .await
+            .map_err(|_| ImageProxyError::Upstream(format!("timed out fetching {}", src)))?
+            .map_err(|e| ImageProxyError::Upstream(e.to_string()))?;
+        if !(200..300).contains(&status) {
+            return Err(ImageProxyError::Upstream(format!(
+                "HTTP {} from {}",
+                status, src
+            )));
+        }
+        // Decoding and resampling are CPU-bound; keep them off the async workers
+        let image = tokio::task::spawn_blocking(move || resize(&body, width, height))
+            .await
+            .map_err(|e| ImageProxyError::Unsupported(e.to_string()))??;
+
+        self.insert(key.clone(), image.clone());
+        Ok(image)
+    }
+
+    fn key_lock(&self, key: &ImageKey) -> Arc<tokio::sync::Mutex<()>> {
+        let mut in_flight = self
+            .in_flight
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        in_flight.entry(key.clone()).or_default().clone()
+    }
+
+    // Expired entries go first, then the oldest until there is room
+    fn insert(&self, key: ImageKey, image: ProxiedImage) {
+        let mut entries = self
+            .entries
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if entries.len() >= MAX_CACHED_IMAGES && !entries.contains_key(&key) {
+            let now = Instant::now();
+            entries.retain(|_, (_, expires_at)| now < *expires_at);
+            while entries.len() >= MAX_CACHED_IMAGES {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, (_, expires_at))| *expires_at)
+                    .map(|(key, _)| key.clone());
+                match oldest {
+                    Some(oldest) => entries.remove(&oldest),
+                    None => break,
+                };
+            }
+        }
+        entries.insert(key, (image, Instant::now() + self.ttl));
+    }
+
+    fn cached(&self, key: &ImageKey) -> Option<ProxiedImage> {
+        let entries = self
+            .entries
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        entries
+            .get(key)
+            .filter(|(_, expires_at)| Instant::now() < *expires_at)
+            .map(|(image, _)| image.clone())
+    }
+
+    pub fn cache_ttl(&self) -> Duration {
+        self.ttl
+    }
+}
+
+// Drops the key lock once its fetch finishes (or is cancelled)
+struct InFlight<'a> {
+    proxy: &'a ImageProxy,
+    key: &'a ImageKey,
+    lock: &'a Arc<tokio::sync::Mutex<()>>,
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        let mut in_flight = self
+            .proxy
+            .in_flight
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        // A later miss may already have installed a fresh lock for the key
+        if in_flight
+            .get(self.key)
+            .is_some_and(|lock| Arc::ptr_eq(lock, self.lock))
+        {
+            in_flight.remove(self.key);
+        }
+    }
+}
+
+// Lower-cased host of an http(s) URL
+fn source_host(src: &str) -> Option<String> {
+    let rest = src
+        .strip_prefix("https://")
+        .or_else(|| src.strip_prefix("http://"))?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    // Credentials in the URL could be used to smuggle a different host
+    if authority.contains('@') {
+        return None;
+    }
+    let host = authority.split(':').next()?;
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+// Both dimensions crop to fill (avatars); one keeps the aspect ratio; neither
+// re-encodes the original size. JPEG
+// stays JPEG, everything else is re-encoded as PNG.
+pub fn resize(
+    bytes: &[u8],
+    width: Option<u32>,
+    height: Option<u32>,
+) -> Result<ProxiedImage, ImageProxyError> {
+    let unsupported = |e: image::ImageError| ImageProxyError::Unsupported(e.to_string());
+    let reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| ImageProxyError::Unsupported(e.to_string()))?;
+    let format = reader.format();
+    let image = reader.decode().map_err(unsupported)?;
+
+    let image = match (width, height) {
+        (Some(w), Some(h)) => image.resize_to_fill(w, h, FilterType::CatmullRom),
+        (Some(w), None) => image.resize(w, u32::MAX, FilterType::CatmullRom),
+        (None, Some(h)) => image.resize(u32::MAX, h, FilterType::CatmullRom),
+        (None, None) => image,
+    };
+
+    let (format, content_type) = match format {
+        Some(ImageFormat::Jpeg) => (ImageFormat::Jpeg, "image/jpeg"),
+        _ => (ImageFormat::Png, "image/png"),
+    };
+    let image = match format {
+        // JPEG has no alpha channel
+        ImageFormat::Jpeg => image::DynamicImage::ImageRgb8(image.to_rgb8()),
+        _ => image,
+    };
+    let mut output = Cursor::new(Vec::new());
+    image.write_to(&mut output, format).map_err(unsupported)?;
+
+    Ok(ProxiedImage {
+        bytes: Arc::new(output.into_inner()),
+        content_type,
+    })
+}
+
+static IMAGE_PROXY: OnceLock<ImageProxy> = OnceLock::new();
+
+pub fn image_proxy() -> &'static ImageProxy {
+    IMAGE_PROXY.get_or_init(ImageProxy::from_config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, routing::get};
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let image = image::RgbaImage::from_pixel(width, height, image::Rgba([200, 30, 30, 255]));
+        let mut bytes = Cursor::new(Vec::new());
+        image.write_to(&mut bytes, ImageFormat::Png).unwrap();
+        bytes.into_inner()
+    }
+
+    #[test]
+    fn test_allowlist_matches_hosts_and_subdomains() {
+        let proxy = ImageProxy::new(vec!["example.com".to_string()], 1024, Duration::ZERO);
+        assert!(proxy.is_allowed("https://example.com/a.png"));
+        assert!(proxy.is_allowed("https://cdn.Example.com:8443/a.png"));
+        assert!(!proxy.is_allowed("https://example.com.evil.io/a.png"));
+        assert!(!proxy.is_allowed("https://example.com@evil.io/a.png"));
+        assert!(!proxy.is_allowed("file:///etc/passwd"));
+    }
+
+    #[tokio::test]
+    async fn test_fetches_resizes_and_caches() {
+        let app = Router::new().route(
+            "/avatar.png",
+            get(|| async { ([("content-type", "image/png")], png(300, 200)) }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let proxy = ImageProxy::new(vec!["127.0.0.1".to_string()], 512, Duration::from_secs(60));
+        let src = format!("http://{}/avatar.png", addr);
+        let image = proxy.get(&src, Some(64), Some(64)).await.unwrap();
+        assert_eq!(image.content_type, "image/png");
+        let decoded = image::load_from_memory(&image.bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (64, 64));
+
+        let scaled = proxy.get(&src, Some(150), None).await.unwrap();
+        let decoded = image::load_from_memory(&scaled.bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (150, 100));
+
+        // Served from memory: the same Arc comes back
+        let again = proxy.get(&src, Some(64), Some(64)).await.unwrap();
+        assert!(Arc::ptr_eq(&image.bytes, &again.bytes));
+
+        assert!(matches!(
+            proxy.get(&src, Some(4096), None).await,
+            Err(ImageProxyError::InvalidRequest(_))
+        ));
+        assert!(matches!(
+            proxy.get(&src, None, None).await,
+            Err(ImageProxyError::InvalidRequest(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_misses_share_one_fetch_and_slow_upstreams_time_out() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static HITS: AtomicUsize = AtomicUsize::new(0);
+        let app = Router::new()
+            .route(
+                "/avatar.png",
+                get(|| async {
+                    HITS.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    ([("content-type", "image/png")], png(40, 40))
+                }),
+            )
+            .route(
+                "/slow.png",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    png(40, 40)
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let proxy = ImageProxy::new(vec!["127.0.0.1".to_string()], 512, Duration::from_secs(60))
+            .timeout(Duration::from_millis(200))
+            .max_fetches(2);
+        let src = format!("http://{}/avatar.png", addr);
+        let results =
+            futures::future::join_all((0..5).map(|_| proxy.get(&src, Some(16), Some(16)))).await;
+        assert!(results.iter().all(|result| result.is_ok()));
+        assert_eq!(HITS.load(Ordering::SeqCst), 1);
+        assert!(proxy.in_flight.lock().unwrap().is_empty());
+
+        let slow = format!("http://{}/slow.png", addr);
+        assert!(matches!(
+            proxy.get(&slow, Some(16), None).await,
+            Err(ImageProxyError::Upstream(_))
+        ));
+    }
+
+    #[test]
+    fn test_full_cache_evicts_the_oldest_entry() {
+        let proxy = ImageProxy::new(Vec::new(), 512, Duration::from_secs(60));
+        let image = ProxiedImage {
+            bytes: Arc::new(Vec::new()),
+            content_type: "image/png",
+        };
+        let key = |n: usize| ImageKey {
+            src: format!("https://example.com/{}.png", n),
+            width: Some(16),
+            height: None,
+        };
+        for n in 0..=MAX_CACHED_IMAGES {
+            proxy.insert(key(n), image.clone());
+        }
+        assert_eq!(proxy.entries.read().unwrap().len(), MAX_CACHED_IMAGES);
+        assert!(proxy.cached(&key(0)).is_none());
+        assert!(proxy.cached(&key(1)).is_some());
+        assert!(proxy.cached(&key(MAX_CACHED_IMAGES)).is_some());
+    }
+}
@@ -0,0 +1,509 @@
+// src/jobs.rs - Background jobs for renders too heavy for a request
+//
+// POST /api/jobs queues a job and answers right away with its id; the job
+// runs on a tokio task, one at a time in submission order, and GET
+// /api/jobs/:id reports its progress. Jobs:
+//   rerender - render every record of a component (or of all components)
+//              afresh into the render cache, e.g. after a data import
+//   export   - render every record of a component into one HTML document,
+//              fetched from GET /api/jobs/:id/result when done
+// With JOBS_DIR set, jobs are saved there as JSON and survive restarts;
+// jobs a restart interrupted are reported as failed. At most JOBS_MAX_QUEUED
+// jobs wait at once, and finished jobs are dropped (from memory and JOBS_DIR)
+// after JOBS_FINISHED_TTL or once more than JOBS_MAX_FINISHED are kept.
+use crate::component_registry::{ComponentError, ComponentRegistry, RenderParams};
+use crate::config::config;
+use crate::ics::iso_timestamp;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, SystemTime};
+use tokio::sync::{Notify, Semaphore};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JobKind {
+    // Every component when `component` is None
+    Rerender {
+        #[serde(default)]
+        component: Option<String>,
+    },
+    Export {
+        component: String,
+        #[serde(default)]
+        context: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Job {
+    pub id: String,
+    #[serde(flatten)]
+    pub kind: JobKind,
+    pub status: JobStatus,
+    // Renders finished (including failed ones) out of `total`
+    pub done: usize,
+    pub total: usize,
+    // Renders that failed without stopping the job, e.g. a missing record
+    pub failed_renders: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    // Output of export jobs, served separately from the status
+    #[serde(default, skip_serializing)]
+    pub result: Option<String>,
+    pub created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<String>,
+}
+
+// Jobs on disk keep their result, which the status JSON leaves out
+#[derive(Serialize, Deserialize)]
+struct StoredJob {
+    #[serde(flatten)]
+    job: Job,
+    #[serde(default)]
+    result: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum JobError {
+    // This many jobs are already waiting
+    QueueFull(usize),
+}
+
+impl std::fmt::Display for JobError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobError::QueueFull(max) => write!(f, "{} jobs are already queued", max),
+        }
+    }
+}
+
+impl std::error::Error for JobError {}
+
+#[derive(Debug)]
+pub struct JobQueue {
+    jobs: DashMap<String, Job>,
+    dir: Option<PathBuf>,
+    // One permit: jobs run one after another, in the order they were queued
+    running: Semaphore,
+    // Woken whenever a job finishes
+    finished: Notify,
+    // Jobs submitted that have not started yet, and how many may be
+    queued: AtomicUsize,
+    max_queued: usize,
+    // Retention of succeeded and failed jobs
+    max_finished: usize,
+    finished_ttl: Duration,
+}
+
+impl JobQueue {
+    // Queue persisting to `dir`, with the jobs already saved there
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        let queue = Self {
+            jobs: DashMap::new(),
+            dir,
+            running: Semaphore::new(1),
+            finished: Notify::new(),
+            queued: AtomicUsize::new(0),
+            max_queued: 100,
+            max_finished: 200,
+            finished_ttl: Duration::from_secs(7 * 24 * 3600),
+        };
+        for mut job in queue.load() {
+            if matches!(job.status, JobStatus::Queued | JobStatus::Running) {
+                job.status = JobStatus::Failed;
+                job.error = Some("interrupted by a restart".to_string());
+                job.finished_at = Some(iso_timestamp(SystemTime::now()));
+                queue.save(&job);
+            }
+            queue.jobs.insert(job.id.clone(), job);
+        }
+        queue
+    }
+
+    pub fn max_queued(mut self, max_queued: usize) -> Self {
+        self.max_queued = max_queued.max(1);
+        self
+    }
+
+    // Finished jobs beyond `max_finished` or older than `ttl` are dropped
+    pub fn retention(mut self, max_finished: usize, ttl: Duration) -> Self {
+        self.max_finished = max_finished.max(1);
+        self.finished_ttl = ttl;
+        self.prune();
+        self
+    }
+
+    pub fn from_config() -> Self {
+        let config = config();
+        Self::new(config.jobs_dir.clone())
+            .max_queued(config.jobs_max_queued)
+            .retention(
+                config.jobs_max_finished,
+                Duration::from_secs(config.jobs_finished_ttl_secs),
+            )
+    }
+
+    pub fn get(&self, id: &str) -> Option<Job> {
+        self.jobs.get(id).map(|job| job.clone())
+    }
+
+    // Every known job, newest first
+    pub fn list(&self) -> Vec<Job> {
+        let mut jobs: Vec<_> = self.jobs.iter().map(|job| job.clone()).collect();
+        jobs.sort_by(|a, b| {
+            b.created_at
+                .cmp(&a.created_at)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        jobs
+    }
+
+    // 🧵 Queue `kind` to run against `components`; returns the queued job, or
+    // QueueFull when max_queued jobs are already waiting
+    pub fn submit(
+        self: &Arc<Self>,
+        kind: JobKind,
+        components: &'static ComponentRegistry,
+    ) -> Result<Job, JobError> {
+        let reserved = self
+            .queued
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |queued| {
+                (queued < self.max_queued).then_some(queued + 1)
+            });
+        if reserved.is_err() {
+            return Err(JobError::QueueFull(self.max_queued));
+        }
+        self.prune();
+
+        let job = Job {
+            id: format!("{:016x}", fastrand::u64(..)),
+            kind: kind.clone(),
+            status: JobStatus::Queued,
+            done: 0,
+            total: 0,
+            failed_renders: 0,
+            error: None,
+            result: None,
+            created_at: iso_timestamp(SystemTime::now()),
+            finished_at: None,
+        };
+        self.jobs.insert(job.id.clone(), job.clone());
+        self.save(&job);
+
+        let queue = Arc::clone(self);
+        let id = job.id.clone();
+        tokio::spawn(async move {
+            let permit = queue.running.acquire().await;
+            queue.queued.fetch_sub(1, Ordering::SeqCst);
+            let Ok(_permit) = permit else {
+                return;
+            };
+            queue.update(&id, |job| job.status = JobStatus::Running);
+            let outcome = queue.run(&id, &kind, components).await;
+            queue.update(&id, |job| {
+                match outcome {
+                    Ok(result) => {
+                        job.status = JobStatus::Succeeded;
+                        job.result = result;
+                    }
+                    Err(err) => {
+                        job.status = JobStatus::Failed;
+                        job.error = Some(err.to_string());
+                    }
+                }
+                job.finished_at = Some(iso_timestamp(SystemTime::now()));
+            });
+            queue.finished.notify_waiters();
+            queue.prune();
+        });
+        Ok(job)
+    }
+
+    // Drop finished jobs past the TTL, then the oldest beyond max_finished
+    fn prune(&self) {
+        let now = SystemTime::now();
+        let cutoff = now.checked_sub(self.finished_ttl).map(iso_timestamp);
+        let mut finished: Vec<(String, String)> = self
+            .jobs
+            .iter()
+            .filter(|job| matches!(job.status, JobStatus::Succeeded | JobStatus::Failed))
+            .map(|job| {
+                let at = job
+                    .finished_at
+                    .clone()
+                    .unwrap_or_else(|| job.created_at.clone());
+                (at, job.id.clone())
+            })
+            .collect();
+        // Newest first; timestamps share one format, so they sort as strings
+        finished.sort_by(|a, b| b.cmp(a));
+        let expired = finished.iter().enumerate().filter(|(i, (at, _))| {
+            *i >= self.max_finished || cutoff.as_ref().is_some_and(|cutoff| at < cutoff)
+        });
+        for (_, (_, id)) in expired {
+            self.jobs.remove(id);
+            if let Some(dir) = &self.dir {
+                let _ = std::fs::remove_file(dir.join(format!("{}.json", id)));
+            }
+        }
+    }
+
+    // The job once it has succeeded or failed (None for unknown ids)
+    pub async fn wait(&self, id: &str) -> Option<Job> {
+        loop {
+            let finished = self.finished.notified();
+            let job = self.get(id)?;
+            if matches!(job.status, JobStatus::Succeeded | JobStatus::Failed) {
+                return Some(job);
+            }
+            finished.await;
+        }
+    }
+
+    async fn run(
+        &self,
+        id: &str,
+        kind: &JobKind,
+        components: &ComponentRegistry,
+    ) -> Result<Option<String>, ComponentError> {
+        let (names, context) = match kind {
+            JobKind::Rerender {
+                component: Some(name),
+            } => (vec![name.clone()], None),
+            JobKind::Rerender { component: None } => {
+                let mut names: Vec<_> = components.list_components().into_iter().cloned().collect();
+                names.sort();
+                (names, None)
+            }
+            JobKind::Export { component, context } => (vec![component.clone()], context.clone()),
+        };
+
+        let mut renders = Vec::new();
+        for name in &names {
+            for record_id in Self::record_ids(components, name).await? {
+                renders.push((name.clone(), record_id));
+            }
+        }
+        self.progress(id, |job| job.total = renders.len());
+
+        let export = matches!(kind, JobKind::Export { .. });
+        let mut document = String::new();
+        for (name, record_id) in &renders {
+            let params = RenderParams {
+                context: context.as_deref(),
+                refresh: !export,
+                ..Default::default()
+            };
+            let rendered = components.render_component(name, record_id, params).await;
+            match rendered {
+                Ok(html) if export => document.push_str(&html),
+                Ok(_) => {}
+                Err(err) => {
+                    eprintln!("Job {}: {} '{}' failed: {}", id, name, record_id, err);
+                    self.progress(id, |job| job.failed_renders += 1);
+                }
+            }
+            self.progress(id, |job| job.done += 1);
+        }
+        Ok(export.then_some(document))
+    }
+
+    // Records a component can render: its own for static and singleton
+    // components, every record of its table otherwise
+    async fn record_ids(
+        components: &ComponentRegistry,
+        name: &str,
+    ) -> Result<Vec<String>, ComponentError> {
+        let component = components
+            .get_component(name)
+            .ok_or_else(|| ComponentError::ComponentNotFound(name.to_string()))?;
+        let single = component.static_record.is_some()
+            || crate::schema::registry()
+                .get_table(&component.table)
+                .is_some_and(|schema| schema.singleton);
+        if single {
+            return Ok(vec![String::new()]);
+        }
+        let records = components
+            .data_source()
+            .get_records(&component.table, None)
+            .await?;
+        Ok(records
+            .into_iter()
+            .filter_map(|mut record| record.remove("id"))
+            .collect())
+    }
+
+    // Change a job's state and save it
+    fn update(&self, id: &str, change: impl FnOnce(&mut Job)) {
+        let job = self.jobs.get_mut(id).map(|mut job| {
+            change(&mut job);
+            job.clone()
+        });
+        if let Some(job) = job {
+            self.save(&job);
+        }
+    }
+
+    // Progress ticks are only kept in memory; the final state is saved
+    fn progress(&self, id: &str, change: impl FnOnce(&mut Job)) {
+        if let Some(mut job) = self.jobs.get_mut(id) {
+            change(&mut job);
+        }
+    }
+
+    fn save(&self, job: &Job) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+        let stored = StoredJob {
+            job: job.clone(),
+            result: job.result.clone(),
+        };
+        let path = dir.join(format!("{}.json", job.id));
+        let written = std::fs::create_dir_all(dir)
+            .and_then(|_| std::fs::write(&path, serde_json::to_vec(&stored).unwrap_or_default()));
+        if let Err(err) = written {
+            eprintln!("Failed to save job {}: {}", path.display(), err);
+        }
+    }
+
+    fn load(&self) -> Vec<Job> {
+        let Some(entries) = self
+            .dir
+            .as_ref()
+            .and_then(|dir| std::fs::read_dir(dir).ok())
+        else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| {
+                let content = std::fs::read(entry.path()).ok()?;
+                let stored: StoredJob = serde_json::from_slice(&content)
+                    .map_err(|e| eprintln!("Ignoring unreadable job {:?}: {}", entry.path(), e))
+                    .ok()?;
+                Some(Job {
+                    result: stored.result,
+                    ..stored.job
+                })
+            })
+            .collect()
+    }
+}
+
+// Global job queue, persisting to JOBS_DIR when set
+static JOB_QUEUE: OnceLock<Arc<JobQueue>> = OnceLock::new();
+
+pub fn job_queue() -> &'static Arc<JobQueue> {
+    JOB_QUEUE.get_or_init(|| Arc::new(JobQueue::from_config()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component_registry::component_registry;
+    use std::time::Duration;
+
+    async fn finished(queue: &JobQueue, id: &str) -> Job {
+        for _ in 0..200 {
+            let job = queue.get(id).unwrap();
+            if matches!(job.status, JobStatus::Succeeded | JobStatus::Failed) {
+                return job;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("job {} did not finish", id);
+    }
+
+    #[tokio::test]
+    async fn test_jobs_run_in_background_and_persist() {
+        let dir = std::env::temp_dir().join(format!("sui-jobs-{}", std::process::id()));
+        let queue = Arc::new(JobQueue::new(Some(dir.clone())));
+
+        let export = JobKind::Export {
+            component: "user_card".to_string(),
+            context: None,
+        };
+        let job = queue.submit(export, component_registry()).unwrap();
+        assert_eq!(job.status, JobStatus::Queued);
+        let job = finished(&queue, &job.id).await;
+        assert_eq!(job.status, JobStatus::Succeeded, "{:?}", job.error);
+        assert_eq!((job.done, job.total, job.failed_renders), (3, 3, 0));
+        let document = job.result.unwrap();
+        assert!(document.contains("John Doe") && document.contains("Bob Wilson"));
+
+        let missing = JobKind::Rerender {
+            component: Some("missing".to_string()),
+        };
+        let failed = queue.submit(missing, component_registry()).unwrap();
+        let failed = finished(&queue, &failed.id).await;
+        assert_eq!(
+            failed.error.as_deref(),
+            Some("Component 'missing' not found")
+        );
+
+        // A restart keeps finished jobs, results included
+        let reloaded = JobQueue::new(Some(dir.clone()));
+        assert!(reloaded.get(&job.id).unwrap().result.is_some());
+        assert_eq!(reloaded.list().len(), 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_queue_is_capped_and_finished_jobs_expire() {
+        let dir = std::env::temp_dir().join(format!("sui-jobs-retention-{}", std::process::id()));
+        let missing = || JobKind::Rerender {
+            component: Some("missing".to_string()),
+        };
+
+        // A job finished long ago is dropped on start, file included
+        std::fs::create_dir_all(&dir).unwrap();
+        let stale = r#"{"id":"stale","kind":"rerender","status":"failed","done":0,"total":0,
+            "failed_renders":0,"created_at":"2000-01-01T00:00:00Z",
+            "finished_at":"2000-01-01T00:00:01Z"}"#;
+        std::fs::write(dir.join("stale.json"), stale).unwrap();
+        let queue = Arc::new(
+            JobQueue::new(Some(dir.clone()))
+                .max_queued(1)
+                .retention(2, Duration::from_secs(3600)),
+        );
+        assert!(queue.get("stale").is_none());
+        assert!(!dir.join("stale.json").exists());
+
+        // While a job runs only one more may wait
+        let running = queue.running.try_acquire().unwrap();
+        let first = queue.submit(missing(), component_registry()).unwrap();
+        let err = queue.submit(missing(), component_registry()).unwrap_err();
+        assert!(matches!(err, JobError::QueueFull(1)));
+        drop(running);
+        finished(&queue, &first.id).await;
+
+        // Only the two newest finished jobs are kept
+        let mut ids = vec![first.id];
+        for _ in 0..2 {
+            tokio::time::sleep(Duration::from_millis(1100)).await;
+            let job = queue.submit(missing(), component_registry()).unwrap();
+            finished(&queue, &job.id).await;
+            ids.push(job.id);
+        }
+        assert!(queue.get(&ids[0]).is_none());
+        assert!(!dir.join(format!("{}.json", ids[0])).exists());
+        assert_eq!(queue.list().len(), 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
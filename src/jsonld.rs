@@ -0,0 +1,77 @@
+// src/jsonld.rs - schema.org structured data for rendered components
+//
+// Tables map a component to a schema.org type and its fields to properties:
+//   [jsonld.user_card]
+//   jsonld_type = "Person"
+//   fields = { name = "name", avatar_url = "image" }
+// and renders requested with jsonld=true get a JSON-LD <script> appended, so
+// pages assembled from fragments still carry structured data for crawlers.
+use crate::schema::JsonLdMapping;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+// The JSON-LD object for a record; empty fields are left out
+pub fn jsonld_object(mapping: &JsonLdMapping, record: &HashMap<String, String>) -> Value {
+    let mut object = Map::new();
+    object.insert("@context".to_string(), Value::from("https://schema.org"));
+    object.insert(
+        "@type".to_string(),
+        Value::from(mapping.jsonld_type.as_str()),
+    );
+
+    for (field, property) in &mapping.fields {
+        if let Some(value) = record.get(field).filter(|value| !value.is_empty()) {
+            object.insert(property.clone(), Value::from(value.as_str()));
+        }
+    }
+    Value::Object(object)
+}
+
+// 🔎 `<script type="application/ld+json">` block for a record
+pub fn jsonld_script(mapping: &JsonLdMapping, record: &HashMap<String, String>) -> String {
+    // `<` only occurs inside JSON strings, where < is equivalent; this
+    // keeps values like "</script>" from ending the block early
+    let json = jsonld_object(mapping, record)
+        .to_string()
+        .replace('<', "\\u003c");
+    format!(r#"<script type="application/ld+json">{}</script>"#, json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jsonld_script() {
+        let mapping = JsonLdMapping {
+            jsonld_type: "Person".to_string(),
+            fields: HashMap::from([
+                ("name".to_string(), "name".to_string()),
+                ("avatar_url".to_string(), "image".to_string()),
+                ("email".to_string(), "email".to_string()),
+            ]),
+        };
+        let record = HashMap::from([
+            ("name".to_string(), "Eve</script><b>".to_string()),
+            (
+                "avatar_url".to_string(),
+                "https://example.com/eve.png".to_string(),
+            ),
+            ("email".to_string(), String::new()),
+        ]);
+
+        let object = jsonld_object(&mapping, &record);
+        assert_eq!(object["@type"], "Person");
+        assert_eq!(object["image"], "https://example.com/eve.png");
+        assert!(object.get("email").is_none());
+
+        let script = jsonld_script(&mapping, &record);
+        assert!(script.starts_with(r#"<script type="application/ld+json">{"@context""#));
+        assert_eq!(script.matches("</script>").count(), 1);
+        let json = script
+            .trim_start_matches(r#"<script type="application/ld+json">"#)
+            .trim_end_matches("</script>");
+        let parsed: Value = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed["name"], "Eve</script><b>");
+    }
+}
@@ -1,12 +1,62 @@
 // Main library entry point
+pub mod analytics;
+pub mod attr_policy;
+pub mod bench;
+pub mod budget;
+pub mod cache;
+pub mod chat_format;
+pub mod class_report;
+pub mod client;
+pub mod codegen;
+pub mod compat;
+pub mod compiled;
 pub mod component_registry;
+pub mod config;
+pub mod content_scan;
+pub mod currency;
+pub mod cursor;
+pub mod data_source;
+pub mod database;
+pub mod describe;
+pub mod facets;
+pub mod field_types;
+pub mod formatter;
+pub mod forms;
+#[cfg(feature = "hot-reload")]
+pub mod hot_reload;
+pub mod http_source;
+pub mod ics;
+pub mod image_proxy;
+pub mod jobs;
+pub mod jsonld;
+pub mod lint;
+pub mod loadgen;
+pub mod locale;
+pub mod memo;
+pub mod oembed;
 pub mod renderer;
+pub mod scheduler;
 pub mod schema;
+pub mod schema_builder;
+pub mod schema_store;
+pub mod search;
+pub mod settings;
+pub mod short_text;
+pub mod tokens;
+pub mod tree;
+pub mod uploads;
+pub mod validation;
+pub mod variant_kinds;
+pub mod views;
 pub mod web;
 
 // Re-export main types for easy access
-pub use component_registry::{ComponentRegistry, component_registry};
-pub use renderer::Renderer;
+pub use component_registry::{
+    ComponentRegistry, ComponentTemplate, component_registry, set_component_registry,
+};
+pub use config::{Config, config};
+pub use field_types::FieldValue;
+pub use renderer::{Page, Renderer};
 pub use schema::{SchemaRegistry, registry};
 pub use web::{create_router, start_server};
 
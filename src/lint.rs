@@ -0,0 +1,315 @@
+// src/lint.rs - Housekeeping checks for large schema sets
+//
+// Unlike validation, nothing reported here renders wrongly; it's schema that
+// does nothing or says things twice:
+//   - variants no context, default or mapping ever picks
+//   - context entries repeating the variant the inherited context already sets
+//   - mock_data fields no variant renders
+//   - `{value}` in attrs of void tags other than img and input, which show no
+//     content, so the element was likely meant to be a content tag
+//   - entries still picking deprecated variants or inheriting deprecated contexts
+// `SchemaRegistry::lint` runs these over every table; GET /api/schemas/lint
+// serves the result.
+use crate::schema::{Context, TableSchema, WILDCARD_FIELD, split_platform_variant};
+use crate::validation::{Diagnostic, Diagnostics};
+use std::collections::HashSet;
+
+// Elements without content; img and input carry their value in attributes
+const VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "link", "meta", "source", "track", "wbr",
+];
+// Variant picked by name rather than through a context (see search.rs)
+const IMPLICIT_VARIANTS: &[&str] = &["highlight"];
+
+// 🧹 Every lint finding in one table schema, all warnings
+pub fn lint_table(table: &str, schema: &TableSchema) -> Vec<Diagnostic> {
+    let mut diagnostics = Diagnostics::new(table);
+    lint_unused_variants(schema, &mut diagnostics);
+    lint_repeated_entries(schema, &mut diagnostics);
+    lint_mock_fields(schema, &mut diagnostics);
+    lint_void_values(schema, &mut diagnostics);
+    lint_deprecated_uses(schema, &mut diagnostics);
+    diagnostics.into_found()
+}
+
+fn contexts(schema: &TableSchema) -> impl Iterator<Item = &Context> {
+    schema
+        .contexts
+        .values()
+        .chain(schema.shared_contexts.values())
+}
+
+fn lint_unused_variants(schema: &TableSchema, diagnostics: &mut Diagnostics) {
+    let mut used: HashSet<(&str, &str)> = HashSet::new();
+    // Names a "*" entry picks for any field that has them
+    let mut wildcard: HashSet<&str> = HashSet::new();
+    for (field, variant) in schema.defaults.iter().flatten() {
+        used.insert((field, variant));
+    }
+    for (field, variant) in contexts(schema).flat_map(|context| &context.fields) {
+        if field == WILDCARD_FIELD {
+            wildcard.insert(variant);
+        } else {
+            used.insert((field, variant));
+        }
+    }
+    for (field, by_value) in &schema.value_variants {
+        for variant in by_value.values() {
+            used.insert((field, variant));
+        }
+    }
+    for (field, relation) in &schema.relations {
+        if let Some(variant) = &relation.variant {
+            used.insert((field, variant));
+        }
+    }
+    for (field, variants) in &schema.variants {
+        for extended in variants
+            .values()
+            .filter_map(|variant| variant.extends.as_ref())
+        {
+            used.insert((field, extended));
+        }
+    }
+
+    let mut fields: Vec<_> = schema.variants.iter().collect();
+    fields.sort_by_key(|(field, _)| *field);
+    for (field, variants) in fields {
+        // A field's only variant renders as its last resort
+        if variants.len() < 2 {
+            continue;
+        }
+        let mut names: Vec<_> = variants.keys().collect();
+        names.sort();
+        for name in names {
+            // Platform variants ("h1@mobile") render wherever their generic one does
+            let generic = split_platform_variant(name).map_or(name.as_str(), |(name, _)| name);
+            let picked = used.contains(&(field.as_str(), generic))
+                || wildcard.contains(generic)
+                || IMPLICIT_VARIANTS.contains(&generic);
+            if !picked {
+                diagnostics.warning(
+                    format!("variants.{}.{}", field, name),
+                    "no context, default or mapping uses this variant".to_string(),
+                );
+            }
+        }
+    }
+}
+
+// The variant `field` gets from the contexts `context` inherits from, and
+// the context setting it
+fn inherited_entry<'s>(
+    schema: &'s TableSchema,
+    context: &'s Context,
+    field: &str,
+) -> Option<(&'s str, &'s str)> {
+    let mut seen = Vec::new();
+    let mut current = context
+        .inherits
+        .as_deref()
+        .and_then(|parent| schema.context(parent));
+    while let Some((name, parent)) = current {
+        if seen.contains(&name) {
+            break;
+        }
+        if let Some(variant) = parent.fields.get(field) {
+            return Some((name, variant));
+        }
+        seen.push(name);
+        current = parent
+            .inherits
+            .as_deref()
+            .and_then(|parent| schema.context(parent));
+    }
+    None
+}
+
+fn lint_repeated_entries(schema: &TableSchema, diagnostics: &mut Diagnostics) {
+    let mut names: Vec<_> = schema.contexts.keys().collect();
+    names.sort();
+    for name in names {
+        let context = &schema.contexts[name];
+        let mut fields: Vec<_> = context.fields.iter().collect();
+        fields.sort();
+        for (field, variant) in fields {
+            if let Some((parent, inherited)) = inherited_entry(schema, context, field)
+                && inherited == variant
+            {
+                diagnostics.warning(
+                    format!("contexts.{}.{}", name, field),
+                    format!("repeats '{}' inherited from '{}'", variant, parent),
+                );
+            }
+        }
+    }
+}
+
+fn lint_mock_fields(schema: &TableSchema, diagnostics: &mut Diagnostics) {
+    // Undeclared fields render through the table's own "*" variants
+    if schema.variants.contains_key(WILDCARD_FIELD) {
+        return;
+    }
+    // Data that is looked up rather than rendered
+    let mut plumbing: HashSet<&str> = HashSet::from(["id"]);
+    plumbing.extend(schema.aliases.keys().map(String::as_str));
+    plumbing.extend(
+        schema
+            .references
+            .values()
+            .map(|reference| reference.key.as_str()),
+    );
+    plumbing.extend(schema.tree.as_ref().map(|tree| tree.parent.as_str()));
+
+    let mut missing: Vec<_> = schema
+        .mock_data
+        .iter()
+        .flatten()
+        .flat_map(|record| record.fields.keys())
+        .filter(|field| !schema.variants.contains_key(*field) && !plumbing.contains(field.as_str()))
+        .collect();
+    missing.sort();
+    missing.dedup();
+    for field in missing {
+        diagnostics.warning(
+            "mock_data".to_string(),
+            format!("field '{}' has mock values but no variants", field),
+        );
+    }
+}
+
+fn lint_void_values(schema: &TableSchema, diagnostics: &mut Diagnostics) {
+    let mut fields: Vec<_> = schema.variants.iter().collect();
+    fields.sort_by_key(|(field, _)| *field);
+    for (field, variants) in fields {
+        let mut variants: Vec<_> = variants.iter().collect();
+        variants.sort_by_key(|(name, _)| *name);
+        for (name, variant) in variants {
+            let uses_value = variant
+                .attrs
+                .iter()
+                .flatten()
+                .any(|(_, value)| value.contains("{value}"));
+            if uses_value && VOID_TAGS.contains(&variant.base.as_str()) {
+                diagnostics.warning(
+                    format!("variants.{}.{}", field, name),
+                    format!(
+                        "<{}> shows no content; {{value}} only reaches attrs",
+                        variant.base
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn lint_deprecated_uses(schema: &TableSchema, diagnostics: &mut Diagnostics) {
+    let deprecated = |field: &str, variant: &str| {
+        schema
+            .variants
+            .get(field)?
+            .get(variant)?
+            .deprecated
+            .as_ref()
+    };
+    let mut entries: Vec<_> = schema
+        .defaults
+        .iter()
+        .flatten()
+        .map(|(field, variant)| (format!("defaults.{}", field), field, variant))
+        .chain(schema.contexts.iter().flat_map(|(name, context)| {
+            let path = move |field| format!("contexts.{}.{}", name, field);
+            context
+                .fields
+                .iter()
+                .map(move |(field, variant)| (path(field), field, variant))
+        }))
+        .collect();
+    entries.sort();
+    for (path, field, variant) in entries {
+        if let Some(message) = deprecated(field, variant) {
+            let message = format!("picks deprecated variant '{}': {}", variant, message);
+            diagnostics.warning(path, message);
+        }
+    }
+
+    let mut contexts: Vec<_> = schema.contexts.iter().collect();
+    contexts.sort_by_key(|(name, _)| *name);
+    for (name, context) in contexts {
+        let parent = context
+            .inherits
+            .as_deref()
+            .and_then(|parent| schema.context(parent));
+        if let Some((
+            parent,
+            Context {
+                deprecated: Some(message),
+                ..
+            },
+        )) = parent
+        {
+            diagnostics.warning(
+                format!("contexts.{}.inherits", name),
+                format!("inherits deprecated context '{}': {}", parent, message),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_reports_dead_and_repeated_schema() {
+        let schema = TableSchema::from_toml_str(
+            r#"
+            [variants.name]
+            h1 = { base = "h1" }
+            h2 = { base = "h2" }
+            big = { extends = "h2", override = "text-xl" }
+            search = { base = "span" }
+            highlight = { base = "mark" }
+            [variants.divider]
+            rule = { base = "hr", attrs = { title = "{value}" } }
+            [variants.avatar]
+            img = { base = "img", attrs = { src = "{value}" } }
+
+            [contexts.card]
+            name = "big"
+
+            [contexts.list]
+            inherits = "card"
+            name = "big"
+
+            [contexts.detail]
+            name = "h1"
+
+            [aliases]
+            full_name = "name"
+
+            [[mock_data]]
+            id = "1"
+            full_name = "Ada"
+            nickname = "ada"
+            "#,
+        )
+        .unwrap();
+
+        let found: Vec<_> = lint_table("users", &schema)
+            .iter()
+            .map(|d| d.to_string())
+            .collect();
+        assert_eq!(
+            found,
+            [
+                "warning: users: variants.name.search: \
+                 no context, default or mapping uses this variant",
+                "warning: users: contexts.list.name: repeats 'big' inherited from 'card'",
+                "warning: users: mock_data: field 'nickname' has mock values but no variants",
+                "warning: users: variants.divider.rule: \
+                 <hr> shows no content; {value} only reaches attrs",
+            ]
+        );
+    }
+}
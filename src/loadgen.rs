@@ -0,0 +1,180 @@
+// src/loadgen.rs - Synthetic render traffic against a running instance
+//
+// Plain HTTP over TCP (one connection per request) keeps the generator free
+// of client dependencies; latencies therefore include connection setup.
+use crate::http_source::http_get;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct LoadgenConfig {
+    // host:port of the instance under test
+    pub target: String,
+    pub components: Vec<String>,
+    pub ids: Vec<String>,
+    pub contexts: Vec<String>,
+    pub themes: Vec<String>,
+    pub concurrency: usize,
+    pub requests: usize,
+}
+
+impl Default for LoadgenConfig {
+    fn default() -> Self {
+        Self {
+            target: "127.0.0.1:3000".to_string(),
+            components: vec!["user_card".to_string()],
+            ids: vec!["1".to_string(), "2".to_string(), "3".to_string()],
+            contexts: vec!["card".to_string(), "list".to_string()],
+            themes: vec!["light".to_string(), "dark".to_string()],
+            concurrency: 16,
+            requests: 1000,
+        }
+    }
+}
+
+impl LoadgenConfig {
+    // Every (component, id, context, theme) path in the mix
+    pub fn request_paths(&self) -> Vec<String> {
+        let mut paths = Vec::new();
+        for component in &self.components {
+            for id in &self.ids {
+                for context in &self.contexts {
+                    for theme in &self.themes {
+                        paths.push(format!(
+                            "/api/{}?id={}&context={}&theme={}",
+                            component, id, context, theme
+                        ));
+                    }
+                }
+            }
+        }
+        paths
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct LoadReport {
+    // Latencies of successful (2xx) requests, sorted ascending
+    pub latencies: Vec<Duration>,
+    pub errors: usize,
+    pub elapsed: Duration,
+}
+
+impl LoadReport {
+    // Nearest-rank percentile, e.g. `percentile(0.99)`
+    pub fn percentile(&self, quantile: f64) -> Option<Duration> {
+        if self.latencies.is_empty() {
+            return None;
+        }
+        let rank = (quantile * self.latencies.len() as f64).ceil() as usize;
+        let index = rank.clamp(1, self.latencies.len()) - 1;
+        Some(self.latencies[index])
+    }
+
+    pub fn requests_per_second(&self) -> f64 {
+        let total = self.latencies.len() + self.errors;
+        total as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+// 🚀 Replay the configured request mix round-robin with `concurrency` workers
+pub async fn run(config: &LoadgenConfig) -> LoadReport {
+    let paths = Arc::new(config.request_paths());
+    if paths.is_empty() || config.requests == 0 {
+        return LoadReport::default();
+    }
+
+    let next = Arc::new(AtomicUsize::new(0));
+    let started = Instant::now();
+    let workers: Vec<_> = (0..config.concurrency.max(1))
+        .map(|_| {
+            let paths = paths.clone();
+            let next = next.clone();
+            let target = config.target.clone();
+            let total = config.requests;
+            tokio::spawn(async move {
+                let mut latencies = Vec::new();
+                let mut errors = 0;
+                loop {
+                    let n = next.fetch_add(1, Ordering::Relaxed);
+                    if n >= total {
+                        break;
+                    }
+                    let request_started = Instant::now();
+                    match http_get(&target, &paths[n % paths.len()]).await {
+                        Ok((status, _)) if (200..300).contains(&status) => {
+                            latencies.push(request_started.elapsed())
+                        }
+                        _ => errors += 1,
+                    }
+                }
+                (latencies, errors)
+            })
+        })
+        .collect();
+
+    let mut report = LoadReport::default();
+    for worker in workers {
+        if let Ok((latencies, errors)) = worker.await {
+            report.latencies.extend(latencies);
+            report.errors += errors;
+        }
+    }
+    report.elapsed = started.elapsed();
+    report.latencies.sort_unstable();
+    report
+}
+
+// Accept "http://host:port", "host:port" or "host" (port 80)
+pub fn parse_target(url: &str) -> String {
+    let host = url.trim_start_matches("http://").trim_end_matches('/');
+    if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{}:80", host)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_and_targets() {
+        let report = LoadReport {
+            latencies: (1..=100).map(Duration::from_millis).collect(),
+            errors: 0,
+            elapsed: Duration::from_secs(1),
+        };
+        assert_eq!(report.percentile(0.5), Some(Duration::from_millis(50)));
+        assert_eq!(report.percentile(0.99), Some(Duration::from_millis(99)));
+        assert_eq!(LoadReport::default().percentile(0.5), None);
+
+        assert_eq!(parse_target("http://localhost:3000/"), "localhost:3000");
+        assert_eq!(parse_target("example.com"), "example.com:80");
+    }
+
+    #[tokio::test]
+    async fn test_run_against_local_server() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let target = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            axum::serve(listener, crate::web::create_router())
+                .await
+                .unwrap();
+        });
+
+        let config = LoadgenConfig {
+            target,
+            ids: vec!["1".to_string(), "404".to_string()],
+            themes: vec!["light".to_string()],
+            concurrency: 4,
+            requests: 20,
+            ..Default::default()
+        };
+        let report = run(&config).await;
+        assert_eq!(report.latencies.len(), 10);
+        assert_eq!(report.errors, 10);
+    }
+}
@@ -0,0 +1,89 @@
+// src/locale.rs - Locale and timezone negotiated from request headers
+//
+// Embedders rarely thread `lang`/`timezone` through query strings; browsers
+// already send Accept-Language, and clients can add X-Timezone. Explicit
+// query parameters always win over these defaults.
+
+// Defaults taken from the request headers
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NegotiatedLocale {
+    // Preferred language tag, e.g. "de-DE"
+    pub lang: Option<String>,
+    // IANA zone name, e.g. "Europe/Berlin"
+    pub timezone: Option<String>,
+}
+
+impl NegotiatedLocale {
+    pub fn from_headers(accept_language: Option<&str>, timezone: Option<&str>) -> Self {
+        Self {
+            lang: accept_language.and_then(preferred_language),
+            timezone: timezone
+                .map(str::trim)
+                .filter(|zone| is_valid_timezone(zone))
+                .map(str::to_string),
+        }
+    }
+}
+
+// 🌍 Highest-weighted language in an Accept-Language header
+// ("de-DE,de;q=0.9,en;q=0.8" -> "de-DE"). Wildcards and q=0 are ignored;
+// ties keep header order.
+pub fn preferred_language(header: &str) -> Option<String> {
+    let mut best: Option<(&str, f32)> = None;
+    for entry in header.split(',') {
+        let mut parts = entry.split(';');
+        let tag = parts.next().unwrap_or_default().trim();
+        let quality = parts
+            .filter_map(|param| param.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+
+        let valid_tag = !tag.is_empty()
+            && tag.len() <= 35
+            && tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            && tag.split('-').all(|subtag| !subtag.is_empty());
+        if !valid_tag || quality <= 0.0 {
+            continue;
+        }
+        if best.is_none_or(|(_, best_quality)| quality > best_quality) {
+            best = Some((tag, quality));
+        }
+    }
+    best.map(|(tag, _)| tag.to_string())
+}
+
+// Shape check for IANA names ("UTC", "Europe/Berlin", "America/Argentina/Buenos_Aires",
+// "Etc/GMT+5"); there is no zone database to check existence against
+pub fn is_valid_timezone(zone: &str) -> bool {
+    !zone.is_empty()
+        && zone.len() <= 64
+        && !zone.starts_with('/')
+        && !zone.ends_with('/')
+        && !zone.contains("//")
+        && zone
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '-' | '+'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiates_language_and_timezone() {
+        assert_eq!(
+            preferred_language("en;q=0.8, de-DE, de;q=0.9").as_deref(),
+            Some("de-DE")
+        );
+        assert_eq!(preferred_language("fr;q=0.5, *;q=1").as_deref(), Some("fr"));
+        assert_eq!(preferred_language("en;q=0"), None);
+        assert_eq!(preferred_language("<script>"), None);
+
+        let locale = NegotiatedLocale::from_headers(Some("pt-BR"), Some(" America/Sao_Paulo "));
+        assert_eq!(locale.lang.as_deref(), Some("pt-BR"));
+        assert_eq!(locale.timezone.as_deref(), Some("America/Sao_Paulo"));
+
+        let locale = NegotiatedLocale::from_headers(None, Some("../etc/passwd"));
+        assert_eq!(locale, NegotiatedLocale::default());
+    }
+}
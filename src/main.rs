@@ -1,13 +1,46 @@
 // Main application entry point for testing and CLI usage
 // src/main.rs
 use dotenv::dotenv;
-use schema_ui_system::{component_registry, start_server};
+use schema_ui_system::bench::{compare, default_corpus, measure};
+use schema_ui_system::budget::budget_tracker;
+use schema_ui_system::class_report::class_report;
+use schema_ui_system::codegen::{tailwind_config, typescript_client};
+use schema_ui_system::compat::{CompatIssue, Severity, check_compat, has_breaking};
+use schema_ui_system::component_registry::{ComponentRegistry, RenderParams};
+use schema_ui_system::config::WarmupEntry;
+use schema_ui_system::jobs::job_queue;
+use schema_ui_system::loadgen::{LoadgenConfig, parse_target};
+use schema_ui_system::schema::{SchemaRegistry, TableSchema, swap_registry};
+use schema_ui_system::schema_store::{SchemaStore, history, rollback};
+use schema_ui_system::search::search_migration;
+use schema_ui_system::tokens::{import_tokens, themes_to_toml};
+use schema_ui_system::{
+    component_registry, config, loadgen, registry, scheduler, set_component_registry, start_server,
+};
+use std::path::Path;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load environment variables
     dotenv().ok();
 
+    // CLI subcommands; no arguments starts the server
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(command) = args.first() {
+        return match command.as_str() {
+            "check-compat" => check_compat_command(&args[1..]),
+            "rollback" => rollback_command(&args[1..]),
+            "import-tokens" => import_tokens_command(&args[1..]),
+            "codegen" => codegen_command(&args[1..]),
+            "compile-registry" => compile_registry_command(&args[1..]),
+            "class-report" => class_report_command(),
+            "budget-check" => budget_check_command(&args[1..]).await,
+            "bench" => bench_command(&args[1..]).await,
+            "loadgen" => loadgen_command(&args[1..]).await,
+            _ => Err(format!("Unknown command: {}", command).into()),
+        };
+    }
+
     // Initialize registries (this loads all schemas and components)
     let _component_registry = component_registry();
 
@@ -18,6 +51,27 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         _component_registry.list_components()
     );
 
+    // Pre-render hot components so the first requests hit a warm cache
+    let warmup = &config().warmup;
+    if !warmup.is_empty() {
+        let report = _component_registry.warm_up(warmup).await;
+        println!("🔥 Warmed {} component renders", report.warmed);
+        for (entry, err) in &report.failed {
+            eprintln!(
+                "Warm-up failed for {}:{}: {}",
+                entry.component, entry.id, err
+            );
+        }
+    }
+
+    // Keep hot renders and static exports fresh in the background
+    let schedule = &config().schedule;
+    if !schedule.is_empty() {
+        let export_dir = config().export_dir.clone();
+        scheduler::start(schedule, component_registry(), job_queue(), export_dir);
+        println!("⏰ Scheduled {} refresh/export tasks", schedule.len());
+    }
+
     // Start web server
     let port = std::env::var("PORT")
         .unwrap_or_else(|_| "3000".to_string())
@@ -28,3 +82,381 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+// check-compat <old.toml> <new.toml> [table]
+// Exits with status 1 when the new schema would break existing consumers.
+fn check_compat_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (Some(old_path), Some(new_path)) = (args.first(), args.get(1)) else {
+        return Err("usage: check-compat <old.toml> <new.toml> [table]".into());
+    };
+
+    let old = TableSchema::from_file(Path::new(old_path))?;
+    let new = TableSchema::from_file(Path::new(new_path))?;
+    let table = match args.get(2) {
+        Some(table) => table.clone(),
+        None => Path::new(new_path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    };
+
+    let registry = component_registry();
+    let components: Vec<_> = registry
+        .list_components()
+        .into_iter()
+        .filter_map(|name| registry.get_component(name))
+        .collect();
+
+    let issues = check_compat(&table, &old, &new, &components);
+    for CompatIssue { severity, message } in &issues {
+        let label = match severity {
+            Severity::Breaking => "BREAKING",
+            Severity::Warning => "warning",
+        };
+        println!("[{}] {}: {}", label, table, message);
+    }
+
+    if has_breaking(&issues) {
+        std::process::exit(1);
+    }
+    println!("✅ {} schema changes are compatible", table);
+    Ok(())
+}
+
+// rollback [version]
+// Without a version, lists the published bundles kept in SCHEMAS_DIR.
+fn rollback_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let store = SchemaStore::from_config();
+    if store.dir().is_none() {
+        return Err("rollback requires SCHEMAS_DIR to be set".into());
+    }
+
+    let Some(version) = args.first() else {
+        println!("Schema bundle versions: {:?}", history(&store));
+        return Ok(());
+    };
+    let version: u64 = version
+        .trim_start_matches('v')
+        .parse()
+        .map_err(|_| format!("Invalid version: {}", version))?;
+
+    rollback(&store, version)?;
+    println!("⏪ Restored schema bundle v{}", version);
+    Ok(())
+}
+
+// import-tokens <tokens.json> [theme]
+// Prints the themes derived from a Style Dictionary / Figma Tokens export as TOML.
+fn import_tokens_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(path) = args.first() else {
+        return Err("usage: import-tokens <tokens.json> [theme]".into());
+    };
+    let theme = args.get(1).map(String::as_str).unwrap_or("default");
+
+    let import = import_tokens(&std::fs::read_to_string(path)?, theme)?;
+    for token in &import.skipped {
+        eprintln!("Skipping composite token: {}", token);
+    }
+    print!("{}", themes_to_toml(&import.themes)?);
+    Ok(())
+}
+
+// codegen tailwind|search|client [output]
+// Writes tailwind.config.js, or the SQL migration adding full-text search
+// columns (or prints it when no output path is given).
+fn codegen_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let output = match args.first().map(String::as_str) {
+        Some("tailwind") => {
+            let components = component_registry();
+            let templates: Vec<_> = components
+                .list_components()
+                .into_iter()
+                .filter_map(|name| components.get_component(name))
+                .collect();
+            tailwind_config(&registry(), &templates)
+        }
+        Some("search") => search_migration(&registry()),
+        Some("client") => {
+            let components = component_registry();
+            let templates: Vec<_> = components
+                .list_components()
+                .into_iter()
+                .filter_map(|name| components.get_component(name))
+                .collect();
+            typescript_client(&registry(), &templates)
+        }
+        _ => return Err("usage: codegen tailwind|search|client [output]".into()),
+    };
+
+    match args.get(1) {
+        Some(path) => {
+            std::fs::write(path, output)?;
+            println!("🛠️ Wrote {}", path);
+        }
+        None => print!("{}", output),
+    }
+    Ok(())
+}
+
+// compile-registry <output>
+// Writes the loaded schemas and compiled templates as a binary bundle; point
+// COMPILED_REGISTRY at it to skip TOML parsing at startup.
+fn compile_registry_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(path) = args.first() else {
+        return Err("usage: compile-registry <output>".into());
+    };
+
+    let components = component_registry();
+    let templates: Vec<_> = components
+        .list_components()
+        .into_iter()
+        .filter_map(|name| components.get_component(name))
+        .collect();
+    registry().save_compiled(Path::new(path), &templates)?;
+    println!(
+        "📦 Wrote {} ({} tables, {} components)",
+        path,
+        registry().list_tables().len(),
+        templates.len()
+    );
+    Ok(())
+}
+
+// class-report
+// Lists class usage counts and theme classes no render can reach.
+fn class_report_command() -> Result<(), Box<dyn std::error::Error>> {
+    let components = component_registry();
+    let templates: Vec<_> = components
+        .list_components()
+        .into_iter()
+        .filter_map(|name| components.get_component(name))
+        .collect();
+    let report = class_report(&registry(), &templates);
+
+    println!("📊 {} classes emitted", report.usage.len());
+    for (class, count) in report.by_usage() {
+        println!("{:>6}  {}", count, class);
+    }
+
+    if !report.unreachable.is_empty() {
+        println!(
+            "\n⚠️  {} unreachable theme classes",
+            report.unreachable.len()
+        );
+        for entry in &report.unreachable {
+            println!("  [{}] {}: {}", entry.theme, entry.tag, entry.class);
+        }
+    }
+    Ok(())
+}
+
+// budget-check [iterations] [--strict]
+// Renders every component for every mock record and context, then reports
+// actual render times against budgets. Strict mode (or STRICT_BUDGETS=true)
+// exits with status 1 on any violation, for use in CI.
+async fn budget_check_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let strict = config().strict_budgets || args.iter().any(|arg| arg == "--strict");
+    let iterations: usize = match args.iter().find(|arg| !arg.starts_with("--")) {
+        Some(n) => n
+            .parse()
+            .map_err(|_| format!("Invalid iteration count: {}", n))?,
+        None => 10,
+    };
+
+    let components = component_registry();
+    let schemas = registry();
+    for _ in 0..iterations {
+        // Cache hits are not renders; start every pass cold
+        components.cache().clear();
+        for name in components.list_components() {
+            let Some(component) = components.get_component(name) else {
+                continue;
+            };
+            let Some(schema) = schemas.get_table(&component.table) else {
+                continue;
+            };
+            for id in components.collection_ids(name, None, None).await? {
+                for context in schema.contexts.keys() {
+                    let params = RenderParams {
+                        context: Some(context),
+                        ..Default::default()
+                    };
+                    components.render_component(name, &id, params).await?;
+                }
+            }
+        }
+    }
+
+    let tracker = budget_tracker();
+    for (key, stats) in tracker.snapshot() {
+        println!(
+            "{:<32} budget {:>7.2}ms  mean {:>7.3}ms  max {:>7.3}ms  {} / {} over",
+            key,
+            stats.budget_ms,
+            stats.mean_ms(),
+            stats.max_ms,
+            stats.violations,
+            stats.renders
+        );
+    }
+
+    let violations = tracker.total_violations();
+    if violations > 0 && strict {
+        eprintln!("❌ {} renders exceeded their budget", violations);
+        std::process::exit(1);
+    }
+    println!("⏱️ Budget check finished with {} violations", violations);
+    Ok(())
+}
+
+async fn bench_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    match args.first().map(String::as_str) {
+        Some("serve") => bench_serve(&args[1..]).await,
+        Some("compare") => bench_compare(&args[1..]).await,
+        _ => Err("usage: bench serve [port] [--cache] | \
+                  bench compare --before <bundle> --after <bundle>"
+            .into()),
+    }
+}
+
+// bench serve [port] [--cache]
+// Serves with the render cache disabled (unless --cache) so load tests measure
+// actual render cost rather than cache lookups.
+async fn bench_serve(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let port = match args.iter().find(|arg| !arg.starts_with("--")) {
+        Some(port) => port
+            .parse()
+            .map_err(|_| format!("Invalid port: {}", port))?,
+        None => 3000,
+    };
+    let cache = args.iter().any(|arg| arg == "--cache");
+
+    let mut components = ComponentRegistry::new();
+    components.set_cache_enabled(cache);
+    if set_component_registry(components).is_err() {
+        return Err("component registry already initialized".into());
+    }
+
+    println!(
+        "🏋️ Bench mode (render cache {})",
+        if cache { "on" } else { "off" }
+    );
+    start_server(port).await
+}
+
+// bench compare --before <bundle> --after <bundle> [--iterations 20]
+//               [--corpus user_card:1,user_card:2:list]
+// Renders one corpus against two compiled bundles (see compile-registry) with
+// the render cache off, and reports per-component render-time and
+// output-size changes.
+async fn bench_compare(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (mut before, mut after, mut iterations, mut corpus) = (None, None, 20, None);
+    for pair in args.chunks(2) {
+        let (flag, value) = match pair {
+            [flag, value] => (flag.as_str(), value.as_str()),
+            _ => return Err(format!("Missing value for {}", pair[0]).into()),
+        };
+        match flag {
+            "--before" => before = Some(SchemaRegistry::load_compiled(Path::new(value))?),
+            "--after" => after = Some(SchemaRegistry::load_compiled(Path::new(value))?),
+            "--iterations" => iterations = value.parse()?,
+            "--corpus" => corpus = Some(WarmupEntry::parse_list(value)),
+            _ => return Err(format!("Unknown bench compare option: {}", flag).into()),
+        }
+    }
+    let (Some(before), Some(after)) = (before, after) else {
+        return Err("usage: bench compare --before <bundle> --after <bundle>".into());
+    };
+
+    // Each side renders with its own schemas and the components compiled with them
+    let components = |schemas| {
+        swap_registry(schemas);
+        let mut components = ComponentRegistry::new();
+        components.set_cache_enabled(false);
+        components
+    };
+    let before_components = components(before);
+    let corpus = match corpus {
+        Some(corpus) => corpus,
+        None => default_corpus(&before_components).await,
+    };
+    let before = measure(&before_components, &corpus, iterations).await;
+    let after = measure(&components(after), &corpus, iterations).await;
+
+    println!("🏋️ {} requests x {} iterations", corpus.len(), iterations);
+    let change = |change: Option<f64>| change.map_or("n/a".to_string(), |c| format!("{:+.1}%", c));
+    for delta in compare(&before, &after) {
+        println!(
+            "{:<24} {:>8.3}ms -> {:>8.3}ms {:>8}  {:>8.0}B -> {:>8.0}B {:>8}  {} -> {} failed",
+            delta.component,
+            delta.before.mean_ms(),
+            delta.after.mean_ms(),
+            change(delta.time_change()),
+            delta.before.mean_bytes(),
+            delta.after.mean_bytes(),
+            change(delta.size_change()),
+            delta.before.failures,
+            delta.after.failures
+        );
+    }
+    Ok(())
+}
+
+// loadgen [--url http://host:port] [--components a,b] [--ids 1,2] [--contexts card,list]
+//         [--themes light,dark] [--concurrency 16] [--requests 1000]
+async fn loadgen_command(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut load = LoadgenConfig::default();
+    let list = |value: &str| {
+        value
+            .split(',')
+            .map(|item| item.trim().to_string())
+            .collect()
+    };
+
+    for pair in args.chunks(2) {
+        let (flag, value) = match pair {
+            [flag, value] => (flag.as_str(), value.as_str()),
+            _ => return Err(format!("Missing value for {}", pair[0]).into()),
+        };
+        match flag {
+            "--url" => load.target = parse_target(value),
+            "--components" => load.components = list(value),
+            "--ids" => load.ids = list(value),
+            "--contexts" => load.contexts = list(value),
+            "--themes" => load.themes = list(value),
+            "--concurrency" => load.concurrency = value.parse()?,
+            "--requests" => load.requests = value.parse()?,
+            _ => return Err(format!("Unknown loadgen option: {}", flag).into()),
+        }
+    }
+
+    println!(
+        "📈 {} requests against {} ({} concurrent, {} distinct renders)",
+        load.requests,
+        load.target,
+        load.concurrency,
+        load.request_paths().len()
+    );
+    let report = loadgen::run(&load).await;
+
+    let ms = |quantile: f64| {
+        report
+            .percentile(quantile)
+            .map(|latency| latency.as_secs_f64() * 1000.0)
+            .unwrap_or(0.0)
+    };
+    println!(
+        "ok {}  errors {}  {:.0} req/s",
+        report.latencies.len(),
+        report.errors,
+        report.requests_per_second()
+    );
+    println!(
+        "p50 {:.2}ms  p95 {:.2}ms  p99 {:.2}ms  max {:.2}ms",
+        ms(0.50),
+        ms(0.95),
+        ms(0.99),
+        ms(1.0)
+    );
+    Ok(())
+}
@@ -0,0 +1,154 @@
+// src/memo.rs - Memoized field renders
+//
+// Collection renders repeat the same values (status = "active", ...) across
+// thousands of rows. Each distinct (table, field, variant, theme, value) is
+// rendered once and then served from a sharded concurrent map, so readers on
+// different rows rarely contend.
+use crate::field_types::FieldType;
+use dashmap::DashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+// Identifies one field render; the value is kept to rule out hash collisions
+#[derive(Debug, Clone, Copy)]
+pub struct MemoKey<'a> {
+    pub table: &'a str,
+    pub field: &'a str,
+    pub variant: &'a str,
+    pub theme: &'a str,
+    pub debug: bool,
+    pub print: bool,
+    // Typed values of fields without a declared type format by their own type
+    pub value_type: Option<FieldType>,
+    pub value: &'a str,
+}
+
+impl MemoKey<'_> {
+    fn hash64(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        let Self {
+            table,
+            field,
+            variant,
+            theme,
+            debug,
+            print,
+            value_type,
+            value,
+        } = self;
+        (
+            table, field, variant, theme, debug, print, value_type, value,
+        )
+            .hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[derive(Debug)]
+struct MemoEntry {
+    value: String,
+    html: String,
+}
+
+#[derive(Debug)]
+pub struct FieldMemo {
+    entries: DashMap<u64, MemoEntry>,
+    capacity: usize,
+}
+
+impl FieldMemo {
+    // `capacity` 0 disables memoization
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: DashMap::new(),
+            capacity,
+        }
+    }
+
+    // 🧠 Return the memoized render, or compute and remember it
+    pub fn get_or_render<F>(&self, key: MemoKey<'_>, render: F) -> Option<String>
+    where
+        F: FnOnce() -> Option<String>,
+    {
+        if self.capacity == 0 {
+            return render();
+        }
+
+        let hash = key.hash64();
+        if let Some(entry) = self.entries.get(&hash)
+            && entry.value == key.value
+        {
+            return Some(entry.html.clone());
+        }
+
+        let html = render()?;
+        // Unbounded value sets (ids, timestamps) would grow the map forever;
+        // start over instead of tracking recency
+        if self.entries.len() >= self.capacity {
+            self.entries.clear();
+        }
+        self.entries.insert(
+            hash,
+            MemoEntry {
+                value: key.value.to_string(),
+                html: html.clone(),
+            },
+        );
+        Some(html)
+    }
+
+    pub fn clear(&self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+// A cloned registry may be edited, so it starts with an empty memo
+impl Clone for FieldMemo {
+    fn clone(&self) -> Self {
+        Self::new(self.capacity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memo_renders_each_value_once() {
+        let memo = FieldMemo::new(2);
+        let key = |value| MemoKey {
+            table: "users",
+            field: "status",
+            variant: "badge",
+            theme: "light",
+            debug: false,
+            print: false,
+            value_type: None,
+            value,
+        };
+
+        let mut renders = 0;
+        for _ in 0..3 {
+            let html = memo.get_or_render(key("active"), || {
+                renders += 1;
+                Some("<span>active</span>".to_string())
+            });
+            assert_eq!(html.as_deref(), Some("<span>active</span>"));
+        }
+        assert_eq!(renders, 1);
+
+        // Full memo starts over rather than growing
+        memo.get_or_render(key("pending"), || Some(String::new()));
+        memo.get_or_render(key("banned"), || Some(String::new()));
+        assert_eq!(memo.len(), 1);
+        assert!(memo.clone().is_empty());
+    }
+}
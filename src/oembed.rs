@@ -0,0 +1,181 @@
+// src/oembed.rs - oEmbed provider (`/oembed?url=...`)
+//
+// Public record URLs are mapped to components with patterns such as
+// OEMBED_ROUTES="user_card=https://example.com/users/{id}", so CMSs and other
+// sites that speak oEmbed can embed a record by pasting its URL.
+use crate::component_registry::{ComponentError, RenderParams, component_registry};
+use crate::config::config;
+use serde::{Deserialize, Serialize};
+
+// A URL pattern with one `{id}` placeholder, rendered by `component`
+#[derive(Debug, Clone, PartialEq)]
+pub struct OembedRoute {
+    pub component: String,
+    pub pattern: String,
+}
+
+impl OembedRoute {
+    // Parse "component=https://example.com/users/{id}"
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (component, pattern) = spec.trim().split_once('=')?;
+        let (component, pattern) = (component.trim(), pattern.trim());
+        if component.is_empty() || pattern.matches("{id}").count() != 1 {
+            return None;
+        }
+        Some(Self {
+            component: component.to_string(),
+            pattern: pattern.to_string(),
+        })
+    }
+
+    // Parse a comma separated list, skipping malformed entries
+    pub fn parse_list(specs: &str) -> Vec<Self> {
+        specs
+            .split(',')
+            .filter(|spec| !spec.trim().is_empty())
+            .filter_map(|spec| {
+                let route = Self::parse(spec);
+                if route.is_none() {
+                    eprintln!("Ignoring malformed oEmbed route: {}", spec);
+                }
+                route
+            })
+            .collect()
+    }
+
+    // Record id when `url` matches the pattern. Query strings, fragments and a
+    // trailing slash on the URL are ignored.
+    pub fn match_url(&self, url: &str) -> Option<String> {
+        let url = url.split(['?', '#']).next()?;
+        let url = url.strip_suffix('/').unwrap_or(url);
+        let (prefix, suffix) = self.pattern.split_once("{id}")?;
+        let id = url.strip_prefix(prefix)?.strip_suffix(suffix)?;
+        let valid = !id.is_empty()
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        valid.then(|| id.to_string())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OembedParams {
+    pub url: String,
+    pub maxwidth: Option<u32>,
+    pub maxheight: Option<u32>,
+    pub format: Option<String>, // only "json" is supported
+}
+
+// The "rich" oEmbed response type
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OembedResponse {
+    pub version: &'static str,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub provider_name: &'static str,
+    pub html: String,
+    pub width: u32,
+    pub height: u32,
+    pub cache_age: u64,
+}
+
+#[derive(Debug)]
+pub enum OembedError {
+    // No route matches the URL
+    NoMatch(String),
+    UnsupportedFormat(String),
+    Render(ComponentError),
+}
+
+impl std::fmt::Display for OembedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OembedError::NoMatch(url) => write!(f, "No embeddable resource at {}", url),
+            OembedError::UnsupportedFormat(format) => {
+                write!(f, "Unsupported oEmbed format: {}", format)
+            }
+            OembedError::Render(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for OembedError {}
+
+// 🔗 Resolve an oEmbed request against `routes` and render the component
+pub async fn resolve_embed(
+    routes: &[OembedRoute],
+    params: &OembedParams,
+) -> Result<OembedResponse, OembedError> {
+    if let Some(format) = params.format.as_deref().filter(|format| *format != "json") {
+        return Err(OembedError::UnsupportedFormat(format.to_string()));
+    }
+    let (route, id) = routes
+        .iter()
+        .find_map(|route| route.match_url(&params.url).map(|id| (route, id)))
+        .ok_or_else(|| OembedError::NoMatch(params.url.clone()))?;
+
+    let html = component_registry()
+        .render_component(&route.component, &id, RenderParams::default())
+        .await
+        .map_err(OembedError::Render)?;
+
+    let config = config();
+    Ok(OembedResponse {
+        version: "1.0",
+        kind: "rich",
+        provider_name: "Schema UI Component API",
+        html,
+        width: params
+            .maxwidth
+            .map_or(config.oembed_width, |max| max.min(config.oembed_width)),
+        height: params
+            .maxheight
+            .map_or(config.oembed_height, |max| max.min(config.oembed_height)),
+        cache_age: config.render_cache_ttl_secs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_resolves_url_to_component_render() {
+        let routes = OembedRoute::parse_list(
+            "user_card=https://example.com/users/{id}, broken=https://example.com/, =x{id}",
+        );
+        assert_eq!(routes.len(), 1);
+        assert_eq!(
+            routes[0]
+                .match_url("https://example.com/users/2/?ref=home")
+                .as_deref(),
+            Some("2")
+        );
+        assert_eq!(
+            routes[0].match_url("https://example.com/users/2/edit"),
+            None
+        );
+        assert_eq!(routes[0].match_url("https://evil.io/users/2"), None);
+
+        let params = OembedParams {
+            url: "https://example.com/users/1".to_string(),
+            maxwidth: Some(200),
+            maxheight: None,
+            format: None,
+        };
+        let embed = resolve_embed(&routes, &params).await.unwrap();
+        assert_eq!(embed.kind, "rich");
+        assert!(embed.html.contains("John Doe"));
+        assert_eq!(embed.width, 200);
+        assert_eq!(embed.height, config().oembed_height);
+
+        let missing = OembedParams {
+            url: "https://example.com/users/999".to_string(),
+            ..params
+        };
+        assert!(matches!(
+            resolve_embed(&routes, &missing).await,
+            Err(OembedError::Render(ComponentError::RecordNotFound(_)))
+        ));
+    }
+}
@@ -1,42 +1,69 @@
 // Renderer module - handles HTML generation without database dependency
+use crate::component_registry::{
+    ComponentError, ComponentRegistry, RenderParams, component_registry,
+};
+use crate::field_types::FieldValue;
 use crate::schema::{SchemaRegistry, registry};
-use std::collections::HashMap;
+use crate::variant_kinds::escape_attr;
+use crate::views::SavedView;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 // Renderer provides high-level rendering utilities
 pub struct Renderer {
-    registry: &'static SchemaRegistry,
+    registry: Arc<SchemaRegistry>,
 }
 
 impl Renderer {
-    // Create new renderer instance
+    // Create new renderer instance over a snapshot of the active registry
     pub fn new() -> Self {
-        Self {
-            registry: registry(),
-        }
+        Self::with_registry(registry())
+    }
+
+    // Render against a specific registry (e.g. one with unpublished drafts applied)
+    pub fn with_registry(registry: Arc<SchemaRegistry>) -> Self {
+        Self { registry }
     }
 
-    // Render a single field value
-    pub fn render_field(
+    // Render a single field value, as text or a typed FieldValue
+    pub fn render_field<'v>(
         &self,
         table: &str,
         field: &str,
         context: &str,
-        value: &str,
+        value: impl Into<FieldValue<'v>>,
     ) -> Option<String> {
         self.registry.render_field(table, field, context, value)
     }
 
-    // Render multiple fields for a record (e.g., entire user object)
-    pub fn render_record(
+    // Render multiple fields for a record (e.g., entire user object). Values
+    // may be strings or FieldValues; aliases and computed fields see their text.
+    pub fn render_record<V>(
         &self,
         table: &str,
         context: &str,
-        data: &HashMap<String, String>,
-    ) -> HashMap<String, String> {
+        data: &HashMap<String, V>,
+    ) -> HashMap<String, String>
+    where
+        for<'v> &'v V: Into<FieldValue<'v>>,
+    {
+        let typed: HashMap<&String, FieldValue> = data
+            .iter()
+            .map(|(field, value)| (field, value.into()))
+            .collect();
+        let text: HashMap<String, String> = typed
+            .iter()
+            .map(|(field, value)| (field.to_string(), value.to_text().into_owned()))
+            .collect();
         let mut rendered = HashMap::new();
 
-        for (field, value) in data {
-            if let Some(html) = self.render_field(table, field, context, value) {
+        for (field, value) in self.materialize(table, &text).iter() {
+            let html = match typed.get(field) {
+                Some(typed) => self.render_field(table, field, context, typed),
+                None => self.render_field(table, field, context, value),
+            };
+            if let Some(html) = html {
                 rendered.insert(field.clone(), html);
             }
         }
@@ -44,6 +71,76 @@ impl Renderer {
         rendered
     }
 
+    // 📄 Render a whole record as HTML. Fields listed in the context's sections
+    // are wrapped in <section> elements (in section order, with an optional
+    // heading); the remaining fields follow, sorted by name.
+    pub fn render_record_html(
+        &self,
+        table: &str,
+        context: &str,
+        data: &HashMap<String, String>,
+    ) -> String {
+        let sections = self
+            .registry
+            .get_table(table)
+            .map(|schema| schema.context_sections(context))
+            .unwrap_or_default();
+        let data = self.materialize(table, data);
+        let mut html = String::new();
+        let mut grouped = HashSet::new();
+
+        for (name, section) in sections {
+            let mut body = String::new();
+            for field in &section.fields {
+                grouped.insert(field.as_str());
+                if let Some(rendered) = data
+                    .get(field)
+                    .and_then(|value| self.render_field(table, field, context, value))
+                {
+                    body.push_str(&rendered);
+                }
+            }
+            // No heading over an empty group
+            if body.is_empty() {
+                continue;
+            }
+
+            html.push_str("<section");
+            let section_css = self.registry.get_theme_css("section");
+            if !section_css.is_empty() {
+                html.push_str(&format!(r#" class="{}""#, section_css));
+            }
+            html.push_str(&format!(r#" data-section="{}">"#, escape_attr(name)));
+            if let Some(heading) = &section.heading {
+                let heading_css = self.registry.get_theme_css("h3");
+                if heading_css.is_empty() {
+                    html.push_str(&format!("<h3>{}</h3>", escape_attr(heading)));
+                } else {
+                    html.push_str(&format!(
+                        r#"<h3 class="{}">{}</h3>"#,
+                        heading_css,
+                        escape_attr(heading)
+                    ));
+                }
+            }
+            html.push_str(&body);
+            html.push_str("</section>");
+        }
+
+        let mut rest: Vec<_> = data
+            .iter()
+            .filter(|(field, _)| !grouped.contains(field.as_str()))
+            .collect();
+        rest.sort_by(|a, b| a.0.cmp(b.0));
+        for (field, value) in rest {
+            if let Some(rendered) = self.render_field(table, field, context, value) {
+                html.push_str(&rendered);
+            }
+        }
+
+        html
+    }
+
     // Render component template with field substitution
     pub fn render_component(
         &self,
@@ -55,7 +152,7 @@ impl Renderer {
         let mut result = template.to_string();
 
         // Replace {field_name} placeholders with rendered HTML
-        for (field, value) in data {
+        for (field, value) in self.materialize(table, data).iter() {
             let placeholder = format!("{{{}}}", field);
             if let Some(rendered_field) = self.render_field(table, field, context, value) {
                 result = result.replace(&placeholder, &rendered_field);
@@ -65,6 +162,18 @@ impl Renderer {
         result
     }
 
+    // The record with the table's `[aliases]` and `[computed]` fields applied
+    fn materialize<'a>(
+        &self,
+        table: &str,
+        data: &'a HashMap<String, String>,
+    ) -> Cow<'a, HashMap<String, String>> {
+        match self.registry.get_table(table) {
+            Some(schema) => schema.materialize(data),
+            None => Cow::Borrowed(data),
+        }
+    }
+
     // List available contexts for a table
     pub fn list_contexts(&self, table: &str) -> Vec<String> {
         if let Some(schema) = self.registry.get_table(table) {
@@ -90,9 +199,141 @@ impl Default for Renderer {
     }
 }
 
+// 📰 A page composed from components, rendered without the HTTP layer:
+//
+//     let html = Page::new()
+//         .title("Account")
+//         .add("user_card", "1")
+//         .add_collection("order_row", "status = 'paid' and total > 100")
+//         .render(RenderParams::default())
+//         .await?;
+//
+// Each block is wrapped in a <section data-sui-block="component">; collection
+// filters use the saved view syntax (see views.rs), "" takes every record.
+#[derive(Debug, Clone, Default)]
+pub struct Page {
+    title: Option<String>,
+    blocks: Vec<PageBlock>,
+}
+
+#[derive(Debug, Clone)]
+enum PageBlock {
+    Component { component: String, id: String },
+    Collection { component: String, filter: String },
+}
+
+impl Page {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    // One record rendered through `component` ("" for static components)
+    pub fn add(mut self, component: &str, id: &str) -> Self {
+        self.blocks.push(PageBlock::Component {
+            component: component.to_string(),
+            id: id.to_string(),
+        });
+        self
+    }
+
+    // Every record of the component's table matching `filter`, one row each
+    pub fn add_collection(mut self, component: &str, filter: &str) -> Self {
+        self.blocks.push(PageBlock::Collection {
+            component: component.to_string(),
+            filter: filter.to_string(),
+        });
+        self
+    }
+
+    // The page as an HTML document, rendered by the global component registry
+    pub async fn render(&self, params: RenderParams<'_>) -> Result<String, ComponentError> {
+        self.render_with(component_registry(), params).await
+    }
+
+    pub async fn render_with(
+        &self,
+        components: &ComponentRegistry,
+        params: RenderParams<'_>,
+    ) -> Result<String, ComponentError> {
+        let mut body = String::new();
+        for block in &self.blocks {
+            let (component, html) = match block {
+                PageBlock::Component { component, id } => {
+                    let html = components
+                        .render_component(component, id, params.clone())
+                        .await?;
+                    (component, html)
+                }
+                PageBlock::Collection { component, filter } => {
+                    let ids = Self::collection_ids(components, component, filter).await?;
+                    let rows = components
+                        .render_collection(component, ids, params.clone())
+                        .await?;
+                    (component, rows.concat())
+                }
+            };
+            body.push_str(&format!(
+                r#"<section data-sui-block="{}">{}</section>"#,
+                escape_attr(component),
+                html
+            ));
+        }
+
+        let title = self
+            .title
+            .as_ref()
+            .map(|title| format!("<title>{}</title>", escape_attr(title)))
+            .unwrap_or_default();
+        let lang = escape_attr(params.lang.unwrap_or("en"));
+        Ok(format!(
+            "<!DOCTYPE html>\n<html lang=\"{}\"><head><meta charset=\"utf-8\">{}</head>\
+             <body><main>{}</main></body></html>",
+            lang, title, body
+        ))
+    }
+
+    async fn collection_ids(
+        components: &ComponentRegistry,
+        component: &str,
+        filter: &str,
+    ) -> Result<Vec<String>, ComponentError> {
+        let table = &components
+            .get_component(component)
+            .ok_or_else(|| ComponentError::ComponentNotFound(component.to_string()))?
+            .table;
+        let view = SavedView {
+            sort: None,
+            filter: Some(filter)
+                .filter(|f| !f.trim().is_empty())
+                .map(str::to_string),
+            context: String::new(),
+            limit: None,
+        };
+        let records = components.data_source().get_records(table, None).await?;
+        let records = view
+            .apply(records, std::time::SystemTime::now())
+            .map_err(|e| {
+                ComponentError::InvalidDefinition(format!(
+                    "collection filter of {}: {}",
+                    component, e
+                ))
+            })?;
+        Ok(records
+            .into_iter()
+            .filter_map(|mut record| record.remove("id"))
+            .collect())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::schema::FieldRenderOptions;
 
     #[test]
     fn test_renderer_creation() {
@@ -111,4 +352,161 @@ mod tests {
             assert!(html.contains("Test User"));
         }
     }
+
+    #[test]
+    fn test_render_record_html_groups_sections() {
+        let renderer = Renderer::new();
+        let record = HashMap::from([
+            ("name".to_string(), "Ada".to_string()),
+            ("email".to_string(), "ada@example.com".to_string()),
+            ("created_at".to_string(), "2024-01-01".to_string()),
+        ]);
+        let html = renderer.render_record_html("users", "detail", &record);
+
+        let profile = html.find(r#"data-section="profile""#).unwrap();
+        let account = html.find(r#"data-section="account""#).unwrap();
+        assert!(profile < account);
+        assert!(html.contains(">Profile</h3>"));
+        // Sections with no values in the record are left out
+        assert!(!html.contains(r#"data-section="media""#));
+        let name = html.find("Ada").unwrap();
+        assert!(profile < name && name < account);
+        assert_eq!(html.matches("</section>").count(), 2);
+
+        // Contexts without sections render every field, ungrouped
+        let card = renderer.render_record_html("users", "card", &record);
+        assert!(!card.contains("<section"));
+        assert!(card.contains("Ada") && card.contains("2024-01-01"));
+    }
+
+    #[test]
+    fn test_aliased_columns_render_as_their_fields() {
+        let mut schemas = SchemaRegistry::load_embedded();
+        let users = schemas.get_table_mut("users").unwrap();
+        users
+            .aliases
+            .insert("full_name".to_string(), "name".to_string());
+        let renderer = Renderer::with_registry(Arc::new(schemas));
+        let record = HashMap::from([("full_name".to_string(), "Ada".to_string())]);
+
+        let fields = renderer.render_record("users", "card", &record);
+        let name = renderer.render_field("users", "name", "card", "Ada");
+        assert_eq!(fields.get("name"), name.as_ref());
+        assert!(!fields.contains_key("full_name"));
+        let card = renderer.render_component("<div>{name}</div>", "users", "card", &record);
+        assert_eq!(card, format!("<div>{}</div>", name.unwrap()));
+    }
+
+    #[test]
+    fn test_computed_fields_render_from_the_record() {
+        let mut schemas = SchemaRegistry::load_embedded();
+        let users = schemas.get_table_mut("users").unwrap();
+        users.computed.insert(
+            "name".to_string(),
+            "{first_name} {last_name|upper}".to_string(),
+        );
+        let renderer = Renderer::with_registry(Arc::new(schemas));
+        let record = HashMap::from([
+            ("first_name".to_string(), "Ada".to_string()),
+            ("last_name".to_string(), "Lovelace".to_string()),
+        ]);
+
+        let fields = renderer.render_record("users", "card", &record);
+        let name = renderer.render_field("users", "name", "card", "Ada LOVELACE");
+        assert_eq!(fields.get("name"), name.as_ref());
+        // A value the record has wins; missing parts leave no stray spaces
+        let given = HashMap::from([("name".to_string(), "Grace".to_string())]);
+        let fields = renderer.render_record("users", "card", &given);
+        let name = renderer.render_field("users", "name", "card", "Grace");
+        assert_eq!(fields.get("name"), name.as_ref());
+        let partial = HashMap::from([("first_name".to_string(), "Ada".to_string())]);
+        let fields = renderer.render_record("users", "card", &partial);
+        let name = renderer.render_field("users", "name", "card", "Ada");
+        assert_eq!(fields.get("name"), name.as_ref());
+    }
+
+    #[test]
+    fn test_typed_values_format_by_their_type() {
+        let renderer = Renderer::with_registry(Arc::new(SchemaRegistry::load_embedded()));
+        let as_text = |value| {
+            renderer
+                .render_field("users", "name", "card", value)
+                .unwrap()
+        };
+        let number = renderer
+            .render_field("users", "name", "card", 1234567)
+            .unwrap();
+        assert_eq!(number, as_text("1,234,567"));
+        let flag = renderer
+            .render_field("users", "name", "card", true)
+            .unwrap();
+        assert_eq!(flag, as_text("Yes"));
+        let json = FieldValue::from(serde_json::json!({"lat": 1.5}));
+        let json = renderer
+            .render_field("users", "name", "card", &json)
+            .unwrap();
+        assert_eq!(json, as_text(r#"{"lat":1.5}"#));
+        // Declared types win over the value's
+        let created = FieldValue::DateTime("2024-01-15".into());
+        let created = renderer
+            .render_field("users", "created_at", "card", created)
+            .unwrap();
+        assert!(created.ends_with(">Jan 15, 2024</time>"), "{}", created);
+        let as_number = renderer
+            .render_field("users", "created_at", "card", 2024)
+            .unwrap();
+        assert!(as_number.ends_with(">2024</time>"), "{}", as_number);
+
+        let record = HashMap::from([
+            ("name".to_string(), FieldValue::Int(1200)),
+            ("email".to_string(), FieldValue::Null),
+        ]);
+        let fields = renderer.render_record("users", "card", &record);
+        assert_eq!(fields["name"], as_text("1,200"));
+        assert!(!fields.contains_key("email"));
+    }
+
+    #[tokio::test]
+    async fn test_page_composes_components() {
+        let page = Page::new()
+            .title("Team & friends")
+            .add("user_card", "1")
+            .add_collection("user_card", "name != 'John Doe'");
+        let html = page.render(RenderParams::default()).await.unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"), "{}", html);
+        assert!(html.contains("<title>Team &amp; friends</title>"));
+        assert_eq!(
+            html.matches(r#"<section data-sui-block="user_card">"#)
+                .count(),
+            2
+        );
+        let collection = &html[html.rfind("<section").unwrap()..];
+        assert!(!collection.contains("John Doe") && collection.contains("Bob Wilson"));
+
+        let broken = Page::new().add_collection("user_card", "name");
+        let err = broken.render(RenderParams::default()).await;
+        assert!(
+            matches!(err, Err(ComponentError::InvalidDefinition(_))),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_debug_source_annotation() {
+        let options = FieldRenderOptions {
+            debug: true,
+            ..Default::default()
+        };
+        let html = registry()
+            .render_field_with("users", "name", "card", "Test User", options)
+            .unwrap();
+        assert!(html.contains(r#"data-sui-src="users.toml#variants.name.h2""#));
+
+        let plain = registry()
+            .render_field("users", "name", "card", "Test User")
+            .unwrap();
+        assert!(!plain.contains("data-sui-src"));
+    }
 }
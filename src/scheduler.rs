@@ -0,0 +1,220 @@
+// src/scheduler.rs - Periodic cache refreshes and static exports
+//
+// SCHEDULE lists tasks separated by `;`, each `every <interval> <task>`:
+//   every 5m refresh user_card:1,user_card:2:list   # warm-up style entries
+//   every 1h export user_card:list                  # component[:context]
+// Intervals take s, m, h or d. Refreshes re-render their entries over the
+// cached copies, so requests never wait on them; exports run as background
+// jobs (see jobs.rs) and, with EXPORT_DIR set, are written there as
+// `<component>[.<context>].html` for a CDN or static host to pick up.
+// Every task runs once at startup, then once per interval.
+use crate::component_registry::ComponentRegistry;
+use crate::config::WarmupEntry;
+use crate::jobs::{JobKind, JobQueue, JobStatus};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskAction {
+    Refresh(Vec<WarmupEntry>),
+    Export {
+        component: String,
+        context: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledTask {
+    pub every: Duration,
+    pub action: TaskAction,
+}
+
+impl ScheduledTask {
+    // Parse "every <interval> refresh <entries>" or "every <interval> export <component[:context]>"
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut parts = spec.split_whitespace();
+        let (Some("every"), Some(interval), Some(task), Some(target), None) = (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        ) else {
+            return Err(format!(
+                "expected 'every <interval> <task> <target>', got '{}'",
+                spec
+            ));
+        };
+        let every = parse_interval(interval)?;
+        let action = match task {
+            "refresh" => {
+                let entries = WarmupEntry::parse_list(target);
+                if entries.is_empty() {
+                    return Err(format!(
+                        "refresh '{}' names no component:id entries",
+                        target
+                    ));
+                }
+                TaskAction::Refresh(entries)
+            }
+            "export" => {
+                let (component, context) = match target.split_once(':') {
+                    Some((component, context)) => (component, Some(context.to_string())),
+                    None => (target, None),
+                };
+                TaskAction::Export {
+                    component: component.to_string(),
+                    context,
+                }
+            }
+            other => return Err(format!("unknown task '{}' (refresh or export)", other)),
+        };
+        Ok(Self { every, action })
+    }
+
+    // Parse a `;` separated list, skipping malformed tasks
+    pub fn parse_list(specs: &str) -> Vec<Self> {
+        specs
+            .split(';')
+            .filter(|spec| !spec.trim().is_empty())
+            .filter_map(|spec| {
+                Self::parse(spec)
+                    .map_err(|e| eprintln!("Ignoring scheduled task '{}': {}", spec.trim(), e))
+                    .ok()
+            })
+            .collect()
+    }
+}
+
+fn parse_interval(interval: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid interval '{}' (e.g. 30s, 5m, 1h)", interval);
+    let unit = interval.chars().last().ok_or_else(invalid)?;
+    let amount: u64 = interval[..interval.len() - unit.len_utf8()]
+        .parse()
+        .map_err(|_| invalid())?;
+    let seconds = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 3_600,
+        'd' => 86_400,
+        _ => return Err(invalid()),
+    };
+    match amount.saturating_mul(seconds) {
+        0 => Err(invalid()),
+        seconds => Ok(Duration::from_secs(seconds)),
+    }
+}
+
+// ⏰ Run every task on its interval until the server stops
+pub fn start(
+    tasks: &[ScheduledTask],
+    components: &'static ComponentRegistry,
+    jobs: &Arc<JobQueue>,
+    export_dir: Option<PathBuf>,
+) -> Vec<JoinHandle<()>> {
+    tasks
+        .iter()
+        .cloned()
+        .map(|task| {
+            let jobs = Arc::clone(jobs);
+            let export_dir = export_dir.clone();
+            tokio::spawn(async move {
+                let mut ticks = tokio::time::interval(task.every);
+                // A slow run pushes the next one back instead of bunching them up
+                ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                loop {
+                    ticks.tick().await;
+                    let run = run_task(&task, components, &jobs, export_dir.as_deref());
+                    if let Err(err) = run.await {
+                        eprintln!("Scheduled {:?} failed: {}", task.action, err);
+                    }
+                }
+            })
+        })
+        .collect()
+}
+
+// One run of a task; refresh failures of single entries are only logged
+pub async fn run_task(
+    task: &ScheduledTask,
+    components: &'static ComponentRegistry,
+    jobs: &Arc<JobQueue>,
+    export_dir: Option<&Path>,
+) -> Result<(), String> {
+    match &task.action {
+        TaskAction::Refresh(entries) => {
+            let report = components.refresh(entries).await;
+            for (entry, err) in &report.failed {
+                eprintln!(
+                    "Refresh failed for {}:{}: {}",
+                    entry.component, entry.id, err
+                );
+            }
+            Ok(())
+        }
+        TaskAction::Export { component, context } => {
+            let kind = JobKind::Export {
+                component: component.clone(),
+                context: context.clone(),
+            };
+            let job = jobs.submit(kind, components).map_err(|e| e.to_string())?;
+            let job = jobs.wait(&job.id).await.ok_or("export job disappeared")?;
+            let (JobStatus::Succeeded, Some(html)) = (job.status, job.result) else {
+                return Err(job
+                    .error
+                    .unwrap_or_else(|| "export produced nothing".to_string()));
+            };
+            let Some(dir) = export_dir else {
+                return Ok(());
+            };
+            let name = match context {
+                Some(context) => format!("{}.{}.html", component, context),
+                None => format!("{}.html", component),
+            };
+            // Write then rename, so the old export is served until the new one is complete
+            let path = dir.join(name);
+            let partial = path.with_extension("html.partial");
+            std::fs::create_dir_all(dir)
+                .and_then(|_| std::fs::write(&partial, html))
+                .and_then(|_| std::fs::rename(&partial, &path))
+                .map_err(|e| format!("writing {}: {}", path.display(), e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component_registry::component_registry;
+
+    #[tokio::test]
+    async fn test_scheduled_tasks_parse_and_export() {
+        let tasks = ScheduledTask::parse_list(
+            "every 5m refresh user_card:1,user_card:2:list; every 1h export user_card:card;\
+             every often export user_card; every 1h publish user_card",
+        );
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].every, Duration::from_secs(300));
+        let TaskAction::Refresh(entries) = &tasks[0].action else {
+            panic!("expected a refresh, got {:?}", tasks[0].action);
+        };
+        assert_eq!(entries[1].context.as_deref(), Some("list"));
+
+        let dir = std::env::temp_dir().join(format!("sui-exports-{}", std::process::id()));
+        let jobs = Arc::new(JobQueue::new(None));
+        let components = component_registry();
+        run_task(&tasks[0], components, &jobs, Some(&dir))
+            .await
+            .unwrap();
+        run_task(&tasks[1], components, &jobs, Some(&dir))
+            .await
+            .unwrap();
+        let export = std::fs::read_to_string(dir.join("user_card.card.html")).unwrap();
+        assert!(export.contains("John Doe"), "{}", export);
+        assert_eq!(jobs.list().len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,39 +1,679 @@
 // src/schema.rs - Enhanced with full rendering logic
+use crate::compiled::{COMPILED_FORMAT_VERSION, CompiledBundle};
+use crate::component_registry::ComponentTemplate;
+use crate::config::config;
+use crate::content_scan::ScanAction;
+use crate::describe::{TableDescription, describe_table};
+use crate::field_types::{
+    EnumField, FieldRules, FieldType, FieldValue, rule_attributes, typed_content,
+};
+use crate::forms::Normalizer;
+use crate::lint::lint_table;
+use crate::memo::{FieldMemo, MemoKey};
+use crate::search::SearchMapping;
+use crate::short_text::fill_record;
+use crate::tree::{ThreadMapping, TreeMapping};
+use crate::validation::{Diagnostic, Severity, validate_references, validate_table};
+use crate::variant_kinds::{KindContext, escape_attr, is_volatile, render_kind};
+use crate::views::SavedView;
+use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct FieldVariant {
+    // May be left out when `extends` supplies it
+    #[serde(default)]
     pub base: String,
+    // Another variant of the same field this one starts from (`extends = "h1"`);
+    // anything set here overrides it, attrs and options key by key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
     #[serde(rename = "override")]
     pub override_class: Option<String>,
     pub extend: Option<String>,
     pub attrs: Option<HashMap<String, String>>,
+    // Built-in renderer for structured values, e.g. "map" (see variant_kinds)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    // Settings for the kind, e.g. `options = { zoom = "12" }`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<HashMap<String, String>>,
+    // Longest text content (in characters) before `overflow` applies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    // What happens to content beyond max_length (default: truncate)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overflow: Option<Overflow>,
+    // Value type for this variant only; overrides the table's `[types]` entry
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub field_type: Option<FieldType>,
+    // Still renders, but reported (`deprecated = "use h2 instead"`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Overflow {
+    // Cut at max_length and append an ellipsis
+    #[default]
+    Truncate,
+    // Cut at max_length and add the theme's `overflow-fade` classes
+    Fade,
+    // Show the cut text in a <summary>, the full value when expanded
+    Details,
+}
+
+// What a render does with a record lacking fields its context requires
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MissingRequired {
+    // Fail the render with ComponentError::MissingFields
+    #[default]
+    Reject,
+    // Render what is there; the missing fields are logged and reported
+    Warn,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct Context {
     pub inherits: Option<String>,
+    // Still renders, but reported (`deprecated = "use card instead"`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+    // Named field groups for record pages (`[contexts.detail.sections.profile]`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub sections: HashMap<String, Section>,
+    // Fields a record must carry to render here (`required = ["name", "email"]`);
+    // not inherited, as an inheriting context may show fewer fields
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required: Vec<String>,
     #[serde(flatten)]
     pub fields: HashMap<String, String>,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Section {
+    pub heading: Option<String>,
+    // Position among the context's sections; ties are ordered by name
+    #[serde(default)]
+    pub order: i32,
+    pub fields: Vec<String>,
+}
+
+// Record fields -> notification, as `{field|filter}` templates
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct PushMapping {
+    pub title: String,
+    pub body: Option<String>,
+    pub icon: Option<String>,
+    pub deep_link: Option<String>,
+}
+
+// Fields of an event-like table, as calendar properties
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct EventMapping {
+    pub start: String,
+    pub end: Option<String>,
+    pub title: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+}
+
+// schema.org type of a component and the property each field maps to
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct JsonLdMapping {
+    pub jsonld_type: String,
+    // field -> schema.org property, e.g. avatar_url = "image"
+    pub fields: HashMap<String, String>,
+}
+
+// Many-to-many link to another table through a join table, rendered as a
+// list of chips: `[relations.tags] through = "user_tags"`, `local_key =
+// "user_id"`, `foreign_key = "tag_id"`, `table = "tags"`, `field = "name"`
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Relation {
+    pub through: String,
+    pub local_key: String,
+    pub foreign_key: String,
+    pub table: String,
+    // Field of the related records shown in each chip
+    pub field: String,
+    // Variant of the relation's own field (`[variants.tags]`) every chip uses;
+    // contexts pick one when unset
+    #[serde(default)]
+    pub variant: Option<String>,
+    // Markup between chips
+    #[serde(default)]
+    pub separator: String,
+}
+
+// Record of another table a field points to: `[references.company] table =
+// "companies"`, `key = "company_id"` renders `{company.name}` as the
+// referenced company's name, with the companies table's variants
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Reference {
+    pub table: String,
+    // Field of this table holding the referenced record's id
+    pub key: String,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct MockRecord {
     #[serde(flatten)]
     pub fields: HashMap<String, String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct TableSchema {
+    // Author-assigned schema version ("2", "1.4.0"), bumped on breaking
+    // rendering changes so API consumers can tell (see compat.rs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    // Shared schema files merged in on load, relative to this file
+    // (`include = ["../shared/timestamps.toml"]`); definitions here win
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    // One row without an id, e.g. site settings (see settings.rs)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub singleton: bool,
+    // Empty in context libraries (e.g. a `common` table others inherit from)
+    #[serde(default)]
     pub variants: HashMap<String, HashMap<String, FieldVariant>>,
     pub defaults: Option<HashMap<String, String>>,
+    // Optional for tables rendered through their defaults alone (`_default`)
+    #[serde(default)]
     pub contexts: HashMap<String, Context>,
+    // Variants picked per language over the context's choice
+    // (`[locales.de.card] name = "h3"` for longer German names); a lang of
+    // "de-AT" uses "de" unless it has its own entry
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub locales: HashMap<String, HashMap<String, HashMap<String, String>>>,
+    // Context tried next when one is unknown or doesn't list a field
+    // (`[fallbacks] compact = "list"`, `list = "card"`); defaults come last
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub fallbacks: HashMap<String, String>,
     pub mock_data: Option<Vec<MockRecord>>,
+    // Many-to-many relations rendered as chips (`{tags}` in a template)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub relations: HashMap<String, Relation>,
+    // Many-to-one links addressed with dot paths (`{company.name}`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub references: HashMap<String, Reference>,
+    // Self-referential tables (`[tree] parent = "parent_id"`), for format=tree
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tree: Option<TreeMapping>,
+    // Components rendering the tree as a comment thread (`[threads.comment_thread]`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub threads: HashMap<String, ThreadMapping>,
+    // Named list presets (`[views.recent_signups]`), see views.rs
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub views: HashMap<String, SavedView>,
+    // Full-text searchable fields (`[search] fields = ["name"]`), see search.rs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search: Option<SearchMapping>,
+    // Record keys that render as a differently named field
+    // (`[aliases] avatar_url = "avatar"`): data column -> schema field
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub aliases: HashMap<String, String>,
+    // Fields built from others (`[computed] full_name = "{first_name} {last_name}"`,
+    // filters as in `[short]`), filled in before rendering
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub computed: HashMap<String, String>,
+    // Variants picked by value over the context's choice, for status-like
+    // fields (`[value_variants] status = { banned = "badge_red" }`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub value_variants: HashMap<String, HashMap<String, String>>,
+    // Stored values of enum fields with their labels and classes, see field_types.rs
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub enums: HashMap<String, EnumField>,
+    // Value types per field (`[types] created_at = "date"`), for default markup
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub types: HashMap<String, FieldType>,
+    // Input constraints per field (`[rules] email = { required = true }`),
+    // rendered as validation attributes on input and textarea variants
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub rules: HashMap<String, FieldRules>,
+    // Roles allowed to set a field through the record endpoints
+    // (`[writable_by] salary = ["admin"]`); unlisted fields are open to all
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub writable_by: HashMap<String, Vec<String>>,
+    // Clean-ups applied to submitted values before `rules` are checked
+    // (`[normalize] email = ["trim", "lowercase"]`), see forms.rs
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub normalize: HashMap<String, Vec<Normalizer>>,
+    // Render-time budgets per field in milliseconds (`[budgets] name = 0.5`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub budgets: HashMap<String, f64>,
+    // One-line plain text templates per field (`[short] name = "{value|abbrev}"`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub short: HashMap<String, String>,
+    // Push notification templates per component (`[push.user_card] title = "{name}"`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub push: HashMap<String, PushMapping>,
+    // Marks an event-like table and names its start/end/title fields (format=ics)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<EventMapping>,
+    // schema.org structured data per component (`[jsonld.user_card] jsonld_type = "Person"`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub jsonld: HashMap<String, JsonLdMapping>,
+    // Scan field values before rendering (user-generated content); overrides CONTENT_SCAN
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_scan: Option<ScanAction>,
+    // Records lacking a context's `required` fields: "reject" (default) or "warn"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub missing_required: Option<MissingRequired>,
+    // File this schema was loaded from (e.g. "users.toml"), used for debug annotations
+    #[serde(skip)]
+    pub source: Option<String>,
+    // context -> field -> variant, pre-resolved when loaded from a compiled bundle
+    #[serde(skip)]
+    pub resolved_contexts: HashMap<String, HashMap<String, String>>,
+    // Contexts of other tables this one inherits from, keyed "table.context"
+    // (`inherits = "common.card"`); filled in by the registry
+    #[serde(skip)]
+    pub shared_contexts: HashMap<String, Context>,
+}
+
+impl TableSchema {
+    // Parse a table schema from TOML source
+    pub fn from_toml_str(content: &str) -> Result<Self, SchemaError> {
+        let mut schema: Self =
+            toml::from_str(content).map_err(|e| SchemaError::Parse(e.to_string()))?;
+        schema.resolve_extends().map_err(SchemaError::Parse)?;
+        Ok(schema)
+    }
+
+    // Parse a table schema from JSON source (same structure as the TOML)
+    pub fn from_json_str(content: &str) -> Result<Self, SchemaError> {
+        let mut schema: Self =
+            serde_json::from_str(content).map_err(|e| SchemaError::Parse(e.to_string()))?;
+        schema.resolve_extends().map_err(SchemaError::Parse)?;
+        Ok(schema)
+    }
+
+    // Parse a table schema from YAML source (same structure as the TOML)
+    pub fn from_yaml_str(content: &str) -> Result<Self, SchemaError> {
+        let mut schema: Self =
+            serde_yaml::from_str(content).map_err(|e| SchemaError::Parse(e.to_string()))?;
+        schema.resolve_extends().map_err(SchemaError::Parse)?;
+        Ok(schema)
+    }
+
+    // 🧬 Fill in variants declared with `extends` from the variants they name,
+    // so renders never need to follow the chain. Unknown targets and cycles
+    // are errors. Platform variants ("h1@mobile") extend their generic
+    // variant unless they say otherwise.
+    pub fn resolve_extends(&mut self) -> Result<(), String> {
+        for (field, variants) in &mut self.variants {
+            let generics: Vec<_> = variants
+                .iter()
+                .filter(|(_, variant)| variant.extends.is_none())
+                .filter_map(|(name, _)| split_platform_variant(name))
+                .filter(|(generic, _)| variants.contains_key(*generic))
+                .map(|(generic, platform)| (generic.to_string(), platform.to_string()))
+                .collect();
+            for (generic, platform) in generics {
+                let name = format!("{}{}{}", generic, PLATFORM_SEPARATOR, platform);
+                if let Some(variant) = variants.get_mut(&name) {
+                    variant.extends = Some(generic);
+                }
+            }
+            let mut names: Vec<_> = variants
+                .iter()
+                .filter(|(_, variant)| variant.extends.is_some())
+                .map(|(name, _)| name.clone())
+                .collect();
+            names.sort();
+            let mut resolved = Vec::new();
+            for name in names {
+                let variant = resolve_variant(variants, &name, &mut Vec::new())
+                    .map_err(|e| format!("variants.{}.{}: {}", field, name, e))?;
+                resolved.push((name, variant));
+            }
+            variants.extend(resolved);
+        }
+        Ok(())
+    }
+
+    // Load a table schema file from disk; `.json` files are parsed as JSON,
+    // `.yaml`/`.yml` as YAML, anything else as TOML. Included files are merged in.
+    pub fn from_file(path: &Path) -> Result<Self, SchemaError> {
+        Self::from_layered_files(path, &[])
+    }
+
+    // Load a base schema with overlays deep-merged over it in order: tables
+    // (variants, contexts, ...) merge key by key, anything else in an overlay
+    // replaces the value below it. Includes are resolved relative to the base file.
+    pub fn from_layered_files(path: &Path, overlays: &[PathBuf]) -> Result<Self, SchemaError> {
+        let schema: Self = match overlays.last() {
+            // Parsed directly so errors keep their line numbers
+            None => parse_schema_file(path)?,
+            Some(last) => {
+                let mut layered: serde_json::Value = parse_schema_file(path)?;
+                for overlay in overlays {
+                    deep_merge(&mut layered, parse_schema_file(overlay)?);
+                }
+                serde_json::from_value(layered)
+                    .map_err(|e| SchemaError::Parse(format!("{}: {}", last.display(), e)))?
+            }
+        };
+        schema.merge_includes_at(path)
+    }
+
+    // The table file exactly as written: no overlays, includes or `extends`
+    // resolved. This is what schema edits start from and write back.
+    pub fn from_unmerged_file(path: &Path) -> Result<Self, SchemaError> {
+        let mut schema: Self = parse_schema_file(path)?;
+        schema.source = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string());
+        Ok(schema)
+    }
+
+    // 🧅 Layer overlays and includes over an unmerged schema, as if it had been
+    // loaded from `path` with `from_layered_files`
+    pub fn with_layers(self, path: &Path, overlays: &[PathBuf]) -> Result<Self, SchemaError> {
+        let mut layered =
+            serde_json::to_value(&self).map_err(|e| SchemaError::Parse(e.to_string()))?;
+        for overlay in overlays {
+            deep_merge(&mut layered, parse_schema_file(overlay)?);
+        }
+        let schema: Self = serde_json::from_value(layered)
+            .map_err(|e| SchemaError::Parse(format!("{}: {}", path.display(), e)))?;
+        schema.merge_includes_at(path)
+    }
+
+    // Merge the includes of the file at `path`, then resolve `extends`
+    fn merge_includes_at(self, path: &Path) -> Result<Self, SchemaError> {
+        self.merge_includes_from(FileSource::Disk, path)
+    }
+
+    fn merge_includes_from(mut self, source: FileSource, path: &Path) -> Result<Self, SchemaError> {
+        let mut chain = vec![source.identity(path).unwrap_or_else(|_| path.to_path_buf())];
+        for include in resolve_includes(source, path, &self.include, &mut chain)? {
+            self.merge_include(include);
+        }
+        // After includes, so shared variants can be extended
+        self.resolve_extends()
+            .map_err(|e| SchemaError::Parse(format!("{}: {}", path.display(), e)))?;
+        self.source = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string());
+        Ok(self)
+    }
+
+    // Add shared definitions without overriding anything the table defines
+    fn merge_include(&mut self, include: SchemaInclude) {
+        for (field, variants) in include.variants {
+            let own = self.variants.entry(field).or_default();
+            for (name, variant) in variants {
+                own.entry(name).or_insert(variant);
+            }
+        }
+        if !include.defaults.is_empty() {
+            let own = self.defaults.get_or_insert_with(HashMap::new);
+            for (field, variant) in include.defaults {
+                own.entry(field).or_insert(variant);
+            }
+        }
+        for (name, context) in include.contexts {
+            match self.contexts.get_mut(&name) {
+                Some(own) => {
+                    for (field, variant) in context.fields {
+                        own.fields.entry(field).or_insert(variant);
+                    }
+                }
+                None => {
+                    self.contexts.insert(name, context);
+                }
+            }
+        }
+    }
+
+    // A context of this table, or a shared one it inherits ("common.card")
+    pub fn context(&self, name: &str) -> Option<(&String, &Context)> {
+        self.contexts
+            .get_key_value(name)
+            .or_else(|| self.shared_contexts.get_key_value(name))
+    }
+
+    // Fields `context` requires that `record` lacks or leaves empty
+    pub fn missing_fields(&self, context: &str, record: &HashMap<String, String>) -> Vec<String> {
+        let Some((_, context)) = self.context(context) else {
+            return Vec::new();
+        };
+        context
+            .required
+            .iter()
+            .filter(|field| record.get(*field).is_none_or(|value| value.is_empty()))
+            .cloned()
+            .collect()
+    }
+
+    // The `[locales]` entry a render in `lang` uses: the tag itself, else its
+    // primary language ("de-AT" -> "de"), ignoring case
+    pub fn locale_key(&self, lang: &str) -> Option<&str> {
+        let primary = lang.split(['-', '_']).next().unwrap_or(lang);
+        let find = |tag: &str| {
+            self.locales
+                .keys()
+                .find(|key| key.eq_ignore_ascii_case(tag))
+        };
+        find(lang).or_else(|| find(primary)).map(String::as_str)
+    }
+
+    // `context` followed by the contexts its `[fallbacks]` chain names, up to
+    // the first repeat
+    pub fn fallback_chain<'a>(&'a self, context: &'a str) -> Vec<&'a str> {
+        let mut chain = vec![context];
+        let mut current = context;
+        while let Some(next) = self.fallbacks.get(current) {
+            if chain.contains(&next.as_str()) {
+                break;
+            }
+            chain.push(next);
+            current = next;
+        }
+        chain
+    }
+
+    // Sections of a context in display order; a context without sections uses
+    // the nearest ancestor's
+    pub fn context_sections(&self, context: &str) -> Vec<(&str, &Section)> {
+        let mut seen = Vec::new();
+        let mut current = self.context(context);
+        while let Some((name, ctx)) = current {
+            if seen.contains(&name) {
+                break;
+            }
+            if !ctx.sections.is_empty() {
+                let mut sections: Vec<_> = ctx
+                    .sections
+                    .iter()
+                    .map(|(name, section)| (name.as_str(), section))
+                    .collect();
+                sections.sort_by(|a, b| a.1.order.cmp(&b.1.order).then(a.0.cmp(b.0)));
+                return sections;
+            }
+            seen.push(name);
+            current = ctx
+                .inherits
+                .as_ref()
+                .and_then(|parent| self.context(parent));
+        }
+        Vec::new()
+    }
+
+    // Rename aliased keys to their schema fields; a record that has the
+    // field as well keeps its own value
+    pub fn apply_aliases(&self, record: &mut HashMap<String, String>) {
+        for (column, field) in &self.aliases {
+            if let Some(value) = record.remove(column) {
+                record.entry(field.clone()).or_insert(value);
+            }
+        }
+    }
+
+    // The data column a schema field is read from
+    pub fn column_for<'a>(&'a self, field: &'a str) -> &'a str {
+        self.aliases
+            .iter()
+            .find(|(_, aliased)| *aliased == field)
+            .map_or(field, |(column, _)| column)
+    }
+
+    // 🔀 The record as the schema sees it: aliased keys renamed to their
+    // fields, then computed fields filled in. Values the record has win.
+    pub fn materialize<'a>(
+        &self,
+        record: &'a HashMap<String, String>,
+    ) -> Cow<'a, HashMap<String, String>> {
+        let aliased = self
+            .aliases
+            .keys()
+            .any(|column| record.contains_key(column));
+        let computed = self
+            .computed
+            .keys()
+            .any(|field| !record.contains_key(field));
+        if !aliased && !computed {
+            return Cow::Borrowed(record);
+        }
+        let mut record = record.clone();
+        self.apply_aliases(&mut record);
+        // Computed from the data only, so their order doesn't matter
+        let values: Vec<_> = self
+            .computed
+            .iter()
+            .filter(|(field, _)| !record.contains_key(*field))
+            .map(|(field, template)| (field.clone(), fill_record(template, &record)))
+            .filter(|(_, value)| !value.is_empty())
+            .collect();
+        record.extend(values);
+        Cow::Owned(record)
+    }
+
+    // The schema as TOML, in the layout schema files use
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string_pretty(self)
+    }
+
+    // Variant every field gets in every context, inheritance and defaults applied
+    pub fn resolve_contexts(&self) -> HashMap<String, HashMap<String, String>> {
+        self.contexts
+            .keys()
+            .map(|context| {
+                let fields = self
+                    .variants
+                    .keys()
+                    .filter_map(|field| {
+                        SchemaRegistry::resolve_variant_for_field(self, field, context)
+                            .map(|variant| (field.clone(), variant))
+                    })
+                    .collect();
+                (context.clone(), fields)
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum SchemaError {
+    Io(String),
+    Parse(String),
+    // Every file that failed during a directory load
+    Multiple(Vec<SchemaError>),
+    UnknownTable(String),
+    // A table others inherit contexts from (the table, the tables inheriting)
+    TableInUse(String, Vec<String>),
 }
 
+impl std::fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaError::Io(msg) => write!(f, "Failed to read schema: {}", msg),
+            SchemaError::Parse(msg) => write!(f, "Failed to parse schema: {}", msg),
+            SchemaError::Multiple(errors) => {
+                write!(f, "{} schema files failed to load", errors.len())?;
+                for error in errors {
+                    write!(f, "\n  - {}", error)?;
+                }
+                Ok(())
+            }
+            SchemaError::UnknownTable(table) => write!(f, "Unknown table '{}'", table),
+            SchemaError::TableInUse(table, users) => write!(
+                f,
+                "Table '{}' still has contexts inherited by: {}",
+                table,
+                users.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+// A deprecated context or variant a render went through
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Deprecation {
+    pub table: String,
+    // e.g. "contexts.compact" or "variants.name.h1"
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for Deprecation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} is deprecated: {}",
+            self.table, self.path, self.message
+        )
+    }
+}
+
+// Why render_field_strict couldn't render a field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenderError {
+    UnknownTable(String),
+    // (table, field)
+    UnknownField(String, String),
+    // (table, context)
+    UnknownContext(String, String),
+    // (table, field, variant): a context or default names a missing variant
+    UnknownVariant(String, String, String),
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::UnknownTable(table) => write!(f, "Unknown table '{}'", table),
+            RenderError::UnknownField(table, field) => {
+                write!(f, "Table '{}' has no field '{}'", table, field)
+            }
+            RenderError::UnknownContext(table, context) => {
+                write!(f, "Table '{}' has no context '{}'", table, context)
+            }
+            RenderError::UnknownVariant(table, field, variant) => write!(
+                f,
+                "Field '{}.{}' has no variant '{}'",
+                table, field, variant
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Theme {
+    // Design tokens ("color-primary-500" = "#3b82f6"), e.g. imported from Style Dictionary
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tokens: HashMap<String, String>,
     #[serde(flatten)]
     pub tags: HashMap<String, String>,
 }
@@ -44,11 +684,132 @@ pub struct ThemeConfig {
     pub themes: HashMap<String, Theme>,
 }
 
+// Where a field's variant was found, reported by debug renders
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContextLevel {
+    // `[value_variants]` entry for the value
+    Value,
+    // The requested context, or the fallback that listed the field
+    Context(String),
+    // `[locales]` entry for the render's language
+    Locale(String),
+    Defaults,
+    // The field's first variant, when nothing names one
+    FirstVariant,
+}
+
+impl std::fmt::Display for ContextLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContextLevel::Value => write!(f, "(value)"),
+            ContextLevel::Context(name) => write!(f, "{}", name),
+            ContextLevel::Locale(lang) => write!(f, "(locale {})", lang),
+            ContextLevel::Defaults => write!(f, "(defaults)"),
+            ContextLevel::FirstVariant => write!(f, "(first variant)"),
+        }
+    }
+}
+
+// Per-call switches for field rendering
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FieldRenderOptions<'a> {
+    // Annotate elements with data-sui-src pointing back to the schema key
+    pub debug: bool,
+    // Viewer's currency for `display_currency = "viewer"` amounts
+    pub currency: Option<&'a str>,
+    // Print output: the print theme's classes, no interactive attributes
+    pub print: bool,
+    // Platform ("web", "mobile", "email") whose `<variant>@<platform>` variants apply
+    pub platform: Option<&'a str>,
+    // Language tag whose `[locales]` entries apply ("de-DE")
+    pub lang: Option<&'a str>,
+    // Type of the caller's FieldValue, for fields that declare none; set by
+    // render_field_with
+    pub value_type: Option<FieldType>,
+}
+
+// Context used for ?platform=print when a table defines it
+pub const PRINT_CONTEXT: &str = "print";
+// Theme whose classes print renders use, if themes.toml defines it
+pub const PRINT_THEME: &str = "print";
+// Schema for fields no table defines; load_from_dir falls back to the built-in one
+pub const DEFAULT_TABLE: &str = "_default";
+const DEFAULT_SCHEMA: &str = include_str!("../schemas/_default/_default.toml");
+// Every schema and include file under schemas/, collected by build.rs
+include!(concat!(env!("OUT_DIR"), "/embedded_schemas.rs"));
+// Context entry (`"*" = "span"`) for every field the context doesn't list
+pub const WILDCARD_FIELD: &str = "*";
+// Separates a variant from the platform it specializes it for (`"h1@mobile"`)
+pub const PLATFORM_SEPARATOR: char = '@';
+
+// The generic variant and platform of a platform variant: "h1@mobile" -> ("h1", "mobile")
+pub fn split_platform_variant(name: &str) -> Option<(&str, &str)> {
+    name.split_once(PLATFORM_SEPARATOR)
+}
+
+// Set an attribute the renderer generates (source maps, ARIA, data-*) unless
+// the variant's own attrs already have it: variant attrs take precedence
+pub(crate) fn insert_generated(attrs: &mut HashMap<String, String>, name: &str, value: String) {
+    if !attrs.keys().any(|key| key.eq_ignore_ascii_case(name)) {
+        attrs.insert(name.to_string(), value);
+    }
+}
+
+// Add an attribute to the first tag of rendered markup
+fn annotate_opening_tag(html: String, name: &str, value: &str) -> String {
+    let name_end = html
+        .strip_prefix('<')
+        .and_then(|rest| rest.find(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/'));
+    let Some(end) = name_end else {
+        return html;
+    };
+    let (tag, rest) = html.split_at(end + 1);
+    format!(r#"{} {}="{}"{}"#, tag, name, escape_attr(value), rest)
+}
+
+// Attributes that only matter on screen (links, focus, editing, handlers)
+fn is_interactive_attribute(name: &str) -> bool {
+    matches!(
+        name,
+        "href" | "target" | "rel" | "tabindex" | "draggable" | "contenteditable" | "autofocus"
+    ) || (name.starts_with("on") && name != "open")
+}
+
 #[derive(Debug, Clone)]
 pub struct SchemaRegistry {
     themes: ThemeConfig,
     tables: HashMap<String, TableSchema>,
     current_theme: String,
+    field_memo: FieldMemo,
+    // Templates shipped in a compiled bundle, registered instead of recompiling
+    compiled_components: Vec<ComponentTemplate>,
+    // tenant -> the tables its overlays restyle, already layered over the base
+    tenants: HashMap<String, HashMap<String, TableSchema>>,
+    tenant_registries: TenantRegistries,
+    context_cache: ContextCache,
+}
+
+// table -> context -> the variant each field gets there, flattened on first
+// use so renders of long lists don't walk inheritance chains per field
+#[derive(Debug, Default)]
+struct ContextCache(DashMap<String, DashMap<String, HashMap<String, String>>>);
+
+// A cloned registry may be edited, so it flattens its contexts afresh
+impl Clone for ContextCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+// Registries built by `for_tenant`, one per tenant
+#[derive(Debug, Default)]
+struct TenantRegistries(DashMap<String, Arc<SchemaRegistry>>);
+
+// A cloned registry may be edited, so it builds its tenant views afresh
+impl Clone for TenantRegistries {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
 }
 
 impl SchemaRegistry {
@@ -57,133 +818,1185 @@ impl SchemaRegistry {
     }
 
     pub fn load_all() -> Self {
-        let mut registry = Self::new();
+        // A compiled bundle skips TOML parsing and template compilation entirely
+        if let Some(path) = &config().compiled_registry {
+            match Self::load_compiled(path) {
+                Ok(registry) => return registry,
+                Err(e) => eprintln!(
+                    "Failed to load compiled registry {}: {}; parsing schemas instead",
+                    path.display(),
+                    e
+                ),
+            }
+        }
 
-        let themes_content = include_str!("../themes.toml");
-        if let Ok(themes) = toml::from_str::<ThemeConfig>(themes_content) {
-            registry.themes = themes;
+        // Prefer the on-disk schema directory when one is configured
+        if let Some(dir) = &config().schemas_dir {
+            match Self::load_from_dir(dir) {
+                Ok(registry) => return registry,
+                Err(e) => eprintln!(
+                    "Failed to load schemas from {}: {}; using embedded schemas",
+                    dir.display(),
+                    e
+                ),
+            }
         }
 
-        let table_schemas = [("users", include_str!("../schemas/users/users.toml"))];
+        Self::load_embedded()
+    }
 
-        for (table_name, content) in table_schemas {
-            match toml::from_str::<TableSchema>(content) {
-                Ok(schema) => {
-                    registry.tables.insert(table_name.to_string(), schema);
+    // Schemas compiled into the binary: every `<table>/<table>.<ext>` file
+    // under schemas/ (TOML wins, as in load_from_dir), includes merged in
+    pub fn load_embedded() -> Self {
+        Self::load_embedded_files(EMBEDDED_SCHEMA_FILES)
+    }
+
+    // `files` are (path relative to the schema dir, content) pairs
+    fn load_embedded_files(files: &[(&str, &str)]) -> Self {
+        let mut registry = Self::with_embedded_themes();
+
+        let mut seen = std::collections::HashSet::new();
+        for extension in SCHEMA_EXTENSIONS {
+            for &(file, content) in files {
+                let Some((table_name, name)) = file.split_once('/') else {
+                    continue;
+                };
+                let is_table_file = name
+                    .strip_suffix(extension)
+                    .and_then(|stem| stem.strip_suffix('.'))
+                    .is_some_and(|stem| stem == table_name);
+                if !is_table_file || table_name == TENANTS_DIR || !seen.insert(table_name) {
+                    continue;
                 }
-                Err(e) => {
-                    eprintln!("Failed to load schema for {}: {}", table_name, e);
+                let path = Path::new(file);
+                let schema = parse_schema_text::<TableSchema>(path, content).and_then(|schema| {
+                    schema.merge_includes_from(FileSource::Embedded(files), path)
+                });
+                match schema {
+                    Ok(schema) => {
+                        registry.tables.insert(table_name.to_string(), schema);
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to load schema for {}: {}", table_name, e);
+                    }
                 }
             }
         }
 
+        registry.link_shared_contexts();
         registry
     }
 
-    pub fn get_table(&self, table: &str) -> Option<&TableSchema> {
-        self.tables.get(table)
+    // Load every `<dir>/<table>/<table>.toml` (or `.json`/`.yaml`) file, layering
+    // `<table>.<APP_ENV>.toml` overlays on top, and each tenant's
+    // `<dir>/tenants/<tenant>/<table>.toml` over those (see `for_tenant`).
+    // Files are parsed in parallel and all failures are reported together.
+    pub fn load_from_dir(dir: &Path) -> Result<Self, SchemaError> {
+        Self::load_layered(dir, Some(&config().environment))
     }
 
-    pub fn list_tables(&self) -> Vec<&String> {
-        self.tables.keys().collect()
+    // 🧅 Like `load_from_dir`, with overlays for `environment` (None skips them)
+    pub fn load_layered(dir: &Path, environment: Option<&str>) -> Result<Self, SchemaError> {
+        let mut registry = Self::with_embedded_themes();
+
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| SchemaError::Io(format!("{}: {}", dir.display(), e)))?;
+        let mut files = Vec::new();
+        for entry in entries.flatten() {
+            let table_dir = entry.path();
+            let Some(table_name) = table_dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+            else {
+                continue;
+            };
+            if !table_dir.is_dir() || table_name == TENANTS_DIR {
+                continue;
+            }
+            // TOML wins when a table has both
+            let schema_path = SCHEMA_EXTENSIONS
+                .iter()
+                .map(|ext| table_dir.join(format!("{}.{}", table_name, ext)))
+                .find(|path| path.is_file());
+            let overlay_path =
+                environment.and_then(|env| overlay_file(&table_dir, &table_name, env));
+            if let Some(schema_path) = schema_path {
+                files.push((table_name, schema_path, overlay_path.into_iter().collect()));
+            }
+        }
+        // Stable diagnostics regardless of directory iteration order
+        files.sort();
+
+        let (tenant_files, mut errors) = tenant_schema_files(&dir.join(TENANTS_DIR), &files);
+        for (tenant, files) in tenant_files {
+            let mut tables = HashMap::new();
+            for (table_name, result) in parse_schema_files(files) {
+                match result {
+                    Ok(schema) => {
+                        tables.insert(table_name, schema);
+                    }
+                    Err(e) => errors.push(e),
+                }
+            }
+            registry.tenants.insert(tenant, tables);
+        }
+        for (table_name, result) in parse_schema_files(files) {
+            match result {
+                Ok(schema) => {
+                    registry.tables.insert(table_name, schema);
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+
+        // Directories without their own fallback schema get the built-in one
+        if !registry.tables.contains_key(DEFAULT_TABLE)
+            && let Ok(mut schema) = TableSchema::from_toml_str(DEFAULT_SCHEMA)
+        {
+            schema.source = Some(format!("{}.toml", DEFAULT_TABLE));
+            registry.tables.insert(DEFAULT_TABLE.to_string(), schema);
+        }
+        registry.link_shared_contexts();
+        match errors.len() {
+            0 => Ok(registry),
+            1 => Err(errors.remove(0)),
+            _ => Err(SchemaError::Multiple(errors)),
+        }
     }
 
-    pub fn get_mock_data(&self, table: &str) -> Vec<HashMap<String, String>> {
-        self.get_table(table)
-            .and_then(|schema| schema.mock_data.as_ref())
-            .map(|mock_data| {
-                mock_data
+    // 📦 Write themes, tables (with pre-resolved contexts) and compiled
+    // component templates as one binary bundle
+    pub fn save_compiled(
+        &self,
+        path: &Path,
+        components: &[&ComponentTemplate],
+    ) -> Result<(), SchemaError> {
+        let mut themes: Vec<_> = self
+            .themes
+            .themes
+            .iter()
+            .map(|(name, theme)| (name.clone(), theme.into()))
+            .collect();
+        themes.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut tables: Vec<_> = self
+            .tables
+            .iter()
+            .map(|(name, schema)| (name.clone(), schema.into()))
+            .collect();
+        tables.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut tenants: Vec<_> = self
+            .tenants
+            .iter()
+            .map(|(tenant, tables)| {
+                let mut tables: Vec<_> = tables
                     .iter()
-                    .map(|record| record.fields.clone())
-                    .collect()
+                    .map(|(name, schema)| (name.clone(), schema.into()))
+                    .collect();
+                tables.sort_by(|a, b| a.0.cmp(&b.0));
+                (tenant.clone(), tables)
             })
-            .unwrap_or_default()
+            .collect();
+        tenants.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let bundle = CompiledBundle {
+            format_version: COMPILED_FORMAT_VERSION,
+            current_theme: self.current_theme.clone(),
+            themes,
+            tables,
+            tenants,
+            components: components
+                .iter()
+                .map(|component| (*component).into())
+                .collect(),
+        };
+        let bytes = bincode::serialize(&bundle)
+            .map_err(|e| SchemaError::Parse(format!("{}: {}", path.display(), e)))?;
+        std::fs::write(path, bytes)
+            .map_err(|e| SchemaError::Io(format!("{}: {}", path.display(), e)))
     }
 
-    pub fn get_mock_record(&self, table: &str, id: &str) -> Option<HashMap<String, String>> {
-        self.get_mock_data(table)
+    // Load a bundle written by save_compiled
+    pub fn load_compiled(path: &Path) -> Result<Self, SchemaError> {
+        let bytes = std::fs::read(path)
+            .map_err(|e| SchemaError::Io(format!("{}: {}", path.display(), e)))?;
+        let bundle: CompiledBundle = bincode::deserialize(&bytes)
+            .map_err(|e| SchemaError::Parse(format!("{}: {}", path.display(), e)))?;
+        if bundle.format_version != COMPILED_FORMAT_VERSION {
+            return Err(SchemaError::Parse(format!(
+                "{}: compiled format v{}, expected v{}",
+                path.display(),
+                bundle.format_version,
+                COMPILED_FORMAT_VERSION
+            )));
+        }
+
+        let mut registry = Self::new();
+        registry.themes.themes = bundle
+            .themes
             .into_iter()
-            .find(|record| record.get("id") == Some(&id.to_string()))
+            .map(|(name, theme)| (name, theme.into()))
+            .collect();
+        registry.tables = bundle
+            .tables
+            .into_iter()
+            .map(|(name, table)| (name, table.into()))
+            .collect();
+        registry.tenants = bundle
+            .tenants
+            .into_iter()
+            .map(|(tenant, tables)| {
+                let tables = tables.into_iter().map(|(name, table)| (name, table.into()));
+                (tenant, tables.collect())
+            })
+            .collect();
+        registry.current_theme = bundle.current_theme;
+        registry.compiled_components = bundle.components.into_iter().map(Into::into).collect();
+        registry.link_shared_contexts();
+        Ok(registry)
     }
 
-    pub fn get_mock_records(
-        &self,
-        table: &str,
-        limit: Option<usize>,
-    ) -> Vec<HashMap<String, String>> {
-        match limit {
-            Some(n) => self.get_mock_data(table).into_iter().take(n).collect(),
-            None => self.get_mock_data(table),
-        }
+    pub fn compiled_components(&self) -> &[ComponentTemplate] {
+        &self.compiled_components
     }
 
-    pub fn set_theme(&mut self, theme_name: &str) {
-        if self.themes.themes.contains_key(theme_name) {
-            self.current_theme = theme_name.to_string();
+    fn with_embedded_themes() -> Self {
+        let mut registry = Self::new();
+
+        let themes_content = include_str!("../themes.toml");
+        if let Ok(themes) = toml::from_str::<ThemeConfig>(themes_content) {
+            registry.themes = themes;
         }
+
+        registry
     }
 
-    pub fn get_current_theme(&self) -> &str {
-        &self.current_theme
+    pub fn get_table(&self, table: &str) -> Option<&TableSchema> {
+        self.tables.get(table)
     }
 
-    // 🎯 MAIN RENDERING METHOD - This is where the magic happens
-    pub fn render_field(
-        &self,
+    pub fn get_table_mut(&mut self, table: &str) -> Option<&mut TableSchema> {
+        // Memoized renders may come from the schema about to be edited
+        self.field_memo.clear();
+        self.context_cache.0.clear();
+        let schema = self.tables.get_mut(table)?;
+        // Edits may change context resolution; fall back to resolving per render
+        schema.resolved_contexts.clear();
+        Some(schema)
+    }
+
+    // 🏢 This registry as `tenant` sees it: tables the tenant's overlays
+    // restyle replace the base ones. Tenants without overlays get this registry.
+    pub fn for_tenant(self: &Arc<Self>, tenant: &str) -> Arc<SchemaRegistry> {
+        let Some(tables) = self.tenants.get(tenant) else {
+            return Arc::clone(self);
+        };
+        let layered = self
+            .tenant_registries
+            .0
+            .entry(tenant.to_string())
+            .or_insert_with(|| {
+                let mut layered = SchemaRegistry {
+                    tenants: HashMap::new(),
+                    ..(**self).clone()
+                };
+                layered.tables.extend(tables.clone());
+                layered.link_shared_contexts();
+                Arc::new(layered)
+            });
+        Arc::clone(&layered)
+    }
+
+    // Tenants with schema overlays, sorted
+    pub fn tenants(&self) -> Vec<&str> {
+        let mut tenants: Vec<_> = self.tenants.keys().map(String::as_str).collect();
+        tenants.sort();
+        tenants
+    }
+
+    // Add (or replace) a table defined in code, e.g. by an embedding app
+    pub fn insert_table(&mut self, table: &str, schema: TableSchema) {
+        self.field_memo.clear();
+        self.context_cache.0.clear();
+        self.tables.insert(table.to_string(), schema);
+        self.link_shared_contexts();
+    }
+
+    // ➕ Add (or replace) a table computed at runtime, e.g. generated from an
+    // ORM model. Unlike insert_table the schema is checked first: `extends` is
+    // resolved and validation errors reject it, leaving the registry as it was.
+    pub fn register_table(
+        &mut self,
         table: &str,
-        field: &str,
-        context: &str,
-        value: &str,
-    ) -> Option<String> {
-        let schema = self.get_table(table)?;
-        let variant_name = Self::resolve_variant_for_field(schema, field, context)?;
-        let field_variants = schema.variants.get(field)?;
-        let variant = field_variants.get(&variant_name)?;
-
-        let base_css = self.get_theme_css(&variant.base);
-        let css_classes = self.build_css_classes(&base_css, variant);
-        let attrs = Self::build_attributes(variant, value, field);
-
-        Some(Self::generate_html(
-            &variant.base,
-            &css_classes,
-            &attrs,
-            value,
-        ))
+        mut schema: TableSchema,
+    ) -> Result<(), SchemaError> {
+        if table.is_empty() {
+            return Err(SchemaError::Parse("table name is empty".to_string()));
+        }
+        self.check_runtime_schema(table, &mut schema)?;
+        self.insert_table(table, schema);
+        Ok(())
     }
-    fn resolve_variant_for_field(
-        schema: &TableSchema,
-        field: &str,
-        context: &str,
-    ) -> Option<String> {
-        // Check if context exists and has this field
-        if let Some(ctx) = schema.contexts.get(context) {
-            if let Some(variant) = ctx.fields.get(field) {
-                return Some(variant.clone());
-            }
 
-            // Check inheritance chain recursively
-            if let Some(parent_context) = &ctx.inherits {
-                return Self::resolve_variant_for_field(schema, field, parent_context);
-            }
+    // 🔁 Swap the schema of an existing table, checked like register_table;
+    // returns the schema it replaced
+    pub fn replace_table(
+        &mut self,
+        table: &str,
+        mut schema: TableSchema,
+    ) -> Result<TableSchema, SchemaError> {
+        if !self.tables.contains_key(table) {
+            return Err(SchemaError::UnknownTable(table.to_string()));
         }
+        self.check_runtime_schema(table, &mut schema)?;
+        let previous = self.tables.get(table).cloned();
+        self.insert_table(table, schema);
+        previous.ok_or_else(|| SchemaError::UnknownTable(table.to_string()))
+    }
 
-        // Fall back to defaults
-        schema
-            .defaults
-            .as_ref()
-            .and_then(|defaults| defaults.get(field).cloned())
-            .or_else(|| {
-                // Last resort: use first available variant for this field
+    // ➖ Retire a table, returning its schema. Tables still inheriting its
+    // contexts ("common.card") keep it in place.
+    pub fn remove_table(&mut self, table: &str) -> Result<TableSchema, SchemaError> {
+        if !self.tables.contains_key(table) {
+            return Err(SchemaError::UnknownTable(table.to_string()));
+        }
+        let prefix = format!("{}.", table);
+        let mut users: Vec<_> = self
+            .tables
+            .iter()
+            .filter(|(name, _)| name.as_str() != table)
+            .filter(|(_, schema)| {
                 schema
-                    .variants
-                    .get(field)
-                    .and_then(|field_variants| field_variants.keys().next().cloned())
+                    .shared_contexts
+                    .keys()
+                    .any(|r| r.starts_with(&prefix))
             })
+            .map(|(name, _)| name.clone())
+            .collect();
+        if !users.is_empty() {
+            users.sort();
+            return Err(SchemaError::TableInUse(table.to_string(), users));
+        }
+        self.field_memo.clear();
+        self.context_cache.0.clear();
+        self.tables
+            .remove(table)
+            .ok_or_else(|| SchemaError::UnknownTable(table.to_string()))
     }
 
-    // Get CSS classes from current theme
-    fn get_theme_css(&self, tag: &str) -> String {
+    // 🧪 Point `field` of one of `table`'s contexts at another variant without
+    // editing schema files, e.g. for a feature-flagged experiment. Returns the
+    // entry it replaced, to patch back when the experiment ends.
+    pub fn patch_context(
+        &mut self,
+        table: &str,
+        context: &str,
+        field: &str,
+        variant: &str,
+    ) -> Result<Option<String>, RenderError> {
+        let schema = self
+            .tables
+            .get(table)
+            .ok_or_else(|| RenderError::UnknownTable(table.to_string()))?;
+        if !schema.contexts.contains_key(context) {
+            return Err(RenderError::UnknownContext(
+                table.to_string(),
+                context.to_string(),
+            ));
+        }
+        let Some(variants) = schema.variants.get(field) else {
+            return Err(RenderError::UnknownField(
+                table.to_string(),
+                field.to_string(),
+            ));
+        };
+        if !variants.contains_key(variant) {
+            return Err(RenderError::UnknownVariant(
+                table.to_string(),
+                field.to_string(),
+                variant.to_string(),
+            ));
+        }
+        let patched = self
+            .get_table_mut(table)
+            .and_then(|schema| schema.contexts.get_mut(context))
+            .ok_or_else(|| RenderError::UnknownContext(table.to_string(), context.to_string()))?;
+        Ok(patched
+            .fields
+            .insert(field.to_string(), variant.to_string()))
+    }
+
+    // Resolve `extends` and reject validation errors, for tables added at
+    // runtime; contexts may inherit from tables already registered
+    fn check_runtime_schema(
+        &self,
+        table: &str,
+        schema: &mut TableSchema,
+    ) -> Result<(), SchemaError> {
+        schema.resolve_extends().map_err(SchemaError::Parse)?;
+        schema.shared_contexts = self.shared_contexts_for(schema);
+        let mut errors: Vec<_> = validate_table(table, schema)
+            .into_iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+            .map(|diagnostic| SchemaError::Parse(diagnostic.to_string()))
+            .collect();
+        match errors.len() {
+            0 => {}
+            1 => return Err(errors.remove(0)),
+            _ => return Err(SchemaError::Multiple(errors)),
+        }
+        schema
+            .source
+            .get_or_insert_with(|| format!("{} (runtime)", table));
+        Ok(())
+    }
+
+    // Context `name` of table `table`, for a "table.context" reference
+    pub fn shared_context(&self, reference: &str) -> Option<&Context> {
+        let (table, context) = reference.split_once('.')?;
+        self.tables.get(table)?.contexts.get(context)
+    }
+
+    // 🔗 Give every table copies of the contexts it inherits from other tables
+    // ("common.card"), following their own inheritance within their table.
+    // Runs after loading and whenever a table changes.
+    pub(crate) fn link_shared_contexts(&mut self) {
+        let linked: Vec<_> = self
+            .tables
+            .iter()
+            .map(|(name, schema)| (name.clone(), self.shared_contexts_for(schema)))
+            .collect();
+        for (name, shared) in linked {
+            if let Some(schema) = self.tables.get_mut(&name) {
+                schema.shared_contexts = shared;
+            }
+        }
+    }
+
+    fn shared_contexts_for(&self, schema: &TableSchema) -> HashMap<String, Context> {
+        let mut shared = HashMap::new();
+        let mut pending: Vec<String> = schema
+            .contexts
+            .values()
+            .filter_map(|context| context.inherits.clone())
+            .filter(|parent| parent.contains('.'))
+            .collect();
+        while let Some(reference) = pending.pop() {
+            if shared.contains_key(&reference) {
+                continue;
+            }
+            let Some(context) = self.shared_context(&reference) else {
+                continue;
+            };
+            let mut context = context.clone();
+            // Parents of a shared context live in its own table
+            if let Some(parent) = &context.inherits
+                && !parent.contains('.')
+            {
+                let table = reference.split_once('.').map_or("", |(table, _)| table);
+                context.inherits = Some(format!("{}.{}", table, parent));
+            }
+            pending.extend(context.inherits.clone());
+            shared.insert(reference, context);
+        }
+        shared
+    }
+
+    // Data tables; the `_default` fallback schema isn't one
+    pub fn list_tables(&self) -> Vec<&String> {
+        self.tables
+            .keys()
+            .filter(|table| *table != DEFAULT_TABLE)
+            .collect()
+    }
+
+    // 🩺 Problems across all tables (unknown variants, dangling inherits, ...),
+    // errors first, then by table and path
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics: Vec<_> = self
+            .tables
+            .iter()
+            .flat_map(|(table, schema)| {
+                let mut found = validate_table(table, schema);
+                found.extend(validate_references(table, schema, |name| {
+                    self.tables.contains_key(name)
+                }));
+                found
+            })
+            .collect();
+        // Tenant tables are reported as "<tenant>/<table>"
+        for (tenant, tables) in &self.tenants {
+            for (table, schema) in tables {
+                diagnostics.extend(validate_table(&format!("{}/{}", tenant, table), schema));
+            }
+        }
+        diagnostics.sort_by(|a, b| {
+            (a.severity, &a.table, &a.path, &a.message)
+                .cmp(&(b.severity, &b.table, &b.path, &b.message))
+        });
+        diagnostics
+    }
+
+    // 🧹 Lint findings across all tables (unused variants, repeated context
+    // entries, ...), by table and path; see lint.rs
+    pub fn lint(&self) -> Vec<Diagnostic> {
+        let mut diagnostics: Vec<_> = self
+            .tables
+            .iter()
+            .flat_map(|(table, schema)| lint_table(table, schema))
+            .collect();
+        diagnostics
+            .sort_by(|a, b| (&a.table, &a.path, &a.message).cmp(&(&b.table, &b.path, &b.message)));
+        diagnostics
+    }
+
+    // 📤 The schema `table` effectively renders with, as a self-contained
+    // schema: includes and `extends` already merged in, and every context
+    // listing the variant each field gets, so `inherits` is dropped
+    pub fn export(&self, table: &str) -> Option<TableSchema> {
+        let schema = self.get_table(table)?;
+        let mut resolved = schema.resolve_contexts();
+        let mut exported = schema.clone();
+        exported.include.clear();
+        for variant in exported
+            .variants
+            .values_mut()
+            .flat_map(|variants| variants.values_mut())
+        {
+            variant.extends = None;
+        }
+        for (name, context) in &mut exported.contexts {
+            // Fields without variants of their own render through "*"
+            let wildcard = Self::context_entry(schema, WILDCARD_FIELD, name);
+            context.fields = resolved.remove(name).unwrap_or_default();
+            context
+                .fields
+                .extend(wildcard.map(|variant| (WILDCARD_FIELD.to_string(), variant)));
+            context.inherits = None;
+        }
+        Some(exported)
+    }
+
+    // 🔎 `table` with contexts resolved and theme classes merged (see describe.rs)
+    pub fn describe(&self, table: &str) -> Option<TableDescription> {
+        describe_table(self, table)
+    }
+
+    pub fn get_mock_data(&self, table: &str) -> Vec<HashMap<String, String>> {
+        self.get_table(table)
+            .and_then(|schema| schema.mock_data.as_ref())
+            .map(|mock_data| {
+                mock_data
+                    .iter()
+                    .map(|record| record.fields.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn get_mock_record(&self, table: &str, id: &str) -> Option<HashMap<String, String>> {
+        self.get_mock_data(table)
+            .into_iter()
+            .find(|record| record.get("id") == Some(&id.to_string()))
+    }
+
+    pub fn get_mock_records(
+        &self,
+        table: &str,
+        limit: Option<usize>,
+    ) -> Vec<HashMap<String, String>> {
+        match limit {
+            Some(n) => self.get_mock_data(table).into_iter().take(n).collect(),
+            None => self.get_mock_data(table),
+        }
+    }
+
+    // Replace the themes with those in a themes file on disk (TOML, or YAML
+    // for `.yaml`/`.yml` files)
+    pub fn load_themes_file(&mut self, path: &Path) -> Result<(), SchemaError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| SchemaError::Io(format!("{}: {}", path.display(), e)))?;
+        let parsed = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml" | "yml") => serde_yaml::from_str(&content).map_err(|e| e.to_string()),
+            _ => toml::from_str(&content).map_err(|e| e.to_string()),
+        };
+        self.themes =
+            parsed.map_err(|e| SchemaError::Parse(format!("{}: {}", path.display(), e)))?;
+        self.field_memo.clear();
+        Ok(())
+    }
+
+    pub fn set_theme(&mut self, theme_name: &str) {
+        if self.themes.themes.contains_key(theme_name) {
+            self.current_theme = theme_name.to_string();
+        }
+    }
+
+    pub fn get_current_theme(&self) -> &str {
+        &self.current_theme
+    }
+
+    pub fn get_theme(&self, theme_name: &str) -> Option<&Theme> {
+        self.themes.themes.get(theme_name)
+    }
+
+    pub fn list_themes(&self) -> Vec<&String> {
+        self.themes.themes.keys().collect()
+    }
+
+    // 🎯 MAIN RENDERING METHOD - This is where the magic happens
+    // (None doesn't say what was missing; render_field_strict does).
+    // Takes text or a typed FieldValue; Null renders nothing.
+    pub fn render_field<'v>(
+        &self,
+        table: &str,
+        field: &str,
+        context: &str,
+        value: impl Into<FieldValue<'v>>,
+    ) -> Option<String> {
+        self.render_field_with(table, field, context, value, FieldRenderOptions::default())
+    }
+
+    // Same as render_field, with per-call options (debug annotations, ...)
+    pub fn render_field_with<'v>(
+        &self,
+        table: &str,
+        field: &str,
+        context: &str,
+        value: impl Into<FieldValue<'v>>,
+        options: FieldRenderOptions<'_>,
+    ) -> Option<String> {
+        let value = value.into();
+        if value == FieldValue::Null {
+            return None;
+        }
+        let options = FieldRenderOptions {
+            value_type: value.field_type(),
+            ..options
+        };
+        self.render_text(table, field, context, &value.to_text(), options)
+    }
+
+    fn render_text(
+        &self,
+        table: &str,
+        field: &str,
+        context: &str,
+        value: &str,
+        options: FieldRenderOptions<'_>,
+    ) -> Option<String> {
+        let defined = self
+            .get_table(table)
+            .filter(|schema| schema.variants.contains_key(field));
+        let Some(schema) = defined else {
+            if let Some((table, field)) = self.referenced_field(table, field) {
+                return self.render_text(table, field, context, value, options);
+            }
+            return self
+                .render_wildcard(table, context, value, options)
+                .or_else(|| self.render_fallback(field, context, value, options));
+        };
+        // 🌐 Value mappings win over the language's choice, which wins over the context's
+        let by_value = schema.value_variants.get(field).and_then(|v| v.get(value));
+        let locale = options.lang.and_then(|lang| schema.locale_key(lang));
+        let localized = locale
+            .and_then(|locale| schema.locales[locale].get(context)?.get(field))
+            .filter(|_| by_value.is_none());
+        if !options.debug {
+            let variant_name = match localized {
+                Some(variant) => variant.clone(),
+                None => self.variant_for_value(table, schema, field, context, value)?,
+            };
+            return self.render_variant_with(table, field, &variant_name, value, options);
+        }
+        // Debug renders say which level of the fallback chain matched
+        let (variant_name, level) = match (by_value, localized, locale) {
+            (Some(variant), _, _) => (variant.clone(), ContextLevel::Value),
+            (None, Some(variant), Some(locale)) => {
+                (variant.clone(), ContextLevel::Locale(locale.to_string()))
+            }
+            _ => Self::resolve_variant_source(schema, field, context)?,
+        };
+        let html = self.render_variant_with(table, field, &variant_name, value, options)?;
+        Some(annotate_opening_tag(
+            html,
+            "data-sui-context",
+            &level.to_string(),
+        ))
+    }
+
+    // 🔗 Table and field a dot path addresses through the table's
+    // `[references]`: ("users", "company.name") -> ("companies", "name").
+    // The field may be a dot path into the referenced table in turn.
+    pub fn referenced_field<'f>(&self, table: &str, path: &'f str) -> Option<(&str, &'f str)> {
+        let (name, field) = path.split_once('.')?;
+        let reference = self.get_table(table)?.references.get(name)?;
+        Some((reference.table.as_str(), field))
+    }
+
+    // ✳️ Fields a table doesn't declare, in a context (or a fallback of it)
+    // with a `"*" = "text"` entry: that variant of the table's own "*" field,
+    // else of `_default`'s
+    fn render_wildcard(
+        &self,
+        table: &str,
+        context: &str,
+        value: &str,
+        options: FieldRenderOptions<'_>,
+    ) -> Option<String> {
+        let schema = self.get_table(table)?;
+        let variant = schema
+            .fallback_chain(context)
+            .into_iter()
+            .find_map(|level| Self::context_entry(schema, WILDCARD_FIELD, level))?;
+        let owner = match schema.variants.get(WILDCARD_FIELD) {
+            Some(variants) if variants.contains_key(&variant) => table,
+            _ => DEFAULT_TABLE,
+        };
+        self.render_variant_with(owner, WILDCARD_FIELD, &variant, value, options)
+    }
+
+    // 🪂 Fields of unknown tables (or unknown to their table) render through
+    // the `_default` schema: its entry for the field, else its "*" entry
+    fn render_fallback(
+        &self,
+        field: &str,
+        context: &str,
+        value: &str,
+        options: FieldRenderOptions<'_>,
+    ) -> Option<String> {
+        let schema = self.get_table(DEFAULT_TABLE)?;
+        let field = if schema.variants.contains_key(field) {
+            field
+        } else {
+            WILDCARD_FIELD
+        };
+        let variant_name = self.variant_for_value(DEFAULT_TABLE, schema, field, context, value)?;
+        self.render_variant_with(DEFAULT_TABLE, field, &variant_name, value, options)
+    }
+
+    // 🚦 Like render_field, but says what was missing instead of falling back:
+    // no `_default` rendering for unknown fields, no defaults for unknown contexts
+    pub fn render_field_strict(
+        &self,
+        table: &str,
+        field: &str,
+        context: &str,
+        value: &str,
+    ) -> Result<String, RenderError> {
+        let schema = self
+            .get_table(table)
+            .ok_or_else(|| RenderError::UnknownTable(table.to_string()))?;
+        if !schema.variants.contains_key(field)
+            && let Some((table, field)) = self.referenced_field(table, field)
+        {
+            return self.render_field_strict(table, field, context, value);
+        }
+        let Some(variants) = schema.variants.get(field).filter(|v| !v.is_empty()) else {
+            return Err(RenderError::UnknownField(
+                table.to_string(),
+                field.to_string(),
+            ));
+        };
+        let known = |context: &str| {
+            schema.resolved_contexts.contains_key(context) || schema.context(context).is_some()
+        };
+        if !schema.fallback_chain(context).into_iter().any(known) {
+            return Err(RenderError::UnknownContext(
+                table.to_string(),
+                context.to_string(),
+            ));
+        }
+        let variant_name = self
+            .variant_for_value(table, schema, field, context, value)
+            .or_else(|| variants.keys().next().cloned())
+            .unwrap_or_default();
+        let options = FieldRenderOptions::default();
+        self.render_variant_with(table, field, &variant_name, value, options)
+            .ok_or(RenderError::UnknownVariant(
+                table.to_string(),
+                field.to_string(),
+                variant_name,
+            ))
+    }
+
+    // 🗓️ Deprecated parts of `table` a render of `fields` in `context` goes
+    // through: the context itself, and each field's variant (resolved from
+    // the record's value when there is one)
+    pub fn deprecations(
+        &self,
+        table: &str,
+        context: &str,
+        fields: &[String],
+        record: &HashMap<String, String>,
+    ) -> Vec<Deprecation> {
+        let Some(schema) = self.get_table(table) else {
+            return Vec::new();
+        };
+        let deprecation = |path: String, message: &String| Deprecation {
+            table: table.to_string(),
+            path,
+            message: message.clone(),
+        };
+        let mut found = Vec::new();
+        if let Some((name, ctx)) = schema.context(context)
+            && let Some(message) = &ctx.deprecated
+        {
+            found.push(deprecation(format!("contexts.{}", name), message));
+        }
+        for (field, variant) in self.rendered_variants(table, context, fields, record) {
+            let message = schema.variants.get(field).and_then(|v| v.get(&variant));
+            if let Some(message) = message.and_then(|variant| variant.deprecated.as_ref()) {
+                found.push(deprecation(
+                    format!("variants.{}.{}", field, variant),
+                    message,
+                ));
+            }
+        }
+        found
+    }
+
+    // (field, variant) for each of `fields` a render in `context` resolves a
+    // variant for, from the record's value when there is one
+    pub fn rendered_variants<'f>(
+        &self,
+        table: &str,
+        context: &str,
+        fields: &'f [String],
+        record: &HashMap<String, String>,
+    ) -> Vec<(&'f str, String)> {
+        let Some(schema) = self.get_table(table) else {
+            return Vec::new();
+        };
+        fields
+            .iter()
+            .filter_map(|field| {
+                let variant = match record.get(field) {
+                    Some(value) => self.variant_for_value(table, schema, field, context, value),
+                    None => self.cached_variant(table, schema, field, context),
+                };
+                Some((field.as_str(), variant?))
+            })
+            .collect()
+    }
+
+    // Render a field with a named variant instead of the one its context picks
+    pub fn render_variant_with(
+        &self,
+        table: &str,
+        field: &str,
+        variant_name: &str,
+        value: &str,
+        options: FieldRenderOptions<'_>,
+    ) -> Option<String> {
+        let schema = self.get_table(table)?;
+        let variants = schema.variants.get(field)?;
+        // 📱 The platform's specialization of the variant, else the generic one
+        let specialized = options.platform.and_then(|platform| {
+            let name = format!("{}{}{}", variant_name, PLATFORM_SEPARATOR, platform);
+            variants.get_key_value(&name)
+        });
+        let (variant_name, variant) = match specialized {
+            Some((name, variant)) => (name.as_str(), variant),
+            None => (variant_name, variants.get(variant_name)?),
+        };
+
+        let theme = if options.print && self.themes.themes.contains_key(PRINT_THEME) {
+            PRINT_THEME
+        } else {
+            self.current_theme.as_str()
+        };
+        let key = MemoKey {
+            table,
+            field,
+            variant: variant_name,
+            theme,
+            debug: options.debug,
+            print: options.print,
+            value_type: options.value_type,
+            value,
+        };
+        let render = || {
+            let base_css = self
+                .get_theme(theme)
+                .and_then(|theme| theme.tags.get(&variant.base))
+                .cloned()
+                .unwrap_or_default();
+            let css_classes = self.build_css_classes(&base_css, variant);
+            let mut attrs = Self::build_attributes(variant, value, field);
+            // Schema attrs the policy forbids never reach the markup
+            let policy = &config().attr_policy;
+            attrs.retain(|name, _| policy.permits(&variant.base, name));
+            // Inputs print as their value; nothing on paper can be clicked
+            let tag = if options.print && variant.base == "input" {
+                attrs.retain(|name, _| !matches!(name.as_str(), "type" | "value" | "name"));
+                "span"
+            } else {
+                variant.base.as_str()
+            };
+            if options.print {
+                attrs.retain(|name, _| !is_interactive_attribute(name));
+            }
+            if let Some(rules) = schema.rules.get(field) {
+                rule_attributes(rules, tag, &mut attrs);
+            }
+
+            // Source map back to the schema key that produced this element
+            if options.debug {
+                let source = schema.source.as_deref().unwrap_or(table);
+                insert_generated(
+                    &mut attrs,
+                    "data-sui-src",
+                    format!("{}#variants.{}.{}", source, field, variant_name),
+                );
+            }
+
+            // Enum values show their label, with the value's classes
+            if variant.kind.is_none()
+                && let Some(spec) = schema.enums.get(field)
+                && spec.contains(value)
+            {
+                insert_generated(&mut attrs, "data-value", escape_attr(value));
+                let classes = match spec.classes.get(value) {
+                    Some(extra) => format!("{} {}", css_classes, extra).trim().to_string(),
+                    None => css_classes,
+                };
+                return Some(Self::generate_html(
+                    tag,
+                    &classes,
+                    &attrs,
+                    &spec.label(value),
+                ));
+            }
+
+            if variant.kind.is_none()
+                && let Some(max_length) = variant.max_length
+                && let Some(cut) = value.char_indices().nth(max_length).map(|(i, _)| i)
+            {
+                let head = value[..cut].trim_end();
+                let strategy = variant.overflow.unwrap_or_default();
+                return Some(match strategy {
+                    Overflow::Truncate => {
+                        Self::generate_html(tag, &css_classes, &attrs, &format!("{}…", head))
+                    }
+                    Overflow::Fade => {
+                        let fade = self
+                            .get_theme(theme)
+                            .and_then(|theme| theme.tags.get("overflow-fade"))
+                            .map_or(css_classes.clone(), |fade| {
+                                format!("{} {}", css_classes, fade).trim().to_string()
+                            });
+                        Self::generate_html(tag, &fade, &attrs, head)
+                    }
+                    // Paper can't be expanded, so print shows everything
+                    Overflow::Details if options.print => {
+                        Self::generate_html(tag, &css_classes, &attrs, value)
+                    }
+                    Overflow::Details => {
+                        let details =
+                            format!("<details><summary>{}…</summary>{}</details>", head, value);
+                        Self::generate_html(tag, &css_classes, &attrs, &details)
+                    }
+                });
+            }
+
+            if variant.kind.is_some()
+                && let Some(html) = render_kind(
+                    variant,
+                    KindContext {
+                        theme: self.get_theme(theme),
+                        currency: options.currency,
+                        field,
+                    },
+                    &css_classes,
+                    attrs.clone(),
+                    value,
+                )
+            {
+                return Some(html);
+            }
+
+            let field_type = variant
+                .field_type
+                .or_else(|| schema.types.get(field).copied())
+                .or(options.value_type);
+            let content = match field_type {
+                Some(field_type) => {
+                    typed_content(field_type, tag, &mut attrs, value, options.print)
+                }
+                None => value.to_string(),
+            };
+            Some(Self::generate_html(tag, &css_classes, &attrs, &content))
+        };
+        // Output that changes over time (exchange rates) is never memoized
+        if is_volatile(variant) {
+            render()
+        } else {
+            self.field_memo.get_or_render(key, render)
+        }
+    }
+
+    // Classes a field gets in a context under a specific theme (no rendering)
+    pub fn field_classes(
+        &self,
+        table: &str,
+        field: &str,
+        context: &str,
+        theme: &str,
+    ) -> Option<String> {
+        let schema = self.get_table(table)?;
+        let variant_name = self.cached_variant(table, schema, field, context)?;
+        let variant = schema.variants.get(field)?.get(&variant_name)?;
+        Some(self.variant_classes(theme, variant))
+    }
+
+    // resolve_variant_for_value, with the context's choice from the cache
+    fn variant_for_value(
+        &self,
+        table: &str,
+        schema: &TableSchema,
+        field: &str,
+        context: &str,
+        value: &str,
+    ) -> Option<String> {
+        match schema
+            .value_variants
+            .get(field)
+            .and_then(|by_value| by_value.get(value))
+        {
+            Some(variant) => Some(variant.clone()),
+            None => self.cached_variant(table, schema, field, context),
+        }
+    }
+
+    // ⚡ resolve_variant_for_field, from `table`'s flattened `context`. Only
+    // contexts the table defines are cached, as requests may name any context.
+    fn cached_variant(
+        &self,
+        table: &str,
+        schema: &TableSchema,
+        field: &str,
+        context: &str,
+    ) -> Option<String> {
+        if let Some(contexts) = self.context_cache.0.get(table)
+            && let Some(fields) = contexts.get(context)
+        {
+            return fields.get(field).cloned();
+        }
+        if schema.context(context).is_none() {
+            return Self::resolve_variant_for_field(schema, field, context);
+        }
+        // Every field any entry, default or variant names; others resolve to nothing
+        let names = schema
+            .variants
+            .keys()
+            .chain(schema.defaults.iter().flatten().map(|(f, _)| f));
+        let entries = schema
+            .contexts
+            .values()
+            .chain(schema.shared_contexts.values());
+        let fields: HashMap<_, _> = names
+            .chain(entries.flat_map(|entry| entry.fields.keys()))
+            .filter_map(|name| {
+                let variant = Self::resolve_variant_for_field(schema, name, context)?;
+                Some((name.clone(), variant))
+            })
+            .collect();
+        let variant = fields.get(field).cloned();
+        let contexts = self.context_cache.0.entry(table.to_string()).or_default();
+        contexts.insert(context.to_string(), fields);
+        variant
+    }
+
+    // The variant `value_variants` maps this value to, else the context's
+    pub(crate) fn resolve_variant_for_value(
+        schema: &TableSchema,
+        field: &str,
+        context: &str,
+        value: &str,
+    ) -> Option<String> {
+        schema
+            .value_variants
+            .get(field)
+            .and_then(|by_value| by_value.get(value))
+            .cloned()
+            .or_else(|| Self::resolve_variant_for_field(schema, field, context))
+    }
+
+    pub(crate) fn resolve_variant_for_field(
+        schema: &TableSchema,
+        field: &str,
+        context: &str,
+    ) -> Option<String> {
+        if let Some(variant) = schema
+            .resolved_contexts
+            .get(context)
+            .and_then(|fields| fields.get(field))
+        {
+            return Some(variant.clone());
+        }
+        Self::resolve_variant_source(schema, field, context).map(|(variant, _)| variant)
+    }
+
+    // 🪜 A field's variant and the level it was found at: per context of the
+    // fallback chain, the field in the context or the contexts it inherits
+    // from, then a "*" entry naming a variant the field has; then defaults
+    pub(crate) fn resolve_variant_source(
+        schema: &TableSchema,
+        field: &str,
+        context: &str,
+    ) -> Option<(String, ContextLevel)> {
+        for level in schema.fallback_chain(context) {
+            let found = Self::context_entry(schema, field, level).or_else(|| {
+                Self::context_entry(schema, WILDCARD_FIELD, level).filter(|variant| {
+                    schema
+                        .variants
+                        .get(field)
+                        .is_some_and(|v| v.contains_key(variant))
+                })
+            });
+            if let Some(variant) = found {
+                return Some((variant, ContextLevel::Context(level.to_string())));
+            }
+        }
+
+        // Fall back to defaults
+        if let Some(variant) = schema
+            .defaults
+            .as_ref()
+            .and_then(|defaults| defaults.get(field))
+        {
+            return Some((variant.clone(), ContextLevel::Defaults));
+        }
+        // Last resort: use first available variant for this field
+        schema
+            .variants
+            .get(field)
+            .and_then(|field_variants| field_variants.keys().next().cloned())
+            .map(|variant| (variant, ContextLevel::FirstVariant))
+    }
+
+    // `field`'s entry in `context`, following `inherits` up the chain
+    fn context_entry(schema: &TableSchema, field: &str, context: &str) -> Option<String> {
+        let mut seen = Vec::new();
+        let mut current = schema.context(context);
+        while let Some((name, ctx)) = current {
+            if let Some(variant) = ctx.fields.get(field) {
+                return Some(variant.clone());
+            }
+            if seen.contains(&name) {
+                break;
+            }
+            seen.push(name);
+            current = ctx
+                .inherits
+                .as_deref()
+                .and_then(|parent| schema.context(parent));
+        }
+        None
+    }
+
+    // Get CSS classes from current theme
+    pub(crate) fn get_theme_css(&self, tag: &str) -> String {
         self.themes
             .themes
             .get(&self.current_theme)
@@ -193,6 +2006,15 @@ impl SchemaRegistry {
     }
 
     // Build final CSS classes (theme + override + extend)
+    // Classes a variant renders with under `theme`
+    pub(crate) fn variant_classes(&self, theme: &str, variant: &FieldVariant) -> String {
+        let base_css = self
+            .get_theme(theme)
+            .and_then(|theme| theme.tags.get(&variant.base))
+            .map_or("", String::as_str);
+        self.build_css_classes(base_css, variant)
+    }
+
     fn build_css_classes(&self, theme_css: &str, variant: &FieldVariant) -> String {
         match (&variant.override_class, &variant.extend) {
             (Some(override_css), None) => override_css.clone(),
@@ -226,8 +2048,11 @@ impl SchemaRegistry {
             .unwrap_or_default()
     }
 
-    // Generate final HTML element
-    fn generate_html(
+    // Generate final HTML element. Every renderer ends here, so this is where
+    // attributes are merged: the generated classes and a `class` attr are
+    // joined without duplicates (first occurrence kept), and every other name
+    // is emitted once (case-insensitively) in name order, so output is stable.
+    pub(crate) fn generate_html(
         tag: &str,
         css_classes: &str,
         attrs: &HashMap<String, String>,
@@ -235,17 +2060,31 @@ impl SchemaRegistry {
     ) -> String {
         let mut html = format!("<{}", tag);
 
-        // Add CSS classes
-        if !css_classes.is_empty() {
-            html.push_str(&format!(" class=\"{}\"", css_classes));
+        let mut classes: Vec<&str> = Vec::new();
+        let class_attrs = attrs
+            .iter()
+            .filter(|(key, _)| key.eq_ignore_ascii_case("class"))
+            .map(|(_, classes)| classes.as_str());
+        for class in std::iter::once(css_classes)
+            .chain(class_attrs)
+            .flat_map(str::split_whitespace)
+        {
+            if !classes.contains(&class) {
+                classes.push(class);
+            }
+        }
+        if !classes.is_empty() {
+            html.push_str(&format!(" class=\"{}\"", classes.join(" ")));
         }
 
-        // Add other attributes
-        for (key, attr_value) in attrs {
-            if key != "class" {
-                // Don't duplicate class
-                html.push_str(&format!(" {}=\"{}\"", key, attr_value));
-            }
+        let mut names: Vec<&String> = attrs
+            .keys()
+            .filter(|key| !key.eq_ignore_ascii_case("class"))
+            .collect();
+        names.sort_by_key(|key| (key.to_ascii_lowercase(), key.as_str()));
+        names.dedup_by(|later, earlier| later.eq_ignore_ascii_case(earlier));
+        for key in names {
+            html.push_str(&format!(" {}=\"{}\"", key, attrs[key]));
         }
 
         // Handle self-closing tags vs content tags
@@ -274,20 +2113,570 @@ impl Default for SchemaRegistry {
             },
             tables: HashMap::new(),
             current_theme: "light".to_string(),
+            field_memo: FieldMemo::new(config().field_memo_capacity),
+            compiled_components: Vec::new(),
+            tenants: HashMap::new(),
+            tenant_registries: TenantRegistries::default(),
+            context_cache: ContextCache::default(),
         }
     }
 }
 
-use std::sync::OnceLock;
-static REGISTRY: OnceLock<SchemaRegistry> = OnceLock::new();
-
-pub fn registry() -> &'static SchemaRegistry {
-    REGISTRY.get_or_init(SchemaRegistry::load_all)
+// `name` with its `extends` chain applied; `chain` holds the variants being
+// resolved, to catch cycles
+pub(crate) fn resolve_variant(
+    variants: &HashMap<String, FieldVariant>,
+    name: &str,
+    chain: &mut Vec<String>,
+) -> Result<FieldVariant, String> {
+    let variant = variants
+        .get(name)
+        .ok_or_else(|| format!("extends unknown variant '{}'", name))?;
+    let Some(parent) = &variant.extends else {
+        return Ok(variant.clone());
+    };
+    chain.push(name.to_string());
+    if chain.contains(parent) {
+        return Err(format!(
+            "extends cycle: {} -> {}",
+            chain.join(" -> "),
+            parent
+        ));
+    }
+    let parent = resolve_variant(variants, parent, chain)?;
+    Ok(variant.clone().inherit(parent))
 }
 
-// Helper function to get a mutable registry for theme switching
-pub fn with_registry_mut<F, R>(f: F) -> R
-where
+impl FieldVariant {
+    // 🧱 A variant rendering as `<base>`, for schemas built in code (see
+    // schema_builder.rs); the setters below fill in the rest
+    pub fn tag(base: &str) -> Self {
+        Self {
+            base: base.to_string(),
+            ..Self::default()
+        }
+    }
+
+    // Replace the theme's classes for the tag
+    pub fn override_class(mut self, classes: &str) -> Self {
+        self.override_class = Some(classes.to_string());
+        self
+    }
+
+    // Add classes after the theme's (or the override's)
+    pub fn extend_class(mut self, classes: &str) -> Self {
+        self.extend = Some(classes.to_string());
+        self
+    }
+
+    // Set an attribute; `{value}` and `{field}` are substituted at render time
+    pub fn attr(mut self, name: &str, value: &str) -> Self {
+        self.attrs
+            .get_or_insert_with(HashMap::new)
+            .insert(name.to_string(), value.to_string());
+        self
+    }
+
+    pub fn kind(mut self, kind: &str) -> Self {
+        self.kind = Some(kind.to_string());
+        self
+    }
+
+    pub fn extends(mut self, variant: &str) -> Self {
+        self.extends = Some(variant.to_string());
+        self
+    }
+
+    // This variant with everything it leaves unset taken from `parent`
+    fn inherit(self, parent: FieldVariant) -> FieldVariant {
+        type Map = Option<HashMap<String, String>>;
+        let merge = |parent: Map, own: Map| match (parent, own) {
+            (Some(mut parent), Some(own)) => {
+                parent.extend(own);
+                Some(parent)
+            }
+            (parent, own) => own.or(parent),
+        };
+        FieldVariant {
+            base: if self.base.is_empty() {
+                parent.base
+            } else {
+                self.base
+            },
+            extends: self.extends,
+            override_class: self.override_class.or(parent.override_class),
+            extend: self.extend.or(parent.extend),
+            attrs: merge(parent.attrs, self.attrs),
+            kind: self.kind.or(parent.kind),
+            options: merge(parent.options, self.options),
+            max_length: self.max_length.or(parent.max_length),
+            overflow: self.overflow.or(parent.overflow),
+            field_type: self.field_type.or(parent.field_type),
+            // Extending a deprecated variant is how teams migrate off it
+            deprecated: self.deprecated,
+        }
+    }
+}
+
+// Shared definitions pulled into tables by `include`; may include further files
+#[derive(Debug, Default, Deserialize)]
+struct SchemaInclude {
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    variants: HashMap<String, HashMap<String, FieldVariant>>,
+    #[serde(default)]
+    defaults: HashMap<String, String>,
+    #[serde(default)]
+    contexts: HashMap<String, Context>,
+}
+
+// Parse a schema (or include) file in the format its extension names
+fn parse_schema_file<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, SchemaError> {
+    parse_schema_text(path, &FileSource::Disk.read(path)?)
+}
+
+fn parse_schema_text<T: serde::de::DeserializeOwned>(
+    path: &Path,
+    content: &str,
+) -> Result<T, SchemaError> {
+    let parsed = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => serde_json::from_str(content).map_err(|e| e.to_string()),
+        Some("yaml" | "yml") => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+        _ => toml::from_str(content).map_err(|e| e.to_string()),
+    };
+    parsed.map_err(|e| SchemaError::Parse(format!("{}: {}", path.display(), e)))
+}
+
+// Where schema files and their includes are read from
+#[derive(Debug, Clone, Copy)]
+enum FileSource<'a> {
+    Disk,
+    // (path relative to the schema dir, content), as build.rs embeds them
+    Embedded(&'a [(&'a str, &'a str)]),
+}
+
+impl FileSource<'_> {
+    fn read(self, path: &Path) -> Result<String, SchemaError> {
+        let content = match self {
+            FileSource::Disk => std::fs::read_to_string(path),
+            FileSource::Embedded(files) => self.identity(path).and_then(|key| {
+                let found = files.iter().find(|(file, _)| Path::new(file) == key);
+                found
+                    .map(|(_, content)| content.to_string())
+                    .ok_or_else(|| std::io::ErrorKind::NotFound.into())
+            }),
+        };
+        content.map_err(|e| SchemaError::Io(format!("{}: {}", path.display(), e)))
+    }
+
+    // The same file reached through any path: canonical on disk; for
+    // embedded files the path with `.` and `..` resolved, if it is embedded
+    fn identity(self, path: &Path) -> std::io::Result<PathBuf> {
+        let FileSource::Embedded(files) = self else {
+            return path.canonicalize();
+        };
+        let mut resolved = PathBuf::new();
+        for part in path.components() {
+            match part {
+                std::path::Component::Normal(name) => resolved.push(name),
+                std::path::Component::CurDir => {}
+                std::path::Component::ParentDir if resolved.pop() => {}
+                _ => return Err(std::io::ErrorKind::NotFound.into()),
+            }
+        }
+        match files.iter().any(|(file, _)| Path::new(file) == resolved) {
+            true => Ok(resolved),
+            false => Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "not embedded",
+            )),
+        }
+    }
+}
+
+// 📎 Load the files `from` includes (and theirs), in merge order: earlier
+// files win over later ones. `chain` holds the (canonical) files being
+// resolved, to catch cycles.
+fn resolve_includes(
+    source: FileSource,
+    from: &Path,
+    includes: &[String],
+    chain: &mut Vec<PathBuf>,
+) -> Result<Vec<SchemaInclude>, SchemaError> {
+    let dir = from.parent().unwrap_or(Path::new("."));
+    let mut resolved = Vec::new();
+    for include in includes {
+        let path = dir.join(include);
+        let canonical = source
+            .identity(&path)
+            .map_err(|e| SchemaError::Io(format!("{}: {}: {}", from.display(), include, e)))?;
+        if chain.contains(&canonical) {
+            return Err(SchemaError::Parse(format!(
+                "{}: include cycle through {}",
+                from.display(),
+                include
+            )));
+        }
+
+        let shared: SchemaInclude = parse_schema_text(&path, &source.read(&path)?)?;
+        chain.push(canonical);
+        let nested = resolve_includes(source, &path, &shared.include, chain)?;
+        chain.pop();
+        // Depth first: a file's own definitions win over the files it includes
+        resolved.push(shared);
+        resolved.extend(nested);
+    }
+    Ok(resolved)
+}
+
+// Merge `overlay` into `base`: objects key by key, anything else replaced
+fn deep_merge(base: &mut serde_json::Value, overlay: serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => deep_merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+// Schema file formats, in order of preference
+const SCHEMA_EXTENSIONS: [&str; 4] = ["toml", "json", "yaml", "yml"];
+
+// `<table_dir>/<table>.<environment>.toml` (or `.json`/`.yaml`), if present
+pub fn overlay_file(table_dir: &Path, table: &str, environment: &str) -> Option<PathBuf> {
+    SCHEMA_EXTENSIONS
+        .iter()
+        .map(|ext| table_dir.join(format!("{}.{}.{}", table, environment, ext)))
+        .find(|path| path.is_file())
+}
+
+// Table name, schema file and the overlays layered over it
+type SchemaFiles = Vec<(String, PathBuf, Vec<PathBuf>)>;
+
+// Directory of a schema dir holding one overlay directory per tenant
+const TENANTS_DIR: &str = "tenants";
+
+// Per tenant in `dir`, its overlays layered over the base `files`; overlays
+// of tables `files` lacks are reported
+fn tenant_schema_files(
+    dir: &Path,
+    files: &SchemaFiles,
+) -> (Vec<(String, SchemaFiles)>, Vec<SchemaError>) {
+    let mut tenants = Vec::new();
+    let mut errors = Vec::new();
+    let tenant_dirs = std::fs::read_dir(dir).into_iter().flatten().flatten();
+    for tenant_dir in tenant_dirs
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+    {
+        let Some(tenant) = tenant_dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+        else {
+            continue;
+        };
+        let mut tenant_files = Vec::new();
+        for path in std::fs::read_dir(&tenant_dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+        {
+            let path = path.path();
+            let extension = path.extension().map(|ext| ext.to_string_lossy());
+            if !extension.is_some_and(|ext| SCHEMA_EXTENSIONS.contains(&ext.as_ref())) {
+                continue;
+            }
+            let table = path.file_stem().map(|n| n.to_string_lossy().to_string());
+            match files.iter().find(|(name, ..)| Some(name) == table.as_ref()) {
+                Some((table, base, overlays)) => {
+                    let mut overlays = overlays.clone();
+                    overlays.push(path);
+                    tenant_files.push((table.clone(), base.clone(), overlays));
+                }
+                None => errors.push(SchemaError::Parse(format!(
+                    "{}: overlays a table without a base schema",
+                    path.display()
+                ))),
+            }
+        }
+        tenant_files.sort();
+        tenants.push((tenant, tenant_files));
+    }
+    tenants.sort();
+    (tenants, errors)
+}
+
+// 🧵 Parse schema files on scoped worker threads, keeping input order
+fn parse_schema_files(files: SchemaFiles) -> Vec<(String, Result<TableSchema, SchemaError>)> {
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(files.len());
+    if workers <= 1 {
+        return files
+            .into_iter()
+            .map(|(table, path, overlays)| {
+                let result = TableSchema::from_layered_files(&path, &overlays);
+                (table, result)
+            })
+            .collect();
+    }
+
+    let chunk_size = files.len().div_ceil(workers);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = files
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|(table, path, overlays)| {
+                            let result = TableSchema::from_layered_files(path, overlays);
+                            (table.clone(), result)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("schema parser thread panicked"))
+            .collect()
+    })
+}
+
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+static REGISTRY: OnceLock<RwLock<Arc<SchemaRegistry>>> = OnceLock::new();
+
+fn registry_cell() -> &'static RwLock<Arc<SchemaRegistry>> {
+    REGISTRY.get_or_init(|| RwLock::new(Arc::new(SchemaRegistry::load_all())))
+}
+
+// Snapshot of the active registry. Cheap to clone; a snapshot is never
+// affected by later swaps, so one render always sees one consistent schema set.
+pub fn registry() -> Arc<SchemaRegistry> {
+    registry_cell()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+// Atomically replace the active registry, returning the previous one
+pub fn swap_registry(new_registry: SchemaRegistry) -> Arc<SchemaRegistry> {
+    let mut active = registry_cell()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    std::mem::replace(&mut *active, Arc::new(new_registry))
+}
+
+// Register a table in the active registry from any thread (see
+// SchemaRegistry::register_table). Renders of a replaced table are dropped
+// from the component cache.
+pub fn register_table(table: &str, schema: TableSchema) -> Result<(), SchemaError> {
+    let replaced = update_registry(|registry| {
+        let replaced = registry.get_table(table).is_some();
+        registry.register_table(table, schema.clone())?;
+        record_change(RuntimeChange::Register(table.to_string(), schema));
+        Ok::<_, SchemaError>(replaced)
+    })?;
+    if replaced {
+        crate::component_registry::component_registry()
+            .cache()
+            .clear();
+    }
+    Ok(())
+}
+
+// Replace a table's schema in the active registry (see
+// SchemaRegistry::replace_table); its cached renders are dropped
+pub fn replace_table(table: &str, schema: TableSchema) -> Result<TableSchema, SchemaError> {
+    let previous = update_registry(|registry| {
+        let previous = registry.replace_table(table, schema.clone())?;
+        record_change(RuntimeChange::Replace(table.to_string(), schema));
+        Ok::<_, SchemaError>(previous)
+    })?;
+    crate::component_registry::component_registry()
+        .cache()
+        .clear();
+    Ok(previous)
+}
+
+// Patch a context entry of the active registry (see
+// SchemaRegistry::patch_context); cached renders are dropped
+pub fn patch_context(
+    table: &str,
+    context: &str,
+    field: &str,
+    variant: &str,
+) -> Result<Option<String>, RenderError> {
+    let previous = update_registry(|registry| {
+        let previous = registry.patch_context(table, context, field, variant)?;
+        record_change(RuntimeChange::Patch {
+            table: table.to_string(),
+            context: context.to_string(),
+            field: field.to_string(),
+            variant: variant.to_string(),
+        });
+        Ok::<_, RenderError>(previous)
+    })?;
+    crate::component_registry::component_registry()
+        .cache()
+        .clear();
+    Ok(previous)
+}
+
+// Retire a table from the active registry without a restart (see
+// SchemaRegistry::remove_table); its cached renders are dropped
+pub fn remove_table(table: &str) -> Result<TableSchema, SchemaError> {
+    let removed = update_registry(|registry| {
+        let removed = registry.remove_table(table)?;
+        record_change(RuntimeChange::Remove(table.to_string()));
+        Ok::<_, SchemaError>(removed)
+    })?;
+    crate::component_registry::component_registry()
+        .cache()
+        .clear();
+    Ok(removed)
+}
+
+// A change made through the functions above instead of schema files. They are
+// kept so a registry rebuilt from files (hot reload) can get them back.
+#[derive(Debug, Clone)]
+enum RuntimeChange {
+    Register(String, TableSchema),
+    Replace(String, TableSchema),
+    Patch {
+        table: String,
+        context: String,
+        field: String,
+        variant: String,
+    },
+    Remove(String),
+}
+
+impl RuntimeChange {
+    fn table(&self) -> &str {
+        match self {
+            RuntimeChange::Register(table, _)
+            | RuntimeChange::Replace(table, _)
+            | RuntimeChange::Remove(table)
+            | RuntimeChange::Patch { table, .. } => table,
+        }
+    }
+
+    fn apply(&self, registry: &mut SchemaRegistry) -> Result<(), String> {
+        match self {
+            RuntimeChange::Register(table, schema) => registry
+                .register_table(table, schema.clone())
+                .map_err(|e| format!("register_table('{}'): {}", table, e)),
+            RuntimeChange::Replace(table, schema) => registry
+                .replace_table(table, schema.clone())
+                .map(drop)
+                .map_err(|e| format!("replace_table('{}'): {}", table, e)),
+            RuntimeChange::Patch {
+                table,
+                context,
+                field,
+                variant,
+            } => registry
+                .patch_context(table, context, field, variant)
+                .map(drop)
+                .map_err(|e| {
+                    format!(
+                        "patch_context('{}', '{}', '{}'): {}",
+                        table, context, field, e
+                    )
+                }),
+            // Already gone from the files is as good as removed
+            RuntimeChange::Remove(table) if registry.get_table(table).is_none() => Ok(()),
+            RuntimeChange::Remove(table) => registry
+                .remove_table(table)
+                .map(drop)
+                .map_err(|e| format!("remove_table('{}'): {}", table, e)),
+        }
+    }
+}
+
+static RUNTIME_CHANGES: Mutex<Vec<RuntimeChange>> = Mutex::new(Vec::new());
+
+// Called with the registry write lock held, so the log has the order the
+// changes were applied in. A table-wide change supersedes earlier changes to
+// that table and a patch an earlier patch of the same entry, so the log stays
+// as small as the set of overrides.
+fn record_change(change: RuntimeChange) {
+    let mut changes = RUNTIME_CHANGES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    changes.retain(|earlier| {
+        if earlier.table() != change.table() {
+            return true;
+        }
+        match (earlier, &change) {
+            (
+                RuntimeChange::Patch { context, field, .. },
+                RuntimeChange::Patch {
+                    context: next_context,
+                    field: next_field,
+                    ..
+                },
+            ) => context != next_context || field != next_field,
+            (_, RuntimeChange::Patch { .. }) => true,
+            _ => false,
+        }
+    });
+    changes.push(change);
+}
+
+// 🔄 Swap in a registry rebuilt from files (see hot_reload), re-applying the
+// runtime changes made so far (register_table, replace_table, patch_context,
+// remove_table) so a reload doesn't undo them. Changes that no longer apply,
+// e.g. a patch of a context the files dropped, are forgotten; their errors are
+// returned for the caller to report.
+pub fn swap_reloaded_registry(mut next: SchemaRegistry) -> Vec<String> {
+    let mut active = registry_cell()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut changes = RUNTIME_CHANGES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut dropped = Vec::new();
+    changes.retain(|change| match change.apply(&mut next) {
+        Ok(()) => true,
+        Err(e) => {
+            dropped.push(e);
+            false
+        }
+    });
+    *active = Arc::new(next);
+    dropped
+}
+
+// Apply a change to a copy of the active registry and swap it in if `f`
+// succeeds. Concurrent updates are serialized, so none of them is lost.
+pub fn update_registry<F, R, E>(f: F) -> Result<R, E>
+where
+    F: FnOnce(&mut SchemaRegistry) -> Result<R, E>,
+{
+    let mut active = registry_cell()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut next = (**active).clone();
+    let result = f(&mut next)?;
+    *active = Arc::new(next);
+    Ok(result)
+}
+
+// Helper function to get a mutable registry for theme switching
+pub fn with_registry_mut<F, R>(f: F) -> R
+where
     F: FnOnce(&mut SchemaRegistry) -> R,
 {
     // Note: This is a simplified approach. In production, you'd want
@@ -295,3 +2684,1239 @@ where
     let mut registry = SchemaRegistry::load_all();
     f(&mut registry)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // (name, value) pairs of the opening tag of generated markup
+    fn opening_tag_attrs(html: &str) -> Vec<(String, String)> {
+        let tag = &html[..html.find('>').unwrap()];
+        let mut attrs = Vec::new();
+        let mut rest = tag.split_once(' ').map_or("", |(_, rest)| rest);
+        while let Some((name, after)) = rest.split_once("=\"") {
+            let (value, after) = after.split_once('"').unwrap();
+            attrs.push((name.trim().to_string(), value.to_string()));
+            rest = after;
+        }
+        attrs
+    }
+
+    proptest! {
+        #[test]
+        fn prop_generate_html_merges_attributes(
+            css_classes in "( ?[a-c]{1,2} ?){0,6}",
+            class_attr in proptest::option::of("([a-c]{1,2} ){0,4}"),
+            variant in proptest::collection::hash_map("[a-dA-D]{1,2}", "[a-z0-9 ]{0,4}", 0..6),
+            generated in proptest::collection::hash_map("[a-dA-D]{1,2}", "[a-z0-9]{0,4}", 0..6),
+        ) {
+            let mut attrs = variant.clone();
+            if let Some(classes) = &class_attr {
+                attrs.insert("class".to_string(), classes.clone());
+            }
+            for (name, value) in &generated {
+                insert_generated(&mut attrs, name, value.clone());
+            }
+            let html = SchemaRegistry::generate_html("div", &css_classes, &attrs, "x");
+            let emitted = opening_tag_attrs(&html);
+
+            // Each name once, compared case-insensitively
+            let mut names: Vec<_> = emitted.iter().map(|(n, _)| n.to_lowercase()).collect();
+            let total = names.len();
+            names.sort();
+            names.dedup();
+            prop_assert_eq!(names.len(), total);
+
+            // Classes: no duplicates, nothing lost
+            let classes: Vec<&str> = emitted
+                .iter()
+                .find(|(name, _)| name == "class")
+                .map(|(_, value)| value.split(' ').collect())
+                .unwrap_or_default();
+            let mut unique = classes.clone();
+            unique.sort();
+            unique.dedup();
+            prop_assert_eq!(unique.len(), classes.len());
+            let attr_classes = class_attr.iter().flat_map(|c| c.split_whitespace());
+            for class in css_classes.split_whitespace().chain(attr_classes) {
+                prop_assert!(classes.contains(&class));
+            }
+
+            // Variant attrs beat generated ones of the same name
+            for (name, value) in &variant {
+                if name.eq_ignore_ascii_case("class") {
+                    continue;
+                }
+                let shown = emitted.iter().find(|(n, _)| n.eq_ignore_ascii_case(name));
+                // Of names differing only in case, the first in sort order is kept
+                let shadowed = variant
+                    .keys()
+                    .any(|other| other < name && other.eq_ignore_ascii_case(name));
+                if !shadowed {
+                    prop_assert_eq!(shown.map(|(_, v)| v), Some(value));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_field_strict_names_what_is_missing() {
+        let registry = SchemaRegistry::load_embedded();
+        let html = registry
+            .render_field_strict("users", "name", "card", "Ada")
+            .unwrap();
+        assert_eq!(
+            Some(html),
+            registry.render_field("users", "name", "card", "Ada")
+        );
+
+        let strict = |table, field, context| {
+            registry
+                .render_field_strict(table, field, context, "x")
+                .unwrap_err()
+        };
+        let table = || "users".to_string();
+        assert_eq!(
+            strict("nowhere", "name", "card"),
+            RenderError::UnknownTable("nowhere".into())
+        );
+        assert_eq!(
+            strict("users", "shoe_size", "card"),
+            RenderError::UnknownField(table(), "shoe_size".into())
+        );
+        assert_eq!(
+            strict("users", "name", "poster"),
+            RenderError::UnknownContext(table(), "poster".into())
+        );
+        // The lenient API still renders these
+        assert!(
+            registry
+                .render_field("users", "shoe_size", "card", "x")
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_load_from_dir_reports_every_broken_file() {
+        let dir = std::env::temp_dir().join(format!("uuie_load_{}", std::process::id()));
+        let users = include_str!("../schemas/users/users.toml");
+        for (table, content) in [
+            ("accounts", users),
+            ("orders", users),
+            ("broken_a", "variants = ["),
+            ("broken_b", "contexts = 1"),
+        ] {
+            std::fs::create_dir_all(dir.join(table)).unwrap();
+            std::fs::write(dir.join(table).join(format!("{}.toml", table)), content).unwrap();
+        }
+
+        let Err(SchemaError::Multiple(errors)) = SchemaRegistry::load_from_dir(&dir) else {
+            panic!("expected aggregated errors");
+        };
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].to_string().contains("broken_a.toml"));
+        assert!(errors[1].to_string().contains("broken_b.toml"));
+
+        for table in ["broken_a", "broken_b"] {
+            std::fs::remove_dir_all(dir.join(table)).unwrap();
+        }
+        let registry = SchemaRegistry::load_from_dir(&dir).unwrap();
+        assert_eq!(registry.list_tables().len(), 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_from_dir_accepts_json_schemas() {
+        let dir = std::env::temp_dir().join(format!("uuie_json_{}", std::process::id()));
+        let users =
+            TableSchema::from_toml_str(include_str!("../schemas/users/users.toml")).unwrap();
+        std::fs::create_dir_all(dir.join("members")).unwrap();
+        std::fs::write(
+            dir.join("members/members.json"),
+            serde_json::to_string_pretty(&users).unwrap(),
+        )
+        .unwrap();
+
+        let registry = SchemaRegistry::load_from_dir(&dir).unwrap();
+        let members = registry.get_table("members").unwrap();
+        assert_eq!(members.source.as_deref(), Some("members.json"));
+        assert_eq!(
+            registry.render_field("members", "email", "card", "a@b.c"),
+            SchemaRegistry::load_embedded().render_field("users", "email", "card", "a@b.c")
+        );
+
+        std::fs::write(dir.join("members/members.json"), "{\"variants\": 1}").unwrap();
+        let err = SchemaRegistry::load_from_dir(&dir).unwrap_err();
+        assert!(err.to_string().contains("members.json"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_embedded_schemas_validate_cleanly() {
+        let mut registry = SchemaRegistry::load_embedded();
+        assert_eq!(registry.validate(), vec![]);
+
+        let card = registry
+            .get_table_mut("users")
+            .unwrap()
+            .contexts
+            .get_mut("card")
+            .unwrap();
+        card.fields.insert("name".to_string(), "h7".to_string());
+        let diagnostics = registry.validate();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].path, "contexts.card.name");
+    }
+
+    #[test]
+    fn test_render_drops_attributes_the_policy_denies() {
+        let mut registry = SchemaRegistry::load_embedded();
+        let link = registry
+            .get_table_mut("users")
+            .unwrap()
+            .variants
+            .get_mut("email")
+            .unwrap();
+        let attrs = link
+            .get_mut("link")
+            .unwrap()
+            .attrs
+            .get_or_insert_with(HashMap::new);
+        attrs.insert("onmouseover".to_string(), "steal()".to_string());
+
+        let html = registry
+            .render_field("users", "email", "list", "a@b.c")
+            .unwrap();
+        assert!(html.contains(r#"href="mailto:a@b.c""#), "{}", html);
+        assert!(!html.contains("onmouseover"), "{}", html);
+    }
+
+    #[test]
+    fn test_typed_fields_get_default_markup() {
+        let registry = SchemaRegistry::load_embedded();
+        let html = registry
+            .render_field("users", "created_at", "card", "2024-01-15T10:30:00Z")
+            .unwrap();
+        let expected = r#"datetime="2024-01-15T10:30:00Z">Jan 15, 2024</time>"#;
+        assert!(html.ends_with(expected), "{}", html);
+        let html = registry
+            .render_field("users", "created_at", "card", "unknown")
+            .unwrap();
+        assert!(html.ends_with(">unknown</time>"), "{}", html);
+    }
+
+    #[test]
+    fn test_platform_variants_specialize_the_generic() {
+        let schema = TableSchema::from_toml_str(
+            r#"
+            [variants.name]
+            heading = { base = "h1", override = "text-3xl", attrs = { title = "{value}" } }
+            "heading@mobile" = { override = "text-xl" }
+            "heading@email" = { base = "strong" }
+
+            [contexts.card]
+            name = "heading"
+            "#,
+        )
+        .unwrap();
+        assert!(crate::lint::lint_table("posts", &schema).is_empty());
+        let mut registry = SchemaRegistry::load_embedded();
+        registry.insert_table("posts", schema);
+        let render = |platform| {
+            let options = FieldRenderOptions {
+                platform,
+                ..Default::default()
+            };
+            registry
+                .render_field_with("posts", "name", "card", "Hi", options)
+                .unwrap()
+        };
+
+        let generic = render(None);
+        assert!(
+            generic.starts_with("<h1") && generic.contains("text-3xl"),
+            "{}",
+            generic
+        );
+        assert_eq!(render(Some("web")), generic);
+        let mobile = render(Some("mobile"));
+        assert!(
+            mobile.starts_with("<h1") && mobile.contains("text-xl"),
+            "{}",
+            mobile
+        );
+        assert!(mobile.contains(r#"title="Hi""#), "{}", mobile);
+        let email = render(Some("email"));
+        assert!(
+            email.starts_with("<strong") && email.contains("text-3xl"),
+            "{}",
+            email
+        );
+    }
+
+    #[test]
+    fn test_locales_override_the_context_variant() {
+        let schema = TableSchema::from_toml_str(
+            r#"
+            [variants.title]
+            large = { base = "h1" }
+            small = { base = "h3" }
+            alert = { base = "strong" }
+
+            [contexts.card]
+            title = "large"
+
+            [value_variants]
+            title = { Achtung = "alert" }
+
+            [locales.de.card]
+            title = "small"
+            "#,
+        )
+        .unwrap();
+        assert!(crate::validation::validate_table("articles", &schema).is_empty());
+        let mut registry = SchemaRegistry::load_embedded();
+        registry.insert_table("articles", schema);
+        let render = |lang, value| {
+            let options = FieldRenderOptions {
+                lang,
+                ..Default::default()
+            };
+            registry
+                .render_field_with("articles", "title", "card", value, options)
+                .unwrap()
+        };
+
+        assert!(render(None, "Hallo").starts_with("<h1"));
+        assert!(render(Some("en"), "Hello").starts_with("<h1"));
+        assert!(render(Some("de"), "Hallo").starts_with("<h3"));
+        assert!(render(Some("DE-at"), "Hallo").starts_with("<h3"));
+        assert!(render(Some("de"), "Achtung").starts_with("<strong"));
+
+        let debug = FieldRenderOptions {
+            debug: true,
+            lang: Some("de-DE"),
+            ..Default::default()
+        };
+        let html = registry
+            .render_field_with("articles", "title", "card", "Hallo", debug)
+            .unwrap();
+        assert!(
+            html.contains(r#"data-sui-context="(locale de)""#),
+            "{}",
+            html
+        );
+    }
+
+    #[test]
+    fn test_rules_become_input_attributes() {
+        let schema = TableSchema::from_toml_str(
+            r#"
+            [variants.email]
+            field = { base = "input", attrs = { type = "email", value = "{value}" } }
+            shown = { base = "span" }
+            [variants.bio]
+            field = { base = "textarea" }
+
+            [contexts.form]
+            email = "field"
+            bio = "field"
+            [contexts.card]
+            email = "shown"
+
+            [rules]
+            email = { required = true, max_length = 120, pattern = "[^@]+@[^@]+" }
+            bio = { min_length = 10, max_length = 5, pattern = "x" }
+            "#,
+        )
+        .unwrap();
+        let found: Vec<_> = crate::validation::validate_table("signups", &schema)
+            .iter()
+            .map(|d| d.to_string())
+            .collect();
+        assert_eq!(
+            found,
+            ["error: signups: rules.bio: min_length 10 exceeds max_length 5"]
+        );
+        let mut registry = SchemaRegistry::load_embedded();
+        registry.insert_table("signups", schema);
+
+        let input = registry
+            .render_field("signups", "email", "form", "a@b.c")
+            .unwrap();
+        let expected = r#" maxlength="120" pattern="[^@]+@[^@]+" required="required" type="email""#;
+        assert!(
+            input.ends_with(&format!(r#"{} value="a@b.c" />"#, expected)),
+            "{}",
+            input
+        );
+        let textarea = registry
+            .render_field("signups", "bio", "form", "Hi")
+            .unwrap();
+        assert!(
+            textarea.starts_with(r#"<textarea maxlength="5" minlength="10">"#),
+            "{}",
+            textarea
+        );
+        let shown = registry
+            .render_field("signups", "email", "card", "a@b.c")
+            .unwrap();
+        assert!(!shown.contains("required"), "{}", shown);
+    }
+
+    #[test]
+    fn test_variant_extends_inherits_and_overrides() {
+        let schema = TableSchema::from_toml_str(
+            r#"
+            [variants.email]
+            link = { base = "a", extend = "underline", attrs = { href = "mailto:x", rel = "me" } }
+            muted = { extends = "link", override = "text-gray-500", attrs = { rel = "nofollow" } }
+            tiny = { extends = "muted", base = "small" }
+
+            [contexts.card]
+            email = "tiny"
+            "#,
+        )
+        .unwrap();
+        let tiny = &schema.variants["email"]["tiny"];
+        assert_eq!(tiny.base, "small");
+        assert_eq!(tiny.extend.as_deref(), Some("underline"));
+        assert_eq!(tiny.override_class.as_deref(), Some("text-gray-500"));
+        let attrs = tiny.attrs.as_ref().unwrap();
+        assert_eq!(
+            (attrs["href"].as_str(), attrs["rel"].as_str()),
+            ("mailto:x", "nofollow")
+        );
+        assert_eq!(
+            schema.variants["email"]["link"].attrs.as_ref().unwrap()["rel"],
+            "me"
+        );
+
+        let err = TableSchema::from_toml_str(
+            "[variants.name]\na = { extends = \"b\" }\nb = { extends = \"a\" }\n\
+             [contexts.card]\nname = \"a\"\n",
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("extends cycle: a -> b -> a"),
+            "{}",
+            err
+        );
+        let err = TableSchema::from_toml_str(
+            "[variants.name]\na = { extends = \"h9\" }\n[contexts.card]\nname = \"a\"\n",
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("variants.name.a: extends unknown variant 'h9'")
+        );
+    }
+
+    #[test]
+    fn test_value_variants_pick_by_value() {
+        let toml = r#"
+            [variants.status]
+            plain = { base = "span" }
+            badge_green = { base = "span", override = "badge-green" }
+            badge_red = { base = "span", override = "badge-red" }
+
+            [value_variants]
+            status = { active = "badge_green", banned = "badge_red" }
+
+            [contexts.card]
+            status = "plain"
+            "#;
+        let mut registry = SchemaRegistry::new();
+        registry.insert_table("accounts", TableSchema::from_toml_str(toml).unwrap());
+        let render = |value| {
+            registry
+                .render_field("accounts", "status", "card", value)
+                .unwrap()
+        };
+        assert_eq!(
+            render("active"),
+            r#"<span class="badge-green">active</span>"#
+        );
+        assert_eq!(render("banned"), r#"<span class="badge-red">banned</span>"#);
+        assert_eq!(render("pending"), "<span>pending</span>");
+
+        let typo = toml.replace(r#"banned = "badge_red""#, r#"banned = "badge_rd""#);
+        let diagnostics = validate_table("accounts", &TableSchema::from_toml_str(&typo).unwrap());
+        assert!(
+            diagnostics
+                .iter()
+                .any(|d| d.to_string().contains("value_variants.status.banned")),
+            "{:?}",
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn test_wildcard_context_entries() {
+        let mut registry = SchemaRegistry::load_embedded();
+        let schema = TableSchema::from_toml_str(
+            r#"
+            [variants.name]
+            h2 = { base = "h2" }
+            plain = { base = "span" }
+
+            [variants.bio]
+            p = { base = "p" }
+
+            [contexts.card]
+            name = "h2"
+            "*" = "plain"
+
+            [contexts.list]
+            inherits = "card"
+            "#,
+        )
+        .unwrap();
+        registry.insert_table("people", schema);
+        assert!(validate_table("people", registry.get_table("people").unwrap()).is_empty());
+
+        let render = |field, context| {
+            registry
+                .render_field("people", field, context, "x")
+                .unwrap()
+        };
+        // Listed fields keep their entry; fields with the variant take it
+        assert!(render("name", "list").starts_with("<h2"));
+        assert!(render("bio", "list").starts_with("<p"));
+        // Undeclared columns use the `_default` variant the entry names
+        let options = FieldRenderOptions::default();
+        let fallback = registry.render_variant_with(DEFAULT_TABLE, "*", "span", "x", options);
+        assert_eq!(Some(render("nickname", "card")), fallback);
+
+        let people = registry.get_table_mut("people").unwrap();
+        let own = people.variants["name"]["h2"].clone();
+        people
+            .variants
+            .insert("*".to_string(), HashMap::from([("plain".to_string(), own)]));
+        let own = registry
+            .render_field("people", "nickname", "list", "x")
+            .unwrap();
+        assert!(own.starts_with("<h2"), "{}", own);
+    }
+
+    #[test]
+    fn test_context_fallback_chains() {
+        let mut registry = SchemaRegistry::load_embedded();
+        let schema = TableSchema::from_toml_str(
+            r#"
+            [variants.name]
+            h2 = { base = "h2" }
+            plain = { base = "span" }
+
+            [variants.bio]
+            p = { base = "p" }
+            small = { base = "small" }
+
+            [defaults]
+            name = "plain"
+            bio = "p"
+
+            [contexts.card]
+            name = "h2"
+            bio = "small"
+
+            [contexts.list]
+            name = "plain"
+
+            [fallbacks]
+            compact = "list"
+            list = "card"
+            loop = "other"
+            other = "loop"
+            "#,
+        )
+        .unwrap();
+        let mut found: Vec<_> = validate_table("people", &schema)
+            .iter()
+            .map(|d| d.to_string())
+            .collect();
+        found.sort();
+        assert_eq!(
+            found,
+            [
+                "error: people: fallbacks.loop: fallback cycle: loop -> other -> loop",
+                "error: people: fallbacks.other: fallback cycle: other -> loop -> other",
+            ]
+        );
+        registry.insert_table("people", schema);
+
+        let render = |field, context| {
+            registry
+                .render_field("people", field, context, "x")
+                .unwrap()
+        };
+        // compact is unknown, list names `name`, card names `bio`
+        assert!(render("name", "compact").starts_with("<span"));
+        assert!(render("bio", "compact").starts_with("<small"));
+        assert!(render("bio", "loop").starts_with("<p"));
+        assert!(
+            registry
+                .render_field_strict("people", "bio", "compact", "x")
+                .is_ok()
+        );
+
+        let debug = FieldRenderOptions {
+            debug: true,
+            ..Default::default()
+        };
+        let levels = [("compact", "card"), ("loop", "(defaults)")];
+        for (context, level) in levels {
+            let html = registry
+                .render_field_with("people", "bio", context, "x", debug)
+                .unwrap();
+            let attr = format!(r#"data-sui-context="{}""#, level);
+            assert!(html.contains(&attr), "{}", html);
+        }
+    }
+
+    #[test]
+    fn test_enum_values_render_as_labels() {
+        let mut registry = SchemaRegistry::load_embedded();
+        let schema = TableSchema::from_toml_str(
+            r#"
+            [variants.role]
+            badge = { base = "span", override = "badge" }
+            [variants.status]
+            badge = { base = "span" }
+
+            [contexts.card]
+
+            [enums]
+            role = ["admin", "read_only"]
+
+            [enums.status]
+            values = ["active", "in_review"]
+            labels = { in_review = "Pending review" }
+            classes = { in_review = "bg-amber-100", gone = "x" }
+            "#,
+        )
+        .unwrap();
+        let found: Vec<_> = validate_table("people", &schema)
+            .iter()
+            .map(|d| d.to_string())
+            .collect();
+        assert_eq!(
+            found,
+            ["warning: people: enums.status: 'gone' is not one of the values"]
+        );
+        registry.insert_table("people", schema);
+
+        let render = |field, value| {
+            registry
+                .render_field("people", field, "card", value)
+                .unwrap()
+        };
+        assert_eq!(
+            render("role", "read_only"),
+            r#"<span class="badge" data-value="read_only">Read only</span>"#
+        );
+        let status = render("status", "in_review");
+        assert!(status.contains(r#" bg-amber-100""#), "{}", status);
+        assert!(status.contains(">Pending review</span>"), "{}", status);
+        // Values outside the enum render as stored
+        assert_eq!(
+            render("role", "owner"),
+            r#"<span class="badge">owner</span>"#
+        );
+    }
+
+    #[test]
+    fn test_wildcard_entries_of_fallback_contexts() {
+        let mut registry = SchemaRegistry::load_embedded();
+        let schema = TableSchema::from_toml_str(
+            r#"
+            [variants.name]
+            h2 = { base = "h2" }
+
+            [contexts.list]
+            name = "h2"
+            "*" = "text"
+
+            [fallbacks]
+            compact = "list"
+            "#,
+        )
+        .unwrap();
+        registry.insert_table("people", schema);
+
+        // A column the schema doesn't know yet still shows, as block text
+        let html = registry
+            .render_field("people", "nickname", "compact", "x")
+            .unwrap();
+        assert!(
+            html.starts_with("<p") && html.ends_with(">x</p>"),
+            "{}",
+            html
+        );
+    }
+
+    #[test]
+    fn test_max_length_overflow_strategies() {
+        let mut registry = SchemaRegistry::load_embedded();
+        let variant = |overflow| FieldVariant {
+            base: "p".to_string(),
+            extends: None,
+            override_class: Some("bio".to_string()),
+            extend: None,
+            attrs: None,
+            kind: None,
+            options: None,
+            max_length: Some(5),
+            overflow,
+            field_type: None,
+            deprecated: None,
+        };
+        let variants = registry
+            .get_table_mut("users")
+            .unwrap()
+            .variants
+            .get_mut("name")
+            .unwrap();
+        variants.insert("cut".to_string(), variant(None));
+        variants.insert("fade".to_string(), variant(Some(Overflow::Fade)));
+        variants.insert("more".to_string(), variant(Some(Overflow::Details)));
+        let mut render = |name: &str, value: &str| {
+            let contexts = &mut registry.get_table_mut("users").unwrap().contexts;
+            contexts
+                .get_mut("card")
+                .unwrap()
+                .fields
+                .insert("name".to_string(), name.to_string());
+            registry
+                .render_field("users", "name", "card", value)
+                .unwrap()
+        };
+
+        assert_eq!(render("cut", "Ada"), r#"<p class="bio">Ada</p>"#);
+        assert_eq!(render("cut", "Ada  Byron"), r#"<p class="bio">Ada…</p>"#);
+        assert_eq!(
+            render("cut", "Ada Lovelace"),
+            r#"<p class="bio">Ada L…</p>"#
+        );
+        let fade = render("fade", "Ada Lovelace");
+        assert!(
+            fade.starts_with(r#"<p class="bio overflow-hidden [mask-image"#),
+            "{}",
+            fade
+        );
+        assert!(fade.ends_with(">Ada L</p>"));
+        assert_eq!(
+            render("more", "Ada Lovelace"),
+            r#"<p class="bio"><details><summary>Ada L…</summary>Ada Lovelace</details></p>"#
+        );
+    }
+
+    #[test]
+    fn test_embedded_schemas_resolve_includes_in_any_format() {
+        let files = [
+            (
+                "shared/audit.toml",
+                "[variants.updated_at]\nago = { base = \"time\" }\n",
+            ),
+            (
+                "posts/posts.json",
+                r#"{"include": ["../shared/audit.toml"], "contexts": {"card": {"updated_at": "ago"}}}"#,
+            ),
+            (
+                "notes/notes.yaml",
+                "variants: { body: { p: { base: \"div\" } } }\n",
+            ),
+            (
+                "notes/notes.toml",
+                "[variants.body]\np = { base = \"p\" }\n",
+            ),
+            (
+                "broken/broken.toml",
+                "include = [\"../shared/missing.toml\"]\n",
+            ),
+        ];
+        let registry = SchemaRegistry::load_embedded_files(&files);
+
+        let posts = registry.get_table("posts").unwrap();
+        assert_eq!(posts.variants["updated_at"]["ago"].base, "time");
+        assert_eq!(posts.source.as_deref(), Some("posts.json"));
+        assert_eq!(
+            registry.get_table("notes").unwrap().variants["body"]["p"].base,
+            "p"
+        );
+        assert!(registry.get_table("broken").is_none());
+        assert!(registry.get_table("shared").is_none());
+    }
+
+    #[test]
+    fn test_includes_merge_shared_definitions() {
+        let dir = std::env::temp_dir().join(format!("uuie_include_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("posts")).unwrap();
+        std::fs::create_dir_all(dir.join("shared")).unwrap();
+        std::fs::write(
+            dir.join("shared/timestamps.toml"),
+            "include = [\"audit.toml\"]\n\
+             [variants.created_at]\ntime = { base = \"time\" }\n\
+             [variants.title]\nh1 = { base = \"h1\" }\n\
+             [contexts.card]\ncreated_at = \"time\"\ntitle = \"h1\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("shared/audit.toml"),
+            "[variants.updated_at]\ntime = { base = \"time\" }\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("posts/posts.toml"),
+            "include = [\"../shared/timestamps.toml\"]\n\
+             [variants.title]\nh2 = { base = \"h2\" }\n\
+             [contexts.card]\ntitle = \"h2\"\n",
+        )
+        .unwrap();
+
+        let registry = SchemaRegistry::load_from_dir(&dir).unwrap();
+        assert!(registry.get_table("shared").is_none());
+        let posts = registry.get_table("posts").unwrap();
+        assert!(posts.variants.contains_key("created_at"));
+        assert!(posts.variants.contains_key("updated_at"));
+        assert_eq!(posts.variants["title"].len(), 2);
+        assert_eq!(posts.contexts["card"].fields["title"], "h2");
+        assert_eq!(posts.contexts["card"].fields["created_at"], "time");
+
+        std::fs::write(
+            dir.join("shared/audit.toml"),
+            "include = [\"timestamps.toml\"]",
+        )
+        .unwrap();
+        let err = SchemaRegistry::load_from_dir(&dir).unwrap_err();
+        assert!(err.to_string().contains("include cycle"), "{}", err);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_environment_overlay_deep_merges_over_base() {
+        let dir = std::env::temp_dir().join(format!("uuie_overlay_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("users")).unwrap();
+        std::fs::copy("schemas/users/users.toml", dir.join("users/users.toml")).unwrap();
+        std::fs::write(
+            dir.join("users/users.prod.yaml"),
+            "variants:\n  name:\n    h1:\n      base: h2\n\
+             contexts:\n  card:\n    name: h1\n",
+        )
+        .unwrap();
+
+        let base = SchemaRegistry::load_layered(&dir, None).unwrap();
+        let prod = SchemaRegistry::load_layered(&dir, Some("prod")).unwrap();
+        let (base, prod) = (
+            base.get_table("users").unwrap(),
+            prod.get_table("users").unwrap(),
+        );
+
+        // Overridden keys change, everything beside them survives
+        assert_eq!(prod.variants["name"]["h1"].base, "h2");
+        assert_eq!(
+            prod.variants["name"]["h1"].override_class,
+            base.variants["name"]["h1"].override_class
+        );
+        assert_eq!(prod.variants["name"].len(), base.variants["name"].len());
+        assert_eq!(prod.variants["email"].len(), base.variants["email"].len());
+        assert_eq!(prod.contexts["card"].fields["name"], "h1");
+        assert_eq!(
+            prod.contexts["card"].fields.len(),
+            base.contexts["card"].fields.len()
+        );
+        assert!(SchemaRegistry::load_layered(&dir, Some("staging")).is_ok());
+
+        std::fs::write(dir.join("users/users.prod.yaml"), "variants: 3\n").unwrap();
+        let err = SchemaRegistry::load_layered(&dir, Some("prod")).unwrap_err();
+        assert!(err.to_string().contains("users.prod.yaml"), "{}", err);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_tenant_overlays_layer_over_base() {
+        let dir = std::env::temp_dir().join(format!("uuie_tenants_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("users")).unwrap();
+        std::fs::create_dir_all(dir.join("tenants/acme")).unwrap();
+        std::fs::copy("schemas/users/users.toml", dir.join("users/users.toml")).unwrap();
+        std::fs::write(
+            dir.join("tenants/acme/users.toml"),
+            "[variants.name.h1]\nbase = \"h2\"\n",
+        )
+        .unwrap();
+
+        let base = Arc::new(SchemaRegistry::load_layered(&dir, None).unwrap());
+        assert_eq!(base.tenants(), ["acme"]);
+        let acme = base.for_tenant("acme");
+        assert_eq!(
+            acme.get_table("users").unwrap().variants["name"]["h1"].base,
+            "h2"
+        );
+        assert_eq!(
+            acme.get_table("users").unwrap().variants["name"]["h1"].override_class,
+            base.get_table("users").unwrap().variants["name"]["h1"].override_class
+        );
+        assert_eq!(
+            base.get_table("users").unwrap().variants["name"]["h1"].base,
+            "h1"
+        );
+        // Built once per tenant; tenants without overlays see the base
+        assert!(Arc::ptr_eq(&acme, &base.for_tenant("acme")));
+        assert!(Arc::ptr_eq(&base, &base.for_tenant("globex")));
+
+        std::fs::write(dir.join("tenants/acme/orders.toml"), "").unwrap();
+        let err = SchemaRegistry::load_layered(&dir, None).unwrap_err();
+        assert!(err.to_string().contains("orders.toml"), "{}", err);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_embedded_schemas_match_schema_dir() {
+        let embedded = SchemaRegistry::load_embedded();
+        let on_disk = SchemaRegistry::load_layered(Path::new("schemas"), None).unwrap();
+        let mut tables = embedded.list_tables();
+        tables.sort();
+        let mut expected = on_disk.list_tables();
+        expected.sort();
+        assert_eq!(tables, expected);
+        assert_eq!(
+            embedded.get_table("users").unwrap().source.as_deref(),
+            Some("users.toml")
+        );
+    }
+
+    #[test]
+    fn test_export_writes_the_effective_schema() {
+        let mut registry = SchemaRegistry::load_embedded();
+        let users = registry.get_table("users").unwrap().clone();
+        let exported = registry.export("users").unwrap();
+        assert_eq!(exported.contexts["list"].inherits, None);
+        assert_eq!(exported.contexts["list"].fields["email"], "link");
+        assert_eq!(exported.contexts["list"].fields["avatar_url"], "small");
+
+        // The export loads back as a schema rendering exactly like the original
+        let reloaded = TableSchema::from_toml_str(&exported.to_toml().unwrap()).unwrap();
+        assert_eq!(reloaded.resolve_contexts(), users.resolve_contexts());
+        registry.insert_table("users_export", reloaded);
+        for context in users.contexts.keys() {
+            assert_eq!(
+                registry.render_field("users_export", "name", context, "Ada"),
+                registry.render_field("users", "name", context, "Ada")
+            );
+        }
+        assert!(registry.export("nope").is_none());
+    }
+
+    #[test]
+    fn test_context_lookups_are_cached_until_an_edit() {
+        let mut registry = SchemaRegistry::load_embedded();
+        let users = registry.get_table("users").unwrap().clone();
+        for context in ["card", "list", "detail", "nope"] {
+            for field in users.variants.keys() {
+                assert_eq!(
+                    registry.cached_variant("users", &users, field, context),
+                    SchemaRegistry::resolve_variant_for_field(&users, field, context)
+                );
+            }
+        }
+        // Only the contexts the table defines are kept
+        let cached = |registry: &SchemaRegistry, context: &str| {
+            registry
+                .context_cache
+                .0
+                .get("users")
+                .is_some_and(|c| c.contains_key(context))
+        };
+        assert!(cached(&registry, "list") && !cached(&registry, "nope"));
+
+        registry
+            .patch_context("users", "card", "email", "input")
+            .unwrap();
+        assert!(!cached(&registry, "list"));
+        let html = registry
+            .render_field("users", "email", "list", "a@b.co")
+            .unwrap();
+        assert!(html.starts_with("<input"), "{}", html);
+    }
+
+    #[test]
+    fn test_patch_context_switches_a_variant() {
+        let mut registry = SchemaRegistry::load_embedded();
+        let link = registry
+            .render_field("users", "email", "list", "a@b.co")
+            .unwrap();
+        assert!(link.starts_with("<a"), "{}", link);
+
+        let previous = registry
+            .patch_context("users", "card", "email", "input")
+            .unwrap();
+        assert_eq!(previous.as_deref(), Some("link"));
+        // Contexts inheriting the patched one follow it
+        let input = registry
+            .render_field("users", "email", "list", "a@b.co")
+            .unwrap();
+        assert!(input.starts_with("<input"), "{}", input);
+
+        assert_eq!(
+            registry.patch_context("users", "card", "email", "muted"),
+            Err(RenderError::UnknownVariant(
+                "users".to_string(),
+                "email".to_string(),
+                "muted".to_string()
+            ))
+        );
+        assert!(matches!(
+            registry.patch_context("users", "nope", "email", "link"),
+            Err(RenderError::UnknownContext(..))
+        ));
+    }
+
+    #[test]
+    fn test_register_table_from_many_threads() {
+        let schema = TableSchema::from_toml_str(
+            "[variants.title]\nh3 = { base = \"h3\" }\n[contexts.card]\ntitle = \"h3\"\n",
+        )
+        .unwrap();
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let schema = schema.clone();
+                scope.spawn(move || register_table(&format!("runtime_{}", i), schema).unwrap());
+            }
+        });
+        let active = registry();
+        for i in 0..8 {
+            let table = format!("runtime_{}", i);
+            let html = active.render_field(&table, "title", "card", "Hi").unwrap();
+            assert!(html.starts_with("<h3"), "{}", html);
+            let source = active.get_table(&table).unwrap().source.clone();
+            assert_eq!(source, Some(format!("{} (runtime)", table)));
+        }
+
+        // Invalid schemas are rejected and leave the registry untouched
+        let mut broken = schema;
+        broken.contexts.get_mut("card").unwrap().inherits = Some("missing".to_string());
+        let err = register_table("runtime_0", broken).unwrap_err();
+        assert!(
+            err.to_string().contains("unknown context 'missing'"),
+            "{}",
+            err
+        );
+        assert!(
+            registry().get_table("runtime_0").unwrap().contexts["card"]
+                .inherits
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_replace_and_remove_tables_at_runtime() {
+        let schema = |tag: &str| {
+            TableSchema::from_toml_str(&format!(
+                "[variants.title]\nt = {{ base = \"{}\" }}\n[contexts.card]\ntitle = \"t\"\n",
+                tag
+            ))
+            .unwrap()
+        };
+        let err = replace_table("retired", schema("h2")).unwrap_err();
+        assert!(matches!(err, SchemaError::UnknownTable(_)), "{}", err);
+
+        register_table("retired", schema("h2")).unwrap();
+        let previous = replace_table("retired", schema("h4")).unwrap();
+        assert!(previous.variants["title"]["t"].base == "h2");
+        let html = registry()
+            .render_field("retired", "title", "card", "Bye")
+            .unwrap();
+        assert!(html.starts_with("<h4"), "{}", html);
+
+        // Tables inheriting from it keep it registered
+        let mut heir = schema("p");
+        heir.contexts.get_mut("card").unwrap().inherits = Some("retired.card".to_string());
+        register_table("retired_heir", heir).unwrap();
+        let err = remove_table("retired").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Table 'retired' still has contexts inherited by: retired_heir"
+        );
+
+        remove_table("retired_heir").unwrap();
+        remove_table("retired").unwrap();
+        assert!(registry().get_table("retired").is_none());
+        assert!(matches!(
+            remove_table("retired"),
+            Err(SchemaError::UnknownTable(_))
+        ));
+    }
+
+    #[test]
+    fn test_reloaded_registry_keeps_runtime_changes() {
+        let schema = TableSchema::from_toml_str(
+            "[variants.title]\nt = { base = \"h3\" }\nalt = { base = \"em\" }\n\
+             [contexts.card]\ntitle = \"t\"\n",
+        )
+        .unwrap();
+        register_table("reload_probe", schema.clone()).unwrap();
+        patch_context("reload_probe", "card", "title", "alt").unwrap();
+
+        // Rebuilt from files, which know nothing of the probe
+        let mut next = (*registry()).clone();
+        next.remove_table("reload_probe").unwrap();
+        assert_eq!(swap_reloaded_registry(next), Vec::<String>::new());
+        let html = registry()
+            .render_field("reload_probe", "title", "card", "Hi")
+            .unwrap();
+        assert!(html.starts_with("<em"), "{}", html);
+
+        // Removed at runtime stays removed even if the files have it
+        remove_table("reload_probe").unwrap();
+        let mut next = (*registry()).clone();
+        next.insert_table("reload_probe", schema);
+        swap_reloaded_registry(next);
+        assert!(registry().get_table("reload_probe").is_none());
+    }
+
+    #[test]
+    fn test_unknown_fields_fall_back_to_the_default_schema() {
+        let mut registry = SchemaRegistry::load_embedded();
+        let span = registry.get_theme_css("span");
+        assert!(!span.is_empty());
+        let expected = format!(r#"<span class="{}">Hello</span>"#, span);
+        assert_eq!(
+            registry.render_field("orders", "status", "card", "Hello"),
+            Some(expected)
+        );
+        let nickname = registry
+            .render_field("users", "nickname", "card", "Hello")
+            .unwrap();
+        assert!(nickname.starts_with("<span"), "{}", nickname);
+        assert!(
+            !registry
+                .list_tables()
+                .iter()
+                .any(|table| *table == DEFAULT_TABLE)
+        );
+
+        // Per-field entries in the fallback schema win over "*"
+        let fallback = registry.get_table_mut(DEFAULT_TABLE).unwrap();
+        let mut variants = fallback.variants["*"].clone();
+        variants.get_mut("span").unwrap().base = "strong".to_string();
+        fallback.variants.insert("status".to_string(), variants);
+        fallback
+            .defaults
+            .as_mut()
+            .unwrap()
+            .insert("status".to_string(), "span".to_string());
+        let status = registry
+            .render_field("orders", "status", "card", "Hello")
+            .unwrap();
+        assert!(status.starts_with("<strong"), "{}", status);
+    }
+
+    #[test]
+    fn test_contexts_inherit_shared_contexts_from_other_tables() {
+        let dir = std::env::temp_dir().join(format!("uuie_shared_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("common")).unwrap();
+        std::fs::create_dir_all(dir.join("members")).unwrap();
+        std::fs::write(
+            dir.join("common/common.toml"),
+            "[contexts.compact]\nname = \"h2\"\n\
+             [contexts.card]\ninherits = \"compact\"\nemail = \"input\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("members/members.toml"),
+            "[variants.name]\nh1 = { base = \"h1\" }\nh2 = { base = \"h2\" }\n\
+             [variants.email]\nlink = { base = \"a\" }\ninput = { base = \"input\" }\n\
+             [defaults]\nname = \"h1\"\nemail = \"link\"\n\
+             [contexts.tile]\ninherits = \"common.card\"\n",
+        )
+        .unwrap();
+
+        let registry = SchemaRegistry::load_layered(&dir, None).unwrap();
+        let name = registry
+            .render_field("members", "name", "tile", "Ada")
+            .unwrap();
+        assert!(name.starts_with("<h2"), "{}", name);
+        let email = registry
+            .render_field("members", "email", "tile", "a@b.co")
+            .unwrap();
+        assert!(email.starts_with("<input"), "{}", email);
+        // The library itself validates cleanly, and so does the inheriting table
+        assert!(registry.validate().is_empty(), "{:?}", registry.validate());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_load_from_dir_accepts_yaml_schemas_and_themes() {
+        let dir = std::env::temp_dir().join(format!("uuie_yaml_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("members")).unwrap();
+        std::fs::write(
+            dir.join("members/members.yml"),
+            "variants:\n  name:\n    h2:\n      base: h2\n\
+             contexts:\n  card:\n    name: h2\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("themes.yaml"), "neon:\n  h2: text-pink-500\n").unwrap();
+
+        let mut registry = SchemaRegistry::load_from_dir(&dir).unwrap();
+        assert_eq!(
+            registry.get_table("members").unwrap().source.as_deref(),
+            Some("members.yml")
+        );
+        registry.load_themes_file(&dir.join("themes.yaml")).unwrap();
+        registry.set_theme("neon");
+        let html = registry
+            .render_field("members", "name", "card", "Ada")
+            .unwrap();
+        assert!(html.contains("text-pink-500"), "{}", html);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_compiled_bundle_round_trip() {
+        let registry = SchemaRegistry::load_embedded();
+        let component = ComponentTemplate::builder()
+            .name("user_badge")
+            .table("users")
+            .template_str("<span>{name}</span>")
+            .build()
+            .unwrap();
+        let path = std::env::temp_dir().join(format!("uuie_compiled_{}.bin", std::process::id()));
+        registry.save_compiled(&path, &[&component]).unwrap();
+
+        let loaded = SchemaRegistry::load_compiled(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let schema = loaded.get_table("users").unwrap();
+        assert!(!schema.resolved_contexts.is_empty());
+        for context in schema.contexts.keys() {
+            for field in schema.variants.keys() {
+                // Attributes are emitted in name order, so full renders compare exactly
+                assert_eq!(
+                    loaded.render_field("users", field, context, "x"),
+                    registry.render_field("users", field, context, "x")
+                );
+                assert_eq!(
+                    loaded.field_classes("users", field, context, "dark"),
+                    registry.field_classes("users", field, context, "dark")
+                );
+            }
+        }
+        assert_eq!(loaded.list_themes().len(), registry.list_themes().len());
+        assert_eq!(
+            loaded.compiled_components()[0].required_fields,
+            vec!["name"]
+        );
+
+        let garbage = std::env::temp_dir().join(format!("uuie_garbage_{}.bin", std::process::id()));
+        std::fs::write(&garbage, b"not a bundle").unwrap();
+        assert!(matches!(
+            SchemaRegistry::load_compiled(&garbage),
+            Err(SchemaError::Parse(_))
+        ));
+        std::fs::remove_file(&garbage).unwrap();
+    }
+}
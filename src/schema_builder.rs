@@ -0,0 +1,222 @@
+// src/schema_builder.rs - Table schemas defined in Rust code
+//
+// The code counterpart of a schema file, for applications that generate or
+// ship their tables themselves:
+//
+//     SchemaBuilder::table("products")
+//         .field("title")
+//         .variant("h2", FieldVariant::tag("h2").override_class("text-xl"))
+//         .default_variant("h2")
+//         .context("card", |card| card.field("title", "h2"))
+//         .register()?;
+//
+// Variant settings go through `.field(..)`, which selects the field the
+// following calls apply to. `build` checks the result the way a loaded file
+// is checked: `extends` is resolved and validation errors fail the build.
+use crate::field_types::FieldType;
+use crate::schema::{Context, FieldVariant, MockRecord, SchemaError, TableSchema, register_table};
+use crate::validation::{Severity, validate_table};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct SchemaBuilder {
+    table: String,
+    schema: TableSchema,
+    field: Option<String>,
+    // Misuse found while building (a variant before any field), reported by build
+    errors: Vec<String>,
+}
+
+impl SchemaBuilder {
+    // 🏗️ Start defining table `table`
+    pub fn table(table: &str) -> Self {
+        Self {
+            table: table.to_string(),
+            schema: TableSchema::default(),
+            field: None,
+            errors: Vec::new(),
+        }
+    }
+
+    // Select (adding if new) the field the next variant/default/type calls set
+    pub fn field(mut self, field: &str) -> Self {
+        self.schema.variants.entry(field.to_string()).or_default();
+        self.field = Some(field.to_string());
+        self
+    }
+
+    pub fn variant(mut self, name: &str, variant: FieldVariant) -> Self {
+        if let Some(field) = self.current_field("variant", name) {
+            let variants = self.schema.variants.entry(field).or_default();
+            variants.insert(name.to_string(), variant);
+        }
+        self
+    }
+
+    // The variant the field renders with when a context doesn't say
+    pub fn default_variant(mut self, name: &str) -> Self {
+        if let Some(field) = self.current_field("default", name) {
+            let defaults = self.schema.defaults.get_or_insert_with(HashMap::new);
+            defaults.insert(field, name.to_string());
+        }
+        self
+    }
+
+    pub fn field_type(mut self, field_type: FieldType) -> Self {
+        if let Some(field) = self.current_field("type", "") {
+            self.schema.types.insert(field, field_type);
+        }
+        self
+    }
+
+    // Define context `name`, e.g. `.context("list", |list| list.inherits("card"))`
+    pub fn context(
+        mut self,
+        name: &str,
+        define: impl FnOnce(ContextBuilder) -> ContextBuilder,
+    ) -> Self {
+        let context = define(ContextBuilder::default()).context;
+        self.schema.contexts.insert(name.to_string(), context);
+        self
+    }
+
+    // Add a mock data row, used when no data source has the table
+    pub fn mock(mut self, fields: &[(&str, &str)]) -> Self {
+        let fields = fields
+            .iter()
+            .map(|(field, value)| (field.to_string(), value.to_string()))
+            .collect();
+        self.schema
+            .mock_data
+            .get_or_insert_with(Vec::new)
+            .push(MockRecord { fields });
+        self
+    }
+
+    // One row without an id, e.g. site settings (see settings.rs)
+    pub fn singleton(mut self) -> Self {
+        self.schema.singleton = true;
+        self
+    }
+
+    pub fn build(self) -> Result<TableSchema, SchemaError> {
+        let mut schema = self.schema;
+        let mut errors: Vec<_> = self.errors.into_iter().map(SchemaError::Parse).collect();
+        if let Err(e) = schema.resolve_extends() {
+            errors.push(SchemaError::Parse(e));
+        }
+        errors.extend(
+            validate_table(&self.table, &schema)
+                .into_iter()
+                .filter(|diagnostic| diagnostic.severity == Severity::Error)
+                .map(|diagnostic| SchemaError::Parse(diagnostic.to_string())),
+        );
+        schema.source = Some(format!("{} (code)", self.table));
+
+        match errors.len() {
+            0 => Ok(schema),
+            1 => Err(errors.remove(0)),
+            _ => Err(SchemaError::Multiple(errors)),
+        }
+    }
+
+    // Build and add (or replace) the table in the global registry
+    pub fn register(self) -> Result<(), SchemaError> {
+        let table = self.table.clone();
+        register_table(&table, self.build()?)
+    }
+
+    fn current_field(&mut self, what: &str, name: &str) -> Option<String> {
+        if self.field.is_none() {
+            self.errors.push(format!(
+                "{}: {} '{}' set before any field was selected",
+                self.table, what, name
+            ));
+        }
+        self.field.clone()
+    }
+}
+
+// Fields (and parent) of one context; see SchemaBuilder::context
+#[derive(Debug, Default, Clone)]
+pub struct ContextBuilder {
+    context: Context,
+}
+
+impl ContextBuilder {
+    // Render `field` with `variant` in this context
+    pub fn field(mut self, field: &str, variant: &str) -> Self {
+        self.context
+            .fields
+            .insert(field.to_string(), variant.to_string());
+        self
+    }
+
+    // Take every field this context doesn't set from `parent`
+    pub fn inherits(mut self, parent: &str) -> Self {
+        self.context.inherits = Some(parent.to_string());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::registry;
+
+    #[test]
+    fn test_builder_registers_a_renderable_table() {
+        SchemaBuilder::table("products")
+            .field("title")
+            .variant("h2", FieldVariant::tag("h2").override_class("text-xl"))
+            .variant(
+                "bold",
+                FieldVariant::tag("h2")
+                    .extends("h2")
+                    .extend_class("font-bold"),
+            )
+            .default_variant("h2")
+            .field("price")
+            .variant(
+                "amount",
+                FieldVariant::tag("span").attr("data-price", "{value}"),
+            )
+            .field_type(FieldType::Number)
+            .context("card", |card| card.field("title", "bold"))
+            .context("list", |list| {
+                list.inherits("card").field("price", "amount")
+            })
+            .mock(&[("id", "1"), ("title", "Lamp"), ("price", "1200")])
+            .register()
+            .unwrap();
+
+        let registry = registry();
+        let title = registry
+            .render_field("products", "title", "list", "Lamp")
+            .unwrap();
+        assert_eq!(title, r#"<h2 class="text-xl font-bold">Lamp</h2>"#);
+        let price = registry
+            .render_field("products", "price", "list", "1200")
+            .unwrap();
+        assert!(price.contains(r#"data-price="1200""#), "{}", price);
+        assert!(price.contains(">1,200</span>"), "{}", price);
+        assert_eq!(registry.get_mock_data("products").len(), 1);
+    }
+
+    #[test]
+    fn test_builder_reports_every_mistake() {
+        let err = SchemaBuilder::table("broken")
+            .variant("h1", FieldVariant::tag("h1"))
+            .field("name")
+            .variant("h2", FieldVariant::tag("h2"))
+            .context("card", |card| card.field("name", "h3").inherits("missing"))
+            .build()
+            .unwrap_err();
+        let SchemaError::Multiple(errors) = err else {
+            panic!("expected several errors, got {}", err);
+        };
+        let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(messages.len(), 3, "{:?}", messages);
+        assert!(messages[0].contains("before any field"), "{:?}", messages);
+    }
+}
@@ -0,0 +1,954 @@
+// src/schema_store.rs - Validated schema edits, persistence and registry hot-swap
+//
+// Edits never touch the live registry directly: they accumulate in a per-table
+// draft, which only reaches production renders once it is published. Every
+// publish also records a versioned snapshot of the whole bundle for rollback.
+use crate::component_registry::component_registry;
+use crate::config::config;
+use crate::schema::{
+    Context, DEFAULT_TABLE, FieldVariant, SchemaRegistry, TableSchema, WILDCARD_FIELD,
+    overlay_file, registry, resolve_variant, swap_registry, update_registry,
+};
+use crate::variant_kinds::KNOWN_KINDS;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
+
+#[derive(Debug, Clone)]
+pub enum SchemaEditError {
+    TableNotFound(String),
+    NoDraft(String),
+    VersionNotFound(u64),
+    Invalid(String),
+    Storage(String),
+}
+
+impl std::fmt::Display for SchemaEditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaEditError::TableNotFound(table) => write!(f, "Table '{}' not found", table),
+            SchemaEditError::NoDraft(table) => write!(f, "Table '{}' has no draft", table),
+            SchemaEditError::VersionNotFound(version) => {
+                write!(f, "Schema bundle version {} not found", version)
+            }
+            SchemaEditError::Invalid(msg) => write!(f, "Invalid schema edit: {}", msg),
+            SchemaEditError::Storage(msg) => write!(f, "Failed to persist schema: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SchemaEditError {}
+
+// Result of an applied edit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EditOutcome {
+    // false when no schema directory is configured (edit is in-memory only)
+    pub persisted: bool,
+}
+
+// Where edited schemas are written. Without a directory, edits only live in
+// the running registry and are lost on restart. Clones share drafts and
+// history; a store made with `new` starts with none.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaStore {
+    dir: Option<PathBuf>,
+    state: Arc<StoreState>,
+}
+
+#[derive(Debug, Default)]
+struct StoreState {
+    // Unpublished edits, keyed by table
+    drafts: RwLock<HashMap<String, TableSchema>>,
+    history: RwLock<Vec<BundleVersion>>,
+    // Registry edits are published to; None means the global one
+    registry: Option<RwLock<Arc<SchemaRegistry>>>,
+}
+
+static STORE: OnceLock<SchemaStore> = OnceLock::new();
+
+impl SchemaStore {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self {
+            dir,
+            state: Arc::default(),
+        }
+    }
+
+    // The store behind the editor API, publishing to the global registry
+    pub fn from_config() -> Self {
+        STORE
+            .get_or_init(|| Self::new(config().schemas_dir.clone()))
+            .clone()
+    }
+
+    // Publish to `registry` instead of the global one (tools and tests)
+    pub fn with_registry(dir: Option<PathBuf>, registry: SchemaRegistry) -> Self {
+        let state = StoreState {
+            registry: Some(RwLock::new(Arc::new(registry))),
+            ..StoreState::default()
+        };
+        Self {
+            dir,
+            state: Arc::new(state),
+        }
+    }
+
+    // The registry published edits go live in
+    pub fn registry(&self) -> Arc<SchemaRegistry> {
+        match &self.state.registry {
+            Some(own) => own
+                .read()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .clone(),
+            None => registry(),
+        }
+    }
+
+    fn update_registry<R>(
+        &self,
+        f: impl FnOnce(&mut SchemaRegistry) -> Result<R, SchemaEditError>,
+    ) -> Result<R, SchemaEditError> {
+        let Some(own) = &self.state.registry else {
+            return update_registry(f);
+        };
+        let mut active = own.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut next = (**active).clone();
+        let result = f(&mut next)?;
+        *active = Arc::new(next);
+        Ok(result)
+    }
+
+    fn swap_registry(&self, next: SchemaRegistry) {
+        match &self.state.registry {
+            Some(own) => {
+                *own.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(next);
+            }
+            None => {
+                swap_registry(next);
+            }
+        }
+    }
+
+    fn drafts(&self) -> &RwLock<HashMap<String, TableSchema>> {
+        &self.state.drafts
+    }
+
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    // `<dir>/<table>/<table>.toml`, the layout SchemaRegistry::load_from_dir reads
+    pub fn table_path(&self, table: &str) -> Option<PathBuf> {
+        self.table_dir(table)
+            .map(|dir| dir.join(format!("{}.toml", table)))
+    }
+
+    // `<dir>/<table>/<table>.draft.toml`, ignored by the registry loader
+    pub fn draft_path(&self, table: &str) -> Option<PathBuf> {
+        self.table_dir(table)
+            .map(|dir| dir.join(format!("{}.draft.toml", table)))
+    }
+
+    // Table names come from request paths, so only a plain name (no
+    // separators or dots) ever becomes a directory
+    fn table_dir(&self, table: &str) -> Option<PathBuf> {
+        let safe = !table.is_empty()
+            && table
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        self.dir
+            .as_ref()
+            .filter(|_| safe)
+            .map(|dir| dir.join(table))
+    }
+
+    // The table file as written, without its overlay or includes merged in
+    pub fn load_base(&self, table: &str) -> Option<TableSchema> {
+        let path = self.table_path(table).filter(|path| path.is_file())?;
+        TableSchema::from_unmerged_file(&path)
+            .map_err(|e| eprintln!("Ignoring unreadable schema: {}", e))
+            .ok()
+    }
+
+    // 🧅 `schema` as the registry would load it from the table file: with the
+    // `<table>.<APP_ENV>` overlay and includes merged in
+    pub fn layered(
+        &self,
+        table: &str,
+        schema: TableSchema,
+    ) -> Result<TableSchema, SchemaEditError> {
+        let Some(path) = self.table_path(table).filter(|path| path.is_file()) else {
+            return Ok(schema);
+        };
+        let overlays: Vec<_> = path
+            .parent()
+            .and_then(|dir| overlay_file(dir, table, &config().environment))
+            .into_iter()
+            .collect();
+        schema
+            .with_layers(&path, &overlays)
+            .map_err(|e| SchemaEditError::Invalid(e.to_string()))
+    }
+
+    // Write the published schema of a table
+    pub fn save_table(&self, table: &str, schema: &TableSchema) -> Result<bool, SchemaEditError> {
+        match self.table_path(table) {
+            Some(path) => write_schema(&path, schema).map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    pub fn save_draft(&self, table: &str, schema: &TableSchema) -> Result<bool, SchemaEditError> {
+        match self.draft_path(table) {
+            Some(path) => write_schema(&path, schema).map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    // Draft left on disk by a previous run, if any
+    pub fn load_draft(&self, table: &str) -> Option<TableSchema> {
+        let path = self.draft_path(table).filter(|path| path.is_file())?;
+        TableSchema::from_file(&path)
+            .map_err(|e| eprintln!("Ignoring unreadable draft: {}", e))
+            .ok()
+    }
+
+    pub fn remove_draft(&self, table: &str) -> Result<(), SchemaEditError> {
+        match self.draft_path(table).filter(|path| path.is_file()) {
+            Some(path) => std::fs::remove_file(&path)
+                .map_err(|e| SchemaEditError::Storage(format!("{}: {}", path.display(), e))),
+            None => Ok(()),
+        }
+    }
+
+    // `<dir>/.history/v<N>/`, laid out like the schema directory itself
+    pub fn bundle_path(&self, version: u64) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(".history").join(format!("v{}", version)))
+    }
+
+    // Write the table files of a published bundle
+    pub fn save_bundle(
+        &self,
+        version: u64,
+        tables: &HashMap<String, TableSchema>,
+    ) -> Result<bool, SchemaEditError> {
+        let Some(bundle_dir) = self.bundle_path(version) else {
+            return Ok(false);
+        };
+        for (table, schema) in tables {
+            let path = bundle_dir.join(table).join(format!("{}.toml", table));
+            write_schema(&path, schema)?;
+        }
+        Ok(true)
+    }
+
+    // The table files of a bundle version as written, without overlays or includes
+    pub fn load_bundle_tables(&self, version: u64) -> Option<HashMap<String, TableSchema>> {
+        let path = self.bundle_path(version).filter(|path| path.is_dir())?;
+        let entries = std::fs::read_dir(&path)
+            .map_err(|e| eprintln!("Ignoring unreadable schema bundle: {}", e))
+            .ok()?;
+        let mut tables = HashMap::new();
+        for entry in entries.flatten() {
+            let table = entry.file_name().to_string_lossy().to_string();
+            let file = entry.path().join(format!("{}.toml", table));
+            if !file.is_file() {
+                continue;
+            }
+            match TableSchema::from_unmerged_file(&file) {
+                Ok(schema) => {
+                    tables.insert(table, schema);
+                }
+                Err(e) => {
+                    eprintln!("Ignoring unreadable schema bundle: {}", e);
+                    return None;
+                }
+            }
+        }
+        Some(tables)
+    }
+
+    // A bundle version as the registry would load it today, with the current
+    // overlays and includes layered over its table files
+    pub fn load_bundle(&self, version: u64) -> Option<SchemaRegistry> {
+        let tables = self.load_bundle_tables(version)?;
+        self.layered_bundle(&tables)
+            .map_err(|e| eprintln!("Ignoring unreadable schema bundle: {}", e))
+            .ok()
+    }
+
+    // The live registry with `tables` (as written) layered in
+    fn layered_bundle(
+        &self,
+        tables: &HashMap<String, TableSchema>,
+    ) -> Result<SchemaRegistry, SchemaEditError> {
+        let mut registry = (*self.registry()).clone();
+        for (table, schema) in tables {
+            let mut layered = self.layered(table, schema.clone())?;
+            if let Some(current) = registry.get_table(table) {
+                layered.source = current.source.clone();
+            }
+            registry.insert_table(table, layered);
+        }
+        Ok(registry)
+    }
+
+    // Every table of `registry` as its file is written; tables without one
+    // (no schema directory, or defined in code) as they are loaded
+    fn table_files(&self, registry: &SchemaRegistry) -> HashMap<String, TableSchema> {
+        registry
+            .list_tables()
+            .into_iter()
+            .filter_map(|table| {
+                let schema = self
+                    .load_base(table)
+                    .or_else(|| registry.get_table(table).cloned())?;
+                Some((table.clone(), schema))
+            })
+            .collect()
+    }
+
+    // Versions recorded on disk, oldest first
+    pub fn bundle_versions(&self) -> Vec<u64> {
+        let Some(entries) = self
+            .dir
+            .as_ref()
+            .and_then(|dir| std::fs::read_dir(dir.join(".history")).ok())
+        else {
+            return Vec::new();
+        };
+
+        let mut versions: Vec<u64> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                name.strip_prefix('v')?.parse().ok()
+            })
+            .collect();
+        versions.sort_unstable();
+        versions
+    }
+
+    pub fn remove_bundle(&self, version: u64) -> Result<(), SchemaEditError> {
+        match self.bundle_path(version).filter(|path| path.is_dir()) {
+            Some(path) => std::fs::remove_dir_all(&path)
+                .map_err(|e| SchemaEditError::Storage(format!("{}: {}", path.display(), e))),
+            None => Ok(()),
+        }
+    }
+}
+
+// Write a schema file, replacing it atomically via rename
+fn write_schema(path: &Path, schema: &TableSchema) -> Result<(), SchemaEditError> {
+    let content =
+        toml::to_string_pretty(schema).map_err(|e| SchemaEditError::Storage(e.to_string()))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| SchemaEditError::Storage(e.to_string()))?;
+    }
+
+    let tmp_path = path.with_extension("toml.tmp");
+    std::fs::write(&tmp_path, content)
+        .and_then(|_| std::fs::rename(&tmp_path, path))
+        .map_err(|e| SchemaEditError::Storage(format!("{}: {}", path.display(), e)))
+}
+
+// Current draft of a table, if it has unpublished edits
+pub fn draft(store: &SchemaStore, table: &str) -> Option<TableSchema> {
+    let drafts = store
+        .drafts()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    drafts
+        .get(table)
+        .cloned()
+        .or_else(|| store.load_draft(table))
+}
+
+// ✏️ Create or replace one variant of a field
+pub fn put_variant(
+    store: &SchemaStore,
+    table: &str,
+    field: &str,
+    variant_name: &str,
+    variant: FieldVariant,
+) -> Result<EditOutcome, SchemaEditError> {
+    if variant.extends.is_none() {
+        validate_variant(variant_name, &variant)?;
+    }
+
+    edit_draft(store, table, |schema| {
+        // `extends` may name a variant from the overlay or an include
+        let mut candidates = store
+            .layered(table, schema.clone())?
+            .variants
+            .remove(field)
+            .unwrap_or_default();
+        let variants = schema.variants.entry(field.to_string()).or_default();
+        // Stored resolved, like variants loaded from a schema file
+        let variant = if variant.extends.is_some() {
+            candidates.extend(variants.clone());
+            candidates.insert(variant_name.to_string(), variant);
+            let resolved =
+                resolve_variant(&candidates, variant_name, &mut Vec::new()).map_err(|e| {
+                    SchemaEditError::Invalid(format!("variant '{}' {}", variant_name, e))
+                })?;
+            validate_variant(variant_name, &resolved)?;
+            resolved
+        } else {
+            variant
+        };
+        variants.insert(variant_name.to_string(), variant);
+        Ok(())
+    })
+}
+
+// ✏️ Create or replace a context
+pub fn put_context(
+    store: &SchemaStore,
+    table: &str,
+    context_name: &str,
+    context: Context,
+) -> Result<EditOutcome, SchemaEditError> {
+    edit_draft(store, table, |schema| {
+        let view = store.layered(table, schema.clone())?;
+        validate_context(store, &view, context_name, &context)?;
+        schema.contexts.insert(context_name.to_string(), context);
+        Ok(())
+    })
+}
+
+// Apply `change` to the table's draft and persist the draft. A new draft starts
+// from the table file as written, so publishing never bakes the environment
+// overlay or includes into it; without one it starts from the live schema.
+// The live registry is left untouched.
+fn edit_draft<F>(
+    store: &SchemaStore,
+    table: &str,
+    change: F,
+) -> Result<EditOutcome, SchemaEditError>
+where
+    F: FnOnce(&mut TableSchema) -> Result<(), SchemaEditError>,
+{
+    // Only tables the registry knows reach the schema directory
+    let live = store
+        .registry()
+        .get_table(table)
+        .cloned()
+        .ok_or_else(|| SchemaEditError::TableNotFound(table.to_string()))?;
+    let mut drafts = store
+        .drafts()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut schema = drafts
+        .get(table)
+        .cloned()
+        .or_else(|| store.load_draft(table))
+        .or_else(|| store.load_base(table))
+        .unwrap_or(live);
+
+    change(&mut schema)?;
+    // The edit must still load once the overlay and includes are merged in
+    store.layered(table, schema.clone())?;
+    let persisted = store.save_draft(table, &schema)?;
+    drafts.insert(table.to_string(), schema);
+
+    Ok(EditOutcome { persisted })
+}
+
+// 🚀 Promote a table's draft to the live registry. The swap is atomic, and a
+// failed write leaves both the draft and the active registry as they were.
+pub fn publish(store: &SchemaStore, table: &str) -> Result<EditOutcome, SchemaEditError> {
+    // Checked before the draft is looked for on disk
+    let previous = store.registry();
+    if previous.get_table(table).is_none() {
+        return Err(SchemaEditError::TableNotFound(table.to_string()));
+    }
+    let mut drafts = store
+        .drafts()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let draft = drafts
+        .get(table)
+        .cloned()
+        .or_else(|| store.load_draft(table))
+        .ok_or_else(|| SchemaEditError::NoDraft(table.to_string()))?;
+    let layered = store.layered(table, draft.clone())?;
+
+    // Snapshot the bundle being replaced so the first publish can be undone
+    let previous = history(store)
+        .is_empty()
+        .then(|| store.table_files(&previous));
+    // Only the table's own file, so the overlay and includes stay separate.
+    // Written before the swap, outside the registry lock.
+    let persisted = store.save_table(table, &draft)?;
+    store.update_registry(|registry: &mut SchemaRegistry| {
+        let schema = registry
+            .get_table_mut(table)
+            .ok_or_else(|| SchemaEditError::TableNotFound(table.to_string()))?;
+        let source = schema.source.take();
+        *schema = layered;
+        schema.source = source;
+        registry.link_shared_contexts();
+        Ok(())
+    })?;
+
+    drafts.remove(table);
+    store.remove_draft(table)?;
+
+    // Cached renders were produced by the previous schema
+    component_registry().cache().clear();
+
+    if let Some(previous) = previous {
+        record_bundle(store, previous)?;
+    }
+    record_bundle(store, store.table_files(&store.registry()))?;
+
+    Ok(EditOutcome { persisted })
+}
+
+// One published schema bundle, kept for rollback. Tables are kept as their
+// files are written, so restoring one never bakes an overlay into it.
+#[derive(Debug, Clone)]
+struct BundleVersion {
+    version: u64,
+    tables: Arc<HashMap<String, TableSchema>>,
+}
+
+// 📚 Published bundle versions available for rollback, oldest first
+pub fn history(store: &SchemaStore) -> Vec<u64> {
+    let history = store
+        .state
+        .history
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut versions = store.bundle_versions();
+    versions.extend(history.iter().map(|entry| entry.version));
+    versions.sort_unstable();
+    versions.dedup();
+    versions
+}
+
+// Record `tables` as the newest version, dropping the oldest beyond the limit
+fn record_bundle(
+    store: &SchemaStore,
+    tables: HashMap<String, TableSchema>,
+) -> Result<u64, SchemaEditError> {
+    let version = history(store).last().map_or(1, |latest| latest + 1);
+    store.save_bundle(version, &tables)?;
+
+    let mut history = store
+        .state
+        .history
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    history.push(BundleVersion {
+        version,
+        tables: Arc::new(tables),
+    });
+
+    let limit = config().schema_history_limit;
+    let excess = history.len().saturating_sub(limit);
+    history.drain(..excess);
+    let on_disk = store.bundle_versions();
+    for old in &on_disk[..on_disk.len().saturating_sub(limit)] {
+        store.remove_bundle(*old)?;
+    }
+
+    Ok(version)
+}
+
+// ⏪ Atomically make a previously published bundle the active registry again.
+// The restored table files are written back to the schema directory as they
+// were, and the live registry gets them with today's overlays layered on.
+pub fn rollback(store: &SchemaStore, version: u64) -> Result<EditOutcome, SchemaEditError> {
+    let snapshot = {
+        let history = store
+            .state
+            .history
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        history
+            .iter()
+            .find(|entry| entry.version == version)
+            .map(|entry| entry.tables.clone())
+    };
+    let tables = match snapshot {
+        Some(tables) => (*tables).clone(),
+        None => store
+            .load_bundle_tables(version)
+            .ok_or(SchemaEditError::VersionNotFound(version))?,
+    };
+    // Layered before anything is written, so a bundle that no longer loads
+    // leaves both the files and the registry as they were
+    let bundle = store.layered_bundle(&tables)?;
+
+    let mut persisted = false;
+    for (table, schema) in &tables {
+        persisted |= store.save_table(table, schema)?;
+    }
+
+    store.swap_registry(bundle);
+    component_registry().cache().clear();
+
+    Ok(EditOutcome { persisted })
+}
+
+fn validate_variant(name: &str, variant: &FieldVariant) -> Result<(), SchemaEditError> {
+    if name.is_empty() {
+        return Err(SchemaEditError::Invalid(
+            "variant name is empty".to_string(),
+        ));
+    }
+    let valid_tag = !variant.base.is_empty()
+        && variant
+            .base
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if !valid_tag {
+        return Err(SchemaEditError::Invalid(format!(
+            "variant '{}' has invalid base tag '{}'",
+            name, variant.base
+        )));
+    }
+    let mut names: Vec<_> = variant
+        .attrs
+        .iter()
+        .flat_map(|attrs| attrs.keys())
+        .collect();
+    names.sort();
+    if let Some(violation) = names
+        .into_iter()
+        .find_map(|attr| config().attr_policy.violation(&variant.base, attr))
+    {
+        return Err(SchemaEditError::Invalid(format!(
+            "variant '{}': {}",
+            name, violation
+        )));
+    }
+    if let Some(kind) = &variant.kind
+        && !KNOWN_KINDS.contains(&kind.as_str())
+    {
+        return Err(SchemaEditError::Invalid(format!(
+            "variant '{}' has unknown kind '{}'",
+            name, kind
+        )));
+    }
+    Ok(())
+}
+
+fn validate_context(
+    store: &SchemaStore,
+    schema: &TableSchema,
+    name: &str,
+    context: &Context,
+) -> Result<(), SchemaEditError> {
+    let live = store.registry();
+    for (field, variant) in &context.fields {
+        // Without "*" variants of its own the table renders `_default`'s
+        let owner = match live.get_table(DEFAULT_TABLE) {
+            Some(fallback) if field == WILDCARD_FIELD && !schema.variants.contains_key(field) => {
+                fallback
+            }
+            _ => schema,
+        };
+        let exists = owner
+            .variants
+            .get(field)
+            .is_some_and(|variants| variants.contains_key(variant));
+        if !exists {
+            return Err(SchemaEditError::Invalid(format!(
+                "context '{}' maps '{}' to unknown variant '{}'",
+                name, field, variant
+            )));
+        }
+    }
+
+    for (section, spec) in &context.sections {
+        if let Some(field) = spec
+            .fields
+            .iter()
+            .find(|f| !schema.variants.contains_key(*f))
+        {
+            return Err(SchemaEditError::Invalid(format!(
+                "section '{}' of context '{}' lists unknown field '{}'",
+                section, name, field
+            )));
+        }
+    }
+
+    // Walk the inheritance chain to make sure it terminates. Shared contexts
+    // ("common.card") come from the live registry, since drafts aren't linked.
+    let mut seen = vec![name.to_string()];
+    let mut parent = context.inherits.clone();
+    while let Some(current) = parent {
+        if seen.contains(&current) {
+            return Err(SchemaEditError::Invalid(format!(
+                "context '{}' has an inheritance cycle through '{}'",
+                name, current
+            )));
+        }
+        let next = schema
+            .context(&current)
+            .map(|(_, next)| next)
+            .or_else(|| live.shared_context(&current))
+            .ok_or_else(|| {
+                SchemaEditError::Invalid(format!(
+                    "context '{}' inherits from unknown context '{}'",
+                    name, current
+                ))
+            })?;
+        parent = match (current.split_once('.'), &next.inherits) {
+            // A shared context's parents live in its own table
+            (Some((table, _)), Some(inherits)) if !inherits.contains('.') => {
+                Some(format!("{}.{}", table, inherits))
+            }
+            _ => next.inherits.clone(),
+        };
+        seen.push(current);
+    }
+
+    Ok(())
+}
+
+// Constant-time comparison for editor tokens
+pub fn token_matches(expected: &str, provided: &str) -> bool {
+    expected.len() == provided.len()
+        && expected
+            .bytes()
+            .zip(provided.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edits_stay_in_draft_until_published() {
+        let dir = std::env::temp_dir().join(format!("uuie_store_{}", std::process::id()));
+        // Its own registry, so the global one other tests render with is untouched
+        let store = SchemaStore::with_registry(Some(dir.clone()), SchemaRegistry::load_embedded());
+        let live_has_tiny = || {
+            let live = store.registry();
+            live.get_table("users").unwrap().variants["name"].contains_key("tiny")
+        };
+
+        let variant = FieldVariant {
+            base: "h4".to_string(),
+            extends: None,
+            override_class: Some("text-sm".to_string()),
+            extend: None,
+            attrs: None,
+            kind: None,
+            options: None,
+            max_length: None,
+            overflow: None,
+            field_type: None,
+            deprecated: None,
+        };
+        let outcome = put_variant(&store, "users", "name", "tiny", variant).unwrap();
+        assert!(outcome.persisted);
+        assert!(store.draft_path("users").unwrap().is_file());
+
+        // Drafts are invisible to production renders until published
+        assert!(!live_has_tiny());
+        assert!(draft(&store, "users").unwrap().variants["name"].contains_key("tiny"));
+
+        publish(&store, "users").unwrap();
+        assert!(live_has_tiny());
+        assert!(!store.draft_path("users").unwrap().is_file());
+        assert!(matches!(
+            publish(&store, "users"),
+            Err(SchemaEditError::NoDraft(_))
+        ));
+
+        let saved = TableSchema::from_file(&store.table_path("users").unwrap()).unwrap();
+        assert_eq!(saved.variants["name"]["tiny"].base, "h4");
+
+        // The first publish records both the original and the new bundle
+        let versions = history(&store);
+        assert_eq!(versions.len(), 2);
+        assert!(store.bundle_path(versions[1]).unwrap().is_dir());
+        rollback(&store, versions[0]).unwrap();
+        assert!(!live_has_tiny());
+        let restored = TableSchema::from_file(&store.table_path("users").unwrap()).unwrap();
+        assert!(!restored.variants["name"].contains_key("tiny"));
+        assert!(matches!(
+            rollback(&store, 999),
+            Err(SchemaEditError::VersionNotFound(999))
+        ));
+
+        let bad_context = Context {
+            inherits: None,
+            deprecated: None,
+            sections: HashMap::new(),
+            required: Vec::new(),
+            fields: HashMap::from([("name".to_string(), "missing".to_string())]),
+        };
+        assert!(matches!(
+            put_context(&store, "users", "broken", bad_context),
+            Err(SchemaEditError::Invalid(_))
+        ));
+        assert!(draft(&store, "users").is_none());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_unknown_or_unsafe_table_names_never_touch_the_directory() {
+        let dir = std::env::temp_dir().join(format!("uuie_store_names_{}", std::process::id()));
+        let store = SchemaStore::with_registry(Some(dir.clone()), SchemaRegistry::load_embedded());
+        for name in ["../users", "..", "users/../users", ".history", ""] {
+            assert!(store.table_path(name).is_none(), "{}", name);
+            assert!(store.draft_path(name).is_none(), "{}", name);
+        }
+
+        // A draft-shaped file next to the traversal target is never read
+        let outside = dir.join("outside");
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::copy(
+            "schemas/users/users.toml",
+            outside.join("outside.draft.toml"),
+        )
+        .unwrap();
+        let context = Context {
+            inherits: None,
+            deprecated: None,
+            sections: HashMap::new(),
+            required: Vec::new(),
+            fields: HashMap::new(),
+        };
+        let name = "users/../outside";
+        assert!(matches!(
+            put_context(&store, name, "summary", context),
+            Err(SchemaEditError::TableNotFound(_))
+        ));
+        assert!(matches!(
+            publish(&store, name),
+            Err(SchemaEditError::TableNotFound(_))
+        ));
+        assert!(draft(&store, name).is_none());
+        assert!(!dir.join("users").exists());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_context_round_trips_through_editor_json() {
+        let store = SchemaStore::with_registry(None, SchemaRegistry::load_embedded());
+        // What GET /api/schemas/users returns for the context, posted back as is
+        let posted = serde_json::json!({
+            "inherits": "card",
+            "deprecated": "use detail instead",
+            "sections": { "profile": { "heading": "Profile", "order": 1, "fields": ["name"] } },
+            "required": ["name", "email"],
+            "name": "h1",
+            "*": "text",
+        });
+        let context: Context = serde_json::from_value(posted.clone()).unwrap();
+        put_context(&store, "users", "summary", context).unwrap();
+
+        let saved = &draft(&store, "users").unwrap().contexts["summary"];
+        assert_eq!(serde_json::to_value(saved).unwrap(), posted);
+    }
+
+    #[test]
+    fn test_publish_keeps_environment_overlay_out_of_base_file() {
+        let dir = std::env::temp_dir().join(format!("uuie_store_overlay_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("users")).unwrap();
+        std::fs::copy("schemas/users/users.toml", dir.join("users/users.toml")).unwrap();
+        let overlay = format!("users/users.{}.toml", config().environment);
+        std::fs::write(dir.join(overlay), "[variants.name.h1]\nbase = \"h2\"\n").unwrap();
+
+        let loaded = SchemaRegistry::load_from_dir(&dir).unwrap();
+        assert_eq!(
+            loaded.get_table("users").unwrap().variants["name"]["h1"].base,
+            "h2"
+        );
+        let store = SchemaStore::with_registry(Some(dir.clone()), loaded);
+
+        let live = store.registry();
+        let mut variant = live.get_table("users").unwrap().variants["name"]["h1"].clone();
+        variant.override_class = Some("text-sm".to_string());
+        put_variant(&store, "users", "name", "tiny", variant).unwrap();
+        publish(&store, "users").unwrap();
+
+        // The base file keeps its own h1 and gains the edit; the overlay still applies live
+        let saved = TableSchema::from_unmerged_file(&store.table_path("users").unwrap()).unwrap();
+        assert_eq!(saved.variants["name"]["h1"].base, "h1");
+        assert!(saved.variants["name"].contains_key("tiny"));
+        let live = store.registry();
+        assert_eq!(
+            live.get_table("users").unwrap().variants["name"]["h1"].base,
+            "h2"
+        );
+        assert!(live.get_table("users").unwrap().variants["name"].contains_key("tiny"));
+        let reloaded = SchemaRegistry::load_from_dir(&dir).unwrap();
+        assert_eq!(
+            reloaded.get_table("users").unwrap().variants["name"]["h1"].base,
+            "h2"
+        );
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_rollback_restores_table_files_without_overlay() {
+        let dir = std::env::temp_dir().join(format!("uuie_store_rollback_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("users")).unwrap();
+        std::fs::copy("schemas/users/users.toml", dir.join("users/users.toml")).unwrap();
+        let overlay = format!("users/users.{}.toml", config().environment);
+        std::fs::write(dir.join(overlay), "[variants.name.h1]\nbase = \"h2\"\n").unwrap();
+        let store = SchemaStore::with_registry(
+            Some(dir.clone()),
+            SchemaRegistry::load_from_dir(&dir).unwrap(),
+        );
+
+        let mut variant =
+            store.registry().get_table("users").unwrap().variants["name"]["h1"].clone();
+        variant.override_class = Some("text-sm".to_string());
+        put_variant(&store, "users", "name", "tiny", variant).unwrap();
+        publish(&store, "users").unwrap();
+        let versions = history(&store);
+
+        let base_h1 = |path: PathBuf| {
+            let saved = TableSchema::from_unmerged_file(&path).unwrap();
+            saved.variants["name"]["h1"].base.clone()
+        };
+        let snapshot = |version| {
+            let path = store.bundle_path(version).unwrap().join("users/users.toml");
+            base_h1(path)
+        };
+        assert_eq!(snapshot(versions[0]), "h1");
+        assert_eq!(snapshot(versions[1]), "h1");
+
+        rollback(&store, versions[0]).unwrap();
+        assert_eq!(base_h1(store.table_path("users").unwrap()), "h1");
+        let live = store.registry();
+        let live_users = live.get_table("users").unwrap();
+        assert_eq!(live_users.variants["name"]["h1"].base, "h2");
+        assert!(!live_users.variants["name"].contains_key("tiny"));
+
+        // A fresh store restores from the files in .history alone
+        let fresh = SchemaStore::with_registry(Some(dir.clone()), (*live).clone());
+        rollback(&fresh, versions[1]).unwrap();
+        let saved = TableSchema::from_unmerged_file(&fresh.table_path("users").unwrap()).unwrap();
+        assert_eq!(saved.variants["name"]["h1"].base, "h1");
+        assert!(saved.variants["name"].contains_key("tiny"));
+        let live = fresh.registry();
+        assert_eq!(
+            live.get_table("users").unwrap().variants["name"]["h1"].base,
+            "h2"
+        );
+        let reloaded = SchemaRegistry::load_from_dir(&dir).unwrap();
+        assert_eq!(
+            reloaded.get_table("users").unwrap().variants["name"]["h1"].base,
+            "h2"
+        );
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,282 @@
+// src/search.rs - Full-text search over declared fields
+//
+// Tables opt in by listing the fields to index:
+//   [search]
+//   fields = ["name", "email"]   # earlier fields rank higher
+//   language = "english"         # Postgres text search configuration
+//   snippet = "name"             # field snippets are cut from (default: first)
+//
+// `codegen search` writes the migration adding a weighted tsvector column and
+// GIN index per table; Postgres then answers GET /api/:table/search?q= with
+// ranked `websearch_to_tsquery` matches. Other sources match words in memory.
+// Matched terms in snippets render through the snippet field's `highlight`
+// variant, else the `_default` schema's.
+use crate::data_source::Record;
+use crate::renderer::Renderer;
+use crate::schema::{DEFAULT_TABLE, FieldRenderOptions, SchemaRegistry};
+use crate::variant_kinds::escape_attr;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+// Snippets mark matched terms with these, so values are never parsed as HTML
+pub(crate) const MATCH_START: char = '\u{2}';
+pub(crate) const MATCH_END: char = '\u{3}';
+// Generated column holding the search vector
+pub const SEARCH_COLUMN: &str = "search_vector";
+// Rank weights for the first, second, third and remaining fields
+const WEIGHTS: [(char, f64); 4] = [('A', 1.0), ('B', 0.4), ('C', 0.2), ('D', 0.1)];
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SearchMapping {
+    pub fields: Vec<String>,
+    #[serde(default = "default_language")]
+    pub language: String,
+    #[serde(default)]
+    pub snippet: Option<String>,
+}
+
+fn default_language() -> String {
+    "english".to_string()
+}
+
+impl SearchMapping {
+    pub fn snippet_field(&self) -> &str {
+        self.snippet
+            .as_deref()
+            .or(self.fields.first().map(String::as_str))
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchHit {
+    pub record: Record,
+    pub rank: f64,
+    // Snippet field text with matches between MATCH_START and MATCH_END
+    pub snippet: String,
+}
+
+// 🛠️ Migration adding the search column and index for every searchable table
+pub fn search_migration(registry: &SchemaRegistry) -> String {
+    let mut tables: Vec<_> = registry.list_tables();
+    tables.sort();
+    let mut sql = String::from("-- Full-text search columns, generated by `codegen search`\n");
+    for table in tables {
+        if let Some(mapping) = registry.get_table(table).and_then(|s| s.search.as_ref()) {
+            sql.push_str(&table_migration(table, mapping));
+        }
+    }
+    sql
+}
+
+fn table_migration(table: &str, mapping: &SearchMapping) -> String {
+    let vector = mapping
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let (weight, _) = WEIGHTS[i.min(WEIGHTS.len() - 1)];
+            format!(
+                "setweight(to_tsvector('{}', coalesce({}::text, '')), '{}')",
+                mapping.language, field, weight
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" || ");
+    format!(
+        "\nALTER TABLE {table} ADD COLUMN IF NOT EXISTS {column} tsvector\n    \
+         GENERATED ALWAYS AS ({vector}) STORED;\n\
+         CREATE INDEX IF NOT EXISTS {table}_{column}_idx ON {table} USING GIN ({column});\n",
+        table = table,
+        column = SEARCH_COLUMN,
+        vector = vector
+    )
+}
+
+// 🔎 In-memory search: records containing every query word in a searchable
+// field, ranked by the weight of the fields the words were found in
+pub fn search_records(
+    records: Vec<Record>,
+    mapping: &SearchMapping,
+    query: &str,
+    limit: Option<usize>,
+) -> Vec<SearchHit> {
+    let terms = words(query);
+    if terms.is_empty() {
+        return Vec::new();
+    }
+    let mut hits: Vec<_> = records
+        .into_iter()
+        .filter_map(|record| {
+            let mut rank = 0.0;
+            let mut found = vec![false; terms.len()];
+            for (i, field) in mapping.fields.iter().enumerate() {
+                let Some(value) = record.get(field) else {
+                    continue;
+                };
+                let (_, weight) = WEIGHTS[i.min(WEIGHTS.len() - 1)];
+                for word in words(value) {
+                    if let Some(t) = terms.iter().position(|term| *term == word) {
+                        found[t] = true;
+                        rank += weight;
+                    }
+                }
+            }
+            if !found.iter().all(|found| *found) {
+                return None;
+            }
+            let text = record
+                .get(mapping.snippet_field())
+                .cloned()
+                .unwrap_or_default();
+            Some(SearchHit {
+                snippet: mark_terms(&text, &terms),
+                record,
+                rank,
+            })
+        })
+        .collect();
+    hits.sort_by(|a, b| {
+        b.rank
+            .partial_cmp(&a.rank)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.record.get("id").cmp(&b.record.get("id")))
+    });
+    if let Some(limit) = limit {
+        hits.truncate(limit);
+    }
+    hits
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+// Wrap the words of `text` matching a term in MATCH_START/MATCH_END
+fn mark_terms(text: &str, terms: &[String]) -> String {
+    let mut marked = String::with_capacity(text.len());
+    let mut word = String::new();
+    let flush = |word: &mut String, marked: &mut String| {
+        if terms.contains(&word.to_lowercase()) {
+            marked.push(MATCH_START);
+            marked.push_str(word);
+            marked.push(MATCH_END);
+        } else {
+            marked.push_str(word);
+        }
+        word.clear();
+    };
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut marked);
+            marked.push(c);
+        }
+    }
+    flush(&mut word, &mut marked);
+    marked
+}
+
+// ✨ A snippet as HTML: plain text escaped, matches through the `highlight`
+// variant of `field` (or of the `_default` schema)
+pub fn render_snippet(
+    registry: &SchemaRegistry,
+    table: &str,
+    field: &str,
+    snippet: &str,
+) -> String {
+    let highlight = |term: &str| {
+        let render = |table, field| {
+            let options = FieldRenderOptions::default();
+            registry.render_variant_with(table, field, "highlight", term, options)
+        };
+        render(table, field)
+            .or_else(|| render(DEFAULT_TABLE, "*"))
+            .unwrap_or_else(|| format!("<mark>{}</mark>", escape_attr(term)))
+    };
+    let mut html = String::new();
+    let mut rest = snippet;
+    while let Some(start) = rest.find(MATCH_START) {
+        html.push_str(&escape_attr(&rest[..start]));
+        let after = &rest[start + MATCH_START.len_utf8()..];
+        let end = after.find(MATCH_END).unwrap_or(after.len());
+        html.push_str(&highlight(&after[..end]));
+        rest = after.get(end + MATCH_END.len_utf8()..).unwrap_or_default();
+    }
+    html.push_str(&escape_attr(rest));
+    html
+}
+
+// Hits as HTML: per hit its snippet, then the record rendered in `context`
+pub fn render_results(
+    registry: Arc<SchemaRegistry>,
+    table: &str,
+    mapping: &SearchMapping,
+    context: &str,
+    hits: &[SearchHit],
+) -> String {
+    let renderer = Renderer::with_registry(registry.clone());
+    let mut html = format!(r#"<div data-sui-search="{}">"#, escape_attr(table));
+    for hit in hits {
+        match hit.record.get("id") {
+            Some(id) => html.push_str(&format!(r#"<div data-id="{}">"#, escape_attr(id))),
+            None => html.push_str("<div>"),
+        }
+        let snippet = render_snippet(&registry, table, mapping.snippet_field(), &hit.snippet);
+        html.push_str(&format!("<p data-sui-snippet>{}</p>", snippet));
+        html.push_str(&renderer.render_record_html(table, context, &hit.record));
+        html.push_str("</div>");
+    }
+    html.push_str("</div>");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn mapping() -> SearchMapping {
+        SearchMapping {
+            fields: vec!["title".to_string(), "body".to_string()],
+            language: default_language(),
+            snippet: Some("body".to_string()),
+        }
+    }
+
+    fn record(id: &str, title: &str, body: &str) -> Record {
+        HashMap::from([
+            ("id".to_string(), id.to_string()),
+            ("title".to_string(), title.to_string()),
+            ("body".to_string(), body.to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_search_ranks_and_highlights() {
+        let records = vec![
+            record("1", "Gardening", "Rust on garden tools"),
+            record("2", "Rust tips", "Writing safe Rust code"),
+            record("3", "Cooking", "Nothing relevant"),
+        ];
+        let hits = search_records(records, &mapping(), "rust", None);
+        let ids: Vec<_> = hits.iter().map(|hit| hit.record["id"].as_str()).collect();
+        assert_eq!(ids, ["2", "1"]);
+        assert_eq!(hits[0].snippet, "Writing safe \u{2}Rust\u{3} code");
+
+        let registry = SchemaRegistry::load_embedded();
+        let html = render_snippet(&registry, "posts", "body", "<b> \u{2}Rust\u{3}");
+        assert_eq!(html, "&lt;b&gt; <mark>Rust</mark>");
+
+        let sql = table_migration("posts", &mapping());
+        let title = "setweight(to_tsvector('english', coalesce(title::text, '')), 'A')";
+        assert!(sql.contains(title), "{}", sql);
+        let index = "CREATE INDEX IF NOT EXISTS posts_search_vector_idx ON posts USING GIN";
+        assert!(sql.contains(index), "{}", sql);
+    }
+}
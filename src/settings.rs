@@ -0,0 +1,216 @@
+// src/settings.rs - Singleton tables (site settings and the like)
+//
+// A table marked `singleton = true` holds exactly one row and is addressed
+// without an id: `GET /api/<table>` renders it, `?format=form` returns an
+// editing form built from the schema, and components over the table render
+// without `id`. Forms post to the record endpoints (see forms.rs), whose
+// per-field errors `record_form` renders into each field's error slot.
+use crate::data_source::{DataError, DataSource, Record, in_scope};
+use crate::field_types::FieldType;
+use crate::forms::FieldErrors;
+use crate::schema::SchemaRegistry;
+use crate::variant_kinds::escape_attr;
+
+// 🗂️ The one row of a singleton table (the first, should there be more)
+pub async fn singleton_record(
+    data_source: &dyn DataSource,
+    table: &str,
+    scope: &[(String, String)],
+) -> Result<Record, DataError> {
+    data_source
+        .get_records(table, Some(1))
+        .await?
+        .into_iter()
+        .find(|record| in_scope(record, scope))
+        .ok_or_else(|| DataError::NotFound(table.to_string()))
+}
+
+// 📝 One labelled input per field of `table`, sorted by name and pre-filled
+// from `record`; input types follow the table's `[types]`
+pub fn settings_form(registry: &SchemaRegistry, table: &str, record: &Record) -> Option<String> {
+    record_form(registry, table, record, &FieldErrors::new())
+}
+
+// Like `settings_form`, with `errors` shown in each field's error slot
+pub fn record_form(
+    registry: &SchemaRegistry,
+    table: &str,
+    record: &Record,
+    errors: &FieldErrors,
+) -> Option<String> {
+    let schema = registry.get_table(table)?;
+    let mut fields: Vec<&String> = schema
+        .variants
+        .keys()
+        .filter(|field| *field != "id")
+        .collect();
+    fields.sort();
+
+    let label_css = registry.get_theme_css("label");
+    let input_css = registry.get_theme_css("input");
+    let mut html = format!(r#"<form data-sui-settings="{}">"#, escape_attr(table));
+    for field in fields {
+        let value = record.get(field.as_str()).map_or("", String::as_str);
+        let id = escape_attr(&format!("{}-{}", table, field));
+        html.push_str(&format!(r#"<label for="{}""#, id));
+        if !label_css.is_empty() {
+            html.push_str(&format!(r#" class="{}""#, label_css));
+        }
+        html.push_str(&format!(">{}</label>", escape_attr(&label(field))));
+
+        let field_type = schema
+            .types
+            .get(field.as_str())
+            .copied()
+            .unwrap_or_default();
+        html.push_str(&format!(
+            r#"<input id="{}" name="{}" type="{}""#,
+            id,
+            escape_attr(field),
+            input_type(field_type)
+        ));
+        if !input_css.is_empty() && field_type != FieldType::Bool {
+            html.push_str(&format!(r#" class="{}""#, input_css));
+        }
+        match field_type {
+            FieldType::Bool => {
+                html.push_str(r#" value="true""#);
+                if matches!(
+                    value.trim().to_ascii_lowercase().as_str(),
+                    "true" | "1" | "yes"
+                ) {
+                    html.push_str(" checked");
+                }
+            }
+            // Date inputs only take the date part of a timestamp
+            FieldType::Date => {
+                let date = value.get(..10).unwrap_or(value);
+                html.push_str(&format!(r#" value="{}""#, escape_attr(date)));
+            }
+            _ => html.push_str(&format!(r#" value="{}""#, escape_attr(value))),
+        }
+        let messages = errors
+            .get(field.as_str())
+            .filter(|messages| !messages.is_empty());
+        if messages.is_some() {
+            html.push_str(&format!(
+                r#" aria-invalid="true" aria-describedby="{}-error""#,
+                id
+            ));
+        }
+        html.push_str(" />");
+        // Always present, so scripts posting as JSON can fill it in too
+        let message = messages.map_or(String::new(), |messages| messages.join("; "));
+        html.push_str(&format!(
+            r#"<p id="{}-error" data-sui-error="{}">{}</p>"#,
+            id,
+            escape_attr(field),
+            escape_attr(&message)
+        ));
+    }
+    html.push_str(r#"<button type="submit">Save</button></form>"#);
+    Some(html)
+}
+
+fn input_type(field_type: FieldType) -> &'static str {
+    match field_type {
+        FieldType::String => "text",
+        FieldType::Number => "number",
+        FieldType::Date => "date",
+        FieldType::Bool => "checkbox",
+        FieldType::Url => "url",
+        FieldType::Email => "email",
+    }
+}
+
+// "support_email" -> "Support email"
+fn label(field: &str) -> String {
+    let words = field.replace(['_', '-'], " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component_registry::{
+        ComponentError, ComponentRegistry, ComponentTemplate, RenderParams,
+    };
+    use crate::schema::{SchemaError, TableSchema, update_registry};
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_singleton_components_render_without_an_id() {
+        let branding = TableSchema::from_toml_str(
+            "singleton = true\n\
+             [variants.tagline]\np = { base = \"p\" }\n\
+             [contexts.card]\ntagline = \"p\"\n\
+             [[mock_data]]\ntagline = \"Built to last\"\n",
+        )
+        .unwrap();
+        update_registry(|registry| {
+            registry.insert_table("branding", branding);
+            Ok::<_, SchemaError>(())
+        })
+        .unwrap();
+
+        let mut registry = ComponentRegistry::new();
+        let component = ComponentTemplate::builder()
+            .name("tagline_banner")
+            .table("branding")
+            .template_str("<aside>{tagline}</aside>")
+            .build()
+            .unwrap();
+        registry.register(component).unwrap();
+
+        let html = registry
+            .render_component("tagline_banner", "", RenderParams::default())
+            .await
+            .unwrap();
+        assert_eq!(html, "<aside><p>Built to last</p></aside>");
+        // Record-bound tables still need an id
+        assert!(matches!(
+            registry
+                .render_component("user_card", "", RenderParams::default())
+                .await,
+            Err(ComponentError::MissingRecordId(_))
+        ));
+    }
+
+    #[test]
+    fn test_settings_form_follows_field_types() {
+        let mut registry = SchemaRegistry::load_embedded();
+        let users = registry.get_table_mut("users").unwrap();
+        users.types.insert("email".to_string(), FieldType::Email);
+        let record = HashMap::from([
+            ("name".to_string(), "Acme \"Inc\"".to_string()),
+            ("email".to_string(), "help@acme.io".to_string()),
+            ("created_at".to_string(), "2024-01-15T10:30:00Z".to_string()),
+        ]);
+
+        let form = settings_form(&registry, "users", &record).unwrap();
+        assert!(
+            form.starts_with(r#"<form data-sui-settings="users">"#),
+            "{}",
+            form
+        );
+        assert!(
+            form.contains(r#"<label for="users-avatar_url""#),
+            "{}",
+            form
+        );
+        assert!(form.contains(">Avatar url</label>"), "{}", form);
+        assert!(
+            form.contains(r#"name="created_at" type="date""#),
+            "{}",
+            form
+        );
+        assert!(form.contains(r#"value="2024-01-15" />"#), "{}", form);
+        assert!(form.contains(r#"name="email" type="email""#), "{}", form);
+        assert!(form.contains(r#"value="Acme &quot;Inc&quot;""#), "{}", form);
+        assert!(settings_form(&registry, "missing", &record).is_none());
+    }
+}
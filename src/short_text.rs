@@ -0,0 +1,232 @@
+// src/short_text.rs - Plain text renders (format=sms, format=push)
+//
+// Tables declare per-field `[short]` templates, e.g.
+//   name = "{value|abbrev}"
+//   created_at = "joined {value|date}"
+// and a component's fields are joined into one line: "Alice J. joined Jan 15".
+// Push payloads use per-component `[push.<component>]` templates over the
+// whole record ("{name|first} joined {created_at|date}").
+use crate::schema::{PushMapping, TableSchema};
+use serde::Serialize;
+use std::collections::HashMap;
+
+// One SMS segment; longer lines are cut with an ellipsis
+pub const MAX_SHORT_LENGTH: usize = 160;
+
+pub(crate) const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+// ✉️ Short line for `fields` (in order) of a record. Fields without a short
+// template, or missing from the record, are left out.
+pub fn short_line(
+    schema: &TableSchema,
+    fields: &[String],
+    record: &HashMap<String, String>,
+) -> String {
+    let parts: Vec<String> = fields
+        .iter()
+        .filter_map(|field| {
+            let template = schema.short.get(field)?;
+            let value = record.get(field)?;
+            Some(render_short(template, value))
+        })
+        .filter(|part| !part.trim().is_empty())
+        .collect();
+    truncate(&parts.join(" "), MAX_SHORT_LENGTH)
+}
+
+// Notification summary of a record, as sent to the push service
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PushPayload {
+    pub title: String,
+    pub body: Option<String>,
+    pub icon: Option<String>,
+    pub deep_link: Option<String>,
+}
+
+// 🔔 Fill a component's push mapping from a record. Fields missing from the
+// record render empty, and entries that end up blank are left out.
+pub fn push_payload(mapping: &PushMapping, record: &HashMap<String, String>) -> PushPayload {
+    let fill = |template: &String| {
+        let text = render_template(template, |field| {
+            Some(record.get(field).map_or("", String::as_str))
+        });
+        Some(text).filter(|text| !text.trim().is_empty())
+    };
+    PushPayload {
+        title: truncate(&fill(&mapping.title).unwrap_or_default(), MAX_SHORT_LENGTH),
+        body: mapping
+            .body
+            .as_ref()
+            .and_then(fill)
+            .map(|body| truncate(&body, MAX_SHORT_LENGTH)),
+        icon: mapping.icon.as_ref().and_then(fill),
+        deep_link: mapping.deep_link.as_ref().and_then(fill),
+    }
+}
+
+// Fill `{value}` / `{value|filter}` placeholders; anything else is literal text
+pub fn render_short(template: &str, value: &str) -> String {
+    render_template(template, |name| (name == "value").then_some(value))
+}
+
+// Fill `{field|filter}` placeholders from a record (missing fields render
+// empty), trimmed; used for `[computed]` fields
+pub(crate) fn fill_record(template: &str, record: &HashMap<String, String>) -> String {
+    let text = render_template(template, |field| {
+        Some(record.get(field).map_or("", String::as_str))
+    });
+    text.trim().to_string()
+}
+
+// Fill `{name|filter|...}` placeholders whose name `lookup` knows; unknown
+// placeholders are kept as written
+fn render_template<'v>(template: &str, lookup: impl Fn(&str) -> Option<&'v str>) -> String {
+    let mut result = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            result.push_str(&rest[start..]);
+            return result;
+        };
+
+        let mut parts = after[..end].split('|').map(str::trim);
+        if let Some(value) = parts.next().and_then(&lookup) {
+            let filtered = parts.fold(value.trim().to_string(), |value, filter| {
+                apply_filter(&value, filter)
+            });
+            result.push_str(&filtered);
+        } else {
+            result.push_str(&rest[start..start + end + 2]);
+        }
+        rest = &after[end + 1..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+// abbrev ("Alice Johnson" -> "Alice J."), date ("2024-01-15T.." -> "Jan 15"),
+// upper, lower, first (first word), truncate:N. Unknown filters are ignored.
+fn apply_filter(value: &str, filter: &str) -> String {
+    let (name, arg) = filter.split_once(':').unwrap_or((filter, ""));
+    match name {
+        "abbrev" => {
+            let words: Vec<&str> = value.split_whitespace().collect();
+            match words.as_slice() {
+                [] => String::new(),
+                [only] => only.to_string(),
+                [first, .., last] => match last.chars().next() {
+                    Some(initial) => format!("{} {}.", first, initial.to_uppercase()),
+                    None => first.to_string(),
+                },
+            }
+        }
+        "date" => short_date(value).unwrap_or_else(|| value.to_string()),
+        "upper" => value.to_uppercase(),
+        "lower" => value.to_lowercase(),
+        "first" => value
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+        "truncate" => match arg.parse() {
+            Ok(max) => truncate(value, max),
+            Err(_) => value.to_string(),
+        },
+        _ => value.to_string(),
+    }
+}
+
+// "Jan 15" from an ISO date or timestamp
+fn short_date(value: &str) -> Option<String> {
+    let date = value.get(..10)?;
+    let mut parts = date.split('-');
+    let (_year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    let month: usize = month.parse().ok()?;
+    let day: u32 = day.parse().ok()?;
+    let name = MONTHS.get(month.checked_sub(1)?)?;
+    (1..=31).contains(&day).then(|| format!("{} {}", name, day))
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max.saturating_sub(1)).collect();
+    cut.truncate(cut.trim_end().len());
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_line_uses_field_templates() {
+        let schema = TableSchema::from_toml_str(
+            r#"
+            [variants.name]
+            h1 = { base = "h1" }
+            [variants.created_at]
+            time = { base = "time" }
+            [contexts.card]
+            name = "h1"
+
+            [short]
+            name = "{value|abbrev}"
+            created_at = "joined {value|date}"
+            "#,
+        )
+        .unwrap();
+        let record = HashMap::from([
+            ("name".to_string(), "Alice  Johnson".to_string()),
+            ("created_at".to_string(), "2024-01-15T10:30:00Z".to_string()),
+            ("email".to_string(), "alice@example.com".to_string()),
+        ]);
+        let fields = ["name", "email", "created_at"].map(String::from);
+        assert_eq!(
+            short_line(&schema, &fields, &record),
+            "Alice J. joined Jan 15"
+        );
+
+        assert_eq!(
+            render_short("{value|upper|truncate:4} {other}", "hello"),
+            "HEL… {other}"
+        );
+        assert_eq!(render_short("on {value|date}", "yesterday"), "on yesterday");
+        assert_eq!(
+            truncate(&"x".repeat(200), MAX_SHORT_LENGTH).chars().count(),
+            160
+        );
+    }
+
+    #[test]
+    fn test_push_payload_from_mapping() {
+        let mapping = PushMapping {
+            title: "{name}".to_string(),
+            body: Some("{name|first} joined {created_at|date}".to_string()),
+            icon: Some("{avatar_url}".to_string()),
+            deep_link: Some("app://users/{id}".to_string()),
+        };
+        let record = HashMap::from([
+            ("id".to_string(), "7".to_string()),
+            ("name".to_string(), "Alice Johnson".to_string()),
+            ("created_at".to_string(), "2024-03-02".to_string()),
+        ]);
+        assert_eq!(
+            push_payload(&mapping, &record),
+            PushPayload {
+                title: "Alice Johnson".to_string(),
+                body: Some("Alice joined Mar 2".to_string()),
+                icon: None,
+                deep_link: Some("app://users/7".to_string()),
+            }
+        );
+    }
+}
@@ -0,0 +1,254 @@
+// src/tokens.rs - Import design tokens (Style Dictionary / Figma Tokens JSON) into themes
+//
+// Raw values ("color.primary.500") become theme tokens named the way Style
+// Dictionary names CSS variables ("color-primary-500"). Tokens under a `tag`
+// or `tags` group hold the utility classes for a tag, and may reference other
+// tokens: `"tags.h1": "text-[{color.primary.500}] font-bold"`.
+use crate::schema::Theme;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+
+// Token groups whose leaves are tag classes rather than raw values
+const TAG_GROUPS: [&str; 2] = ["tag", "tags"];
+
+// Deepest alias chain followed before assuming a cycle
+const MAX_ALIAS_DEPTH: usize = 16;
+
+#[derive(Debug, Clone)]
+pub enum TokenImportError {
+    Parse(String),
+    UnresolvedAlias(String),
+    AliasCycle(String),
+}
+
+impl std::fmt::Display for TokenImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenImportError::Parse(msg) => write!(f, "Invalid token file: {}", msg),
+            TokenImportError::UnresolvedAlias(alias) => {
+                write!(f, "Token alias '{{{}}}' does not resolve", alias)
+            }
+            TokenImportError::AliasCycle(token) => {
+                write!(f, "Token '{}' has a circular alias", token)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TokenImportError {}
+
+#[derive(Debug, Default)]
+pub struct TokenImport {
+    pub themes: HashMap<String, Theme>,
+    // Tokens that have no theme representation (composite values), by path
+    pub skipped: Vec<String>,
+}
+
+// 🎨 Convert a token export into themes. A Figma Tokens export with several
+// token sets yields one theme per set; a Style Dictionary file yields a single
+// theme called `default_theme`.
+pub fn import_tokens(json: &str, default_theme: &str) -> Result<TokenImport, TokenImportError> {
+    let document: Value =
+        serde_json::from_str(json).map_err(|e| TokenImportError::Parse(e.to_string()))?;
+    let Value::Object(root) = document else {
+        return Err(TokenImportError::Parse(
+            "expected a JSON object".to_string(),
+        ));
+    };
+
+    let mut import = TokenImport::default();
+    let sets: Vec<(String, BTreeMap<String, String>)> = match token_set_order(&root) {
+        Some(order) => order
+            .into_iter()
+            .filter_map(|name| {
+                let set = root.get(&name)?;
+                Some((name, flatten_tokens(set, &mut import.skipped)))
+            })
+            .collect(),
+        None => vec![(
+            default_theme.to_string(),
+            flatten_tokens(&Value::Object(root), &mut import.skipped),
+        )],
+    };
+
+    // Aliases may point into any set, but the set's own tokens win
+    let mut all_tokens = BTreeMap::new();
+    for (_, tokens) in &sets {
+        all_tokens.extend(tokens.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+
+    for (name, tokens) in &sets {
+        let mut theme = Theme {
+            tokens: HashMap::new(),
+            tags: HashMap::new(),
+        };
+        for (path, raw) in tokens {
+            let value = resolve_aliases(path, raw, tokens, &all_tokens, 0)?;
+            match path.split_once('.') {
+                Some((group, tag)) if TAG_GROUPS.contains(&group) => {
+                    theme.tags.insert(tag.to_string(), value);
+                }
+                _ => {
+                    theme.tokens.insert(path.replace('.', "-"), value);
+                }
+            }
+        }
+        import.themes.insert(name.clone(), theme);
+    }
+
+    Ok(import)
+}
+
+// Render themes as a themes.toml document (keys sorted for stable diffs)
+pub fn themes_to_toml(themes: &HashMap<String, Theme>) -> Result<String, TokenImportError> {
+    let table =
+        toml::Value::try_from(themes).map_err(|e| TokenImportError::Parse(e.to_string()))?;
+    toml::to_string_pretty(&table).map_err(|e| TokenImportError::Parse(e.to_string()))
+}
+
+// Token sets of a Figma Tokens (Tokens Studio) export, in declared order
+fn token_set_order(root: &Map<String, Value>) -> Option<Vec<String>> {
+    let order = root.get("$metadata")?.get("tokenSetOrder")?.as_array()?;
+    Some(
+        order
+            .iter()
+            .filter_map(|name| name.as_str().map(str::to_string))
+            .collect(),
+    )
+}
+
+// Flatten nested token groups into dotted paths. A token is any object with a
+// `value` (Style Dictionary) or `$value` (W3C / Figma) key.
+fn flatten_tokens(group: &Value, skipped: &mut Vec<String>) -> BTreeMap<String, String> {
+    let mut tokens = BTreeMap::new();
+    collect_tokens(group, "", &mut tokens, skipped);
+    tokens
+}
+
+fn collect_tokens(
+    node: &Value,
+    path: &str,
+    tokens: &mut BTreeMap<String, String>,
+    skipped: &mut Vec<String>,
+) {
+    let Value::Object(entries) = node else {
+        return;
+    };
+
+    if let Some(value) = entries.get("$value").or_else(|| entries.get("value")) {
+        match value {
+            Value::String(s) => {
+                tokens.insert(path.to_string(), s.clone());
+            }
+            Value::Number(n) => {
+                tokens.insert(path.to_string(), n.to_string());
+            }
+            Value::Bool(b) => {
+                tokens.insert(path.to_string(), b.to_string());
+            }
+            _ => skipped.push(path.to_string()),
+        }
+        return;
+    }
+
+    for (key, child) in entries {
+        // `$type`, `$description`, `$metadata`, ... are annotations, not groups
+        if key.starts_with('$') {
+            continue;
+        }
+        let child_path = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", path, key)
+        };
+        collect_tokens(child, &child_path, tokens, skipped);
+    }
+}
+
+// Replace every `{path.to.token}` reference with the referenced value
+fn resolve_aliases(
+    token: &str,
+    raw: &str,
+    local: &BTreeMap<String, String>,
+    global: &BTreeMap<String, String>,
+    depth: usize,
+) -> Result<String, TokenImportError> {
+    if depth > MAX_ALIAS_DEPTH {
+        return Err(TokenImportError::AliasCycle(token.to_string()));
+    }
+
+    let mut resolved = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let alias = &rest[start + 1..start + len];
+        let target = local
+            .get(alias)
+            .or_else(|| global.get(alias))
+            .ok_or_else(|| TokenImportError::UnresolvedAlias(alias.to_string()))?;
+
+        resolved.push_str(&rest[..start]);
+        resolved.push_str(&resolve_aliases(token, target, local, global, depth + 1)?);
+        rest = &rest[start + len + 1..];
+    }
+    resolved.push_str(rest);
+
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_style_dictionary_import() {
+        let json = r##"{
+            "color": {
+                "brand": { "500": { "value": "#3b82f6", "type": "color" } },
+                "text": { "value": "{color.brand.500}" }
+            },
+            "shadow": { "card": { "value": { "x": 0, "y": 1 } } },
+            "tags": {
+                "h1": { "value": "text-4xl font-bold text-[{color.text}]" },
+                "link": { "value": "underline" }
+            }
+        }"##;
+
+        let import = import_tokens(json, "brand").unwrap();
+        let theme = &import.themes["brand"];
+        assert_eq!(theme.tokens["color-text"], "#3b82f6");
+        assert_eq!(theme.tags["h1"], "text-4xl font-bold text-[#3b82f6]");
+        assert_eq!(theme.tags["link"], "underline");
+        assert_eq!(import.skipped, vec!["shadow.card".to_string()]);
+
+        let toml = themes_to_toml(&import.themes).unwrap();
+        let reparsed: crate::schema::ThemeConfig = toml::from_str(&toml).unwrap();
+        assert_eq!(reparsed.themes["brand"].tags["link"], "underline");
+        assert_eq!(
+            reparsed.themes["brand"].tokens["color-brand-500"],
+            "#3b82f6"
+        );
+    }
+
+    #[test]
+    fn test_figma_token_sets_become_themes() {
+        let json = r##"{
+            "global": { "gray": { "900": { "$value": "#111827", "$type": "color" } } },
+            "light": { "tag": { "h2": { "$value": "text-[{gray.900}]" } } },
+            "dark": { "tag": { "h2": { "$value": "text-[{missing}]" } } },
+            "$metadata": { "tokenSetOrder": ["global", "light"] }
+        }"##;
+
+        let import = import_tokens(json, "unused").unwrap();
+        assert_eq!(import.themes["light"].tags["h2"], "text-[#111827]");
+        assert!(!import.themes.contains_key("dark"));
+
+        let broken = r#"{ "a": { "value": "{b}" }, "b": { "value": "{a}" } }"#;
+        assert!(matches!(
+            import_tokens(broken, "t"),
+            Err(TokenImportError::AliasCycle(_))
+        ));
+    }
+}
@@ -0,0 +1,379 @@
+// src/tree.rs - Nested rendering of self-referential tables
+//
+// Tables declaring `[tree] parent = "parent_id"` (categories, org charts,
+// comment threads) can render a record with all its descendants
+// (`format=tree`): each node through the component, as nested <ul> lists.
+// Items carry `data-depth` and the theme's `tree-depth-<n>` classes; the
+// outer list uses `tree`, child lists `tree-children`.
+//
+// Components listed under `[threads.<component>]` render as comment threads:
+// replies are ordered (`order = "newest"`, by `sort_by`) and each level shows
+// `page_size` of them, followed by a "load more" marker fetching the next page.
+use crate::data_source::Record;
+use crate::schema::SchemaRegistry;
+use crate::variant_kinds::escape_attr;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TreeMapping {
+    // Field holding the parent record's id
+    pub parent: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThreadOrder {
+    #[default]
+    Oldest,
+    Newest,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ThreadMapping {
+    // Field replies are ordered by (ISO timestamps order as text)
+    #[serde(default = "default_sort_by")]
+    pub sort_by: String,
+    #[serde(default)]
+    pub order: ThreadOrder,
+    // Replies shown per level before a "load more" marker; None shows all
+    #[serde(default)]
+    pub page_size: Option<usize>,
+}
+
+fn default_sort_by() -> String {
+    "created_at".to_string()
+}
+
+// URL of the page of replies to (parent id) from (offset) on
+pub type LoadMoreUrl<'a> = &'a dyn Fn(&str, usize) -> String;
+
+#[derive(Default)]
+pub struct TreeOptions<'a> {
+    // Reply order and paging, for components declared as threads
+    pub thread: Option<&'a ThreadMapping>,
+    // Render only the root's replies from this index on (a "load more" page)
+    pub offset: usize,
+    // Where "load more" markers link to; without one they are plain text
+    pub load_more: Option<LoadMoreUrl<'a>>,
+}
+
+// 🌳 `root` and its `descendants` as nested lists, with `render` producing
+// each node's content. Records whose parent isn't in the tree are left out,
+// and a record reached twice (a cycle in the data) renders once. With an
+// offset, only the root's list items from there on are returned.
+pub fn render_tree<E>(
+    registry: &SchemaRegistry,
+    mapping: &TreeMapping,
+    root: &Record,
+    descendants: &[Record],
+    options: &TreeOptions<'_>,
+    mut render: impl FnMut(&Record) -> Result<String, E>,
+) -> Result<String, E> {
+    let mut children: HashMap<&str, Vec<&Record>> = HashMap::new();
+    for record in descendants {
+        if let Some(parent) = record.get(&mapping.parent) {
+            children.entry(parent).or_default().push(record);
+        }
+    }
+    if let Some(thread) = options.thread {
+        for replies in children.values_mut() {
+            replies.sort_by(|a, b| a.get(&thread.sort_by).cmp(&b.get(&thread.sort_by)));
+            if thread.order == ThreadOrder::Newest {
+                replies.reverse();
+            }
+        }
+    }
+
+    let mut tree = TreeHtml {
+        registry,
+        options,
+        children,
+        visited: HashSet::new(),
+    };
+    match root.get("id") {
+        Some(id) if options.offset > 0 => {
+            tree.visited.insert(id);
+            tree.items(id, 1, options.offset, &mut render)
+        }
+        _ => Ok(format!(
+            "{}{}</ul>",
+            open_list(registry, "tree"),
+            tree.node(root, 0, &mut render)?
+        )),
+    }
+}
+
+struct TreeHtml<'a> {
+    registry: &'a SchemaRegistry,
+    options: &'a TreeOptions<'a>,
+    children: HashMap<&'a str, Vec<&'a Record>>,
+    visited: HashSet<&'a str>,
+}
+
+impl<'a> TreeHtml<'a> {
+    fn node<E>(
+        &mut self,
+        record: &'a Record,
+        depth: usize,
+        render: &mut impl FnMut(&Record) -> Result<String, E>,
+    ) -> Result<String, E> {
+        let id = record.get("id").map(String::as_str);
+        if let Some(id) = id
+            && !self.visited.insert(id)
+        {
+            return Ok(String::new());
+        }
+
+        let css = self
+            .registry
+            .get_theme_css(&format!("tree-depth-{}", depth));
+        let mut html = String::from("<li");
+        if !css.is_empty() {
+            html.push_str(&format!(r#" class="{}""#, css));
+        }
+        html.push_str(&format!(r#" data-depth="{}">"#, depth));
+        html.push_str(&render(record)?);
+        if let Some(id) = id {
+            let items = self.items(id, depth + 1, 0, render)?;
+            if !items.is_empty() {
+                html.push_str(&open_list(self.registry, "tree-children"));
+                html.push_str(&items);
+                html.push_str("</ul>");
+            }
+        }
+        html.push_str("</li>");
+        Ok(html)
+    }
+
+    // List items for the children of `parent` from `offset` on: one page for
+    // threads, then a "load more" marker when replies remain
+    fn items<E>(
+        &mut self,
+        parent: &str,
+        depth: usize,
+        offset: usize,
+        render: &mut impl FnMut(&Record) -> Result<String, E>,
+    ) -> Result<String, E> {
+        let mut children = self.children.get(parent).cloned().unwrap_or_default();
+        children.retain(|child| {
+            child
+                .get("id")
+                .is_none_or(|id| !self.visited.contains(id.as_str()))
+        });
+        let start = offset.min(children.len());
+        let end = self
+            .options
+            .thread
+            .and_then(|thread| thread.page_size)
+            .map_or(children.len(), |size| (start + size).min(children.len()));
+
+        let mut html = String::new();
+        for child in &children[start..end] {
+            html.push_str(&self.node(child, depth, render)?);
+        }
+        if end < children.len() {
+            html.push_str(&self.load_more(parent, end, children.len() - end));
+        }
+        Ok(html)
+    }
+
+    fn load_more(&self, parent: &str, offset: usize, remaining: usize) -> String {
+        let css = self.registry.get_theme_css("tree-load-more");
+        let mut html = String::from("<li");
+        if !css.is_empty() {
+            html.push_str(&format!(r#" class="{}""#, css));
+        }
+        html.push_str(&format!(
+            r#" data-sui-load-more data-parent="{}" data-offset="{}">"#,
+            escape_attr(parent),
+            offset
+        ));
+        let label = format!("Load {} more", remaining);
+        match self.options.load_more {
+            Some(url) => html.push_str(&format!(
+                r#"<a href="{}">{}</a>"#,
+                escape_attr(&url(parent, offset)),
+                label
+            )),
+            None => html.push_str(&label),
+        }
+        html.push_str("</li>");
+        html
+    }
+}
+
+fn open_list(registry: &SchemaRegistry, tag: &str) -> String {
+    match registry.get_theme_css(tag) {
+        css if css.is_empty() => "<ul>".to_string(),
+        css => format!(r#"<ul class="{}">"#, css),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component_registry::{ComponentRegistry, ComponentTemplate, RenderParams};
+    use crate::schema::{SchemaError, TableSchema, update_registry};
+
+    fn record(id: &str, parent: &str) -> Record {
+        HashMap::from([
+            ("id".to_string(), id.to_string()),
+            ("parent_id".to_string(), parent.to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_render_tree_nests_by_parent() {
+        let mapping = TreeMapping {
+            parent: "parent_id".to_string(),
+        };
+        // "1" reappears below "3": a cycle in the data renders once
+        let descendants = [
+            record("2", "1"),
+            record("3", "2"),
+            record("4", "1"),
+            record("1", "3"),
+        ];
+        let html = render_tree(
+            &SchemaRegistry::new(),
+            &mapping,
+            &record("1", ""),
+            &descendants,
+            &TreeOptions::default(),
+            |record| Ok::<_, ()>(record["id"].clone()),
+        )
+        .unwrap();
+        assert_eq!(
+            html,
+            "<ul><li data-depth=\"0\">1<ul>\
+             <li data-depth=\"1\">2<ul><li data-depth=\"2\">3</li></ul></li>\
+             <li data-depth=\"1\">4</li>\
+             </ul></li></ul>"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tree_component_renders_descendants() {
+        let categories = TableSchema::from_toml_str(
+            "[tree]\nparent = \"parent_id\"\n\
+             [variants.name]\nplain = { base = \"b\" }\n\
+             [contexts.card]\nname = \"plain\"\n\
+             [[mock_data]]\nid = \"c1\"\nname = \"Books\"\n\
+             [[mock_data]]\nid = \"c2\"\nparent_id = \"c1\"\nname = \"Fiction\"\n\
+             [[mock_data]]\nid = \"c3\"\nparent_id = \"c2\"\nname = \"Poetry\"\n\
+             [[mock_data]]\nid = \"c4\"\nname = \"Music\"\n",
+        )
+        .unwrap();
+        update_registry(|registry| {
+            registry.insert_table("categories", categories);
+            Ok::<_, SchemaError>(())
+        })
+        .unwrap();
+
+        let mut registry = ComponentRegistry::new();
+        let component = ComponentTemplate::builder()
+            .name("category_node")
+            .table("categories")
+            .template_str("{name}")
+            .build()
+            .unwrap();
+        registry.register(component).unwrap();
+
+        let html = registry
+            .render_component_tree("category_node", "c2", 0, RenderParams::default())
+            .await
+            .unwrap();
+        assert!(html.starts_with("<ul"), "{}", html);
+        assert!(
+            html.contains(r#"data-depth="0"><b>Fiction</b>"#),
+            "{}",
+            html
+        );
+        assert!(
+            html.contains(r#"data-depth="1"><b>Poetry</b></li>"#),
+            "{}",
+            html
+        );
+        assert!(
+            !html.contains("Books") && !html.contains("Music"),
+            "{}",
+            html
+        );
+        // Tables without [tree] can't render one
+        assert!(
+            registry
+                .render_component_tree("user_card", "1", 0, RenderParams::default())
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_thread_component_orders_and_pages_replies() {
+        let comments = TableSchema::from_toml_str(
+            "[tree]\nparent = \"parent_id\"\n\
+             [threads.comment_thread]\norder = \"newest\"\npage_size = 2\n\
+             [variants.body]\nplain = { base = \"p\" }\n\
+             [contexts.card]\nbody = \"plain\"\n\
+             [[mock_data]]\nid = \"p1\"\nbody = \"Post\"\n\
+             [[mock_data]]\nid = \"r1\"\nparent_id = \"p1\"\nbody = \"First\"\n\
+             created_at = \"2024-01-01T09:00:00Z\"\n\
+             [[mock_data]]\nid = \"r2\"\nparent_id = \"p1\"\nbody = \"Second\"\n\
+             created_at = \"2024-01-02T09:00:00Z\"\n\
+             [[mock_data]]\nid = \"r3\"\nparent_id = \"p1\"\nbody = \"Third\"\n\
+             created_at = \"2024-01-03T09:00:00Z\"\n\
+             [[mock_data]]\nid = \"r4\"\nparent_id = \"r1\"\nbody = \"Nested\"\n",
+        )
+        .unwrap();
+        update_registry(|registry| {
+            registry.insert_table("comments", comments);
+            Ok::<_, SchemaError>(())
+        })
+        .unwrap();
+
+        let mut registry = ComponentRegistry::new();
+        let component = ComponentTemplate::builder()
+            .name("comment_thread")
+            .table("comments")
+            .template_str("{body}")
+            .build()
+            .unwrap();
+        registry.register(component).unwrap();
+
+        let html = registry
+            .render_component_tree("comment_thread", "p1", 0, RenderParams::default())
+            .await
+            .unwrap();
+        let third = html.find("Third").unwrap();
+        assert!(third < html.find("Second").unwrap(), "{}", html);
+        assert!(!html.contains("First"), "{}", html);
+        assert!(
+            html.contains(r#"data-sui-load-more data-parent="p1" data-offset="2">"#),
+            "{}",
+            html
+        );
+        assert!(
+            html.contains(r#"href="/api/comment_thread?id=p1&amp;format=tree&amp;offset=2""#),
+            "{}",
+            html
+        );
+
+        // The next page holds the oldest reply and its own replies, without the post
+        let page = registry
+            .render_component_tree("comment_thread", "p1", 2, RenderParams::default())
+            .await
+            .unwrap();
+        assert!(page.starts_with(r#"<li"#), "{}", page);
+        assert!(
+            page.contains("First") && page.contains("Nested"),
+            "{}",
+            page
+        );
+        assert!(
+            !page.contains("Post") && !page.contains("load-more"),
+            "{}",
+            page
+        );
+    }
+}
@@ -0,0 +1,573 @@
+// src/validation.rs - Whole-schema checks with structured diagnostics
+//
+// Most schema mistakes still parse: a context pointing at a misspelled variant
+// silently falls through to the defaults, and a section listing an unknown
+// field just renders nothing. `SchemaRegistry::validate` reports them up front.
+use crate::config::config;
+use crate::schema::{TableSchema, WILDCARD_FIELD};
+use crate::variant_kinds::KNOWN_KINDS;
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    // The schema renders differently than written
+    Error,
+    // Probably a mistake, but renders as written
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub table: String,
+    // Dotted path to the offending entry, e.g. "contexts.card.name"
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(
+            f,
+            "{}: {}: {}: {}",
+            severity, self.table, self.path, self.message
+        )
+    }
+}
+
+pub(crate) struct Diagnostics<'a> {
+    table: &'a str,
+    found: Vec<Diagnostic>,
+}
+
+impl<'a> Diagnostics<'a> {
+    pub(crate) fn new(table: &'a str) -> Self {
+        Self {
+            table,
+            found: Vec::new(),
+        }
+    }
+
+    pub(crate) fn into_found(self) -> Vec<Diagnostic> {
+        self.found
+    }
+
+    fn push(&mut self, severity: Severity, path: String, message: String) {
+        self.found.push(Diagnostic {
+            severity,
+            table: self.table.to_string(),
+            path,
+            message,
+        });
+    }
+
+    fn error(&mut self, path: String, message: String) {
+        self.push(Severity::Error, path, message);
+    }
+
+    pub(crate) fn warning(&mut self, path: String, message: String) {
+        self.push(Severity::Warning, path, message);
+    }
+}
+
+// 🩺 Every problem found in one table schema
+pub fn validate_table(table: &str, schema: &TableSchema) -> Vec<Diagnostic> {
+    let mut diagnostics = Diagnostics {
+        table,
+        found: Vec::new(),
+    };
+    check_variants(schema, &mut diagnostics);
+    check_defaults(schema, &mut diagnostics);
+    check_value_variants(schema, &mut diagnostics);
+    check_contexts(schema, &mut diagnostics);
+    check_fallbacks(schema, &mut diagnostics);
+    check_locales(schema, &mut diagnostics);
+    check_enums(schema, &mut diagnostics);
+    check_rules(schema, &mut diagnostics);
+    check_field_mappings(schema, &mut diagnostics);
+    check_singleton(schema, &mut diagnostics);
+    check_relations(schema, &mut diagnostics);
+    check_threads(schema, &mut diagnostics);
+    check_views(schema, &mut diagnostics);
+    check_search(schema, &mut diagnostics);
+    diagnostics.found
+}
+
+fn check_variants(schema: &TableSchema, diagnostics: &mut Diagnostics) {
+    // Fields differing only in case are almost always a typo'd duplicate
+    let mut lowercase: HashMap<String, &str> = HashMap::new();
+    let mut fields: Vec<_> = schema.variants.keys().collect();
+    fields.sort();
+    for field in fields {
+        if let Some(other) = lowercase.insert(field.to_lowercase(), field) {
+            diagnostics.warning(
+                format!("variants.{}", field),
+                format!(
+                    "field '{}' duplicates '{}' with different case",
+                    field, other
+                ),
+            );
+        }
+    }
+
+    for (field, variants) in &schema.variants {
+        if variants.is_empty() {
+            diagnostics.warning(
+                format!("variants.{}", field),
+                format!("field '{}' has no variants and never renders", field),
+            );
+        }
+        for (name, variant) in variants {
+            let path = format!("variants.{}.{}", field, name);
+            let valid_tag = !variant.base.is_empty()
+                && variant
+                    .base
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-');
+            if !valid_tag {
+                diagnostics.error(path.clone(), format!("invalid base tag '{}'", variant.base));
+            }
+            if let Some(kind) = &variant.kind
+                && !KNOWN_KINDS.contains(&kind.as_str())
+            {
+                diagnostics.error(path.clone(), format!("unknown kind '{}'", kind));
+            }
+            let mut names: Vec<_> = variant
+                .attrs
+                .iter()
+                .flat_map(|attrs| attrs.keys())
+                .collect();
+            names.sort();
+            for name in names {
+                if let Some(violation) = config().attr_policy.violation(&variant.base, name) {
+                    diagnostics.error(path.clone(), violation);
+                }
+            }
+            if variant.max_length == Some(0) {
+                diagnostics.warning(path, "max_length = 0 hides every value".to_string());
+            }
+        }
+    }
+}
+
+fn check_defaults(schema: &TableSchema, diagnostics: &mut Diagnostics) {
+    for (field, variant) in schema.defaults.iter().flatten() {
+        let path = format!("defaults.{}", field);
+        check_variant_reference(schema, path, field, variant, diagnostics);
+    }
+}
+
+fn check_value_variants(schema: &TableSchema, diagnostics: &mut Diagnostics) {
+    for (field, by_value) in &schema.value_variants {
+        for (value, variant) in by_value {
+            let path = format!("value_variants.{}.{}", field, value);
+            check_variant_reference(schema, path, field, variant, diagnostics);
+        }
+    }
+}
+
+fn check_contexts(schema: &TableSchema, diagnostics: &mut Diagnostics) {
+    // Tables without variants are context libraries ("common"); their fields
+    // belong to whichever tables inherit them
+    let library = schema.variants.is_empty();
+    for (name, context) in &schema.contexts {
+        for (field, variant) in context.fields.iter().filter(|_| !library) {
+            let path = format!("contexts.{}.{}", name, field);
+            // Without "*" variants of its own the table renders `_default`'s
+            if field == WILDCARD_FIELD && !schema.variants.contains_key(WILDCARD_FIELD) {
+                continue;
+            }
+            check_variant_reference(schema, path, field, variant, diagnostics);
+        }
+
+        if let Some(parent) = &context.inherits
+            && schema.context(parent).is_none()
+        {
+            diagnostics.error(
+                format!("contexts.{}.inherits", name),
+                format!("inherits from unknown context '{}'", parent),
+            );
+        }
+        if let Some(cycle) = inheritance_cycle(schema, name) {
+            diagnostics.error(
+                format!("contexts.{}.inherits", name),
+                format!("inheritance cycle: {}", cycle.join(" -> ")),
+            );
+        }
+
+        let mut sectioned: HashMap<&str, &str> = HashMap::new();
+        let mut sections: Vec<_> = context.sections.iter().collect();
+        sections.sort_by_key(|(section, _)| *section);
+        for (section, spec) in sections {
+            let path = format!("contexts.{}.sections.{}", name, section);
+            for field in &spec.fields {
+                if !library && !schema.variants.contains_key(field) {
+                    diagnostics.error(path.clone(), format!("unknown field '{}'", field));
+                }
+                if let Some(other) = sectioned.insert(field, section) {
+                    diagnostics.warning(
+                        path.clone(),
+                        format!("field '{}' is also listed in section '{}'", field, other),
+                    );
+                }
+            }
+        }
+    }
+}
+
+// Every fallback chain must end at a context the table has
+fn check_fallbacks(schema: &TableSchema, diagnostics: &mut Diagnostics) {
+    let mut fallbacks: Vec<_> = schema.fallbacks.iter().collect();
+    fallbacks.sort();
+    for (from, to) in fallbacks {
+        let path = format!("fallbacks.{}", from);
+        let chain = schema.fallback_chain(from);
+        let last = chain.last().copied().unwrap_or(from);
+        if schema.fallbacks.get(last) == Some(from) {
+            diagnostics.error(
+                path,
+                format!("fallback cycle: {} -> {}", chain.join(" -> "), from),
+            );
+        } else if schema.context(to).is_none() && !schema.fallbacks.contains_key(to) {
+            diagnostics.error(path, format!("falls back to unknown context '{}'", to));
+        }
+    }
+}
+
+// Labels and classes only apply to listed values
+fn check_enums(schema: &TableSchema, diagnostics: &mut Diagnostics) {
+    for (field, spec) in &schema.enums {
+        let path = format!("enums.{}", field);
+        if spec.values.is_empty() {
+            diagnostics.warning(path.clone(), "enum lists no values".to_string());
+        }
+        let mut unlisted: Vec<_> = spec
+            .labels
+            .keys()
+            .chain(spec.classes.keys())
+            .filter(|value| !spec.contains(value))
+            .collect();
+        unlisted.sort();
+        unlisted.dedup();
+        for value in unlisted {
+            diagnostics.warning(
+                path.clone(),
+                format!("'{}' is not one of the values", value),
+            );
+        }
+    }
+}
+
+// Language overrides name a context and a variant of the field
+fn check_locales(schema: &TableSchema, diagnostics: &mut Diagnostics) {
+    for (lang, contexts) in &schema.locales {
+        for (context, fields) in contexts {
+            if schema.context(context).is_none() {
+                diagnostics.error(
+                    format!("locales.{}.{}", lang, context),
+                    format!("unknown context '{}'", context),
+                );
+            }
+            for (field, variant) in fields {
+                let path = format!("locales.{}.{}.{}", lang, context, field);
+                check_variant_reference(schema, path, field, variant, diagnostics);
+            }
+        }
+    }
+}
+
+// Length limits no value can meet, and rules no form control shows
+fn check_rules(schema: &TableSchema, diagnostics: &mut Diagnostics) {
+    for (field, rules) in &schema.rules {
+        let path = format!("rules.{}", field);
+        if let (Some(min), Some(max)) = (rules.min_length, rules.max_length)
+            && min > max
+        {
+            let message = format!("min_length {} exceeds max_length {}", min, max);
+            diagnostics.error(path.clone(), message);
+        }
+        let has_control = schema.variants.get(field).is_some_and(|variants| {
+            variants
+                .values()
+                .any(|variant| matches!(variant.base.as_str(), "input" | "textarea"))
+        });
+        if !has_control {
+            diagnostics.warning(
+                path,
+                "no input or textarea variant shows these rules".to_string(),
+            );
+        }
+    }
+}
+
+// Fields named by the type, text, push, event and JSON-LD mappings
+fn check_field_mappings(schema: &TableSchema, diagnostics: &mut Diagnostics) {
+    let mut check = |path: String, field: &str| {
+        if !schema.variants.contains_key(field) {
+            diagnostics.warning(path, format!("unknown field '{}'", field));
+        }
+    };
+    for field in schema.types.keys() {
+        check(format!("types.{}", field), field);
+    }
+    for field in schema.short.keys() {
+        check(format!("short.{}", field), field);
+    }
+    for (column, field) in &schema.aliases {
+        check(format!("aliases.{}", column), field);
+    }
+    for field in schema.computed.keys() {
+        check(format!("computed.{}", field), field);
+    }
+    for field in schema.enums.keys() {
+        check(format!("enums.{}", field), field);
+    }
+    for field in schema.writable_by.keys() {
+        check(format!("writable_by.{}", field), field);
+    }
+    for field in schema.normalize.keys() {
+        check(format!("normalize.{}", field), field);
+    }
+    for (component, mapping) in &schema.jsonld {
+        for field in mapping.fields.keys() {
+            check(format!("jsonld.{}.fields", component), field);
+        }
+    }
+    if let Some(event) = &schema.event {
+        let fields = [Some(&event.start), event.end.as_ref(), Some(&event.title)];
+        let optional = [event.description.as_ref(), event.location.as_ref()];
+        for field in fields.into_iter().chain(optional).flatten() {
+            check("event".to_string(), field);
+        }
+    }
+}
+
+fn check_singleton(schema: &TableSchema, diagnostics: &mut Diagnostics) {
+    let rows = schema.mock_data.as_ref().map_or(0, Vec::len);
+    if schema.singleton && rows > 1 {
+        diagnostics.warning(
+            "mock_data".to_string(),
+            format!(
+                "singleton table has {} mock rows; only the first is used",
+                rows
+            ),
+        );
+    }
+}
+
+// Chips render with the relation field's own variants, so it needs some
+fn check_relations(schema: &TableSchema, diagnostics: &mut Diagnostics) {
+    for (name, relation) in &schema.relations {
+        let path = format!("relations.{}", name);
+        match (schema.variants.get(name), &relation.variant) {
+            (None, _) => diagnostics.error(
+                path,
+                format!("relation '{}' has no variants to render chips with", name),
+            ),
+            (Some(_), Some(variant)) => {
+                check_variant_reference(schema, path, name, variant, diagnostics)
+            }
+            (Some(_), None) => {}
+        }
+    }
+}
+
+// 🔗 References must point at loaded tables
+pub(crate) fn validate_references(
+    table: &str,
+    schema: &TableSchema,
+    known_table: impl Fn(&str) -> bool,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Diagnostics {
+        table,
+        found: Vec::new(),
+    };
+    let mut references: Vec<_> = schema.references.iter().collect();
+    references.sort_by_key(|(name, _)| *name);
+    for (name, reference) in references {
+        let path = format!("references.{}", name);
+        if !known_table(&reference.table) {
+            let message = format!("references unknown table '{}'", reference.table);
+            diagnostics.error(path, message);
+        }
+    }
+    diagnostics.found
+}
+
+// Threads page through a tree, so the table has to declare one
+fn check_threads(schema: &TableSchema, diagnostics: &mut Diagnostics) {
+    if schema.tree.is_some() {
+        return;
+    }
+    for component in schema.threads.keys() {
+        diagnostics.error(
+            format!("threads.{}", component),
+            "threads need a [tree] declaring the parent field".to_string(),
+        );
+    }
+}
+
+// Views render in a context and sort/filter by fields of the table
+fn check_views(schema: &TableSchema, diagnostics: &mut Diagnostics) {
+    for (name, view) in &schema.views {
+        let path = format!("views.{}", name);
+        if schema.context(&view.context).is_none() {
+            diagnostics.error(path.clone(), format!("unknown context '{}'", view.context));
+        }
+        let mut fields = Vec::new();
+        match view.sort_order() {
+            Ok(sort) => fields.extend(sort.map(|(field, _)| field.to_string())),
+            Err(message) => diagnostics.error(path.clone(), message),
+        }
+        match view.conditions() {
+            Ok(conditions) => fields.extend(conditions.into_iter().map(|c| c.field)),
+            Err(message) => diagnostics.error(path.clone(), message),
+        }
+        for field in fields.iter().filter(|field| *field != "id") {
+            if !schema.variants.contains_key(field) {
+                diagnostics.warning(path.clone(), format!("unknown field '{}'", field));
+            }
+        }
+    }
+}
+
+// Search settings end up in SQL, so they must be plain names
+fn check_search(schema: &TableSchema, diagnostics: &mut Diagnostics) {
+    let Some(search) = &schema.search else {
+        return;
+    };
+    let plain = |name: &str| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+    if search.fields.is_empty() {
+        diagnostics.error(
+            "search.fields".to_string(),
+            "no fields to search".to_string(),
+        );
+    }
+    if !plain(&search.language) {
+        diagnostics.error(
+            "search.language".to_string(),
+            format!("invalid text search configuration '{}'", search.language),
+        );
+    }
+    for field in search.fields.iter().chain(&search.snippet) {
+        if !plain(field) {
+            diagnostics.error(
+                "search".to_string(),
+                format!("invalid field name '{}'", field),
+            );
+        } else if !schema.variants.contains_key(field) {
+            diagnostics.warning("search".to_string(), format!("unknown field '{}'", field));
+        }
+    }
+}
+
+fn check_variant_reference(
+    schema: &TableSchema,
+    path: String,
+    field: &str,
+    variant: &str,
+    diagnostics: &mut Diagnostics,
+) {
+    match schema.variants.get(field) {
+        None => diagnostics.error(path, format!("unknown field '{}'", field)),
+        Some(variants) if !variants.contains_key(variant) => {
+            let mut known: Vec<_> = variants.keys().map(String::as_str).collect();
+            known.sort();
+            diagnostics.error(
+                path,
+                format!(
+                    "unknown variant '{}' for field '{}' (known: {})",
+                    variant,
+                    field,
+                    known.join(", ")
+                ),
+            );
+        }
+        Some(_) => {}
+    }
+}
+
+// The chain of contexts when following `inherits` from `start` loops back on itself
+fn inheritance_cycle(schema: &TableSchema, start: &str) -> Option<Vec<String>> {
+    let mut chain = vec![start.to_string()];
+    let mut current = schema.context(start)?.1.inherits.clone();
+    while let Some(name) = current {
+        let repeated = chain.contains(&name);
+        chain.push(name.clone());
+        if repeated {
+            // Only report cycles that pass through `start`, once per member
+            return (chain[0] == name).then_some(chain);
+        }
+        current = schema.context(&name)?.1.inherits.clone();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_table_reports_dangling_references() {
+        let schema = TableSchema::from_toml_str(
+            r#"
+            [variants.name]
+            h1 = { base = "h1" }
+            [variants.Name]
+            h2 = { base = "h 2" }
+            [variants.email]
+            link = { base = "a", attrs = { href = "mailto:{value}", onclick = "x()" } }
+
+            [defaults]
+            name = "h3"
+
+            [contexts.card]
+            inherits = "list"
+            name = "h1"
+            title = "h1"
+
+            [contexts.list]
+            inherits = "card"
+
+            [contexts.detail]
+            inherits = "missing"
+
+            [contexts.detail.sections.a]
+            fields = ["name"]
+            [contexts.detail.sections.b]
+            fields = ["name", "bio"]
+            "#,
+        )
+        .unwrap();
+
+        let mut found: Vec<String> = validate_table("users", &schema)
+            .iter()
+            .map(|d| format!("{}", d))
+            .collect();
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                "error: users: contexts.card.inherits: inheritance cycle: card -> list -> card",
+                "error: users: contexts.card.title: unknown field 'title'",
+                "error: users: contexts.detail.inherits: inherits from unknown context 'missing'",
+                "error: users: contexts.detail.sections.b: unknown field 'bio'",
+                "error: users: contexts.list.inherits: inheritance cycle: list -> card -> list",
+                "error: users: defaults.name: unknown variant 'h3' for field 'name' (known: h1)",
+                "error: users: variants.Name.h2: invalid base tag 'h 2'",
+                "error: users: variants.email.link: attribute 'onclick' is denied (on*)",
+                "warning: users: contexts.detail.sections.b: \
+                 field 'name' is also listed in section 'a'",
+                "warning: users: variants.name: field 'name' duplicates 'Name' with different case",
+            ]
+        );
+    }
+}
@@ -0,0 +1,872 @@
+// src/variant_kinds.rs - Built-in renderers for structured field values
+//
+// A variant with `kind = "..."` turns its raw value into richer markup instead
+// of printing it verbatim. Values a kind cannot interpret fall back to plain
+// rendering, so bad data still shows up rather than disappearing.
+use crate::config::config;
+use crate::currency::{currency_converter, format_amount};
+use crate::schema::{FieldVariant, SchemaRegistry, Theme, insert_generated};
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub const KNOWN_KINDS: [&str; 7] = [
+    "map",
+    "sparkline",
+    "chart",
+    "progress",
+    "rating",
+    "currency",
+    "upload",
+];
+
+// What a kind may need to know about the render besides the value
+#[derive(Debug, Default, Clone, Copy)]
+pub struct KindContext<'a> {
+    // Design tokens for kinds that pick colors
+    pub theme: Option<&'a Theme>,
+    // Viewer's currency for `display_currency = "viewer"`
+    pub currency: Option<&'a str>,
+    // Field being rendered (the form name of an upload input)
+    pub field: &'a str,
+}
+
+// 🧩 Render `value` through the variant's kind; None means render it plainly
+pub fn render_kind(
+    variant: &FieldVariant,
+    ctx: KindContext<'_>,
+    css_classes: &str,
+    attrs: HashMap<String, String>,
+    value: &str,
+) -> Option<String> {
+    let theme = ctx.theme;
+    match variant.kind.as_deref()? {
+        "map" => render_map(variant, css_classes, attrs, value),
+        "sparkline" | "chart" => render_series(variant, theme, css_classes, attrs, value),
+        "progress" => render_progress(variant, theme, css_classes, attrs, value),
+        "rating" => render_rating(variant, theme, css_classes, attrs, value),
+        "currency" => render_currency(variant, ctx.currency, css_classes, attrs, value),
+        "upload" => Some(render_upload(variant, ctx.field, css_classes, attrs, value)),
+        _ => None,
+    }
+}
+
+// Kinds whose output depends on more than the value (e.g. exchange rates)
+pub fn is_volatile(variant: &FieldVariant) -> bool {
+    variant.kind.as_deref() == Some("currency") && option(variant, "display_currency").is_some()
+}
+
+fn option<'a>(variant: &'a FieldVariant, key: &str) -> Option<&'a str> {
+    variant.options.as_ref()?.get(key).map(String::as_str)
+}
+
+fn option_or<T: std::str::FromStr>(variant: &FieldVariant, key: &str, default: T) -> T {
+    option(variant, key)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+// Escape a value for use inside a double-quoted attribute
+pub(crate) fn escape_attr(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// ---- map ------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoPoint {
+    pub lat: f64,
+    pub lng: f64,
+}
+
+// "lat,lng", {"lat":..,"lng"|"lon":..} or GeoJSON (a Point is used as is;
+// other geometries are centred on their bounding box)
+pub fn parse_geo(value: &str) -> Option<GeoPoint> {
+    let value = value.trim();
+    let point = if value.starts_with('{') {
+        let json: Value = serde_json::from_str(value).ok()?;
+        match (json.get("lat"), json.get("lng").or_else(|| json.get("lon"))) {
+            (Some(lat), Some(lng)) => GeoPoint {
+                lat: lat.as_f64()?,
+                lng: lng.as_f64()?,
+            },
+            _ => geojson_center(&json)?,
+        }
+    } else {
+        let (lat, lng) = value.split_once(',')?;
+        GeoPoint {
+            lat: lat.trim().parse().ok()?,
+            lng: lng.trim().parse().ok()?,
+        }
+    };
+
+    let valid = (-90.0..=90.0).contains(&point.lat) && (-180.0..=180.0).contains(&point.lng);
+    valid.then_some(point)
+}
+
+fn geojson_center(json: &Value) -> Option<GeoPoint> {
+    let mut bounds: Option<(f64, f64, f64, f64)> = None;
+    collect_positions(json, &mut |lng, lat| {
+        let (min_lng, min_lat, max_lng, max_lat) = bounds.get_or_insert((lng, lat, lng, lat));
+        *min_lng = min_lng.min(lng);
+        *min_lat = min_lat.min(lat);
+        *max_lng = max_lng.max(lng);
+        *max_lat = max_lat.max(lat);
+    });
+    let (min_lng, min_lat, max_lng, max_lat) = bounds?;
+    Some(GeoPoint {
+        lat: (min_lat + max_lat) / 2.0,
+        lng: (min_lng + max_lng) / 2.0,
+    })
+}
+
+// Visit every [lng, lat] position under coordinates/geometry/geometries/features
+fn collect_positions(json: &Value, visit: &mut impl FnMut(f64, f64)) {
+    fn walk(coordinates: &Value, visit: &mut impl FnMut(f64, f64)) {
+        let Some(items) = coordinates.as_array() else {
+            return;
+        };
+        match (
+            items.first().and_then(Value::as_f64),
+            items.get(1).and_then(Value::as_f64),
+        ) {
+            (Some(lng), Some(lat)) => visit(lng, lat),
+            _ => items.iter().for_each(|item| walk(item, visit)),
+        }
+    }
+
+    if let Some(coordinates) = json.get("coordinates") {
+        walk(coordinates, visit);
+    }
+    if let Some(geometry) = json.get("geometry") {
+        collect_positions(geometry, visit);
+    }
+    for key in ["geometries", "features"] {
+        if let Some(items) = json.get(key).and_then(Value::as_array) {
+            items.iter().for_each(|item| collect_positions(item, visit));
+        }
+    }
+}
+
+// Static map image URL for the configured provider
+pub fn static_map_url(point: GeoPoint, zoom: u8, width: u32, height: u32) -> String {
+    let config = config();
+    let template = match (&config.map_static_url, config.map_provider.as_str()) {
+        (Some(template), _) => template.as_str(),
+        (None, "mapbox") => {
+            "https://api.mapbox.com/styles/v1/mapbox/streets-v12/static/pin-s({lng},{lat})/{lng},{lat},{zoom}/{width}x{height}?access_token={token}"
+        }
+        _ => {
+            "https://staticmap.openstreetmap.de/staticmap.php?center={lat},{lng}&zoom={zoom}&size={width}x{height}&markers={lat},{lng},red-pushpin"
+        }
+    };
+
+    template
+        .replace("{lat}", &format!("{:.6}", point.lat))
+        .replace("{lng}", &format!("{:.6}", point.lng))
+        .replace("{zoom}", &zoom.to_string())
+        .replace("{width}", &width.to_string())
+        .replace("{height}", &height.to_string())
+        .replace(
+            "{token}",
+            config.mapbox_access_token.as_deref().unwrap_or_default(),
+        )
+}
+
+// `img` base: static map image. Any other base: a container carrying the
+// coordinates in data attributes for a client-side map library, with a
+// plain link to the location as its content.
+fn render_map(
+    variant: &FieldVariant,
+    css_classes: &str,
+    mut attrs: HashMap<String, String>,
+    value: &str,
+) -> Option<String> {
+    let point = parse_geo(value)?;
+    let zoom = option_or(variant, "zoom", 13u8).min(20);
+
+    if variant.base == "img" {
+        let width = option_or(variant, "width", 600u32);
+        let height = option_or(variant, "height", 300u32);
+        let src = escape_attr(&static_map_url(point, zoom, width, height));
+        insert_generated(&mut attrs, "src", src);
+        insert_generated(
+            &mut attrs,
+            "alt",
+            format!("Map of {:.5}, {:.5}", point.lat, point.lng),
+        );
+        return Some(SchemaRegistry::generate_html(
+            &variant.base,
+            css_classes,
+            &attrs,
+            "",
+        ));
+    }
+
+    insert_generated(&mut attrs, "data-map-lat", format!("{:.6}", point.lat));
+    insert_generated(&mut attrs, "data-map-lng", format!("{:.6}", point.lng));
+    insert_generated(&mut attrs, "data-map-zoom", zoom.to_string());
+    insert_generated(
+        &mut attrs,
+        "data-map-provider",
+        config().map_provider.clone(),
+    );
+    if value.trim_start().starts_with('{') {
+        insert_generated(&mut attrs, "data-map-geojson", escape_attr(value));
+    }
+    let link = format!(
+        "<a href=\"https://www.openstreetmap.org/?mlat={lat:.6}&amp;mlon={lng:.6}#map={zoom}/{lat:.6}/{lng:.6}\">{lat:.5}, {lng:.5}</a>",
+        lat = point.lat,
+        lng = point.lng,
+        zoom = zoom
+    );
+    Some(SchemaRegistry::generate_html(
+        &variant.base,
+        css_classes,
+        &attrs,
+        &link,
+    ))
+}
+
+// ---- sparkline / chart ----------------------------------------------------
+
+// JSON array (`[1, 2.5, "3"]`) or comma/whitespace separated numbers
+pub fn parse_series(value: &str) -> Option<Vec<f64>> {
+    let value = value.trim();
+    let series: Vec<f64> = if value.starts_with('[') {
+        let items: Vec<Value> = serde_json::from_str(value).ok()?;
+        items
+            .iter()
+            .map(|item| match item {
+                Value::Number(n) => n.as_f64(),
+                Value::String(s) => s.trim().parse().ok(),
+                _ => None,
+            })
+            .collect::<Option<_>>()?
+    } else {
+        value
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|item| !item.is_empty())
+            .map(|item| item.parse().ok())
+            .collect::<Option<_>>()?
+    };
+
+    (!series.is_empty() && series.iter().all(|n| n.is_finite())).then_some(series)
+}
+
+// Option naming a theme token (e.g. "color-primary-500") or a literal color
+fn series_color(
+    variant: &FieldVariant,
+    theme: Option<&Theme>,
+    key: &str,
+    default_token: &str,
+) -> String {
+    let name = option(variant, key).unwrap_or(default_token);
+    if let Some(color) = theme.and_then(|theme| theme.tokens.get(name)) {
+        return escape_attr(color);
+    }
+    // Unknown token names ("color-brand") inherit the text color; plain CSS
+    // color names ("teal") and functional notations are used as given
+    let literal = name.starts_with('#') || name.starts_with("rgb") || name.starts_with("hsl");
+    if literal || option(variant, key).is_some() && !name.contains('-') {
+        escape_attr(name)
+    } else {
+        "currentColor".to_string()
+    }
+}
+
+// Inline SVG, always rendered as <svg> whatever the variant base. Sparklines
+// draw a polyline (optionally filled below), charts draw one bar per value.
+fn render_series(
+    variant: &FieldVariant,
+    theme: Option<&Theme>,
+    css_classes: &str,
+    mut attrs: HashMap<String, String>,
+    value: &str,
+) -> Option<String> {
+    let series = parse_series(value)?;
+    let width = option_or(variant, "width", 120.0f64).max(1.0);
+    let height = option_or(variant, "height", 32.0f64).max(1.0);
+    let color = series_color(variant, theme, "color", "color-chart-line");
+
+    let min = series.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = series.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    // Bars grow from zero unless every value is negative
+    let floor = if variant.kind.as_deref() == Some("chart") {
+        min.min(0.0)
+    } else {
+        min
+    };
+    let range = (max - floor).max(f64::EPSILON);
+    let y = |n: f64| height - (n - floor) / range * height;
+
+    let shapes = if variant.kind.as_deref() == Some("chart") {
+        let slot = width / series.len() as f64;
+        let gap = option_or(variant, "gap", 0.2f64).clamp(0.0, 0.9) * slot;
+        series
+            .iter()
+            .enumerate()
+            .map(|(i, n)| {
+                let top = y(*n);
+                format!(
+                    "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"{}\" />",
+                    i as f64 * slot + gap / 2.0,
+                    top,
+                    slot - gap,
+                    (y(floor) - top).max(0.0),
+                    color
+                )
+            })
+            .collect::<String>()
+    } else {
+        let step = if series.len() > 1 {
+            width / (series.len() - 1) as f64
+        } else {
+            0.0
+        };
+        let points: Vec<String> = series
+            .iter()
+            .enumerate()
+            .map(|(i, n)| format!("{:.2},{:.2}", i as f64 * step, y(*n)))
+            .collect();
+        let stroke_width = option_or(variant, "stroke_width", 2.0f64);
+        let mut shapes = String::new();
+        if option(variant, "fill").is_some() {
+            let fill = series_color(variant, theme, "fill", "color-chart-fill");
+            shapes.push_str(&format!(
+                "<polygon points=\"0,{h:.2} {} {w:.2},{h:.2}\" fill=\"{}\" stroke=\"none\" />",
+                points.join(" "),
+                fill,
+                w = step * (series.len() - 1) as f64,
+                h = height
+            ));
+        }
+        shapes.push_str(&format!(
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"round\" stroke-linejoin=\"round\" />",
+            points.join(" "),
+            color,
+            stroke_width
+        ));
+        shapes
+    };
+
+    let summary = format!(
+        "{} values, min {}, max {}, last {}",
+        series.len(),
+        min,
+        max,
+        series[series.len() - 1]
+    );
+    insert_generated(
+        &mut attrs,
+        "xmlns",
+        "http://www.w3.org/2000/svg".to_string(),
+    );
+    insert_generated(&mut attrs, "viewBox", format!("0 0 {} {}", width, height));
+    insert_generated(&mut attrs, "width", width.to_string());
+    insert_generated(&mut attrs, "height", height.to_string());
+    insert_generated(&mut attrs, "preserveAspectRatio", "none".to_string());
+    insert_generated(&mut attrs, "role", "img".to_string());
+    insert_generated(&mut attrs, "aria-label", summary.clone());
+
+    Some(SchemaRegistry::generate_html(
+        "svg",
+        css_classes,
+        &attrs,
+        &format!("<title>{}</title>{}", summary, shapes),
+    ))
+}
+
+// ---- progress / rating -----------------------------------------------------
+//
+// The variant base only selects the theme classes of the outer element
+// (`base = "progress"` / `base = "rating"`); inner parts use the theme's
+// "progress-bar", "rating-star", "rating-star-half" and "rating-star-empty"
+// classes unless the matching `*_class` option overrides them.
+
+fn part_classes(
+    variant: &FieldVariant,
+    theme: Option<&Theme>,
+    option_key: &str,
+    tag: &str,
+) -> String {
+    option(variant, option_key)
+        .or_else(|| {
+            theme
+                .and_then(|theme| theme.tags.get(tag))
+                .map(String::as_str)
+        })
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn format_number(n: f64) -> String {
+    if n.fract() == 0.0 {
+        format!("{}", n as i64)
+    } else {
+        format!("{}", n)
+    }
+}
+
+// "42" or "42%" against the `max` option (default 100), or "3/5"
+pub fn parse_progress(value: &str, default_max: f64) -> Option<(f64, f64)> {
+    let value = value.trim();
+    let (current, max) = match value.split_once('/') {
+        Some((current, max)) => (current.trim().parse().ok()?, max.trim().parse().ok()?),
+        None => (
+            value.trim_end_matches('%').trim().parse().ok()?,
+            default_max,
+        ),
+    };
+    let valid = f64::is_finite(current) && f64::is_finite(max) && max > 0.0;
+    valid.then_some((current.clamp(0.0, max), max))
+}
+
+fn render_progress(
+    variant: &FieldVariant,
+    theme: Option<&Theme>,
+    css_classes: &str,
+    mut attrs: HashMap<String, String>,
+    value: &str,
+) -> Option<String> {
+    let (current, max) = parse_progress(value, option_or(variant, "max", 100.0f64))?;
+    let percent = current / max * 100.0;
+
+    insert_generated(&mut attrs, "role", "progressbar".to_string());
+    insert_generated(&mut attrs, "aria-valuenow", format_number(current));
+    insert_generated(&mut attrs, "aria-valuemin", "0".to_string());
+    insert_generated(&mut attrs, "aria-valuemax", format_number(max));
+    insert_generated(
+        &mut attrs,
+        "aria-valuetext",
+        format!("{}%", percent.round()),
+    );
+
+    let bar_classes = part_classes(variant, theme, "bar_class", "progress-bar");
+    let bar = format!(
+        "<div{} style=\"width: {:.1}%\"></div>",
+        if bar_classes.is_empty() {
+            String::new()
+        } else {
+            format!(" class=\"{}\"", bar_classes)
+        },
+        percent
+    );
+    Some(SchemaRegistry::generate_html(
+        "div",
+        css_classes,
+        &attrs,
+        &bar,
+    ))
+}
+
+fn render_rating(
+    variant: &FieldVariant,
+    theme: Option<&Theme>,
+    css_classes: &str,
+    mut attrs: HashMap<String, String>,
+    value: &str,
+) -> Option<String> {
+    let max = option_or(variant, "max", 5u32).clamp(1, 10);
+    let rating: f64 = value.trim().parse().ok().filter(|n: &f64| n.is_finite())?;
+    // Nearest half star
+    let rating = (rating.clamp(0.0, max as f64) * 2.0).round() / 2.0;
+
+    insert_generated(&mut attrs, "role", "img".to_string());
+    let label = format!("{} out of {}", format_number(rating), max);
+    insert_generated(&mut attrs, "aria-label", label);
+
+    let full = part_classes(variant, theme, "star_class", "rating-star");
+    let half = Some(part_classes(
+        variant,
+        theme,
+        "half_class",
+        "rating-star-half",
+    ))
+    .filter(|classes| !classes.is_empty())
+    .unwrap_or_else(|| full.clone());
+    let empty = part_classes(variant, theme, "empty_class", "rating-star-empty");
+    let stars: String = (1..=max)
+        .map(|star| {
+            let classes = match rating - (star - 1) as f64 {
+                filled if filled >= 1.0 => &full,
+                filled if filled >= 0.5 => &half,
+                _ => &empty,
+            };
+            format!("<span class=\"{}\" aria-hidden=\"true\">★</span>", classes)
+        })
+        .collect();
+
+    Some(SchemaRegistry::generate_html(
+        "span",
+        css_classes,
+        &attrs,
+        &stars,
+    ))
+}
+
+// ---- currency --------------------------------------------------------------
+
+// "12.50", "12.50 EUR" or "EUR 12.50"; without a code the variant's
+// `currency` option (or CURRENCY_BASE) applies
+pub fn parse_money<'a>(value: &'a str, default_currency: &'a str) -> Option<(f64, &'a str)> {
+    let value = value.trim();
+    let is_code = |s: &str| s.len() == 3 && s.chars().all(|c| c.is_ascii_alphabetic());
+    let (amount, currency) = match value.split_once(char::is_whitespace) {
+        Some((code, amount)) if is_code(code) => (amount.trim(), code),
+        Some((amount, code)) if is_code(code.trim()) => (amount, code.trim()),
+        Some(_) => return None,
+        None => (value, default_currency),
+    };
+    let amount: f64 = amount.replace(',', "").parse().ok()?;
+    amount.is_finite().then_some((amount, currency))
+}
+
+// `display_currency = "viewer"` converts to the viewer's currency, a code
+// ("EUR") always converts to it; unset shows the stored currency. Without a
+// rate the stored amount is shown.
+fn render_currency(
+    variant: &FieldVariant,
+    viewer_currency: Option<&str>,
+    css_classes: &str,
+    mut attrs: HashMap<String, String>,
+    value: &str,
+) -> Option<String> {
+    let default_currency = option(variant, "currency").unwrap_or(&config().currency_base);
+    let (amount, stored) = parse_money(value, default_currency)?;
+    let target = match option(variant, "display_currency") {
+        Some("viewer") => viewer_currency,
+        other => other,
+    };
+
+    let converted = target.and_then(|target| {
+        currency_converter()
+            .convert(amount, stored, target)
+            .map(|converted| (converted, target))
+    });
+    let (shown, currency) = converted.unwrap_or((amount, stored));
+    let currency = currency.to_ascii_uppercase();
+
+    insert_generated(&mut attrs, "data-currency", currency.clone());
+    insert_generated(&mut attrs, "data-amount", format!("{:.2}", shown));
+    if !currency.eq_ignore_ascii_case(stored) {
+        let original = format_amount(amount, stored);
+        insert_generated(&mut attrs, "data-original", escape_attr(&original));
+        insert_generated(&mut attrs, "title", escape_attr(&original));
+    }
+
+    Some(SchemaRegistry::generate_html(
+        &variant.base,
+        css_classes,
+        &attrs,
+        &format_amount(shown, &currency),
+    ))
+}
+
+// ---- upload ----------------------------------------------------------------
+
+// A file input named after the field (`accept` option limits the file types),
+// posted by the page to /api/:table/:id/upload/:field, which answers with this
+// markup again. The current file, if any, is linked before the input.
+fn render_upload(
+    variant: &FieldVariant,
+    field: &str,
+    css_classes: &str,
+    mut attrs: HashMap<String, String>,
+    value: &str,
+) -> String {
+    insert_generated(&mut attrs, "type", "file".to_string());
+    insert_generated(&mut attrs, "name", escape_attr(field));
+    insert_generated(&mut attrs, "data-upload-field", escape_attr(field));
+    if let Some(accept) = option(variant, "accept") {
+        insert_generated(&mut attrs, "accept", escape_attr(accept));
+    }
+    let input = SchemaRegistry::generate_html("input", css_classes, &attrs, "");
+    let value = value.trim();
+    if value.is_empty() {
+        return input;
+    }
+    let name = value.rsplit('/').next().unwrap_or(value);
+    format!(
+        "<a href=\"{}\" data-upload-current>{}</a>{}",
+        escape_attr(value),
+        escape_attr(name),
+        input
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map_variant(base: &str) -> FieldVariant {
+        FieldVariant {
+            base: base.to_string(),
+            extends: None,
+            override_class: None,
+            extend: None,
+            attrs: None,
+            kind: Some("map".to_string()),
+            options: Some(HashMap::from([("zoom".to_string(), "10".to_string())])),
+            max_length: None,
+            overflow: None,
+            field_type: None,
+            deprecated: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_geo_formats() {
+        let berlin = GeoPoint {
+            lat: 52.52,
+            lng: 13.405,
+        };
+        assert_eq!(parse_geo("52.52, 13.405"), Some(berlin));
+        assert_eq!(parse_geo(r#"{"lat": 52.52, "lon": 13.405}"#), Some(berlin));
+        assert_eq!(
+            parse_geo(
+                r#"{"type":"Feature","geometry":{"type":"Point","coordinates":[13.405,52.52]}}"#
+            ),
+            Some(berlin)
+        );
+        assert_eq!(
+            parse_geo(r#"{"type":"LineString","coordinates":[[0,0],[10,20]]}"#),
+            Some(GeoPoint {
+                lat: 10.0,
+                lng: 5.0
+            })
+        );
+        assert_eq!(parse_geo("95,10"), None);
+        assert_eq!(parse_geo("somewhere"), None);
+    }
+
+    #[test]
+    fn test_map_renders_image_or_container() {
+        let html = render_kind(
+            &map_variant("img"),
+            KindContext::default(),
+            "rounded",
+            HashMap::new(),
+            "52.52,13.405",
+        )
+        .unwrap();
+        assert!(html.starts_with("<img class=\"rounded\""));
+        assert!(html.contains("center=52.520000,13.405000&amp;zoom=10"));
+
+        let geojson = r#"{"type":"Point","coordinates":[13.405,52.52]}"#;
+        let html = render_kind(
+            &map_variant("div"),
+            KindContext::default(),
+            "",
+            HashMap::new(),
+            geojson,
+        )
+        .unwrap();
+        assert!(html.contains("data-map-lat=\"52.520000\""));
+        assert!(html.contains("data-map-geojson=\"{&quot;type&quot;"));
+        assert!(html.contains("openstreetmap.org/?mlat=52.520000"));
+
+        assert_eq!(
+            render_kind(
+                &map_variant("div"),
+                KindContext::default(),
+                "",
+                HashMap::new(),
+                "n/a"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_series_kinds_render_svg_with_theme_colors() {
+        assert_eq!(parse_series("[1, 2.5, \"3\"]"), Some(vec![1.0, 2.5, 3.0]));
+        assert_eq!(parse_series("4, 5 6"), Some(vec![4.0, 5.0, 6.0]));
+        assert_eq!(parse_series("1, two"), None);
+        assert_eq!(parse_series(""), None);
+
+        let theme = Theme {
+            tokens: HashMap::from([("color-primary-500".to_string(), "#3b82f6".to_string())]),
+            tags: HashMap::new(),
+        };
+        let variant = |kind: &str| FieldVariant {
+            base: "svg".to_string(),
+            extends: None,
+            override_class: None,
+            extend: None,
+            attrs: None,
+            kind: Some(kind.to_string()),
+            options: Some(HashMap::from([
+                ("color".to_string(), "color-primary-500".to_string()),
+                ("width".to_string(), "100".to_string()),
+                ("height".to_string(), "10".to_string()),
+            ])),
+            max_length: None,
+            overflow: None,
+            field_type: None,
+            deprecated: None,
+        };
+
+        let ctx = KindContext {
+            theme: Some(&theme),
+            ..Default::default()
+        };
+        let html =
+            render_kind(&variant("sparkline"), ctx, "h-8", HashMap::new(), "0,5,10").unwrap();
+        assert!(html.starts_with("<svg class=\"h-8\""));
+        assert!(html.contains("points=\"0.00,10.00 50.00,5.00 100.00,0.00\""));
+        assert!(html.contains("stroke=\"#3b82f6\""));
+        assert!(html.contains("<title>3 values, min 0, max 10, last 10</title>"));
+
+        let html = render_kind(
+            &variant("chart"),
+            KindContext::default(),
+            "",
+            HashMap::new(),
+            "[2, 4]",
+        )
+        .unwrap();
+        assert_eq!(html.matches("<rect").count(), 2);
+        assert!(html.contains("fill=\"currentColor\""));
+    }
+
+    #[test]
+    fn test_progress_and_rating_markup() {
+        assert_eq!(parse_progress("42%", 100.0), Some((42.0, 100.0)));
+        assert_eq!(parse_progress("3 / 4", 100.0), Some((3.0, 4.0)));
+        assert_eq!(parse_progress("150", 100.0), Some((100.0, 100.0)));
+        assert_eq!(parse_progress("1/0", 100.0), None);
+
+        let theme = Theme {
+            tokens: HashMap::new(),
+            tags: HashMap::from([
+                ("progress-bar".to_string(), "bg-blue-600".to_string()),
+                ("rating-star".to_string(), "text-yellow-400".to_string()),
+                ("rating-star-empty".to_string(), "text-gray-300".to_string()),
+            ]),
+        };
+        let variant = |kind: &str| FieldVariant {
+            base: kind.to_string(),
+            extends: None,
+            override_class: None,
+            extend: None,
+            attrs: None,
+            kind: Some(kind.to_string()),
+            options: None,
+            max_length: None,
+            overflow: None,
+            field_type: None,
+            deprecated: None,
+        };
+
+        let ctx = KindContext {
+            theme: Some(&theme),
+            ..Default::default()
+        };
+        let html = render_kind(&variant("progress"), ctx, "h-2", HashMap::new(), "3/4").unwrap();
+        assert!(html.starts_with("<div class=\"h-2\""));
+        assert!(html.contains("role=\"progressbar\""));
+        assert!(html.contains("aria-valuenow=\"3\""));
+        assert!(html.contains("aria-valuemax=\"4\""));
+        assert!(html.contains("<div class=\"bg-blue-600\" style=\"width: 75.0%\"></div>"));
+
+        let html = render_kind(&variant("rating"), ctx, "", HashMap::new(), "3.4").unwrap();
+        assert!(html.contains("aria-label=\"3.5 out of 5\""));
+        // Half star falls back to the full star classes when the theme has none
+        assert_eq!(html.matches("text-yellow-400").count(), 4);
+        assert_eq!(html.matches("text-gray-300").count(), 1);
+        assert_eq!(
+            render_kind(&variant("rating"), ctx, "", HashMap::new(), "great"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_currency_parsing_and_unconverted_fallback() {
+        assert_eq!(parse_money("1,234.5", "USD"), Some((1234.5, "USD")));
+        assert_eq!(parse_money("EUR 12", "USD"), Some((12.0, "EUR")));
+        assert_eq!(parse_money("12.00 gbp", "USD"), Some((12.0, "gbp")));
+        assert_eq!(parse_money("12 dollars", "USD"), None);
+
+        let variant = FieldVariant {
+            base: "span".to_string(),
+            extends: None,
+            override_class: None,
+            extend: None,
+            attrs: None,
+            kind: Some("currency".to_string()),
+            options: Some(HashMap::from([
+                ("currency".to_string(), "EUR".to_string()),
+                ("display_currency".to_string(), "viewer".to_string()),
+            ])),
+            max_length: None,
+            overflow: None,
+            field_type: None,
+            deprecated: None,
+        };
+        assert!(is_volatile(&variant));
+
+        // Same currency needs no rate; unknown rates show the stored amount
+        let ctx = KindContext {
+            currency: Some("EUR"),
+            ..Default::default()
+        };
+        let html = render_kind(&variant, ctx, "", HashMap::new(), "1999.5").unwrap();
+        assert!(html.contains("data-amount=\"1999.50\""));
+        assert!(html.ends_with(">€1,999.50</span>"));
+
+        let ctx = KindContext {
+            currency: Some("ZZZ"),
+            ..Default::default()
+        };
+        let html = render_kind(&variant, ctx, "", HashMap::new(), "5").unwrap();
+        assert!(html.contains("data-currency=\"EUR\""));
+        assert!(!html.contains("data-original"));
+    }
+
+    #[test]
+    fn test_upload_renders_a_file_input_with_the_current_file() {
+        let variant = FieldVariant {
+            base: "input".to_string(),
+            extends: None,
+            override_class: None,
+            extend: None,
+            attrs: None,
+            kind: Some("upload".to_string()),
+            options: Some(HashMap::from([(
+                "accept".to_string(),
+                "image/*".to_string(),
+            )])),
+            max_length: None,
+            overflow: None,
+            field_type: None,
+            deprecated: None,
+        };
+        let ctx = KindContext {
+            field: "avatar_url",
+            ..Default::default()
+        };
+
+        let html = render_kind(&variant, ctx, "", HashMap::new(), "").unwrap();
+        assert_eq!(
+            html,
+            "<input accept=\"image/*\" data-upload-field=\"avatar_url\" name=\"avatar_url\" type=\"file\" />"
+        );
+
+        let html = render_kind(
+            &variant,
+            ctx,
+            "",
+            HashMap::new(),
+            "/uploads/users/1/a/1-me.png",
+        )
+        .unwrap();
+        assert!(html.starts_with(
+            "<a href=\"/uploads/users/1/a/1-me.png\" data-upload-current>1-me.png</a>"
+        ));
+        assert!(html.ends_with("type=\"file\" />"));
+    }
+}
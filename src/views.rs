@@ -0,0 +1,261 @@
+// src/views.rs - Saved views: named list presets declared per table
+//
+// A view bundles the sort, filter and context a list is usually shown with,
+// so pages ask for it by name (GET /api/:table/views/:view) instead of
+// repeating the parameters:
+//   [views.recent_signups]
+//   sort = "created_at:desc"
+//   filter = "created_at > now()-7d"
+//   context = "list"
+//   limit = 20
+//
+// Filters are conditions joined with `and`, each `field op value` with op one
+// of = != < <= > >=. Values compare numerically when both sides are numbers
+// and as text otherwise, which orders ISO timestamps correctly; `now()`,
+// optionally shifted (`now()-7d`, `now()+2h`; units s m h d w), stands for
+// the current UTC time in that form.
+use crate::cursor::compare_values;
+use crate::data_source::Record;
+use crate::ics::iso_timestamp;
+use crate::renderer::Renderer;
+use crate::variant_kinds::escape_attr;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SavedView {
+    // "field" or "field:desc" ("field:asc" is the default order)
+    #[serde(default)]
+    pub sort: Option<String>,
+    #[serde(default)]
+    pub filter: Option<String>,
+    // Context the records render in
+    #[serde(default = "default_context")]
+    pub context: String,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+fn default_context() -> String {
+    "list".to_string()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condition {
+    pub field: String,
+    pub op: String,
+    pub value: String,
+}
+
+const OPERATORS: [&str; 6] = ["!=", "<=", ">=", "=", "<", ">"];
+
+impl SavedView {
+    // The sort field and whether it's descending
+    pub fn sort_order(&self) -> Result<Option<(&str, bool)>, String> {
+        let Some(sort) = &self.sort else {
+            return Ok(None);
+        };
+        let (field, direction) = sort.split_once(':').unwrap_or((sort, "asc"));
+        let descending = match direction.trim() {
+            "asc" => false,
+            "desc" => true,
+            other => return Err(format!("unknown sort direction '{}' (asc or desc)", other)),
+        };
+        match field.trim() {
+            "" => Err(format!("sort '{}' names no field", sort)),
+            field => Ok(Some((field, descending))),
+        }
+    }
+
+    pub fn conditions(&self) -> Result<Vec<Condition>, String> {
+        let Some(filter) = &self.filter else {
+            return Ok(Vec::new());
+        };
+        filter.split(" and ").map(parse_condition).collect()
+    }
+
+    // 🔭 The records this view shows, filtered, sorted and limited as declared
+    pub fn apply(&self, mut records: Vec<Record>, now: SystemTime) -> Result<Vec<Record>, String> {
+        let conditions = self
+            .conditions()?
+            .into_iter()
+            .map(|condition| {
+                let value = resolve_value(&condition.value, now)?;
+                Ok(Condition { value, ..condition })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        records.retain(|record| {
+            conditions
+                .iter()
+                .all(|condition| matches(record, condition))
+        });
+
+        if let Some((field, descending)) = self.sort_order()? {
+            records.sort_by(|a, b| {
+                let order = compare_field(a, b, field).then_with(|| compare_field(a, b, "id"));
+                if descending { order.reverse() } else { order }
+            });
+        }
+        if let Some(limit) = self.limit {
+            records.truncate(limit);
+        }
+        Ok(records)
+    }
+}
+
+fn parse_condition(condition: &str) -> Result<Condition, String> {
+    let start = condition
+        .find(['=', '!', '<', '>'])
+        .ok_or_else(|| format!("condition '{}' has no operator", condition.trim()))?;
+    let op = OPERATORS
+        .into_iter()
+        .find(|op| condition[start..].starts_with(op))
+        .ok_or_else(|| format!("unknown operator in '{}'", condition.trim()))?;
+    let field = condition[..start].trim();
+    let value = condition[start + op.len()..].trim();
+    if field.is_empty() || value.is_empty() {
+        return Err(format!(
+            "condition '{}' needs a field and a value",
+            condition.trim()
+        ));
+    }
+    let value = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value);
+    Ok(Condition {
+        field: field.to_string(),
+        op: op.to_string(),
+        value: value.to_string(),
+    })
+}
+
+// `now()` and shifted forms become timestamps; anything else is literal
+fn resolve_value(value: &str, now: SystemTime) -> Result<String, String> {
+    let Some(shift) = value.strip_prefix("now()") else {
+        return Ok(value.to_string());
+    };
+    let shift = shift.trim();
+    if shift.is_empty() {
+        return Ok(iso_timestamp(now));
+    }
+    let invalid = || format!("invalid time offset in '{}' (e.g. now()-7d)", value);
+    let (sign, rest) = match shift.chars().next() {
+        Some(sign @ ('-' | '+')) => (sign, shift[1..].trim()),
+        _ => return Err(invalid()),
+    };
+    let unit = rest.chars().last().ok_or_else(invalid)?;
+    let amount: u64 = rest[..rest.len() - unit.len_utf8()]
+        .parse()
+        .map_err(|_| invalid())?;
+    let seconds = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 3_600,
+        'd' => 86_400,
+        'w' => 604_800,
+        _ => return Err(invalid()),
+    };
+    let offset = Duration::from_secs(amount.saturating_mul(seconds));
+    let time = match sign {
+        '-' => now.checked_sub(offset),
+        _ => now.checked_add(offset),
+    };
+    time.map(iso_timestamp).ok_or_else(invalid)
+}
+
+// Records without the field never match
+fn matches(record: &Record, condition: &Condition) -> bool {
+    let Some(value) = record.get(&condition.field) else {
+        return false;
+    };
+    let order = compare_values(value, &condition.value);
+    match condition.op.as_str() {
+        "=" => order.is_eq(),
+        "!=" => order.is_ne(),
+        "<" => order.is_lt(),
+        "<=" => order.is_le(),
+        ">" => order.is_gt(),
+        _ => order.is_ge(),
+    }
+}
+
+fn compare_field(a: &Record, b: &Record, field: &str) -> Ordering {
+    let value = |record: &Record| record.get(field).cloned().unwrap_or_default();
+    compare_values(&value(a), &value(b))
+}
+
+// The view's records as HTML, each rendered whole in the view's context
+pub fn render_view(
+    renderer: &Renderer,
+    table: &str,
+    name: &str,
+    view: &SavedView,
+    records: &[Record],
+) -> String {
+    let mut html = format!(r#"<div data-sui-view="{}">"#, escape_attr(name));
+    for record in records {
+        match record.get("id") {
+            Some(id) => html.push_str(&format!(r#"<div data-id="{}">"#, escape_attr(id))),
+            None => html.push_str("<div>"),
+        }
+        html.push_str(&renderer.render_record_html(table, &view.context, record));
+        html.push_str("</div>");
+    }
+    html.push_str("</div>");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn record(id: &str, created_at: &str, plan: &str) -> Record {
+        HashMap::from([
+            ("id".to_string(), id.to_string()),
+            ("created_at".to_string(), created_at.to_string()),
+            ("plan".to_string(), plan.to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_view_filters_sorts_and_limits() {
+        let view = SavedView {
+            sort: Some("created_at:desc".to_string()),
+            filter: Some("created_at > now()-7d and plan != 'free'".to_string()),
+            context: default_context(),
+            limit: Some(2),
+        };
+        // 2024-01-15T00:00:00Z
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_705_276_800);
+        let records = vec![
+            record("1", "2024-01-14T09:00:00Z", "pro"),
+            record("2", "2024-01-01T09:00:00Z", "pro"),
+            record("3", "2024-01-13T09:00:00Z", "free"),
+            record("4", "2024-01-10T09:00:00Z", "team"),
+            record("5", "2024-01-12T09:00:00Z", "pro"),
+        ];
+        let shown = view.apply(records, now).unwrap();
+        let ids: Vec<_> = shown.iter().map(|r| r["id"].as_str()).collect();
+        assert_eq!(ids, ["1", "5"]);
+
+        let broken = |filter: &str| SavedView {
+            filter: Some(filter.to_string()),
+            ..view.clone()
+        };
+        assert!(broken("created_at").conditions().is_err());
+        assert!(
+            broken("created_at > now()-7y")
+                .apply(Vec::new(), now)
+                .is_err()
+        );
+        let bad_sort = SavedView {
+            sort: Some("created_at:up".to_string()),
+            ..view
+        };
+        assert!(bad_sort.sort_order().is_err());
+    }
+}
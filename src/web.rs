@@ -1,55 +1,202 @@
 // src/web.rs - Web API endpoints for component system
 use axum::{
-    Router,
-    extract::{Path, Query},
-    http::StatusCode,
-    response::{Html, IntoResponse},
-    routing::get,
+    Extension, Router,
+    body::Body,
+    extract::{DefaultBodyLimit, Json, Path, Query, Request},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    middleware::{self, Next},
+    response::{Html, IntoResponse, Response},
+    routing::{get, post, put},
 };
+use futures::{SinkExt, StreamExt};
 use serde::Deserialize;
 
 use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
 
-use crate::component_registry::{ComponentError, RenderParams, component_registry};
+use crate::analytics::usage_tracker;
+use crate::budget::budget_tracker;
+use crate::chat_format::ChatFormat;
+use crate::codegen::typescript_client;
+use crate::compat::compare_versions;
+use crate::component_registry::{
+    ComponentError, ComponentRegistry, RenderOutput, RenderParams, component_registry,
+};
+use crate::config::config;
+use crate::cursor::sentinel;
+use crate::data_source::{DataError, Record};
+use crate::facets::{Facet, render_facets};
+use crate::formatter::pretty_print_html;
+use crate::forms::{ROLE_HEADER, SubmitError, forbidden_fields, submit_record, validators};
+use crate::http_source::encode;
+use crate::image_proxy::{ImageProxyError, image_proxy};
+use crate::jobs::{Job, JobError, JobKind, job_queue};
+use crate::locale::NegotiatedLocale;
+use crate::oembed::{OembedError, OembedParams, resolve_embed};
+use crate::renderer::Renderer;
+use crate::schema::{Context, FieldVariant, RenderError, SchemaRegistry, TableSchema, registry};
+use crate::schema_store::{
+    EditOutcome, SchemaEditError, SchemaStore, draft, history, publish, put_context, put_variant,
+    rollback, token_matches,
+};
+use crate::search::{render_results, render_snippet};
+use crate::settings::{record_form, settings_form, singleton_record};
+use crate::uploads::{UploadError, upload_key, upload_storage};
+use crate::validation::Severity;
+use crate::views::render_view;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::SystemTime;
 
 #[derive(Debug, Deserialize)]
 pub struct ComponentParams {
-    // Required
-    pub id: String,
+    // Required, except by static components (footer, banner)
+    pub id: Option<String>,
 
     // Optional with defaults
     pub context: Option<String>,  // default: "card"
     pub platform: Option<String>, // default: "web"
     pub format: Option<String>,   // default: "html"
+    pub offset: Option<usize>,    // format=tree: next page of a thread's replies
     pub theme: Option<String>,    // default: "light"
-    pub lang: Option<String>,     // default: "en"
+    pub lang: Option<String>,     // default: Accept-Language, then "en"
+    pub timezone: Option<String>, // default: X-Timezone header
+    pub currency: Option<String>, // viewer currency, e.g. "EUR"
+    pub tenant: Option<String>,   // customer whose schema overlays apply
+    pub minify: Option<bool>,     // default: false
+    pub jsonld: Option<bool>,     // default: false, append schema.org JSON-LD
+    pub pretty: Option<bool>,     // default: false, development only
+    pub debug: Option<bool>,      // default: false, development only
 }
 
 // 🚀 Main API endpoint: GET /api/:component
 pub async fn render_component_api(
     Path(component_name): Path<String>,
     Query(params): Query<ComponentParams>,
+    Query(args): Query<HashMap<String, String>>,
+    Extension(locale): Extension<NegotiatedLocale>,
 ) -> impl IntoResponse {
+    // Singleton tables (site settings) are addressed by name, without an id
+    if component_registry()
+        .get_component(&component_name)
+        .is_none()
+        && registry()
+            .get_table(&component_name)
+            .is_some_and(|schema| schema.singleton)
+    {
+        return singleton_response(&component_name, &params).await;
+    }
     let registry = component_registry();
+    // Every format needs the component's required params
+    if let Some(component) = registry.get_component(&component_name)
+        && let Err(err) = ComponentRegistry::required_args(component, Some(&args))
+    {
+        return component_error_response(err);
+    }
+    // Record-bound components report a missing id from the render
+    let id = params.id.as_deref().unwrap_or_default();
+    let lang = params.lang.as_deref().or(locale.lang.as_deref());
+    let timezone = params.timezone.as_deref().or(locale.timezone.as_deref());
+    // What the text formats go by: the record's scope, tenant and context
+    let text_params = RenderParams {
+        context: params.context.as_deref(),
+        tenant: params.tenant.as_deref(),
+        args: Some(&args),
+        ..Default::default()
+    };
+
+    // Plain one-line text for notification services, built from [short] templates
+    if params.format.as_deref() == Some("sms") {
+        let sms = registry.render_component_sms(&component_name, id, text_params);
+        return match sms.await {
+            Ok(line) => line.into_response(),
+            Err(err) => component_error_response(err),
+        };
+    }
+    if params.format.as_deref() == Some("push") {
+        let push = registry.render_component_push(&component_name, id, text_params);
+        return match push.await {
+            Ok(payload) => axum::Json(payload).into_response(),
+            Err(err) => component_error_response(err),
+        };
+    }
+    // The record and everything below it, for `[tree]` tables
+    if params.format.as_deref() == Some("tree") {
+        let offset = params.offset.unwrap_or(0);
+        let params = RenderParams {
+            context: params.context.as_deref(),
+            platform: params.platform.as_deref(),
+            theme: params.theme.as_deref(),
+            lang,
+            timezone,
+            currency: params.currency.as_deref(),
+            tenant: params.tenant.as_deref(),
+            args: Some(&args),
+            ..Default::default()
+        };
+        return match registry
+            .render_component_tree(&component_name, id, offset, params)
+            .await
+        {
+            Ok(html) => Html(html).into_response(),
+            Err(err) => component_error_response(err),
+        };
+    }
+    if params.format.as_deref() == Some("ics") {
+        let ics = registry
+            .render_component_ics(&component_name, Some(id), None, text_params)
+            .await;
+        return ics_response(ics);
+    }
+    // Block Kit / embed JSON for chat-ops bots
+    if let Some(chat) = params.format.as_deref().and_then(ChatFormat::parse) {
+        return match registry
+            .render_component_chat(&component_name, id, chat, text_params)
+            .await
+        {
+            Ok(message) => axum::Json(message).into_response(),
+            Err(err) => component_error_response(err),
+        };
+    }
 
     match registry
-        .render_component(
+        .render_component_output(
             &component_name,
-            &params.id,
+            id,
             RenderParams {
                 context: params.context.as_deref(),
                 platform: params.platform.as_deref(),
                 theme: params.theme.as_deref(),
-                lang: params.lang.as_deref(),
+                lang,
+                timezone,
+                currency: params.currency.as_deref(),
+                tenant: params.tenant.as_deref(),
                 format: params.format.as_deref(),
+                minify: params.minify.unwrap_or(false),
+                jsonld: params.jsonld.unwrap_or(false),
+                debug: params.debug.unwrap_or(false) && config().is_development(),
+                args: Some(&args),
+                ..Default::default()
             },
         )
         .await
     {
-        Ok(html) => {
+        Ok(RenderOutput {
+            html,
+            degraded,
+            flagged,
+            deprecations,
+            missing,
+        }) => {
+            // Re-indented output for humans; ignored outside development
+            let html = if params.pretty.unwrap_or(false) && config().is_development() {
+                pretty_print_html(&html)
+            } else {
+                html
+            };
+
             // Future: handle different formats here
-            match params.format.as_deref().unwrap_or("html") {
+            let mut response = match params.format.as_deref().unwrap_or("html") {
                 "html" => Html(html).into_response(),
                 "text" => html.into_response(), // Plain text
                 "json" => {
@@ -58,24 +205,578 @@ pub async fn render_component_api(
                         "id": params.id,
                         "html": html,
                         "context": params.context.unwrap_or_else(|| "card".to_string()),
-                        "theme": params.theme.unwrap_or_else(|| "light".to_string())
+                        "theme": params.theme.unwrap_or_else(|| "light".to_string()),
+                        "lang": lang.unwrap_or("en"),
+                        "timezone": timezone,
+                        "deprecations": deprecations
                     });
                     axum::Json(json_response).into_response()
                 }
                 _ => (StatusCode::BAD_REQUEST, "Unsupported format").into_response(),
+            };
+
+            // Rendered from stale cache or mock data during a data source outage
+            if degraded {
+                response
+                    .headers_mut()
+                    .insert("x-data-degraded", HeaderValue::from_static("true"));
+            }
+            // Details are logged and listed in format=json; the header counts them
+            if !deprecations.is_empty() {
+                response
+                    .headers_mut()
+                    .insert("x-deprecations", deprecations.len().into());
             }
+            // Field names come from the schema, so they are valid header text
+            if !flagged.is_empty()
+                && let Ok(value) = HeaderValue::from_str(&flagged.join(","))
+            {
+                response.headers_mut().insert("x-content-flagged", value);
+            }
+            if !missing.is_empty()
+                && let Ok(value) = HeaderValue::from_str(&missing.join(","))
+            {
+                response.headers_mut().insert("x-missing-fields", value);
+            }
+            response
+        }
+        Err(err) => component_error_response(err),
+    }
+}
+
+// 🗂️ GET /api/:table for a singleton table: the row rendered in a context,
+// `format=form` for an editing form, or `format=json` for the raw values
+async fn singleton_response(table: &str, params: &ComponentParams) -> Response {
+    let data_source = component_registry().data_source();
+    let record = match singleton_record(data_source.as_ref(), table, &[]).await {
+        Ok(record) => record,
+        Err(err) => return component_error_response(err.into()),
+    };
+    let schema_registry = registry();
+    match params.format.as_deref().unwrap_or("html") {
+        "html" => {
+            let context = params.context.as_deref().unwrap_or("card");
+            let renderer = Renderer::with_registry(schema_registry);
+            Html(renderer.render_record_html(table, context, &record)).into_response()
         }
-        Err(ComponentError::ComponentNotFound(name)) => (
+        "form" => match settings_form(&schema_registry, table, &record) {
+            Some(form) => Html(form).into_response(),
+            None => component_error_response(ComponentError::RecordNotFound(table.to_string())),
+        },
+        "json" => axum::Json(record).into_response(),
+        _ => (StatusCode::BAD_REQUEST, "Unsupported format").into_response(),
+    }
+}
+
+fn ics_response(ics: Result<String, ComponentError>) -> Response {
+    match ics {
+        Ok(ics) => (
+            [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+            ics,
+        )
+            .into_response(),
+        Err(err) => component_error_response(err),
+    }
+}
+
+fn component_error_response(err: ComponentError) -> Response {
+    match err {
+        ComponentError::ComponentNotFound(name) => (
             StatusCode::NOT_FOUND,
             format!("Component '{}' not found", name),
         )
             .into_response(),
-        Err(ComponentError::RecordNotFound(id)) => (
+        ComponentError::RecordNotFound(id) => (
             StatusCode::NOT_FOUND,
             format!("Record with id '{}' not found", id),
         )
             .into_response(),
-        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+        err @ (ComponentError::UnsupportedFormat(_)
+        | ComponentError::MissingRecordId(_)
+        | ComponentError::InvalidCursor(_)) => {
+            (StatusCode::BAD_REQUEST, err.to_string()).into_response()
+        }
+        err @ ComponentError::UnsafeContent(..) => {
+            (StatusCode::UNPROCESSABLE_ENTITY, err.to_string()).into_response()
+        }
+        ComponentError::MissingParams(missing) => {
+            let message = ComponentError::MissingParams(missing.clone()).to_string();
+            let body = serde_json::json!({ "error": message, "missing": missing });
+            (StatusCode::UNPROCESSABLE_ENTITY, axum::Json(body)).into_response()
+        }
+        err => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    }
+}
+
+// 📨 POST /api/:table/records - create a record from a JSON object of field values
+pub async fn create_record_api(
+    headers: HeaderMap,
+    Path(table): Path<String>,
+    Json(record): Json<Record>,
+) -> Response {
+    submit_response(&headers, &table, None, record).await
+}
+
+// ✏️ PUT /api/:table/records/:id - update the fields the JSON object carries
+pub async fn update_record_api(
+    headers: HeaderMap,
+    Path((table, id)): Path<(String, String)>,
+    Json(record): Json<Record>,
+) -> Response {
+    submit_response(&headers, &table, Some(&id), record).await
+}
+
+async fn submit_response(
+    headers: &HeaderMap,
+    table: &str,
+    id: Option<&str>,
+    record: Record,
+) -> Response {
+    let role = headers.get(ROLE_HEADER).and_then(|role| role.to_str().ok());
+    // Browsers get the form back with the errors in place
+    let wants_html = headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/html"));
+    let submitted = wants_html.then(|| record.clone());
+    let data_source = component_registry().data_source();
+    let schemas = registry();
+    let result = submit_record(
+        data_source.as_ref(),
+        &schemas,
+        &validators(),
+        table,
+        id,
+        role,
+        record,
+    )
+    .await;
+    match (result, submitted) {
+        (Ok(written), _) => {
+            let status = if id.is_some() {
+                StatusCode::OK
+            } else {
+                StatusCode::CREATED
+            };
+            (status, axum::Json(serde_json::json!({ "id": written }))).into_response()
+        }
+        (Err(SubmitError::Invalid(errors)), Some(submitted)) => {
+            match record_form(&schemas, table, &submitted, &errors) {
+                Some(form) => (StatusCode::UNPROCESSABLE_ENTITY, Html(form)).into_response(),
+                None => submit_error_response(SubmitError::Invalid(errors)),
+            }
+        }
+        (Err(err), _) => submit_error_response(err),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UploadParams {
+    // Context the field is rendered in afterwards
+    pub context: String,
+    // Original file name, kept (made safe) in the stored name
+    pub filename: Option<String>,
+}
+
+// 📤 POST /api/:table/:id/upload/:field?context=edit - the body is the file.
+// It is streamed to the upload storage, the URL saved in the field through
+// the same checks as a record update, and the field rendered again.
+pub async fn upload_api(
+    headers: HeaderMap,
+    Path((table, id, field)): Path<(String, String, String)>,
+    Query(params): Query<UploadParams>,
+    body: Body,
+) -> Response {
+    let schemas = registry();
+    let Some(schema) = schemas.get_table(&table) else {
+        return submit_error_response(SubmitError::TableNotFound(table));
+    };
+    let is_upload = schema.variants.get(&field).is_some_and(|variants| {
+        variants
+            .values()
+            .any(|variant| variant.kind.as_deref() == Some("upload"))
+    });
+    if !is_upload {
+        let message = format!("'{}' is not an upload field of '{}'", field, table);
+        return (StatusCode::NOT_FOUND, message).into_response();
+    }
+    // Nothing is stored for a caller that may not write the field
+    let role = headers.get(ROLE_HEADER).and_then(|role| role.to_str().ok());
+    let forbidden = forbidden_fields(
+        schema,
+        role,
+        &Record::from([(field.clone(), String::new())]),
+    );
+    if !forbidden.is_empty() {
+        return submit_error_response(SubmitError::Forbidden(forbidden));
+    }
+    let data_source = component_registry().data_source();
+    if let Err(err) = data_source.get_record(&table, &id).await {
+        return component_error_response(err.into());
+    }
+
+    let key = match upload_key(&table, &id, &field, params.filename.as_deref()) {
+        Ok(key) => key,
+        Err(err) => return upload_error_response(err),
+    };
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok());
+    let stream = body
+        .into_data_stream()
+        .map(|chunk| chunk.map_err(std::io::Error::other))
+        .boxed();
+    let url = match upload_storage().store(&key, content_type, stream).await {
+        Ok(url) => url,
+        Err(err) => return upload_error_response(err),
+    };
+
+    let record = Record::from([(field.clone(), url.clone())]);
+    let validators = validators();
+    let saved = submit_record(
+        data_source.as_ref(),
+        &schemas,
+        &validators,
+        &table,
+        Some(&id),
+        role,
+        record,
+    );
+    if let Err(err) = saved.await {
+        return submit_error_response(err);
+    }
+    match schemas.render_field(&table, &field, &params.context, url.as_str()) {
+        Some(html) => Html(html).into_response(),
+        None => {
+            let message = format!(
+                "Field '{}' has no variant in context '{}'",
+                field, params.context
+            );
+            (StatusCode::BAD_REQUEST, message).into_response()
+        }
+    }
+}
+
+fn upload_error_response(err: UploadError) -> Response {
+    let status = match err {
+        UploadError::InvalidKey(_) => StatusCode::BAD_REQUEST,
+        UploadError::TooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+        UploadError::Storage(_) => StatusCode::BAD_GATEWAY,
+    };
+    (status, err.to_string()).into_response()
+}
+
+fn submit_error_response(err: SubmitError) -> Response {
+    let message = err.to_string();
+    match err {
+        SubmitError::TableNotFound(_) => (StatusCode::NOT_FOUND, message).into_response(),
+        SubmitError::Empty => (StatusCode::BAD_REQUEST, message).into_response(),
+        SubmitError::Forbidden(fields) => {
+            let body = serde_json::json!({ "error": message, "fields": fields });
+            (StatusCode::FORBIDDEN, axum::Json(body)).into_response()
+        }
+        SubmitError::Invalid(fields) => {
+            let body = serde_json::json!({ "error": message, "fields": fields });
+            (StatusCode::UNPROCESSABLE_ENTITY, axum::Json(body)).into_response()
+        }
+        SubmitError::Data(DataError::ReadOnly(_)) => {
+            (StatusCode::METHOD_NOT_ALLOWED, message).into_response()
+        }
+        SubmitError::Data(err) => component_error_response(err.into()),
+    }
+}
+
+// 🔗 oEmbed provider: GET /oembed?url=https://example.com/users/1
+pub async fn oembed_api(Query(params): Query<OembedParams>) -> impl IntoResponse {
+    match resolve_embed(&config().oembed_routes, &params).await {
+        Ok(embed) => axum::Json(embed).into_response(),
+        Err(err @ OembedError::NoMatch(_)) => {
+            (StatusCode::NOT_FOUND, err.to_string()).into_response()
+        }
+        // The oEmbed spec asks for 501 when the format is not available
+        Err(err @ OembedError::UnsupportedFormat(_)) => {
+            (StatusCode::NOT_IMPLEMENTED, err.to_string()).into_response()
+        }
+        Err(OembedError::Render(err)) => component_error_response(err),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CollectionParams {
+    pub limit: Option<usize>,
+    pub context: Option<String>,
+    pub theme: Option<String>,
+    pub lang: Option<String>,
+    pub timezone: Option<String>,
+    pub currency: Option<String>,
+    pub tenant: Option<String>,
+    pub format: Option<String>, // "html" (streamed rows) or "ics"
+    pub minify: Option<bool>,
+    pub sort: Option<String>,  // field pages are ordered by; default: "id"
+    pub after: Option<String>, // cursor from the previous page's sentinel
+}
+
+// 📚 Collection endpoint: GET /api/:component/collection
+// Rows are streamed to the client as they finish rendering; the bounded
+// channel keeps a slow client from buffering the whole collection in memory.
+pub async fn render_collection_api(
+    Path(component_name): Path<String>,
+    Query(params): Query<CollectionParams>,
+    Query(args): Query<HashMap<String, String>>,
+    Extension(locale): Extension<NegotiatedLocale>,
+) -> impl IntoResponse {
+    let registry = component_registry();
+
+    // One calendar for the whole collection, so calendar clients can subscribe
+    if params.format.as_deref() == Some("ics") {
+        let ics_params = RenderParams {
+            tenant: params.tenant.as_deref(),
+            args: Some(&args),
+            ..Default::default()
+        };
+        let ics = registry
+            .render_component_ics(&component_name, None, params.limit, ics_params)
+            .await;
+        return ics_response(ics);
+    }
+
+    let page = registry
+        .collection_page(
+            &component_name,
+            params.sort.as_deref(),
+            params.after.as_deref(),
+            params.limit,
+            Some(&args),
+        )
+        .await;
+    let (record_ids, next_cursor) = match page {
+        Ok(page) => page,
+        Err(err) => return component_error_response(err),
+    };
+    // The next page repeats this request's params with the new cursor
+    let next_page = next_cursor.as_ref().map(|cursor| {
+        let mut query: Vec<_> = args
+            .iter()
+            .filter(|(name, _)| !matches!(name.as_str(), "after" | "sort"))
+            .map(|(name, value)| format!("{}={}", encode(name), encode(value)))
+            .collect();
+        query.sort();
+        query.push(format!("after={}", cursor));
+        let url = format!(
+            "/api/{}/collection?{}",
+            encode(&component_name),
+            query.join("&")
+        );
+        sentinel(cursor, &url)
+    });
+
+    let (mut tx, rx) = futures::channel::mpsc::channel::<Result<String, ComponentError>>(
+        registry.max_concurrent_renders(),
+    );
+
+    tokio::spawn(async move {
+        let rows = registry.render_collection_stream(
+            &component_name,
+            record_ids,
+            RenderParams {
+                context: params.context.as_deref(),
+                theme: params.theme.as_deref(),
+                lang: params.lang.as_deref().or(locale.lang.as_deref()),
+                timezone: params.timezone.as_deref().or(locale.timezone.as_deref()),
+                currency: params.currency.as_deref(),
+                tenant: params.tenant.as_deref(),
+                minify: params.minify.unwrap_or(false),
+                args: Some(&args),
+                ..Default::default()
+            },
+        );
+        futures::pin_mut!(rows);
+
+        while let Some(row) = rows.next().await {
+            if tx.send(row).await.is_err() {
+                // Client went away, stop rendering
+                return;
+            }
+        }
+        if let Some(next_page) = next_page {
+            tx.send(Ok(next_page)).await.ok();
+        }
+    });
+
+    let mut response = Html(Body::from_stream(rx)).into_response();
+    if let Some(cursor) = next_cursor
+        && let Ok(value) = HeaderValue::from_str(&cursor)
+    {
+        response.headers_mut().insert("x-next-cursor", value);
+    }
+    response
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ViewParams {
+    pub format: Option<String>, // "html" or "json"
+}
+
+// 🔭 Saved view: GET /api/:table/views/:view renders the table's records
+// with the sort, filter and context declared under `[views.<view>]`
+pub async fn render_view_api(
+    Path((table, view_name)): Path<(String, String)>,
+    Query(params): Query<ViewParams>,
+) -> Response {
+    let schema_registry = registry();
+    let Some(view) = schema_registry
+        .get_table(&table)
+        .and_then(|schema| schema.views.get(&view_name))
+        .cloned()
+    else {
+        return (
+            StatusCode::NOT_FOUND,
+            format!("View '{}' not found for table '{}'", view_name, table),
+        )
+            .into_response();
+    };
+
+    let data_source = component_registry().data_source();
+    let records = match data_source.get_records(&table, None).await {
+        Ok(records) => records,
+        Err(err) => return component_error_response(err.into()),
+    };
+    let records = match view.apply(records, SystemTime::now()) {
+        Ok(records) => records,
+        Err(message) => {
+            let message = format!("View '{}.{}' is invalid: {}", table, view_name, message);
+            return (StatusCode::INTERNAL_SERVER_ERROR, message).into_response();
+        }
+    };
+
+    match params.format.as_deref().unwrap_or("html") {
+        "html" => {
+            let renderer = Renderer::with_registry(schema_registry);
+            Html(render_view(&renderer, &table, &view_name, &view, &records)).into_response()
+        }
+        "json" => axum::Json(records).into_response(),
+        _ => (StatusCode::BAD_REQUEST, "Unsupported format").into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FacetParams {
+    pub fields: Option<String>, // comma-separated, e.g. "status,plan"
+    pub format: Option<String>, // "html" (filter chips) or "json"
+}
+
+// 🧮 Facets: GET /api/:table/facets?fields=status,plan
+pub async fn facets_api(Path(table): Path<String>, Query(params): Query<FacetParams>) -> Response {
+    let schema_registry = registry();
+    let Some(schema) = schema_registry.get_table(&table) else {
+        return (
+            StatusCode::NOT_FOUND,
+            format!("Table '{}' not found", table),
+        )
+            .into_response();
+    };
+    let fields: Vec<&str> = params
+        .fields
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .collect();
+    if fields.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            "List the fields to facet in ?fields=",
+        )
+            .into_response();
+    }
+    // Field names end up in queries, so only declared ones are accepted
+    if let Some(unknown) = fields
+        .iter()
+        .find(|field| !schema.variants.contains_key(**field))
+    {
+        let message = format!("Unknown field '{}' in table '{}'", unknown, table);
+        return (StatusCode::BAD_REQUEST, message).into_response();
+    }
+
+    let data_source = component_registry().data_source();
+    let mut facets = Vec::new();
+    for field in fields {
+        match data_source.get_facet(&table, field).await {
+            Ok(values) => facets.push(Facet {
+                field: field.to_string(),
+                values,
+            }),
+            Err(err) => return component_error_response(err.into()),
+        }
+    }
+
+    match params.format.as_deref().unwrap_or("html") {
+        "html" => Html(render_facets(&schema_registry, &table, &facets)).into_response(),
+        "json" => axum::Json(facets).into_response(),
+        _ => (StatusCode::BAD_REQUEST, "Unsupported format").into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchParams {
+    pub q: Option<String>,
+    pub limit: Option<usize>,
+    pub context: Option<String>, // context hits render in; default "list"
+    pub format: Option<String>,  // "html" or "json"
+}
+
+// 🔎 Full-text search: GET /api/:table/search?q=rust+async
+pub async fn search_api(Path(table): Path<String>, Query(params): Query<SearchParams>) -> Response {
+    let schema_registry = registry();
+    let Some(mapping) = schema_registry
+        .get_table(&table)
+        .and_then(|schema| schema.search.clone())
+    else {
+        let message = format!("Table '{}' is not searchable", table);
+        return (StatusCode::NOT_FOUND, message).into_response();
+    };
+    let query = params.q.as_deref().unwrap_or_default().trim();
+    if query.is_empty() {
+        return (StatusCode::BAD_REQUEST, "Missing search query ?q=").into_response();
+    }
+
+    let data_source = component_registry().data_source();
+    let hits = match data_source
+        .search(&table, &mapping, query, params.limit)
+        .await
+    {
+        Ok(hits) => hits,
+        Err(err) => return component_error_response(err.into()),
+    };
+
+    match params.format.as_deref().unwrap_or("html") {
+        "html" => {
+            let context = params.context.as_deref().unwrap_or("list");
+            Html(render_results(
+                schema_registry,
+                &table,
+                &mapping,
+                context,
+                &hits,
+            ))
+            .into_response()
+        }
+        "json" => {
+            let field = mapping.snippet_field();
+            let hits: Vec<_> = hits
+                .iter()
+                .map(|hit| {
+                    serde_json::json!({
+                        "record": hit.record,
+                        "rank": hit.rank,
+                        "snippet": render_snippet(&schema_registry, &table, field, &hit.snippet),
+                    })
+                })
+                .collect();
+            axum::Json(hits).into_response()
+        }
+        _ => (StatusCode::BAD_REQUEST, "Unsupported format").into_response(),
     }
 }
 
@@ -91,6 +792,21 @@ pub async fn list_components_api() -> impl IntoResponse {
     }))
 }
 
+// 📦 GET /api/client.ts - the typed TypeScript client for the components
+// registered right now (same output as `codegen client`)
+pub async fn typescript_client_api() -> impl IntoResponse {
+    let components = component_registry();
+    let templates: Vec<_> = components
+        .list_components()
+        .into_iter()
+        .filter_map(|name| components.get_component(name))
+        .collect();
+    (
+        [(header::CONTENT_TYPE, "text/typescript; charset=utf-8")],
+        typescript_client(&registry(), &templates),
+    )
+}
+
 // 🔍 Get component info/schema
 pub async fn component_info_api(Path(component_name): Path<String>) -> impl IntoResponse {
     let registry = component_registry();
@@ -100,8 +816,14 @@ pub async fn component_info_api(Path(component_name): Path<String>) -> impl Into
             "name": component.name,
             "table": component.table,
             "required_fields": component.required_fields,
+            "slots": component.slots,
+            "requires": component.requires,
+            "static_record": component.static_record,
             "template_preview": component.template,
-            "example_url": format!("/api/{}?id=1&context=card&theme=light", component.name)
+            "example_url": match component.static_record {
+                Some(_) => format!("/api/{}?context=card&theme=light", component.name),
+                None => format!("/api/{}?id=1&context=card&theme=light", component.name),
+            }
         }))
         .into_response(),
         None => (
@@ -112,6 +834,450 @@ pub async fn component_info_api(Path(component_name): Path<String>) -> impl Into
     }
 }
 
+// 📖 GET /api/schemas - tables available for editing
+pub async fn list_schemas_api() -> impl IntoResponse {
+    let registry = registry();
+    let mut tables: Vec<_> = registry.list_tables().into_iter().cloned().collect();
+    tables.sort();
+
+    axum::Json(serde_json::json!({ "tables": tables }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SchemaViewParams {
+    pub draft: Option<bool>, // default: false (published schema)
+}
+
+// Registry as it would look with the table's draft published
+fn registry_with_draft(table: &str) -> Arc<SchemaRegistry> {
+    let live = registry();
+    let store = SchemaStore::from_config();
+    let Some(draft) = draft(&store, table).and_then(|draft| store.layered(table, draft).ok())
+    else {
+        return live;
+    };
+
+    let mut preview = (*live).clone();
+    if let Some(schema) = preview.get_table_mut(table) {
+        *schema = draft;
+    }
+    Arc::new(preview)
+}
+
+// 📖 GET /api/schemas/:table - raw schema as JSON (`?draft=true` for unpublished edits)
+pub async fn get_schema_api(
+    Path(table): Path<String>,
+    Query(params): Query<SchemaViewParams>,
+) -> Response {
+    let registry = match params.draft {
+        Some(true) => registry_with_draft(&table),
+        _ => registry(),
+    };
+
+    match registry.get_table(&table) {
+        Some(schema) => axum::Json(schema).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            format!("Table '{}' not found", table),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompareParams {
+    // Each side is a published bundle ("3" or "v3"), "current" or "draft"
+    pub from: String,
+    pub to: Option<String>, // default: "current"
+}
+
+// The table as of a bundle version, the live registry or its draft
+fn schema_at(table: &str, at: &str) -> Result<TableSchema, (StatusCode, String)> {
+    let not_found = || {
+        let message = format!("Table '{}' not found in '{}'", table, at);
+        (StatusCode::NOT_FOUND, message)
+    };
+    let registry = match at {
+        "current" => registry(),
+        "draft" => registry_with_draft(table),
+        version => {
+            let Ok(version) = version.trim_start_matches('v').parse::<u64>() else {
+                let message = format!("Invalid schema version '{}'", at);
+                return Err((StatusCode::BAD_REQUEST, message));
+            };
+            let bundle = SchemaStore::from_config().load_bundle(version);
+            Arc::new(bundle.ok_or_else(not_found)?)
+        }
+    };
+    registry.get_table(table).cloned().ok_or_else(not_found)
+}
+
+// ⚖️ GET /api/schemas/:table/compare?from=3&to=current - what changed between
+// two versions of a schema and whether rendering consumers would break
+pub async fn compare_schema_api(
+    Path(table): Path<String>,
+    Query(params): Query<CompareParams>,
+) -> Response {
+    let to = params.to.as_deref().unwrap_or("current");
+    let (old, new) = match (schema_at(&table, &params.from), schema_at(&table, to)) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(error), _) | (_, Err(error)) => return error.into_response(),
+    };
+
+    let components = component_registry();
+    let templates: Vec<_> = components
+        .list_components()
+        .into_iter()
+        .filter_map(|name| components.get_component(name))
+        .collect();
+    axum::Json(compare_versions(&table, &old, &new, &templates)).into_response()
+}
+
+// 🔎 GET /api/schemas/:table/resolved - contexts flattened, theme classes merged
+pub async fn resolved_schema_api(
+    Path(table): Path<String>,
+    Query(params): Query<SchemaViewParams>,
+) -> Response {
+    let registry = match params.draft {
+        Some(true) => registry_with_draft(&table),
+        _ => registry(),
+    };
+
+    match registry.describe(&table) {
+        Some(description) => axum::Json(description).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            format!("Table '{}' not found", table),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportParams {
+    pub format: Option<String>, // "toml" (default) or "json"
+}
+
+// 📤 GET /api/schemas/:table/export?format=toml|json - the effective schema
+// (includes, extends and context inheritance resolved) as a schema file
+pub async fn export_schema_api(
+    Path(table): Path<String>,
+    Query(params): Query<ExportParams>,
+) -> Response {
+    let Some(schema) = registry().export(&table) else {
+        return (
+            StatusCode::NOT_FOUND,
+            format!("Table '{}' not found", table),
+        )
+            .into_response();
+    };
+    match params.format.as_deref().unwrap_or("toml") {
+        "json" => axum::Json(schema).into_response(),
+        "toml" => match schema.to_toml() {
+            Ok(toml) => ([(header::CONTENT_TYPE, "application/toml")], toml).into_response(),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        },
+        other => (
+            StatusCode::BAD_REQUEST,
+            format!("Unsupported format: {}", other),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FieldRenderParams {
+    pub context: String,
+    #[serde(default)]
+    pub value: String,
+    pub draft: Option<bool>,
+}
+
+// 🚦 GET /api/schemas/:table/fields/:field?context=card&value=Ada - one field
+// rendered strictly: 404 for an unknown table or field, 400 for an unknown
+// context, 422 when the schema names a variant it doesn't define
+pub async fn render_field_api(
+    Path((table, field)): Path<(String, String)>,
+    Query(params): Query<FieldRenderParams>,
+) -> Response {
+    let registry = match params.draft {
+        Some(true) => registry_with_draft(&table),
+        _ => registry(),
+    };
+    match registry.render_field_strict(&table, &field, &params.context, &params.value) {
+        Ok(html) => Html(html).into_response(),
+        Err(err) => {
+            let status = match err {
+                RenderError::UnknownTable(_) | RenderError::UnknownField(..) => {
+                    StatusCode::NOT_FOUND
+                }
+                RenderError::UnknownContext(..) => StatusCode::BAD_REQUEST,
+                RenderError::UnknownVariant(..) => StatusCode::UNPROCESSABLE_ENTITY,
+            };
+            (status, err.to_string()).into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PreviewParams {
+    pub context: Option<String>,
+    pub id: Option<String>,
+    pub draft: Option<bool>,
+}
+
+// 👀 GET /api/schemas/:table/preview - every field of a mock record rendered
+// in a context, so the editor can show the effect of unsaved choices
+pub async fn preview_schema_api(
+    Path(table): Path<String>,
+    Query(params): Query<PreviewParams>,
+) -> Response {
+    let registry = match params.draft {
+        Some(true) => registry_with_draft(&table),
+        _ => registry(),
+    };
+    let record = match &params.id {
+        Some(id) => registry.get_mock_record(&table, id),
+        None => registry.get_mock_data(&table).into_iter().next(),
+    };
+    let Some(record) = record else {
+        return (StatusCode::NOT_FOUND, "No mock record to preview").into_response();
+    };
+
+    let context = params.context.as_deref().unwrap_or("card");
+    let mut rendered: Vec<_> = Renderer::with_registry(registry.clone())
+        .render_record(&table, context, &record)
+        .into_iter()
+        .collect();
+    rendered.sort();
+
+    let html: Vec<_> = rendered.into_iter().map(|(_, html)| html).collect();
+    Html(html.join("\n")).into_response()
+}
+
+// 🎨 GET /editor - visual schema editor built on the schema endpoints
+pub async fn editor_page() -> Html<&'static str> {
+    Html(include_str!("editor.html"))
+}
+
+// 🔐 Schema editing requires `Authorization: Bearer <SCHEMA_EDITOR_TOKEN>`
+// Returns the error response to send when the caller is not allowed to edit
+fn reject_unauthorized_editor(headers: &HeaderMap) -> Option<Response> {
+    let Some(expected) = config().schema_editor_token.as_deref() else {
+        return Some((StatusCode::FORBIDDEN, "Schema editing is disabled").into_response());
+    };
+
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match provided {
+        Some(token) if token_matches(expected, token) => None,
+        _ => Some((StatusCode::UNAUTHORIZED, "Invalid schema editor token").into_response()),
+    }
+}
+
+fn edit_response(result: Result<EditOutcome, SchemaEditError>) -> Response {
+    match result {
+        Ok(outcome) => axum::Json(serde_json::json!({
+            "status": "ok",
+            "persisted": outcome.persisted
+        }))
+        .into_response(),
+        Err(
+            err @ (SchemaEditError::TableNotFound(_)
+            | SchemaEditError::NoDraft(_)
+            | SchemaEditError::VersionNotFound(_)),
+        ) => (StatusCode::NOT_FOUND, err.to_string()).into_response(),
+        Err(err @ SchemaEditError::Invalid(_)) => {
+            (StatusCode::UNPROCESSABLE_ENTITY, err.to_string()).into_response()
+        }
+        Err(err @ SchemaEditError::Storage(_)) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response()
+        }
+    }
+}
+
+// Run a schema edit on the blocking pool: it writes files while holding the
+// store's locks
+async fn blocking_edit<F>(edit: F) -> Response
+where
+    F: FnOnce(&SchemaStore) -> Result<EditOutcome, SchemaEditError> + Send + 'static,
+{
+    let store = SchemaStore::from_config();
+    match tokio::task::spawn_blocking(move || edit(&store)).await {
+        Ok(result) => edit_response(result),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+// ✏️ PUT /api/schemas/:table/variants/:field/:variant
+pub async fn put_variant_api(
+    headers: HeaderMap,
+    Path((table, field, variant_name)): Path<(String, String, String)>,
+    Json(variant): Json<FieldVariant>,
+) -> Response {
+    if let Some(response) = reject_unauthorized_editor(&headers) {
+        return response;
+    }
+    blocking_edit(move |store| put_variant(store, &table, &field, &variant_name, variant)).await
+}
+
+// ✏️ PUT /api/schemas/:table/contexts/:context
+pub async fn put_context_api(
+    headers: HeaderMap,
+    Path((table, context_name)): Path<(String, String)>,
+    Json(context): Json<Context>,
+) -> Response {
+    if let Some(response) = reject_unauthorized_editor(&headers) {
+        return response;
+    }
+    blocking_edit(move |store| put_context(store, &table, &context_name, context)).await
+}
+
+// 🚀 POST /api/schemas/:table/publish - promote the reviewed draft to production
+pub async fn publish_schema_api(headers: HeaderMap, Path(table): Path<String>) -> Response {
+    if let Some(response) = reject_unauthorized_editor(&headers) {
+        return response;
+    }
+    blocking_edit(move |store| publish(store, &table)).await
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RollbackParams {
+    pub version: u64,
+}
+
+// 🩺 GET /api/schemas/validate - diagnostics for the active schemas
+pub async fn validate_schemas_api() -> impl IntoResponse {
+    let diagnostics = registry().validate();
+    let valid = diagnostics.iter().all(|d| d.severity == Severity::Warning);
+    axum::Json(serde_json::json!({ "valid": valid, "diagnostics": diagnostics }))
+}
+
+// 🧹 GET /api/schemas/lint - housekeeping findings for the active schemas
+pub async fn lint_schemas_api() -> impl IntoResponse {
+    axum::Json(serde_json::json!({ "diagnostics": registry().lint() }))
+}
+
+// 📚 GET /api/schemas/versions - published bundle versions available for rollback
+pub async fn schema_versions_api() -> impl IntoResponse {
+    axum::Json(serde_json::json!({ "versions": history(&SchemaStore::from_config()) }))
+}
+
+// ⏪ POST /api/schemas/rollback?version=N - reactivate a previously published bundle
+pub async fn rollback_schemas_api(
+    headers: HeaderMap,
+    Query(params): Query<RollbackParams>,
+) -> Response {
+    if let Some(response) = reject_unauthorized_editor(&headers) {
+        return response;
+    }
+    blocking_edit(move |store| rollback(store, params.version)).await
+}
+
+// 🧵 POST /api/jobs - queue a rerender or export (body: {"kind": "export",
+// "component": "user_card"}); answers 202 with the job to poll
+pub async fn submit_job_api(headers: HeaderMap, Json(kind): Json<JobKind>) -> Response {
+    if let Some(response) = reject_unauthorized_editor(&headers) {
+        return response;
+    }
+    let job = match job_queue().submit(kind, component_registry()) {
+        Ok(job) => job,
+        Err(err @ JobError::QueueFull(_)) => {
+            let retry = [(header::RETRY_AFTER, "30")];
+            return (StatusCode::SERVICE_UNAVAILABLE, retry, err.to_string()).into_response();
+        }
+    };
+    let status_url = format!("/api/jobs/{}", job.id);
+    (
+        StatusCode::ACCEPTED,
+        [(header::LOCATION, status_url)],
+        axum::Json(job),
+    )
+        .into_response()
+}
+
+pub async fn list_jobs_api() -> impl IntoResponse {
+    axum::Json(serde_json::json!({ "jobs": job_queue().list() }))
+}
+
+// GET /api/jobs/:id - status and progress of a job
+pub async fn job_status_api(Path(id): Path<String>) -> Response {
+    match job_queue().get(&id) {
+        Some(job) => axum::Json(job).into_response(),
+        None => (StatusCode::NOT_FOUND, format!("Job '{}' not found", id)).into_response(),
+    }
+}
+
+// GET /api/jobs/:id/result - what a finished export job rendered
+pub async fn job_result_api(Path(id): Path<String>) -> Response {
+    match job_queue().get(&id) {
+        Some(Job {
+            result: Some(result),
+            ..
+        }) => Html(result).into_response(),
+        Some(job) => {
+            let message = format!("Job '{}' has no result ({:?})", id, job.status);
+            (StatusCode::CONFLICT, message).into_response()
+        }
+        None => (StatusCode::NOT_FOUND, format!("Job '{}' not found", id)).into_response(),
+    }
+}
+
+// 📊 GET /api/analytics/usage - render counts by component, context, variant
+// and theme (empty unless USAGE_ANALYTICS is on)
+pub async fn usage_analytics_api() -> impl IntoResponse {
+    let tracker = usage_tracker();
+    let mut body = serde_json::to_value(tracker.snapshot()).unwrap_or_default();
+    body["enabled"] = tracker.is_enabled().into();
+    axum::Json(body)
+}
+
+// ⏱️ GET /api/budgets - recorded render times against declared budgets
+pub async fn budgets_api() -> impl IntoResponse {
+    let tracker = budget_tracker();
+    axum::Json(serde_json::json!({
+        "budgets": tracker.snapshot(),
+        "violations": tracker.total_violations()
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImageParams {
+    pub src: String,
+    pub w: Option<u32>,
+    pub h: Option<u32>,
+}
+
+// 🖼️ Resized copy of an allowlisted remote image
+pub async fn image_proxy_api(Query(params): Query<ImageParams>) -> Response {
+    let proxy = image_proxy();
+    match proxy.get(&params.src, params.w, params.h).await {
+        Ok(image) => (
+            [
+                (header::CONTENT_TYPE, image.content_type.to_string()),
+                (
+                    header::CACHE_CONTROL,
+                    format!("public, max-age={}", proxy.cache_ttl().as_secs()),
+                ),
+            ],
+            image.bytes.as_ref().clone(),
+        )
+            .into_response(),
+        Err(e) => {
+            let status = match e {
+                ImageProxyError::NotAllowed(_) => StatusCode::FORBIDDEN,
+                ImageProxyError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+                ImageProxyError::Upstream(_) => StatusCode::BAD_GATEWAY,
+                ImageProxyError::Unsupported(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            };
+            (status, e.to_string()).into_response()
+        }
+    }
+}
+
 // 🏠 Root API info
 pub async fn api_root() -> impl IntoResponse {
     axum::Json(serde_json::json!({
@@ -119,8 +1285,31 @@ pub async fn api_root() -> impl IntoResponse {
         "version": "0.1.0",
         "endpoints": {
             "components": "/api/components",
+            "typescript_client": "/api/client.ts",
+            "budgets": "/api/budgets",
+            "usage_analytics": "/api/analytics/usage",
+            "jobs": "POST /api/jobs {kind: rerender|export, component}; GET /api/jobs/:id",
             "render": "/api/:component?id={id}&context={context}&theme={theme}",
-            "info": "/api/:component/info"
+            "info": "/api/:component/info",
+            "singleton": "/api/:table?format={html|form|json}",
+            "tree": "/api/:component?id={id}&format=tree",
+            "collection": "/api/:component/collection?limit={limit}&sort={field}&after={cursor}",
+            "view": "/api/:table/views/:view?format={html|json}",
+            "facets": "/api/:table/facets?fields={field,...}&format={html|json}",
+            "search": "/api/:table/search?q={query}&limit={limit}&format={html|json}",
+            "oembed": "/oembed?url={url}&maxwidth={width}&maxheight={height}",
+            "edit_variant": "PUT /api/schemas/:table/variants/:field/:variant",
+            "edit_context": "PUT /api/schemas/:table/contexts/:context",
+            "publish": "POST /api/schemas/:table/publish",
+            "schema_versions": "/api/schemas/versions",
+            "validate_schemas": "/api/schemas/validate",
+            "lint_schemas": "/api/schemas/lint",
+            "resolved_schema": "/api/schemas/:table/resolved?draft={bool}",
+            "export_schema": "/api/schemas/:table/export?format={toml|json}",
+            "compare_schema": "/api/schemas/:table/compare?from={N|draft}&to={N|current|draft}",
+            "render_field": "/api/schemas/:table/fields/:field?context=&value=",
+            "rollback": "POST /api/schemas/rollback?version=N",
+            "editor": "/editor"
         },
         "examples": [
             "/api/user_card?id=1",
@@ -130,18 +1319,83 @@ pub async fn api_root() -> impl IntoResponse {
     }))
 }
 
+// 🌍 Negotiate lang/timezone defaults from Accept-Language and X-Timezone.
+// Responses vary on both headers, so shared caches keep them apart.
+pub async fn negotiate_locale(mut request: Request, next: Next) -> Response {
+    let headers = request.headers();
+    let locale = NegotiatedLocale::from_headers(
+        headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok()),
+        headers
+            .get("x-timezone")
+            .and_then(|value| value.to_str().ok()),
+    );
+    request.extensions_mut().insert(locale);
+
+    let mut response = next.run(request).await;
+    response.headers_mut().append(
+        header::VARY,
+        HeaderValue::from_static("Accept-Language, X-Timezone"),
+    );
+    response
+}
+
 // 🌐 Create the web router
 pub fn create_router() -> Router {
     Router::new()
         // API routes
         .route("/api", get(api_root))
         .route("/api/components", get(list_components_api))
+        .route("/api/client.ts", get(typescript_client_api))
+        .route("/api/budgets", get(budgets_api))
+        .route("/api/analytics/usage", get(usage_analytics_api))
+        .route("/api/jobs", get(list_jobs_api).post(submit_job_api))
+        .route("/api/jobs/:id", get(job_status_api))
+        .route("/api/jobs/:id/result", get(job_result_api))
         .route("/api/:component", get(render_component_api))
         .route("/api/:component/info", get(component_info_api))
+        .route("/api/:component/collection", get(render_collection_api))
+        // The first segment names a table here; axum needs one name per position
+        .route("/api/:component/views/:view", get(render_view_api))
+        .route("/api/:component/facets", get(facets_api))
+        .route("/api/:component/search", get(search_api))
+        .route("/api/:component/records", post(create_record_api))
+        .route("/api/:component/records/:id", put(update_record_api))
+        // Upload size is enforced by the storage (UPLOAD_MAX_BYTES)
+        .route(
+            "/api/:component/:id/upload/:field",
+            post(upload_api).layer(DefaultBodyLimit::disable()),
+        )
+        .route("/img", get(image_proxy_api))
+        .route("/oembed", get(oembed_api))
+        // Schema editor routes
+        .route("/editor", get(editor_page))
+        .route("/api/schemas", get(list_schemas_api))
+        .route("/api/schemas/versions", get(schema_versions_api))
+        .route("/api/schemas/validate", get(validate_schemas_api))
+        .route("/api/schemas/lint", get(lint_schemas_api))
+        .route("/api/schemas/rollback", post(rollback_schemas_api))
+        .route("/api/schemas/:table", get(get_schema_api))
+        .route("/api/schemas/:table/preview", get(preview_schema_api))
+        .route("/api/schemas/:table/resolved", get(resolved_schema_api))
+        .route("/api/schemas/:table/export", get(export_schema_api))
+        .route("/api/schemas/:table/compare", get(compare_schema_api))
+        .route("/api/schemas/:table/fields/:field", get(render_field_api))
+        .route(
+            "/api/schemas/:table/variants/:field/:variant",
+            put(put_variant_api),
+        )
+        .route(
+            "/api/schemas/:table/contexts/:context",
+            put(put_context_api),
+        )
+        .route("/api/schemas/:table/publish", post(publish_schema_api))
         // Add middleware
         .layer(
             ServiceBuilder::new()
                 .layer(CorsLayer::permissive()) // For development
+                .layer(middleware::from_fn(negotiate_locale))
                 .into_inner(),
         )
 }
@@ -158,6 +1412,38 @@ pub async fn start_server(port: u16) -> Result<(), Box<dyn std::error::Error>> {
     println!("   GET /api/components - List all components");
     println!("   GET /api/user_card?id=1 - Render user card component");
     println!("   GET /api/user_card/info - Get component schema");
+    println!("   GET /api/user_card/collection?limit=10 - Render a collection of user cards");
+    println!("   GET /editor - Visual schema editor");
+    println!("   GET /img?src=...&w=128&h=128 - Resized allowlisted images");
+
+    // A typo'd variant name renders nothing, so surface it at startup
+    for diagnostic in registry().validate() {
+        eprintln!("⚠️  {}", diagnostic);
+    }
+
+    // Pick up schema and theme edits without a restart while developing
+    #[cfg(feature = "hot-reload")]
+    let _hot_reload = if config().is_development() {
+        let schemas_dir = config()
+            .schemas_dir
+            .clone()
+            .unwrap_or_else(|| std::path::PathBuf::from("schemas"));
+        match crate::hot_reload::watch(&schemas_dir, std::path::Path::new("themes.toml")) {
+            Ok(watcher) => {
+                println!(
+                    "🔄 Watching {} and themes.toml for changes",
+                    schemas_dir.display()
+                );
+                Some(watcher)
+            }
+            Err(e) => {
+                eprintln!("Hot reload unavailable: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
 
     let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port)).await?;
     axum::serve(listener, app).await?;
@@ -186,8 +1472,348 @@ mod tests {
             .await;
         assert_eq!(response.status_code(), StatusCode::OK);
 
+        // Record-bound components need an id
+        let response = server.get("/api/user_card").await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
         // Test component info
         let response = server.get("/api/user_card/info").await;
         assert_eq!(response.status_code(), StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn test_singleton_table_endpoint() {
+        let site = crate::schema::TableSchema::from_toml_str(
+            "singleton = true\n\
+             [variants.brand]\nh1 = { base = \"h1\" }\n\
+             [contexts.card]\nbrand = \"h1\"\n\
+             [[mock_data]]\nbrand = \"Acme\"\n",
+        )
+        .unwrap();
+        crate::schema::update_registry(|registry| {
+            registry.insert_table("site", site);
+            Ok::<_, crate::schema::SchemaError>(())
+        })
+        .unwrap();
+        let server = TestServer::new(create_router().into_make_service()).unwrap();
+
+        let response = server.get("/api/site").await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(
+            response.text().contains(">Acme</h1>"),
+            "{}",
+            response.text()
+        );
+
+        let form = server
+            .get("/api/site")
+            .add_query_param("format", "form")
+            .await;
+        assert!(
+            form.text().contains(r#"name="brand" type="text""#),
+            "{}",
+            form.text()
+        );
+
+        let json = server
+            .get("/api/site")
+            .add_query_param("format", "json")
+            .await;
+        assert_eq!(json.json::<serde_json::Value>()["brand"], "Acme");
+    }
+
+    #[tokio::test]
+    async fn test_saved_view_endpoint() {
+        let signups = crate::schema::TableSchema::from_toml_str(
+            "[views.paying]\nsort = \"joined:desc\"\nfilter = \"plan != 'free'\"\n\
+             context = \"card\"\n\
+             [variants.name]\nplain = { base = \"b\" }\n\
+             [contexts.card]\nname = \"plain\"\n\
+             [[mock_data]]\nid = \"1\"\nname = \"Ann\"\nplan = \"pro\"\njoined = \"2024-01-01\"\n\
+             [[mock_data]]\nid = \"2\"\nname = \"Ben\"\nplan = \"free\"\njoined = \"2024-01-02\"\n\
+             [[mock_data]]\nid = \"3\"\nname = \"Cy\"\nplan = \"team\"\njoined = \"2024-01-03\"\n",
+        )
+        .unwrap();
+        crate::schema::update_registry(|registry| {
+            registry.insert_table("signups", signups);
+            Ok::<_, crate::schema::SchemaError>(())
+        })
+        .unwrap();
+        let server = TestServer::new(create_router().into_make_service()).unwrap();
+
+        let response = server.get("/api/signups/views/paying").await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let body = response.text();
+        assert!(
+            body.starts_with(r#"<div data-sui-view="paying"><div data-id="3">"#),
+            "{}",
+            body
+        );
+        let (cy, ann) = (
+            body.find("<b>Cy</b>").unwrap(),
+            body.find("<b>Ann</b>").unwrap(),
+        );
+        assert!(cy < ann && !body.contains("Ben"), "{}", body);
+
+        let json = server
+            .get("/api/signups/views/paying")
+            .add_query_param("format", "json")
+            .await;
+        assert_eq!(json.json::<serde_json::Value>()[0]["name"], "Cy");
+
+        let missing = server.get("/api/signups/views/missing").await;
+        assert_eq!(missing.status_code(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_facets_endpoint() {
+        let server = TestServer::new(create_router().into_make_service()).unwrap();
+
+        let json = server
+            .get("/api/users/facets")
+            .add_query_param("fields", "name")
+            .add_query_param("format", "json")
+            .await;
+        assert_eq!(json.status_code(), StatusCode::OK);
+        let facets = json.json::<serde_json::Value>();
+        assert_eq!(facets[0]["field"], "name");
+        assert_eq!(facets[0]["values"][0]["count"], 1);
+
+        let html = server
+            .get("/api/users/facets")
+            .add_query_param("fields", "name")
+            .await;
+        assert!(
+            html.text()
+                .contains(r#"data-sui-facet="name" data-value="John Doe""#)
+        );
+
+        let unknown = server
+            .get("/api/users/facets")
+            .add_query_param("fields", "name,password; DROP TABLE users")
+            .await;
+        assert_eq!(unknown.status_code(), StatusCode::BAD_REQUEST);
+        let missing = server
+            .get("/api/nowhere/facets")
+            .add_query_param("fields", "x")
+            .await;
+        assert_eq!(missing.status_code(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_search_endpoint() {
+        let posts = crate::schema::TableSchema::from_toml_str(
+            "[search]\nfields = [\"title\", \"body\"]\nsnippet = \"body\"\n\
+             [variants.title]\nplain = { base = \"b\" }\n\
+             [variants.body]\nhighlight = { base = \"em\" }\n\
+             [contexts.list]\ntitle = \"plain\"\n\
+             [[mock_data]]\nid = \"1\"\ntitle = \"Async\"\nbody = \"Rust & async\"\n\
+             [[mock_data]]\nid = \"2\"\ntitle = \"Rust\"\nbody = \"All about rust\"\n\
+             [[mock_data]]\nid = \"3\"\ntitle = \"Go\"\nbody = \"Goroutines\"\n",
+        )
+        .unwrap();
+        crate::schema::update_registry(|registry| {
+            registry.insert_table("posts", posts);
+            Ok::<_, crate::schema::SchemaError>(())
+        })
+        .unwrap();
+        let server = TestServer::new(create_router().into_make_service()).unwrap();
+
+        let html = server
+            .get("/api/posts/search")
+            .add_query_param("q", "rust")
+            .await;
+        let body = html.text();
+        assert!(
+            body.starts_with(r#"<div data-sui-search="posts"><div data-id="2">"#),
+            "{}",
+            body
+        );
+        let snippet = "<p data-sui-snippet><em>Rust</em> &amp; async</p>";
+        assert!(body.contains(snippet), "{}", body);
+        assert!(!body.contains("Goroutines"), "{}", body);
+
+        let json = server
+            .get("/api/posts/search")
+            .add_query_param("q", "rust")
+            .add_query_param("format", "json")
+            .await;
+        let hits = json.json::<serde_json::Value>();
+        assert_eq!(hits[0]["snippet"], "All about <em>rust</em>");
+
+        let missing = server.get("/api/posts/search").await;
+        assert_eq!(missing.status_code(), StatusCode::BAD_REQUEST);
+        let unsearchable = server
+            .get("/api/users/search")
+            .add_query_param("q", "x")
+            .await;
+        assert_eq!(unsearchable.status_code(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_compare_schema_versions() {
+        let server = TestServer::new(create_router().into_make_service()).unwrap();
+
+        let same = server
+            .get("/api/schemas/users/compare")
+            .add_query_param("from", "current")
+            .await;
+        assert_eq!(same.status_code(), StatusCode::OK);
+        let comparison = same.json::<serde_json::Value>();
+        assert_eq!(comparison["breaking"], false);
+        assert_eq!(
+            comparison["diff"]["removed_contexts"],
+            serde_json::json!([])
+        );
+
+        let invalid = server
+            .get("/api/schemas/users/compare")
+            .add_query_param("from", "latest")
+            .await;
+        assert_eq!(invalid.status_code(), StatusCode::BAD_REQUEST);
+        let unknown = server
+            .get("/api/schemas/nowhere/compare")
+            .add_query_param("from", "current")
+            .await;
+        assert_eq!(unknown.status_code(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_render_field_endpoint_reports_what_is_missing() {
+        let server = TestServer::new(create_router().into_make_service()).unwrap();
+        let render = |table: &str, field: &str, context: &str| {
+            server
+                .get(&format!("/api/schemas/{}/fields/{}", table, field))
+                .add_query_param("context", context)
+                .add_query_param("value", "Ada")
+        };
+
+        let ok = render("users", "name", "card").await;
+        assert_eq!(ok.status_code(), StatusCode::OK);
+        assert!(ok.text().contains("Ada"));
+        assert_eq!(
+            render("nowhere", "name", "card").await.status_code(),
+            StatusCode::NOT_FOUND
+        );
+        let field = render("users", "shoe_size", "card").await;
+        assert_eq!(field.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(field.text(), "Table 'users' has no field 'shoe_size'");
+        let context = render("users", "name", "poster").await;
+        assert_eq!(context.status_code(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_locale_defaults_from_headers() {
+        let server = TestServer::new(create_router().into_make_service()).unwrap();
+
+        let response = server
+            .get("/api/user_card")
+            .add_query_param("id", "1")
+            .add_query_param("format", "json")
+            .add_header(
+                header::ACCEPT_LANGUAGE,
+                HeaderValue::from_static("en;q=0.5, de-DE"),
+            )
+            .add_header(
+                header::HeaderName::from_static("x-timezone"),
+                HeaderValue::from_static("Europe/Berlin"),
+            )
+            .await;
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["lang"], "de-DE");
+        assert_eq!(body["timezone"], "Europe/Berlin");
+        assert!(
+            response.headers()[header::VARY]
+                .to_str()
+                .unwrap()
+                .contains("Accept-Language")
+        );
+
+        // Explicit query parameters win over headers
+        let response = server
+            .get("/api/user_card")
+            .add_query_param("id", "1")
+            .add_query_param("format", "json")
+            .add_query_param("lang", "fr")
+            .add_header(header::ACCEPT_LANGUAGE, HeaderValue::from_static("de"))
+            .await;
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["lang"], "fr");
+        assert!(body["timezone"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_collection_api() {
+        let app = create_router();
+        let server = TestServer::new(app.into_make_service()).unwrap();
+
+        let response = server
+            .get("/api/user_card/collection")
+            .add_query_param("limit", "2")
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let body = response.text();
+        assert!(body.contains("John Doe"));
+        assert!(body.contains("Jane Smith"));
+        assert!(!body.contains("Bob Wilson"));
+
+        // The sentinel at the end of a page leads to the next one
+        let cursor = response.header("x-next-cursor");
+        let cursor = cursor.to_str().unwrap();
+        let next_url = format!("/api/user_card/collection?limit=2&amp;after={}", cursor);
+        assert!(body.ends_with(&format!(
+            r#"<div data-sui-sentinel data-next-cursor="{}" data-next-url="{}"></div>"#,
+            cursor, next_url
+        )));
+        let response = server
+            .get("/api/user_card/collection")
+            .add_query_param("limit", "2")
+            .add_query_param("after", cursor)
+            .await;
+        let body = response.text();
+        assert!(body.contains("Bob Wilson") && !body.contains("John Doe"));
+        assert!(!body.contains("data-sui-sentinel"));
+        let response = server
+            .get("/api/user_card/collection")
+            .add_query_param("after", "not-a-cursor")
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        let response = server.get("/api/missing/collection").await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_schema_editor_requires_token() {
+        let app = create_router();
+        let server = TestServer::new(app.into_make_service()).unwrap();
+
+        // No SCHEMA_EDITOR_TOKEN configured in tests, so editing is disabled
+        let response = server
+            .put("/api/schemas/users/variants/name/h5")
+            .json(&serde_json::json!({ "base": "h5" }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_editor_read_endpoints() {
+        let app = create_router();
+        let server = TestServer::new(app.into_make_service()).unwrap();
+
+        let response = server.get("/editor").await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        let response = server.get("/api/schemas/users").await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.json::<serde_json::Value>()["contexts"]["card"].is_object());
+
+        let response = server
+            .get("/api/schemas/users/preview")
+            .add_query_param("context", "list")
+            .add_query_param("id", "2")
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.text().contains("Jane Smith"));
+    }
 }